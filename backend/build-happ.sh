@@ -24,7 +24,7 @@ echo "━━━━━━━━━━━━━━━━━━━━━━━━
 echo "1️⃣  Building Integrity Zomes..."
 echo "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
 
-for zome in listings reputation transactions arbitration messaging; do
+for zome in listings reputation transactions arbitration messaging notifications; do
     echo "  📦 Building ${zome}_integrity..."
     cargo build --release --target wasm32-unknown-unknown -p "${zome}_integrity"
 done
@@ -34,7 +34,7 @@ echo "━━━━━━━━━━━━━━━━━━━━━━━━
 echo "2️⃣  Building Coordinator Zomes..."
 echo "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
 
-for zome in listings reputation transactions arbitration messaging; do
+for zome in listings reputation transactions arbitration messaging notifications; do
     echo "  📦 Building ${zome}..."
     cargo build --release --target wasm32-unknown-unknown -p "${zome}"
 done
@@ -45,19 +45,19 @@ echo "3️⃣  Copying WASM Files..."
 echo "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
 
 # Create zome directories
-for zome in listings reputation transactions arbitration messaging; do
+for zome in listings reputation transactions arbitration messaging notifications; do
     mkdir -p "zomes/${zome}"
 done
 
 # Copy integrity zomes
-for zome in listings reputation transactions arbitration messaging; do
+for zome in listings reputation transactions arbitration messaging notifications; do
     echo "  📁 Copying ${zome}_integrity.wasm..."
     cp "target/wasm32-unknown-unknown/release/${zome}_integrity.wasm" \
        "zomes/${zome}/integrity.wasm"
 done
 
 # Copy coordinator zomes
-for zome in listings reputation transactions arbitration messaging; do
+for zome in listings reputation transactions arbitration messaging notifications; do
     echo "  📁 Copying ${zome}.wasm..."
     cp "target/wasm32-unknown-unknown/release/${zome}.wasm" \
        "zomes/${zome}/coordinator.wasm"
@@ -12,7 +12,7 @@ pub mod error_handling {
     /// Centralized error conversion for to_app_option()
     ///
     /// This eliminates the repetitive pattern of:
-    /// ```rust
+    /// ```ignore
     /// record.entry()
     ///     .to_app_option()
     ///     .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Deserialization error: {:?}", e))))?
@@ -57,7 +57,7 @@ pub mod remote_calls {
     /// Type-safe remote call wrapper
     ///
     /// Eliminates the repetitive pattern of:
-    /// ```rust
+    /// ```ignore
     /// let current_agent = agent_info()?.agent_initial_pubkey;
     /// let response = call_remote(
     ///     current_agent.clone(),
@@ -94,7 +94,7 @@ pub mod remote_calls {
                 )))
             }),
             _ => Err(wasm_error!(WasmErrorInner::Guest(
-                format!("Remote call to {}.{} failed", zome_name, function_name).into()
+                format!("Remote call to {}.{} failed", zome_name, function_name)
             ))),
         }
     }
@@ -125,7 +125,7 @@ pub mod link_queries {
     /// Get all links with the standard Local strategy
     ///
     /// Eliminates the repetitive pattern of:
-    /// ```rust
+    /// ```ignore
     /// let links = get_links(
     ///     LinkQuery::try_new(base, LinkTypes::X)?,
     ///     GetStrategy::Local,
@@ -138,6 +138,17 @@ pub mod link_queries {
         get_links(LinkQuery::try_new(base, link_type)?, GetStrategy::Local)
     }
 
+    /// Get all links with the Network strategy
+    ///
+    /// For reads that must see links written by other agents, not only
+    /// what this node has already gossiped.
+    pub fn get_links_network(
+        base: impl Into<AnyLinkableHash>,
+        link_type: impl TryInto<LinkTypeFilter, Error = WasmError>,
+    ) -> ExternResult<Vec<Link>> {
+        get_links(LinkQuery::try_new(base, link_type)?, GetStrategy::Network)
+    }
+
     /// Get all links and deserialize their targets as entries
     pub fn get_linked_entries<T>(
         base: impl Into<AnyLinkableHash>,
@@ -161,6 +172,95 @@ pub mod link_queries {
     }
 }
 
+/// Record utilities
+pub mod records {
+    use super::*;
+
+    /// Get the most recent version of an entry
+    ///
+    /// `get()` on an action hash returns that exact action's record, so an
+    /// entry updated via `update_entry(original_hash, ..)` would otherwise
+    /// keep returning its first version. This follows the updates recorded
+    /// against each action (newest first) until it reaches one that has
+    /// not been updated, so both update styles are supported: every update
+    /// against the original, or each update against the previous version.
+    pub fn get_latest_record(original_hash: ActionHash) -> ExternResult<Option<Record>> {
+        let mut current_hash = original_hash;
+
+        loop {
+            let details = match get_details(current_hash, GetOptions::default())? {
+                Some(Details::Record(details)) => details,
+                Some(_) => {
+                    return Err(wasm_error!(WasmErrorInner::Guest(
+                        "Expected record details".into()
+                    )))
+                }
+                None => return Ok(None),
+            };
+
+            match details
+                .updates
+                .iter()
+                .max_by_key(|update| update.action().timestamp())
+            {
+                Some(latest) => current_hash = latest.action_address().clone(),
+                None => return Ok(Some(details.record)),
+            }
+        }
+    }
+
+    /// Get every version of an entry, oldest first
+    ///
+    /// Follows the updates recorded against each action, so both update
+    /// styles are supported (see `get_latest_record`).
+    pub fn get_all_versions(original_hash: ActionHash) -> ExternResult<Vec<Record>> {
+        let mut versions = Vec::new();
+        let mut pending = vec![original_hash];
+
+        while let Some(current_hash) = pending.pop() {
+            match get_details(current_hash, GetOptions::default())? {
+                Some(Details::Record(details)) => {
+                    pending.extend(
+                        details
+                            .updates
+                            .iter()
+                            .map(|update| update.action_address().clone()),
+                    );
+                    versions.push(details.record);
+                }
+                Some(_) => {
+                    return Err(wasm_error!(WasmErrorInner::Guest(
+                        "Expected record details".into()
+                    )))
+                }
+                None => {}
+            }
+        }
+
+        versions.sort_by_key(|record| record.action().timestamp());
+        Ok(versions)
+    }
+
+    /// Get the create action an entry's update chain started from
+    ///
+    /// Accepts the hash of any version, so callers can key links on the
+    /// original action no matter which version they were handed.
+    pub fn get_original_action_hash(action_hash: ActionHash) -> ExternResult<ActionHash> {
+        let mut current_hash = action_hash;
+
+        loop {
+            let record = get(current_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
+                WasmErrorInner::Guest("Record not found".into())
+            ))?;
+
+            match record.action() {
+                Action::Update(update) => current_hash = update.original_action_address.clone(),
+                _ => return Ok(current_hash),
+            }
+        }
+    }
+}
+
 /// Validation utilities
 pub mod validation {
     use super::*;
@@ -201,7 +301,7 @@ pub mod time {
     /// Get current timestamp as u64 microseconds
     ///
     /// Eliminates the repetitive pattern of:
-    /// ```rust
+    /// ```ignore
     /// sys_time()?.as_micros() as u64
     /// ```
     pub fn now_micros() -> ExternResult<u64> {
@@ -214,6 +314,119 @@ pub mod time {
     }
 }
 
+/// Money in any currency, as fixed-point minor units
+///
+/// Amounts are integers in the currency's smallest unit (cents for USD/EUR,
+/// yen for JPY, whatever a community token defines), so no floating point
+/// is involved. Currencies are ISO-4217 codes or custom currencies
+/// registered in the DNA properties.
+pub mod money {
+    use super::*;
+
+    /// Currency that MATL value weighting is expressed in
+    pub const REFERENCE_CURRENCY: &str = "USD";
+
+    /// Fixed-point scale of exchange rates (1.0 == RATE_SCALE)
+    pub const RATE_SCALE: u64 = 1_000_000_000;
+
+    /// An amount of money
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    pub struct Money {
+        /// Amount in minor units of `currency`
+        pub amount_minor: u64,
+
+        /// ISO-4217 or registered custom currency code
+        pub currency: String,
+    }
+
+    impl Money {
+        pub fn new(amount_minor: u64, currency: &str) -> Self {
+            Self {
+                amount_minor,
+                currency: currency.to_string(),
+            }
+        }
+
+        /// Money in the reference currency (USD cents)
+        pub fn reference(amount_minor: u64) -> Self {
+            Self::new(amount_minor, REFERENCE_CURRENCY)
+        }
+
+        /// Multiply by a quantity, None on overflow
+        pub fn times(&self, quantity: u32) -> Option<Money> {
+            self.amount_minor
+                .checked_mul(quantity as u64)
+                .map(|amount_minor| Money::new(amount_minor, &self.currency))
+        }
+
+        /// Add money of the same currency, None on overflow or mismatch
+        pub fn checked_add(&self, other: &Money) -> Option<Money> {
+            if self.currency != other.currency {
+                return None;
+            }
+            self.amount_minor
+                .checked_add(other.amount_minor)
+                .map(|amount_minor| Money::new(amount_minor, &self.currency))
+        }
+
+        /// Convert at `rate` target minor units per source minor unit,
+        /// scaled by `RATE_SCALE`, rounding down
+        pub fn convert(&self, rate: u64, to: &str) -> Option<Money> {
+            let amount = self.amount_minor as u128 * rate as u128 / RATE_SCALE as u128;
+            u64::try_from(amount).ok().map(|amount_minor| Money::new(amount_minor, to))
+        }
+    }
+
+    /// A custom currency registered in the DNA properties
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct CustomCurrency {
+        /// Currency code (3-8 uppercase letters or digits)
+        pub code: String,
+
+        /// Human-readable name
+        pub name: String,
+
+        /// Digits after the decimal point
+        pub decimals: u8,
+
+        /// Highest allowed price, in minor units
+        pub max_price_minor: u64,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, Default, SerializedBytes)]
+    struct CurrencyProperties {
+        #[serde(default)]
+        custom_currencies: Vec<CustomCurrency>,
+    }
+
+    /// Whether a code looks like a currency code
+    ///
+    /// ISO-4217 codes are three uppercase letters; custom codes may be up
+    /// to eight uppercase letters or digits.
+    pub fn is_valid_currency_code(code: &str) -> bool {
+        (3..=8).contains(&code.len())
+            && code
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+    }
+
+    /// Custom currencies registered in the DNA properties
+    pub fn registered_currencies() -> ExternResult<Vec<CustomCurrency>> {
+        let properties = dna_info()?.modifiers.properties;
+        Ok(CurrencyProperties::try_from(properties)
+            .unwrap_or_default()
+            .custom_currencies)
+    }
+
+    /// Find a registered custom currency by code
+    pub fn find_custom_currency<'a>(
+        currencies: &'a [CustomCurrency],
+        code: &str,
+    ) -> Option<&'a CustomCurrency> {
+        currencies.iter().find(|currency| currency.code == code)
+    }
+}
+
 /// Common result types and error enums
 pub mod types {
     use super::*;
@@ -252,6 +465,52 @@ mod tests {
     // Note: These are unit tests for non-HDK dependent functions
     // Integration tests would require a Holochain conductor
 
+    #[test]
+    fn test_money_arithmetic() {
+        use money::Money;
+
+        let price = Money::new(1999, "EUR");
+        assert_eq!(price.times(3), Some(Money::new(5997, "EUR")));
+        assert_eq!(Money::new(u64::MAX, "EUR").times(2), None);
+
+        // Different currencies never add up
+        assert_eq!(price.checked_add(&Money::new(1, "USD")), None);
+        assert_eq!(price.checked_add(&Money::new(1, "EUR")), Some(Money::new(2000, "EUR")));
+    }
+
+    #[test]
+    fn test_money_conversion_is_fixed_point() {
+        use money::{Money, RATE_SCALE};
+
+        // 1 EUR cent = 1.08 USD cents
+        let rate = 1_080_000_000;
+        assert_eq!(
+            Money::new(10_000, "EUR").convert(rate, "USD"),
+            Some(Money::reference(10_800))
+        );
+
+        // Rounds down
+        assert_eq!(Money::new(1, "EUR").convert(rate, "USD"), Some(Money::reference(1)));
+
+        // Identity rate
+        assert_eq!(
+            Money::new(1999, "USD").convert(RATE_SCALE, "USD"),
+            Some(Money::reference(1999))
+        );
+    }
+
+    #[test]
+    fn test_currency_codes() {
+        use money::is_valid_currency_code;
+
+        assert!(is_valid_currency_code("USD"));
+        assert!(is_valid_currency_code("MYC"));
+        assert!(is_valid_currency_code("MYCELIX1"));
+        assert!(!is_valid_currency_code("usd"));
+        assert!(!is_valid_currency_code("US"));
+        assert!(!is_valid_currency_code("TOOLONGCODE"));
+    }
+
     #[test]
     fn test_error_types() {
         use types::MError;
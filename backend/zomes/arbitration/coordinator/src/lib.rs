@@ -1,6 +1,11 @@
 use hdk::prelude::*;
 use arbitration_integrity::*;
-use mycelix_common::{error_handling, link_queries, remote_calls, time};
+use mycelix_common::{error_handling, link_queries, money, records, remote_calls, time, validation};
+
+/// Anchor for the opt-in arbitrator registry
+const ARBITRATOR_POOL_PATH: &str = "arbitrator_pool";
+
+const MICROS_PER_DAY: i64 = 24 * 60 * 60 * 1_000_000;
 
 /// File a dispute for a transaction
 ///
@@ -12,12 +17,7 @@ pub fn file_dispute(input: FileDisputeInput) -> ExternResult<DisputeOutput> {
     let filer = agent_info.agent_initial_pubkey.clone();
 
     // Get transaction details (from transactions zome)
-    // Use shared utility for remote calls
-    let transaction: TransactionInfo = remote_calls::call_zome(
-        "transactions",
-        "get_transaction",
-        input.transaction_hash.clone(),
-    )?;
+    let transaction = get_transaction_terms(input.transaction_hash.clone())?;
 
     // Verify filer is buyer or seller
     if filer != transaction.buyer && filer != transaction.seller {
@@ -36,6 +36,15 @@ pub fn file_dispute(input: FileDisputeInput) -> ExternResult<DisputeOutput> {
         evidence_cids: input.evidence_cids,
         status: DisputeStatus::Filed,
         arbitrators: Vec::new(), // Will be assigned in next step
+        panel_candidates: Vec::new(),
+        panel_seed: None,
+        commit_deadline: None,
+        reveal_deadline: None,
+        replacement_rounds: Vec::new(),
+        replacement_heads: Vec::new(),
+        appeal_hash: None,
+        original_panel: Vec::new(),
+        result_hash: None,
         created_at: time::now()?,
         updated_at: time::now()?,
     };
@@ -78,43 +87,59 @@ pub fn file_dispute(input: FileDisputeInput) -> ExternResult<DisputeOutput> {
     Ok(DisputeOutput {
         dispute_hash: action_hash,
         dispute: dispute_with_arbitrators,
+        result: None,
     })
 }
 
 /// Assign arbitrators to a dispute (internal helper)
 ///
-/// This selects agents with high MATL scores (>0.7) to vote.
+/// This draws 3-5 registered arbitrators with high MATL scores (>0.7),
+/// capped at the `max_arbitrators` DNA property.
 /// Excludes buyer, seller, and the filer.
+///
+/// Draw weights are MATL composites, which include arbitration
+/// reliability, so arbitrators with a good track record are favored.
+///
+/// The draw is seeded from the dispute hash and the filer's chain head,
+/// which becomes the `prev_action` of the dispute update, and the
+/// candidate snapshot (as of the seed) is stored on the dispute, so
+/// either party can recompute the panel (integrity validation does the
+/// same) or correct the snapshot (see `correct_panel_candidates`).
+///
+/// If the pool cannot yet supply a full panel the dispute stays `Filed`,
+/// and `assign_arbitrators` can be called again once more agents register.
 fn assign_arbitrators_internal(
     dispute_hash: ActionHash,
     mut dispute: Dispute,
 ) -> ExternResult<Dispute> {
-    // In a real implementation, this would:
-    // 1. Query the network for agents with MATL score > 0.7
-    // 2. Exclude buyer, seller, and filer
-    // 3. Select 3-5 arbitrators randomly from high-scoring agents
-    //
-    // For now, we'll create a placeholder implementation
-    // that would be filled in with actual network queries
-
-    // Placeholder: In production, query for high-MATL agents
-    let high_matl_agents: Vec<AgentPubKey> = Vec::new();
-
-    // Filter out parties to the dispute
-    let eligible_arbitrators: Vec<AgentPubKey> = high_matl_agents
-        .into_iter()
-        .filter(|agent| {
-            agent != &dispute.buyer && agent != &dispute.seller && agent != &dispute.filed_by
-        })
-        .take(3) // Select 3 arbitrators
-        .collect();
+    let properties = arbitration_properties()?;
+
+    // Nothing may be written between reading the seed and the update
+    let (seed_head, _, seed_time) = agent_info()?.chain_head;
+
+    let mut drawn = dispute.clone();
+    drawn.panel_candidates = get_arbitrator_candidates(seed_time)?;
+
+    let eligible_arbitrators = draw_arbitrators(
+        &dispute_hash,
+        &seed_head,
+        &panel_pool(&drawn),
+        properties.max_arbitrators as usize,
+    );
+
+    if eligible_arbitrators.len() < MIN_ARBITRATORS {
+        return Ok(dispute);
+    }
 
     dispute.arbitrators = eligible_arbitrators.clone();
+    dispute.panel_candidates = drawn.panel_candidates;
+    dispute.panel_seed = Some(seed_head);
     dispute.status = DisputeStatus::UnderReview;
     dispute.updated_at = time::now()?;
+    set_phase_deadlines(&mut dispute, &properties);
 
     // Update the dispute entry
-    update_entry(dispute_hash.clone(), &dispute)?;
+    update_dispute(&dispute_hash, &dispute)?;
 
     // Create links for arbitrators
     for arbitrator in eligible_arbitrators {
@@ -129,19 +154,402 @@ fn assign_arbitrators_internal(
     Ok(dispute)
 }
 
-/// Submit an arbitration vote
+/// Retry arbitrator assignment for a dispute still waiting on a panel
+///
+/// Only the filer can retry, since the draw is seeded from their chain.
+#[hdk_extern]
+pub fn assign_arbitrators(dispute_hash: ActionHash) -> ExternResult<DisputeOutput> {
+    let dispute: Dispute = get_entry_from_hash(dispute_hash.clone())?;
+
+    validation::verify_caller_is(&dispute.filed_by)?;
+
+    if dispute.status != DisputeStatus::Filed {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Cannot assign arbitrators to dispute with status {:?}",
+            dispute.status
+        ))));
+    }
+
+    let dispute = assign_arbitrators_internal(dispute_hash.clone(), dispute)?;
+
+    Ok(DisputeOutput {
+        dispute_hash,
+        dispute,
+        result: None,
+    })
+}
+
+/// Complete or refresh the candidate snapshot a panel was drawn from
+///
+/// Validation cannot list the registry, so a party who did not draw the
+/// panel checks the snapshot instead: this re-reads the registry as of
+/// the draw's seed, adds arbitrators the snapshot left out and swaps in
+/// later scores, then redraws the panel under the same seed. Only
+/// possible before any replacement and before the commit deadline.
+#[hdk_extern]
+pub fn correct_panel_candidates(dispute_hash: ActionHash) -> ExternResult<DisputeOutput> {
+    let (_, mut dispute) = get_dispute_version(dispute_hash.clone())?;
+
+    validation::verify_caller_is_one_of(&[dispute.buyer.clone(), dispute.seller.clone()])?;
+
+    if dispute.status != DisputeStatus::UnderReview
+        || !dispute.replacement_rounds.is_empty()
+        || deadline_passed(dispute.commit_deadline)?
+    {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Panel candidates can only be corrected before the panel starts voting".into()
+        )));
+    }
+
+    let seed_head = dispute.panel_seed.clone().ok_or(wasm_error!(WasmErrorInner::Guest(
+        "Dispute has no panel to correct".into()
+    )))?;
+    let seed_time = get(seed_head.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Panel seed not found".into())))?
+        .action()
+        .timestamp();
+
+    let corrected = corrected_candidates(&dispute.panel_candidates, get_arbitrator_candidates(seed_time)?);
+    if corrected == dispute.panel_candidates {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Panel candidate snapshot is already complete".into()
+        )));
+    }
+
+    let properties = arbitration_properties()?;
+    let previous_panel = dispute.arbitrators.clone();
+
+    dispute.panel_candidates = corrected;
+    dispute.arbitrators = draw_arbitrators(
+        &dispute_hash,
+        &seed_head,
+        &panel_pool(&dispute),
+        max_panel_size(&dispute, properties.max_arbitrators as usize),
+    );
+    dispute.updated_at = time::now()?;
+
+    update_dispute(&dispute_hash, &dispute)?;
+
+    for arbitrator in &dispute.arbitrators {
+        if !previous_panel.contains(arbitrator) {
+            create_link(
+                arbitrator.clone(),
+                dispute_hash.clone(),
+                LinkTypes::AgentToArbitrationOpportunities,
+                (),
+            )?;
+        }
+    }
+
+    Ok(DisputeOutput {
+        dispute_hash,
+        dispute,
+        result: None,
+    })
+}
+
+/// Merge a recorded candidate snapshot with a fresh read of the registry
+///
+/// Keeps every recorded candidate and their registration, takes the
+/// fresh score where it differs and adds candidates the snapshot missed.
+/// The result is ordered by agent, like the snapshot.
+pub fn corrected_candidates(
+    recorded: &[ArbitratorCandidate],
+    fresh: Vec<ArbitratorCandidate>,
+) -> Vec<ArbitratorCandidate> {
+    let mut corrected = recorded.to_vec();
+
+    for candidate in fresh {
+        match corrected.iter_mut().find(|c| c.agent == candidate.agent) {
+            Some(existing) if existing.registration_hash == candidate.registration_hash => {
+                existing.matl_score = candidate.matl_score;
+                existing.score_hash = candidate.score_hash;
+            }
+            Some(_) => {}
+            None => corrected.push(candidate),
+        }
+    }
+
+    corrected.sort_by(|a, b| a.agent.cmp(&b.agent));
+    corrected
+}
+
+/// Opt in to the arbitrator pool
+///
+/// Registered agents become candidates for future dispute panels
+/// as long as their MATL score stays above the arbitrator threshold.
+#[hdk_extern]
+pub fn register_as_arbitrator(_: ()) -> ExternResult<ArbitratorRegistrationOutput> {
+    let agent = agent_info()?.agent_initial_pubkey;
+
+    if get_registered_arbitrators()?.contains(&agent) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Already registered as an arbitrator".into()
+        )));
+    }
+
+    let registration = ArbitratorRegistration {
+        agent: agent.clone(),
+        registered_at: time::now()?,
+    };
+
+    let action_hash = create_entry(&EntryTypes::ArbitratorRegistration(registration.clone()))?;
+
+    create_link(
+        Path::from(ARBITRATOR_POOL_PATH).path_entry_hash()?,
+        action_hash.clone(),
+        LinkTypes::ArbitratorPool,
+        (),
+    )?;
+
+    create_link(
+        agent,
+        action_hash.clone(),
+        LinkTypes::AgentToArbitratorRegistration,
+        (),
+    )?;
+
+    Ok(ArbitratorRegistrationOutput {
+        registration_hash: action_hash,
+        registration,
+    })
+}
+
+/// Leave the arbitrator pool
+///
+/// Existing panel assignments are kept; the agent is only excluded
+/// from future draws.
+#[hdk_extern]
+pub fn unregister_as_arbitrator(_: ()) -> ExternResult<()> {
+    let agent = agent_info()?.agent_initial_pubkey;
+
+    let links = link_queries::get_links_local(
+        Path::from(ARBITRATOR_POOL_PATH).path_entry_hash()?,
+        LinkTypes::ArbitratorPool,
+    )?;
+
+    for link in links {
+        if link.author == agent {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Get all agents currently registered as arbitrators
+#[hdk_extern]
+pub fn get_arbitrator_pool(_: ()) -> ExternResult<Vec<AgentPubKey>> {
+    get_registered_arbitrators()
+}
+
+/// Submit evidence for a dispute
+///
+/// Either party can add a statement and supporting files while the
+/// dispute is `Filed` or `UnderReview`, so the responding party can
+/// answer the filer before arbitrators vote.
+#[hdk_extern]
+pub fn submit_dispute_evidence(
+    input: SubmitDisputeEvidenceInput,
+) -> ExternResult<DisputeEvidenceOutput> {
+    let dispute: Dispute = get_entry_from_hash(input.dispute_hash.clone())?;
+
+    // Verify caller is buyer or seller
+    let submitter = validation::verify_caller_is_one_of(&[
+        dispute.buyer.clone(),
+        dispute.seller.clone(),
+    ])?;
+
+    if dispute.status != DisputeStatus::Filed && dispute.status != DisputeStatus::UnderReview {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Cannot submit evidence for dispute with status {:?}",
+            dispute.status
+        ))));
+    }
+
+    let evidence = DisputeEvidence {
+        dispute_hash: input.dispute_hash.clone(),
+        submitted_by: submitter,
+        statement: input.statement,
+        evidence_cids: input.evidence_cids,
+        submitted_at: time::now()?,
+    };
+
+    let action_hash = create_entry(&EntryTypes::DisputeEvidence(evidence.clone()))?;
+
+    create_link(
+        input.dispute_hash,
+        action_hash.clone(),
+        LinkTypes::DisputeToEvidence,
+        (),
+    )?;
+
+    Ok(DisputeEvidenceOutput {
+        evidence_hash: action_hash,
+        evidence,
+    })
+}
+
+/// Get the chronological evidence record for a dispute
+///
+/// Starts with the filer's original reason and evidence, followed by
+/// every evidence submission from either party, oldest first.
+#[hdk_extern]
+pub fn get_dispute_timeline(dispute_hash: ActionHash) -> ExternResult<DisputeTimeline> {
+    let dispute: Dispute = get_entry_from_hash(dispute_hash.clone())?;
+
+    let mut evidence = Vec::new();
+    for link in link_queries::get_links_local(dispute_hash.clone(), LinkTypes::DisputeToEvidence)? {
+        if let Some(action_hash) = link.target.into_action_hash() {
+            if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
+                // Use shared utility for deserialization
+                evidence.push(DisputeEvidenceOutput {
+                    evidence_hash: action_hash,
+                    evidence: error_handling::deserialize_entry(&record)?,
+                });
+            }
+        }
+    }
+
+    Ok(DisputeTimeline {
+        dispute_hash: dispute_hash.clone(),
+        events: build_dispute_timeline(&dispute_hash, &dispute, evidence),
+    })
+}
+
+/// Merge the filing and evidence submissions into one ordered timeline
+pub fn build_dispute_timeline(
+    dispute_hash: &ActionHash,
+    dispute: &Dispute,
+    evidence: Vec<DisputeEvidenceOutput>,
+) -> Vec<DisputeTimelineEvent> {
+    let mut events = vec![DisputeTimelineEvent {
+        source_hash: dispute_hash.clone(),
+        kind: TimelineEventKind::Filing,
+        submitted_by: dispute.filed_by.clone(),
+        statement: dispute.reason.clone(),
+        evidence_cids: dispute.evidence_cids.clone(),
+        timestamp: dispute.created_at,
+    }];
+
+    events.extend(evidence.into_iter().map(|output| DisputeTimelineEvent {
+        source_hash: output.evidence_hash,
+        kind: TimelineEventKind::Evidence,
+        submitted_by: output.evidence.submitted_by,
+        statement: output.evidence.statement,
+        evidence_cids: output.evidence.evidence_cids,
+        timestamp: output.evidence.submitted_at,
+    }));
+
+    // Stable sort keeps the filing first on equal timestamps
+    events.sort_by_key(|event| event.timestamp);
+
+    events
+}
+
+/// Commit to an arbitration vote (phase 1 of 2)
+///
+/// Only a salted hash of the decision is published, so arbitrators
+/// cannot see earlier votes before casting their own. Keep the salt:
+/// it is needed to reveal the vote later.
+#[hdk_extern]
+pub fn commit_arbitration_vote(
+    input: CommitArbitrationVoteInput,
+) -> ExternResult<VoteCommitmentOutput> {
+    let agent_info = agent_info()?;
+    let arbitrator = agent_info.agent_initial_pubkey.clone();
+
+    // Get the dispute version the commitment is made against
+    let (dispute_version_hash, dispute) = get_dispute_version(input.dispute_hash.clone())?;
+
+    // Verify caller is an assigned arbitrator
+    if !dispute.arbitrators.contains(&arbitrator) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Not an assigned arbitrator for this dispute".into()
+        )));
+    }
+
+    // Commitments are only accepted during review, before the deadline
+    if dispute.status != DisputeStatus::UnderReview || commit_deadline_passed(&dispute)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Commit phase is closed for this dispute".into()
+        )));
+    }
+
+    let commitments = panel_commitments(
+        &dispute,
+        get_dispute_commitments(input.dispute_hash.clone())?,
+    );
+    if commitments.iter().any(|c| c.commitment.arbitrator == arbitrator) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Already committed a vote for this dispute".into()
+        )));
+    }
+
+    if !REFUND_PERCENT_STEPS.contains(&input.refund_percent) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Refund percent must be one of {:?}",
+            REFUND_PERCENT_STEPS
+        ))));
+    }
+
+    if input.salt.len() < MIN_COMMITMENT_SALT_LEN {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Salt must be at least {} characters",
+            MIN_COMMITMENT_SALT_LEN
+        ))));
+    }
+
+    let commitment = VoteCommitment {
+        dispute_hash: input.dispute_hash.clone(),
+        arbitrator: arbitrator.clone(),
+        dispute_version_hash,
+        commitment: compute_vote_commitment(
+            &input.dispute_hash,
+            &arbitrator,
+            input.refund_percent,
+            &input.salt,
+        )?,
+        committed_at: time::now()?,
+    };
+
+    let action_hash = create_entry(&EntryTypes::VoteCommitment(commitment.clone()))?;
+
+    create_link(
+        input.dispute_hash.clone(),
+        action_hash.clone(),
+        LinkTypes::DisputeToCommitments,
+        (),
+    )?;
+
+    // Once every arbitrator has committed, open the reveal phase
+    if commitments.len() + 1 == dispute.arbitrators.len() {
+        let mut updated_dispute = dispute;
+        updated_dispute.status = DisputeStatus::Revealing;
+        updated_dispute.updated_at = time::now()?;
+        update_dispute(&input.dispute_hash, &updated_dispute)?;
+    }
+
+    Ok(VoteCommitmentOutput {
+        commitment_hash: action_hash,
+        commitment,
+    })
+}
+
+/// Reveal an arbitration vote (phase 2 of 2)
 ///
-/// Arbitrators cast their vote with reasoning.
-/// Vote is weighted by the arbitrator's MATL score.
+/// Arbitrators reveal their decision and salt once all commitments are in,
+/// or once the commit deadline has passed. The reveal must hash to the
+/// published commitment. Vote is weighted by the arbitrator's MATL score.
 #[hdk_extern]
-pub fn submit_arbitration_vote(
-    input: SubmitArbitrationVoteInput,
+pub fn reveal_arbitration_vote(
+    input: RevealArbitrationVoteInput,
 ) -> ExternResult<ArbitrationVoteOutput> {
     let agent_info = agent_info()?;
     let arbitrator = agent_info.agent_initial_pubkey.clone();
 
-    // Get dispute
-    let dispute: Dispute = get_entry_from_hash(input.dispute_hash.clone())?;
+    // Get the dispute version the vote is revealed against
+    let (dispute_version_hash, dispute) = get_dispute_version(input.dispute_hash.clone())?;
 
     // Verify caller is an assigned arbitrator
     if !dispute.arbitrators.contains(&arbitrator) {
@@ -150,24 +558,53 @@ pub fn submit_arbitration_vote(
         )));
     }
 
+    // Reveals open once all commitments are in or the deadline has passed
+    if !reveal_open(&dispute, time::now()?) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Reveal phase has not started for this dispute".into()
+        )));
+    }
+
+    let commitments = panel_commitments(
+        &dispute,
+        get_dispute_commitments(input.dispute_hash.clone())?,
+    );
+    let own_commitment = commitments
+        .iter()
+        .find(|c| c.commitment.arbitrator == arbitrator)
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "No vote commitment found for this arbitrator".into()
+        )))?;
+
+    let votes = panel_votes(&dispute, get_dispute_votes(input.dispute_hash.clone())?);
+    if votes.iter().any(|v| v.vote.arbitrator == arbitrator) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Vote already revealed for this dispute".into()
+        )));
+    }
+
     // Get arbitrator's MATL score
-    // Use shared utility for remote calls
-    let matl_score: f64 = remote_calls::call_zome(
-        "reputation",
-        "get_agent_matl_score",
-        arbitrator.clone(),
-    )?;
+    let matl_score = get_matl_composite(arbitrator.clone())?.unwrap_or(0.0);
 
     // Create vote entry
     let vote = ArbitrationVote {
         dispute_hash: input.dispute_hash.clone(),
         arbitrator: arbitrator.clone(),
-        favor_buyer: input.favor_buyer,
+        dispute_version_hash,
+        commitment_hash: own_commitment.commitment_hash.clone(),
+        refund_percent: input.refund_percent,
+        salt: input.salt,
         reasoning: input.reasoning,
         arbitrator_matl_score: matl_score,
         voted_at: time::now()?,
     };
 
+    if !reveal_matches_commitment(&vote, &commitments)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Revealed vote does not match the commitment".into()
+        )));
+    }
+
     let action_hash = create_entry(&EntryTypes::ArbitrationVote(vote.clone()))?;
 
     // Create link from dispute to vote
@@ -178,15 +615,22 @@ pub fn submit_arbitration_vote(
         (),
     )?;
 
-    // Check if all arbitrators have voted
-    let all_votes = get_dispute_votes(input.dispute_hash.clone())?;
+    // Whole panel revealed -> Voting, otherwise make sure the dispute
+    // reflects that the reveal phase is open (stragglers are handled by
+    // `handle_arbitration_timeout`)
+    let next_status = if votes.len() + 1 == dispute.arbitrators.len() {
+        Some(DisputeStatus::Voting)
+    } else if dispute.status == DisputeStatus::UnderReview {
+        Some(DisputeStatus::Revealing)
+    } else {
+        None
+    };
 
-    if all_votes.len() == dispute.arbitrators.len() {
-        // All votes collected, update status to Voting
+    if let Some(status) = next_status {
         let mut updated_dispute = dispute;
-        updated_dispute.status = DisputeStatus::Voting;
+        updated_dispute.status = status;
         updated_dispute.updated_at = time::now()?;
-        update_entry(input.dispute_hash, &updated_dispute)?;
+        update_dispute(&input.dispute_hash, &updated_dispute)?;
     }
 
     Ok(ArbitrationVoteOutput {
@@ -197,15 +641,27 @@ pub fn submit_arbitration_vote(
 
 /// Finalize arbitration using MRC (Mutual Reputation Consensus)
 ///
-/// This implements the weighted voting algorithm:
-/// weighted_decision = Σ(vote * arbitrator_matl_score) / Σ(arbitrator_matl_scores)
+/// Each arbitrator votes a refund percentage (0/25/50/75/100).
+/// The outcome is the MATL-weighted median of those votes:
+/// - 100% refund: resolved for the buyer
+/// - 0% refund: resolved for the seller
+/// - anything in between: split outcome
+///
+/// Compensation is the transaction value scaled by the median refund.
 ///
-/// If weighted_decision > 0.66 (66% threshold), buyer wins.
-/// Otherwise, seller wins.
+/// For an appealed dispute the appeal panel's result is written as an
+/// update to the first result, and the appellant's reputation stake is
+/// slashed if the outcome did not move in their favor. No funds are held
+/// for the stake, so there is nothing to release or forfeit here: a
+/// slashed stake is a `ForfeitedStake` MATL failure weighted by its amount.
+///
+/// Only the parties and the panel can finalize.
 #[hdk_extern]
 pub fn finalize_arbitration(dispute_hash: ActionHash) -> ExternResult<ArbitrationResultOutput> {
-    // Get dispute
-    let dispute: Dispute = get_entry_from_hash(dispute_hash.clone())?;
+    // Get the voting version of the dispute the result decides
+    let (dispute_version_hash, dispute) = get_dispute_version(dispute_hash.clone())?;
+
+    verify_caller_is_participant(&dispute)?;
 
     // Verify status is Voting
     if dispute.status != DisputeStatus::Voting {
@@ -215,114 +671,140 @@ pub fn finalize_arbitration(dispute_hash: ActionHash) -> ExternResult<Arbitratio
         ))));
     }
 
-    // Get all votes
-    let votes = get_dispute_votes(dispute_hash.clone())?;
+    // Get all commitments and revealed votes from the current panel
+    let commitments = panel_commitments(&dispute, get_dispute_commitments(dispute_hash.clone())?);
+    let votes = panel_votes(&dispute, get_dispute_votes(dispute_hash.clone())?);
+
+    // Calculate weighted vote using MRC algorithm (verified reveals only)
+    let tally = calculate_weighted_vote(&votes, &commitments)?;
 
-    // Verify all arbitrators have voted
-    if votes.len() != dispute.arbitrators.len() {
+    // Verify every arbitrator has revealed, unless the reveal deadline
+    // has passed and a quorum of the panel has voted (validation checks
+    // the result the same way)
+    let counted = tally.vote_hashes.len();
+    let quorum_reached = deadline_passed(dispute.reveal_deadline)?
+        && has_quorum(counted, dispute.arbitrators.len());
+    if counted < dispute.arbitrators.len() && !quorum_reached {
         return Err(wasm_error!(WasmErrorInner::Guest(
-            "Not all arbitrators have voted yet".into()
+            "Not all arbitrators have revealed their votes yet".into()
         )));
     }
 
-    // Calculate weighted vote using MRC algorithm
-    let (weighted_vote, _total_weight) = calculate_weighted_vote(&votes)?;
-
-    // Determine winner (>0.66 = buyer wins)
-    const THRESHOLD: f64 = 0.66;
-    let buyer_wins = weighted_vote > THRESHOLD;
-
-    let (winner, loser, status) = if buyer_wins {
-        (
-            dispute.buyer.clone(),
-            dispute.seller.clone(),
-            DisputeStatus::ResolvedBuyer,
-        )
-    } else {
-        (
-            dispute.seller.clone(),
-            dispute.buyer.clone(),
-            DisputeStatus::ResolvedSeller,
-        )
-    };
+    let (winner, loser, status) = resolution(&dispute, tally.refund_percent);
 
     // Get transaction value for compensation calculation
-    let transaction: TransactionInfo = remote_calls::call_zome(
-        "transactions",
-        "get_transaction",
-        dispute.transaction_hash.clone(),
-    )?;
+    let transaction = get_transaction_terms(dispute.transaction_hash.clone())?;
 
-    // Calculate compensation based on transaction value and dispute outcome
-    // Compensation formula:
-    // - Base: transaction value
-    // - Strong consensus (>0.85 or <0.15): full compensation
-    // - Moderate consensus (0.66-0.85 or 0.15-0.34): 75% compensation
-    // - Weak consensus (near threshold): 50% compensation
-    let consensus_strength = if weighted_vote > 0.5 {
-        weighted_vote
-    } else {
-        1.0 - weighted_vote
-    };
+    // Compensation is the median refund share of the transaction total,
+    // in the transaction's currency
+    let compensation_cents = refund_amount_cents(
+        transaction.total_price_cents,
+        tally.refund_percent,
+    );
 
-    let compensation_multiplier = if consensus_strength >= 0.85 {
-        1.0  // Strong consensus: full compensation
-    } else if consensus_strength >= 0.75 {
-        0.75 // Moderate consensus: 75% compensation
-    } else {
-        0.50 // Weak consensus: 50% compensation
+    let outcome = match status {
+        DisputeStatus::ResolvedBuyer => "in favor of buyer",
+        DisputeStatus::ResolvedSeller => "in favor of seller",
+        _ => "as a split",
     };
 
-    let compensation_cents = Some(
-        (transaction.transaction_value_cents as f64 * compensation_multiplier) as u64
-    );
+    // Appeal: compare against the first result it supersedes
+    let appeal = match &dispute.appeal_hash {
+        Some(appeal_hash) => {
+            let appeal: Appeal = get_entry_from_hash(appeal_hash.clone())?;
+            let original: ArbitrationResult =
+                get_entry_from_hash(appeal.original_result_hash.clone())?;
+            let stake_slashed = !appeal_succeeded(
+                appeal.appellant == dispute.buyer,
+                original.refund_percent,
+                tally.refund_percent,
+            );
+            Some((appeal, original, stake_slashed))
+        }
+        None => None,
+    };
+    let stake_slashed = appeal.as_ref().is_some_and(|(_, _, slashed)| *slashed);
 
     // Create result entry
     let result = ArbitrationResult {
         dispute_hash: dispute_hash.clone(),
-        winner: winner.clone(),
-        loser: loser.clone(),
-        weighted_vote,
-        total_votes: votes.len() as u32,
-        compensation_cents,
+        dispute_version_hash,
+        winner,
+        loser,
+        weighted_vote: tally.weighted_vote,
+        refund_percent: tally.refund_percent,
+        total_votes: tally.vote_hashes.len() as u32,
+        vote_hashes: tally.vote_hashes.clone(),
+        compensation_cents: Some(compensation_cents),
+        currency: transaction.currency.clone(),
+        appeal_hash: dispute.appeal_hash.clone(),
+        stake_slashed,
         summary: format!(
-            "Resolved in favor of {} with weighted vote of {:.2}. Compensation: {} cents ({}% based on consensus strength)",
-            if buyer_wins { "buyer" } else { "seller" },
-            weighted_vote,
-            compensation_cents.unwrap_or(0),
-            (compensation_multiplier * 100.0) as u32
+            "Resolved {}{} with a weighted-median refund of {}% (mean {:.2}). Compensation: {} {} (minor units)",
+            outcome,
+            if appeal.is_some() { " on appeal" } else { "" },
+            tally.refund_percent,
+            tally.weighted_vote,
+            compensation_cents,
+            transaction.currency,
         ),
         finalized_at: time::now()?,
     };
 
-    let result_hash = create_entry(&EntryTypes::ArbitrationResult(result.clone()))?;
+    let result_hash = match &appeal {
+        // The appeal result supersedes the first result
+        Some((appeal, _, _)) => update_entry(appeal.original_result_hash.clone(), &result)?,
+        None => {
+            let result_hash = create_entry(&EntryTypes::ArbitrationResult(result.clone()))?;
+
+            // Create link from dispute to result
+            create_link(
+                dispute_hash.clone(),
+                result_hash.clone(),
+                LinkTypes::DisputeToResult,
+                (),
+            )?;
+
+            result_hash
+        }
+    };
 
-    // Create link from dispute to result
-    create_link(
-        dispute_hash.clone(),
-        result_hash.clone(),
-        LinkTypes::DisputeToResult,
-        (),
-    )?;
+    // Score every panel member against the outcome
+    record_arbitrator_outcomes(&dispute, &result_hash)?;
 
-    // Update dispute status
+    // Update dispute status, citing the result validation checks it against
     let mut updated_dispute = dispute;
     updated_dispute.status = status;
+    updated_dispute.result_hash = Some(result_hash.clone());
     updated_dispute.updated_at = time::now()?;
-    update_entry(dispute_hash, &updated_dispute)?;
+    update_dispute(&dispute_hash, &updated_dispute)?;
 
-    // Update MATL scores based on outcome
-    // Winner gets positive feedback, loser gets negative
-    // Use shared utility for remote calls
-    remote_calls::call_zome_void(
-        "reputation",
-        "update_matl_score",
-        UpdateMatlInput {
-            agent: loser,
-            successful: false,
-            transaction_value_cents: transaction.transaction_value_cents,
-        },
-    )?;
+    // Update MATL scores of both parties based on outcome
+    let outcomes = dispute_outcomes(
+        &result,
+        appeal.as_ref().map(|(appeal, original, _)| (appeal, original)),
+    );
+    for (agent, outcome) in outcomes {
+        // MATL weighs outcomes in the reference currency
+        let transaction_value_cents = match (&outcome, &appeal) {
+            (DisputeOutcome::ForfeitedStake, Some((appeal, _, _))) => {
+                transaction.reference_amount(appeal.reputation_stake_cents)
+            }
+            _ => transaction.matl_value_cents(),
+        };
+
+        // Use shared utility for remote calls
+        remote_calls::call_zome_void(
+            "reputation",
+            "record_dispute_outcome",
+            DisputeOutcomeInput {
+                agent,
+                outcome,
+                transaction_value_cents,
+                result_hash: result_hash.clone(),
+            },
+        )?;
+    }
 
     Ok(ArbitrationResultOutput {
         result_hash,
@@ -330,27 +812,450 @@ pub fn finalize_arbitration(dispute_hash: ActionHash) -> ExternResult<Arbitratio
     })
 }
 
-/// Get arbitration opportunities for the current user
+/// MATL updates a result triggers, as (agent, outcome)
 ///
-/// Returns disputes where the user is an assigned arbitrator
-/// and hasn't voted yet.
-#[hdk_extern]
-pub fn get_arbitration_opportunities(_: ()) -> ExternResult<DisputesResponse> {
-    let agent_info = agent_info()?;
-    let agent = agent_info.agent_initial_pubkey;
-
-    // Use shared utility for get_links
-    let links = link_queries::get_links_local(
-        agent.clone(),
-        LinkTypes::AgentToArbitrationOpportunities,
-    )?;
+/// The winner counts as a success and the loser as a failure (split
+/// outcomes have neither). On appeal, a party whose side did not change
+/// is not counted twice, and a failed appeal costs the appellant their
+/// reputation stake.
+pub fn dispute_outcomes(
+    result: &ArbitrationResult,
+    appeal: Option<(&Appeal, &ArbitrationResult)>,
+) -> Vec<(AgentPubKey, DisputeOutcome)> {
+    let original = appeal.map(|(_, original)| original);
+    let mut outcomes = Vec::new();
+
+    if let Some(winner) = &result.winner {
+        if original.and_then(|original| original.winner.as_ref()) != Some(winner) {
+            outcomes.push((winner.clone(), DisputeOutcome::Won));
+        }
+    }
 
-    let mut disputes = Vec::new();
+    if let Some(loser) = &result.loser {
+        if original.and_then(|original| original.loser.as_ref()) != Some(loser) {
+            outcomes.push((loser.clone(), DisputeOutcome::Lost));
+        }
+    }
+
+    if let Some((appeal, _)) = appeal {
+        if result.stake_slashed {
+            outcomes.push((appeal.appellant.clone(), DisputeOutcome::ForfeitedStake));
+        }
+    }
+
+    outcomes
+}
+
+/// Appeal an arbitration result
+///
+/// The losing party (either party after a split) may appeal once, within
+/// `appeal_window_days` of the result, by putting reputation worth at
+/// least `appeal_stake_percent` of the transaction value at stake (no
+/// funds are held; see `Appeal`). A larger panel is
+/// drawn from arbitrators above `APPEAL_MIN_ARBITRATOR_MATL`, excluding
+/// the first panel, and the dispute goes back under review.
+#[hdk_extern]
+pub fn file_appeal(input: FileAppealInput) -> ExternResult<DisputeOutput> {
+    let mut dispute: Dispute = get_entry_from_hash(input.dispute_hash.clone())?;
+
+    let appellant = validation::verify_caller_is_one_of(&[
+        dispute.buyer.clone(),
+        dispute.seller.clone(),
+    ])?;
+
+    if !matches!(
+        dispute.status,
+        DisputeStatus::ResolvedBuyer | DisputeStatus::ResolvedSeller | DisputeStatus::ResolvedSplit
+    ) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Cannot appeal dispute with status {:?}",
+            dispute.status
+        ))));
+    }
+
+    if dispute.appeal_hash.is_some() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Dispute has already been appealed".into()
+        )));
+    }
+
+    let result_output = get_dispute_result(input.dispute_hash.clone())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("Dispute has no result to appeal".into())
+    ))?;
+    let result = result_output.result;
+
+    // Winners have nothing to appeal
+    if let Some(loser) = &result.loser {
+        if loser != &appellant {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Only the losing party can appeal".into()
+            )));
+        }
+    }
+
+    let properties = arbitration_properties()?;
+    if deadline_passed(Some(appeal_deadline(&result, &properties)))? {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Appeal window of {} days has closed",
+            properties.appeal_window_days
+        ))));
+    }
+
+    let transaction = get_transaction_terms(dispute.transaction_hash.clone())?;
+
+    // The stake is valued in the transaction's currency
+    let minimum_stake = appeal_minimum_stake_cents(
+        transaction.total_price_cents,
+        properties.appeal_stake_percent,
+    );
+    if input.reputation_stake_cents < minimum_stake.max(1) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Appeal reputation stake must be at least {} {} (minor units)",
+            minimum_stake.max(1),
+            transaction.currency
+        ))));
+    }
+
+    let appeal = Appeal {
+        dispute_hash: input.dispute_hash.clone(),
+        original_result_hash: result_output.result_hash,
+        appellant,
+        reason: input.reason,
+        reputation_stake_cents: input.reputation_stake_cents,
+        filed_at: time::now()?,
+    };
+
+    let original_panel = dispute.arbitrators.clone();
+    let max_arbitrators = properties.max_arbitrators as usize + APPEAL_EXTRA_ARBITRATORS;
+
+    // Stand-in for the appeal version, to read its draw pool
+    let mut appealed = dispute.clone();
+    appealed.appeal_hash = Some(input.dispute_hash.clone());
+    appealed.original_panel = original_panel.clone();
+    appealed.panel_candidates = get_arbitrator_candidates(time::now()?)?;
+
+    // The appeal panel must be larger than the one it reviews. The draw
+    // itself is seeded after the appeal is written, so check the pool first.
+    if panel_pool(&appealed).len().min(max_arbitrators) <= original_panel.len() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Not enough higher-tier arbitrators available for an appeal panel".into()
+        )));
+    }
+
+    let appeal_hash = create_entry(&EntryTypes::Appeal(appeal))?;
+
+    create_link(
+        input.dispute_hash.clone(),
+        appeal_hash.clone(),
+        LinkTypes::DisputeToAppeal,
+        (),
+    )?;
+
+    // Nothing may be written between reading the seed and the update
+    let (seed_head, _, seed_time) = agent_info()?.chain_head;
+
+    dispute.appeal_hash = Some(appeal_hash);
+    dispute.original_panel = original_panel;
+    dispute.panel_candidates = get_arbitrator_candidates(seed_time)?;
+    dispute.arbitrators = draw_arbitrators(
+        &input.dispute_hash,
+        &seed_head,
+        &panel_pool(&dispute),
+        max_arbitrators,
+    );
+    dispute.panel_seed = Some(seed_head);
+    dispute.replacement_rounds = Vec::new();
+    dispute.replacement_heads = Vec::new();
+    dispute.status = DisputeStatus::UnderReview;
+    dispute.updated_at = time::now()?;
+    set_phase_deadlines(&mut dispute, &properties);
+
+    update_dispute(&input.dispute_hash, &dispute)?;
+
+    for arbitrator in &dispute.arbitrators {
+        create_link(
+            arbitrator.clone(),
+            input.dispute_hash.clone(),
+            LinkTypes::AgentToArbitrationOpportunities,
+            (),
+        )?;
+    }
+
+    Ok(DisputeOutput {
+        dispute_hash: input.dispute_hash,
+        dispute,
+        result: None,
+    })
+}
+
+/// Get the settlement a dispute's escrow should follow
+///
+/// Returns the refund owed to the buyer once the result is final:
+/// either an appeal result, or a first result whose appeal window has
+/// closed without an appeal. Called by the transactions zome to settle
+/// a disputed transaction's escrow.
+#[hdk_extern]
+pub fn get_dispute_settlement(dispute_hash: ActionHash) -> ExternResult<Option<DisputeSettlement>> {
+    let dispute: Dispute = get_entry_from_hash(dispute_hash.clone())?;
+
+    if !matches!(
+        dispute.status,
+        DisputeStatus::ResolvedBuyer | DisputeStatus::ResolvedSeller | DisputeStatus::ResolvedSplit
+    ) {
+        return Ok(None);
+    }
+
+    let result_output = match get_dispute_result(dispute_hash)? {
+        Some(result_output) => result_output,
+        None => return Ok(None),
+    };
+    let result = &result_output.result;
+
+    let is_final = match (&dispute.appeal_hash, &result.appeal_hash) {
+        // Appeal decided
+        (Some(_), Some(_)) => true,
+        // No appeal: final once the window has closed
+        (None, _) => deadline_passed(Some(appeal_deadline(result, &arbitration_properties()?)))?,
+        // Appeal pending
+        (Some(_), None) => false,
+    };
+
+    if !is_final {
+        return Ok(None);
+    }
+
+    // Escrow validation reads the compensation from the result it cites,
+    // so cite the deciding version (an appeal result updates the first)
+    let deciding_hash = match records::get_latest_record(result_output.result_hash.clone())? {
+        Some(record) => record.action_address().clone(),
+        None => result_output.result_hash.clone(),
+    };
+
+    Ok(Some(DisputeSettlement {
+        transaction_hash: dispute.transaction_hash,
+        result_hash: deciding_hash,
+        compensation_cents: result.compensation_cents.unwrap_or(0),
+    }))
+}
+
+/// End of the appeal window for a result
+fn appeal_deadline(result: &ArbitrationResult, properties: &ArbitrationProperties) -> Timestamp {
+    let window_micros = properties.appeal_window_days as i64 * MICROS_PER_DAY;
+    Timestamp::from_micros(result.finalized_at.as_micros() + window_micros)
+}
+
+/// Minimum appeal reputation stake for a transaction value
+pub fn appeal_minimum_stake_cents(transaction_value_cents: u64, stake_percent: u32) -> u64 {
+    (transaction_value_cents as u128 * stake_percent as u128 / 100) as u64
+}
+
+/// Handle a passed arbitration deadline
+///
+/// The parties or the panel can call this once a phase deadline has passed:
+/// - Commit deadline: arbitrators who never committed are replaced
+/// - Reveal deadline: with a quorum of revealed votes the dispute is
+///   finalized, otherwise arbitrators who did not reveal are replaced
+///
+/// Non-responsive arbitrators receive a MATL penalty once their
+/// replacement is recorded. Validation needs each one's chain to have
+/// moved past the missed deadline, so members not seen since then are
+/// kept for now; with nobody to replace at the commit deadline, the
+/// reveal phase opens without them.
+#[hdk_extern]
+pub fn handle_arbitration_timeout(
+    dispute_hash: ActionHash,
+) -> ExternResult<ArbitrationTimeoutOutput> {
+    let mut dispute: Dispute = get_entry_from_hash(dispute_hash.clone())?;
+
+    verify_caller_is_participant(&dispute)?;
+
+    if dispute.status != DisputeStatus::UnderReview && dispute.status != DisputeStatus::Revealing {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "No arbitration deadline applies to dispute with status {:?}",
+            dispute.status
+        ))));
+    }
+
+    let commitments = panel_commitments(&dispute, get_dispute_commitments(dispute_hash.clone())?);
+    let votes = panel_votes(&dispute, get_dispute_votes(dispute_hash.clone())?);
+
+    let non_responsive: Vec<AgentPubKey> = if deadline_passed(dispute.reveal_deadline)? {
+        let non_revealed: Vec<AgentPubKey> = dispute
+            .arbitrators
+            .iter()
+            .filter(|agent| !votes.iter().any(|v| &v.vote.arbitrator == *agent))
+            .cloned()
+            .collect();
+
+        // Enough votes are in: finalize without the stragglers
+        // (finalization records them as non-responsive)
+        if has_quorum(votes.len(), dispute.arbitrators.len()) {
+            dispute.status = DisputeStatus::Voting;
+            dispute.updated_at = time::now()?;
+            update_dispute(&dispute_hash, &dispute)?;
+
+            let result = finalize_arbitration(dispute_hash.clone())?;
+
+            return Ok(ArbitrationTimeoutOutput {
+                dispute: get_entry_from_hash(dispute_hash.clone())?,
+                dispute_hash,
+                replaced_arbitrators: Vec::new(),
+                result: Some(result),
+            });
+        }
+
+        non_revealed
+    } else if dispute.status == DisputeStatus::UnderReview
+        && deadline_passed(dispute.commit_deadline)?
+    {
+        dispute
+            .arbitrators
+            .iter()
+            .filter(|agent| !commitments.iter().any(|c| &c.commitment.arbitrator == *agent))
+            .cloned()
+            .collect()
+    } else {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "No arbitration deadline has passed yet".into()
+        )));
+    };
+
+    let missed_deadline = if deadline_passed(dispute.reveal_deadline)? {
+        dispute.reveal_deadline
+    } else {
+        dispute.commit_deadline
+    }
+    .unwrap_or(Timestamp::from_micros(0));
+
+    let mut replaced = Vec::new();
+    let mut heads = Vec::new();
+    for arbitrator in non_responsive {
+        if let Some(head) = chain_head_after(arbitrator.clone(), missed_deadline)? {
+            replaced.push(arbitrator);
+            heads.push(head);
+        }
+    }
+
+    if replaced.is_empty() {
+        if dispute.status != DisputeStatus::UnderReview || deadline_passed(dispute.reveal_deadline)? {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Non-responsive arbitrators have not been active since the deadline".into()
+            )));
+        }
+
+        dispute.status = DisputeStatus::Revealing;
+        dispute.updated_at = time::now()?;
+        update_dispute(&dispute_hash, &dispute)?;
+
+        return Ok(ArbitrationTimeoutOutput {
+            dispute_hash,
+            dispute,
+            replaced_arbitrators: Vec::new(),
+            result: None,
+        });
+    }
+
+    let dispute = replace_arbitrators(dispute_hash.clone(), dispute, replaced.clone(), heads)?;
+
+    Ok(ArbitrationTimeoutOutput {
+        dispute_hash,
+        dispute,
+        replaced_arbitrators: replaced,
+        result: None,
+    })
+}
+
+/// Replace non-responsive arbitrators (internal helper)
+///
+/// Records a replacement round on the dispute, with a chain head of each
+/// removed arbitrator written after the missed deadline, and redraws the
+/// panel from candidates who have not yet served, so validation can check
+/// the removals and replay the draw. Phase deadlines restart for the new
+/// panel.
+fn replace_arbitrators(
+    dispute_hash: ActionHash,
+    mut dispute: Dispute,
+    non_responsive: Vec<AgentPubKey>,
+    heads: Vec<ActionHash>,
+) -> ExternResult<Dispute> {
+    let properties = arbitration_properties()?;
+
+    let seed_head = dispute.panel_seed.clone().ok_or(wasm_error!(WasmErrorInner::Guest(
+        "Dispute has no panel to replace".into()
+    )))?;
+
+    let previous_panel = dispute.arbitrators.clone();
+    dispute.replacement_rounds.push(non_responsive.clone());
+    dispute.replacement_heads.push(heads);
+    dispute.arbitrators = expected_panel(
+        &dispute_hash,
+        &seed_head,
+        &panel_pool(&dispute),
+        max_panel_size(&dispute, properties.max_arbitrators as usize),
+        &dispute.replacement_rounds,
+    );
+
+    if dispute.arbitrators.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Arbitrator pool exhausted: no replacements available".into()
+        )));
+    }
+
+    // Remaining members may all have committed already (pool exhausted)
+    let commitments = panel_commitments(&dispute, get_dispute_commitments(dispute_hash.clone())?);
+    dispute.status = if commitments.len() == dispute.arbitrators.len() {
+        DisputeStatus::Revealing
+    } else {
+        DisputeStatus::UnderReview
+    };
+    dispute.updated_at = time::now()?;
+    set_phase_deadlines(&mut dispute, &properties);
+
+    // Validation checks the replacement against the missed deadline
+    let replacement_hash = update_dispute(&dispute_hash, &dispute)?;
+
+    penalize_non_responsive(&non_responsive, &replacement_hash)?;
+
+    for arbitrator in &dispute.arbitrators {
+        if !previous_panel.contains(arbitrator) {
+            create_link(
+                arbitrator.clone(),
+                dispute_hash.clone(),
+                LinkTypes::AgentToArbitrationOpportunities,
+                (),
+            )?;
+        }
+    }
+
+    Ok(dispute)
+}
+
+/// Get arbitration opportunities for the current user
+///
+/// Returns disputes where the user is an assigned arbitrator
+/// and hasn't voted yet.
+#[hdk_extern]
+pub fn get_arbitration_opportunities(_: ()) -> ExternResult<DisputesResponse> {
+    let agent_info = agent_info()?;
+    let agent = agent_info.agent_initial_pubkey;
+
+    // Use shared utility for get_links
+    let links = link_queries::get_links_local(
+        agent.clone(),
+        LinkTypes::AgentToArbitrationOpportunities,
+    )?;
+
+    let mut disputes = Vec::new();
 
     for link in links {
         if let Some(action_hash) = link.target.into_action_hash() {
             let dispute: Dispute = get_entry_from_hash(action_hash.clone())?;
 
+            // Skip disputes the user was replaced on
+            if !dispute.arbitrators.contains(&agent) {
+                continue;
+            }
+
             // Check if user has already voted
             let votes = get_dispute_votes(action_hash.clone())?;
             let already_voted = votes.iter().any(|v| v.vote.arbitrator == agent);
@@ -359,6 +1264,7 @@ pub fn get_arbitration_opportunities(_: ()) -> ExternResult<DisputesResponse> {
                 disputes.push(DisputeOutput {
                     dispute_hash: action_hash,
                     dispute,
+                    result: None,
                 });
             }
         }
@@ -368,16 +1274,22 @@ pub fn get_arbitration_opportunities(_: ()) -> ExternResult<DisputesResponse> {
 }
 
 /// Get a dispute by hash
+///
+/// Returns the latest version of the dispute and its current result.
+/// An appeal result supersedes the first result, so the result's update
+/// chain is followed to its latest version.
 #[hdk_extern]
 pub fn get_dispute(dispute_hash: ActionHash) -> ExternResult<Option<DisputeOutput>> {
-    match get(dispute_hash.clone(), GetOptions::default())? {
+    match records::get_latest_record(dispute_hash.clone())? {
         Some(record) => {
             // Use shared utility for deserialization
             let dispute: Dispute = error_handling::deserialize_entry(&record)?;
+            let result = get_dispute_result(dispute_hash.clone())?;
 
             Ok(Some(DisputeOutput {
                 dispute_hash,
                 dispute,
+                result,
             }))
         }
         None => Ok(None),
@@ -386,36 +1298,219 @@ pub fn get_dispute(dispute_hash: ActionHash) -> ExternResult<Option<DisputeOutpu
 
 // ===== Helper Functions =====
 
+/// Outcome of counting a dispute's votes
+struct VoteTally {
+    /// Weighted mean refund fraction (0.0-1.0)
+    weighted_vote: f64,
+    /// Weighted-median refund percentage
+    refund_percent: u8,
+    /// Verified votes counted
+    vote_hashes: Vec<ActionHash>,
+}
+
 /// Calculate weighted vote using MRC algorithm
 ///
-/// Formula: Σ(vote * matl_score) / Σ(matl_scores)
-/// Returns (weighted_vote, total_weight)
+/// Mean: Σ(refund * matl_score) / Σ(matl_scores)
+/// Median: see `weighted_median_refund`
+/// Only reveals that hash to their arbitrator's commitment are counted,
+/// and each arbitrator is counted once.
 fn calculate_weighted_vote(
     votes: &[ArbitrationVoteOutput],
-) -> ExternResult<(f64, f64)> {
-    let mut weighted_sum = 0.0;
-    let mut total_weight = 0.0;
+    commitments: &[VoteCommitmentOutput],
+) -> ExternResult<VoteTally> {
+    let mut counted: Vec<AgentPubKey> = Vec::new();
+    let mut vote_hashes: Vec<ActionHash> = Vec::new();
+    let mut weighted_refunds: Vec<(u8, f64)> = Vec::new();
 
     for vote_output in votes {
-        let vote_value = if vote_output.vote.favor_buyer {
-            1.0
-        } else {
-            0.0
-        };
+        if counted.contains(&vote_output.vote.arbitrator)
+            || !reveal_matches_commitment(&vote_output.vote, commitments)?
+        {
+            continue;
+        }
+        counted.push(vote_output.vote.arbitrator.clone());
+        vote_hashes.push(vote_output.vote_hash.clone());
 
-        weighted_sum += vote_value * vote_output.vote.arbitrator_matl_score;
-        total_weight += vote_output.vote.arbitrator_matl_score;
+        weighted_refunds.push((
+            vote_output.vote.refund_percent,
+            vote_output.vote.arbitrator_matl_score,
+        ));
     }
 
-    if total_weight == 0.0 {
-        return Err(wasm_error!(WasmErrorInner::Guest(
-            "No valid votes (total weight is zero)".into()
-        )));
+    let (weighted_vote, refund_percent) = tally_refunds(&weighted_refunds).ok_or(wasm_error!(
+        WasmErrorInner::Guest("No valid votes (total weight is zero)".into())
+    ))?;
+
+    Ok(VoteTally {
+        weighted_vote,
+        refund_percent,
+        vote_hashes,
+    })
+}
+
+/// Refund owed for a transaction value and refund percentage
+pub fn refund_amount_cents(transaction_value_cents: u64, refund_percent: u8) -> u64 {
+    (transaction_value_cents as u128 * refund_percent as u128 / 100) as u64
+}
+
+/// Check a revealed vote against the commitment it references
+fn reveal_matches_commitment(
+    vote: &ArbitrationVote,
+    commitments: &[VoteCommitmentOutput],
+) -> ExternResult<bool> {
+    let commitment = match commitments
+        .iter()
+        .find(|c| c.commitment_hash == vote.commitment_hash)
+    {
+        Some(output) => &output.commitment,
+        None => return Ok(false),
+    };
+
+    if commitment.arbitrator != vote.arbitrator || commitment.dispute_hash != vote.dispute_hash {
+        return Ok(false);
+    }
+
+    let expected = compute_vote_commitment(
+        &vote.dispute_hash,
+        &vote.arbitrator,
+        vote.refund_percent,
+        &vote.salt,
+    )?;
+
+    Ok(expected == commitment.commitment)
+}
+
+/// Whether the commit phase deadline for a dispute has passed
+fn commit_deadline_passed(dispute: &Dispute) -> ExternResult<bool> {
+    deadline_passed(dispute.commit_deadline)
+}
+
+/// Whether an optional deadline has passed
+fn deadline_passed(deadline: Option<Timestamp>) -> ExternResult<bool> {
+    match deadline {
+        Some(deadline) => Ok(time::now()? > deadline),
+        None => Ok(false),
     }
+}
+
+/// Set commit and reveal deadlines from `arbitration_timeout_days`
+///
+/// The commit phase gets the first half of the timeout, the reveal phase
+/// runs until the full timeout.
+fn set_phase_deadlines(dispute: &mut Dispute, properties: &ArbitrationProperties) {
+    let timeout_micros = properties.arbitration_timeout_days as i64 * MICROS_PER_DAY;
+    let start = dispute.updated_at.as_micros();
+
+    dispute.commit_deadline = Some(Timestamp::from_micros(start + timeout_micros / 2));
+    dispute.reveal_deadline = Some(Timestamp::from_micros(start + timeout_micros));
+}
 
-    let weighted_vote = weighted_sum / total_weight;
+/// Keep only commitments from the dispute's current panel
+fn panel_commitments(
+    dispute: &Dispute,
+    commitments: Vec<VoteCommitmentOutput>,
+) -> Vec<VoteCommitmentOutput> {
+    commitments
+        .into_iter()
+        .filter(|c| dispute.arbitrators.contains(&c.commitment.arbitrator))
+        .collect()
+}
 
-    Ok((weighted_vote, total_weight))
+/// Keep only votes from the dispute's current panel
+fn panel_votes(dispute: &Dispute, votes: Vec<ArbitrationVoteOutput>) -> Vec<ArbitrationVoteOutput> {
+    votes
+        .into_iter()
+        .filter(|v| dispute.arbitrators.contains(&v.vote.arbitrator))
+        .collect()
+}
+
+/// Apply a MATL penalty to arbitrators replaced for missing a deadline
+///
+/// The reputation zome checks the replacement round on the dispute update.
+fn penalize_non_responsive(
+    arbitrators: &[AgentPubKey],
+    replacement_hash: &ActionHash,
+) -> ExternResult<()> {
+    for arbitrator in arbitrators {
+        // Use shared utility for remote calls
+        remote_calls::call_zome_void(
+            "reputation",
+            "record_arbitration_outcome",
+            ArbitrationOutcomeInput {
+                arbitrator: arbitrator.clone(),
+                source: ArbitrationOutcomeSource::Replacement(replacement_hash.clone()),
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Get an agent's chain head, if written after `deadline`
+///
+/// Replacement validation walks the arbitrator's chain back from this
+/// action to show they never responded. Returns None for agents not seen
+/// since the deadline.
+fn chain_head_after(agent: AgentPubKey, deadline: Timestamp) -> ExternResult<Option<ActionHash>> {
+    let activity = get_agent_activity(agent, ChainQueryFilter::new(), ActivityRequest::Status)?;
+    let Some(highest) = activity.highest_observed else {
+        return Ok(None);
+    };
+
+    for head in highest.hash {
+        if let Some(record) = get(head.clone(), GetOptions::default())? {
+            if record.action().timestamp() > deadline {
+                return Ok(Some(head));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Feed a finalized panel into each arbitrator's reliability record
+///
+/// The reputation zome scores each member from the result's votes: on
+/// agreement with the final refund and on revealing before the deadline,
+/// and members without a counted vote as non-responsive.
+fn record_arbitrator_outcomes(dispute: &Dispute, result_hash: &ActionHash) -> ExternResult<()> {
+    for arbitrator in &dispute.arbitrators {
+        // Use shared utility for remote calls
+        remote_calls::call_zome_void(
+            "reputation",
+            "record_arbitration_outcome",
+            ArbitrationOutcomeInput {
+                arbitrator: arbitrator.clone(),
+                source: ArbitrationOutcomeSource::Result(result_hash.clone()),
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Get all vote commitments for a dispute
+fn get_dispute_commitments(dispute_hash: ActionHash) -> ExternResult<Vec<VoteCommitmentOutput>> {
+    // Use shared utility for get_links
+    let links = link_queries::get_links_local(dispute_hash, LinkTypes::DisputeToCommitments)?;
+
+    let mut commitments = Vec::new();
+
+    for link in links {
+        if let Some(action_hash) = link.target.into_action_hash() {
+            if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
+                // Use shared utility for deserialization
+                let commitment: VoteCommitment = error_handling::deserialize_entry(&record)?;
+
+                commitments.push(VoteCommitmentOutput {
+                    commitment_hash: action_hash,
+                    commitment,
+                });
+            }
+        }
+    }
+
+    Ok(commitments)
 }
 
 /// Get all votes for a dispute
@@ -442,11 +1537,191 @@ fn get_dispute_votes(dispute_hash: ActionHash) -> ExternResult<Vec<ArbitrationVo
     Ok(votes)
 }
 
-/// Get entry from action hash (helper)
+/// Get the current result of a dispute, following appeal updates
+fn get_dispute_result(dispute_hash: ActionHash) -> ExternResult<Option<ArbitrationResultOutput>> {
+    let links = link_queries::get_links_local(dispute_hash, LinkTypes::DisputeToResult)?;
+
+    let original_hash = match links.into_iter().find_map(|link| link.target.into_action_hash()) {
+        Some(action_hash) => action_hash,
+        None => return Ok(None),
+    };
+
+    match records::get_latest_record(original_hash.clone())? {
+        Some(record) => Ok(Some(ArbitrationResultOutput {
+            result_hash: original_hash,
+            // Use shared utility for deserialization
+            result: error_handling::deserialize_entry(&record)?,
+        })),
+        None => Ok(None),
+    }
+}
+
+/// Get all agents registered in the arbitrator pool
+fn get_registered_arbitrators() -> ExternResult<Vec<AgentPubKey>> {
+    let registrations: Vec<ArbitratorRegistration> = link_queries::get_linked_entries(
+        Path::from(ARBITRATOR_POOL_PATH).path_entry_hash()?,
+        LinkTypes::ArbitratorPool,
+    )?;
+
+    let mut agents: Vec<AgentPubKey> = Vec::new();
+    for registration in registrations {
+        if !agents.contains(&registration.agent) {
+            agents.push(registration.agent);
+        }
+    }
+
+    Ok(agents)
+}
+
+/// Get every registered arbitrator with a MATL score as a panel candidate
+///
+/// The snapshot is taken as of `as_of` (the draw's seed): registrations
+/// written later are left out, and each candidate's score is the latest
+/// version written by then. Each candidate cites their registration and
+/// score entries, which validation checks the draw against. Agents
+/// without a score yet are skipped. Candidates are ordered by agent.
+fn get_arbitrator_candidates(as_of: Timestamp) -> ExternResult<Vec<ArbitratorCandidate>> {
+    let links = link_queries::get_links_network(
+        Path::from(ARBITRATOR_POOL_PATH).path_entry_hash()?,
+        LinkTypes::ArbitratorPool,
+    )?;
+
+    let mut candidates: Vec<ArbitratorCandidate> = Vec::new();
+    for link in links {
+        let Some(registration_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(registration_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        if record.action().timestamp() > as_of {
+            continue;
+        }
+        let registration: ArbitratorRegistration = error_handling::deserialize_entry(&record)?;
+
+        if candidates.iter().any(|c| c.agent == registration.agent) {
+            continue;
+        }
+
+        if let Some((score_hash, score)) = get_matl_score_as_of(registration.agent.clone(), as_of)? {
+            candidates.push(ArbitratorCandidate {
+                agent: registration.agent,
+                matl_score: score.composite,
+                registration_hash,
+                score_hash,
+            });
+        }
+    }
+
+    candidates.sort_by(|a, b| a.agent.cmp(&b.agent));
+    Ok(candidates)
+}
+
+/// Get the latest MATL score an agent had written by `as_of`
+///
+/// Walks back from the current score through the versions it was
+/// computed from.
+fn get_matl_score_as_of(
+    agent: AgentPubKey,
+    as_of: Timestamp,
+) -> ExternResult<Option<(ActionHash, MatlScoreTerms)>> {
+    let Some(latest) = get_matl_score(agent)? else {
+        return Ok(None);
+    };
+
+    let mut score_hash = latest.score_hash;
+    loop {
+        let Some(record) = get(score_hash.clone(), GetOptions::default())? else {
+            return Ok(None);
+        };
+        let score: MatlScoreTerms = error_handling::deserialize_entry(&record)?;
+
+        if record.action().timestamp() <= as_of {
+            return Ok(Some((score_hash, score)));
+        }
+
+        match score.previous_score {
+            Some(previous) => score_hash = previous,
+            None => return Ok(None),
+        }
+    }
+}
+
+/// The part of `reputation_integrity::MatlScore` panel selection reads
+///
+/// Decoded from the reputation zome's reply rather than linking its
+/// integrity zome, whose exported entry defs would clash with ours.
+#[derive(Serialize, Deserialize, Debug)]
+struct MatlComposite {
+    composite: f64,
+}
+
+/// A MATL score with the action hash it was read from
+#[derive(Serialize, Deserialize, Debug)]
+struct MatlScoreRecord {
+    score_hash: ActionHash,
+    score: MatlComposite,
+}
+
+/// Get an agent's latest MATL score from the reputation zome
+///
+/// Returns None for agents that have no score yet.
+fn get_matl_score(agent: AgentPubKey) -> ExternResult<Option<MatlScoreRecord>> {
+    // Use shared utility for remote calls
+    remote_calls::call_zome("reputation", "get_agent_matl_score_record", agent)
+}
+
+/// Get an agent's MATL composite score, None for agents without a score
+fn get_matl_composite(agent: AgentPubKey) -> ExternResult<Option<f64>> {
+    Ok(get_matl_score(agent)?.map(|score| score.score.composite))
+}
+
+/// Get the terms of a transaction from the transactions zome
+fn get_transaction_terms(transaction_hash: ActionHash) -> ExternResult<TransactionTerms> {
+    // Use shared utility for remote calls
+    let output: Option<TransactionInfo> =
+        remote_calls::call_zome("transactions", "get_transaction", transaction_hash)?;
+
+    output
+        .map(|output| output.transaction)
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Transaction not found".into())))
+}
+
+/// Verify the caller is a party to the dispute or sits on its panel
+fn verify_caller_is_participant(dispute: &Dispute) -> ExternResult<AgentPubKey> {
+    let mut participants = vec![dispute.buyer.clone(), dispute.seller.clone()];
+    participants.extend(dispute.arbitrators.iter().cloned());
+
+    validation::verify_caller_is_one_of(&participants)
+}
+
+/// Get the latest version of a dispute with its action hash
+fn get_dispute_version(dispute_hash: ActionHash) -> ExternResult<(ActionHash, Dispute)> {
+    let record = records::get_latest_record(dispute_hash)?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Dispute not found".into())))?;
+
+    Ok((
+        record.action_address().clone(),
+        error_handling::deserialize_entry(&record)?,
+    ))
+}
+
+/// Write a new dispute version on top of the latest one
+///
+/// Updates are chained so validation can check each one against the
+/// version it replaces.
+fn update_dispute(dispute_hash: &ActionHash, dispute: &Dispute) -> ExternResult<ActionHash> {
+    let latest = records::get_latest_record(dispute_hash.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Dispute not found".into())))?;
+
+    update_entry(latest.action_address().clone(), dispute)
+}
+
+/// Get the latest version of an entry from its original action hash (helper)
 fn get_entry_from_hash<T: TryFrom<SerializedBytes, Error = SerializedBytesError>>(
     hash: ActionHash,
 ) -> ExternResult<T> {
-    let record = get(hash, GetOptions::default())?
+    let record = records::get_latest_record(hash)?
         .ok_or(wasm_error!(WasmErrorInner::Guest("Entry not found".into())))?;
 
     // Use shared utility for deserialization
@@ -466,6 +1741,8 @@ pub struct FileDisputeInput {
 pub struct DisputeOutput {
     pub dispute_hash: ActionHash,
     pub dispute: Dispute,
+    /// Current result (populated by `get_dispute`)
+    pub result: Option<ArbitrationResultOutput>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -474,9 +1751,61 @@ pub struct DisputesResponse {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct SubmitArbitrationVoteInput {
+pub struct SubmitDisputeEvidenceInput {
     pub dispute_hash: ActionHash,
-    pub favor_buyer: bool,
+    pub statement: String,
+    pub evidence_cids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DisputeEvidenceOutput {
+    pub evidence_hash: ActionHash,
+    pub evidence: DisputeEvidence,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum TimelineEventKind {
+    /// The original dispute filing
+    Filing,
+    /// A later evidence submission
+    Evidence,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DisputeTimelineEvent {
+    /// Dispute hash for the filing, evidence hash otherwise
+    pub source_hash: ActionHash,
+    pub kind: TimelineEventKind,
+    pub submitted_by: AgentPubKey,
+    pub statement: String,
+    pub evidence_cids: Vec<String>,
+    pub timestamp: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DisputeTimeline {
+    pub dispute_hash: ActionHash,
+    pub events: Vec<DisputeTimelineEvent>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommitArbitrationVoteInput {
+    pub dispute_hash: ActionHash,
+    pub refund_percent: u8,
+    pub salt: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VoteCommitmentOutput {
+    pub commitment_hash: ActionHash,
+    pub commitment: VoteCommitment,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RevealArbitrationVoteInput {
+    pub dispute_hash: ActionHash,
+    pub refund_percent: u8,
+    pub salt: String,
     pub reasoning: String,
 }
 
@@ -492,18 +1821,113 @@ pub struct ArbitrationResultOutput {
     pub result: ArbitrationResult,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArbitratorRegistrationOutput {
+    pub registration_hash: ActionHash,
+    pub registration: ArbitratorRegistration,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileAppealInput {
+    pub dispute_hash: ActionHash,
+    pub reason: String,
+    /// Transaction value the appellant's MATL is risked against
+    #[serde(alias = "stake_cents")]
+    pub reputation_stake_cents: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DisputeSettlement {
+    pub transaction_hash: ActionHash,
+    pub result_hash: ActionHash,
+    pub compensation_cents: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArbitrationTimeoutOutput {
+    pub dispute_hash: ActionHash,
+    pub dispute: Dispute,
+    pub replaced_arbitrators: Vec<AgentPubKey>,
+    pub result: Option<ArbitrationResultOutput>,
+}
+
+/// The parts of the transactions zome's `TransactionOutput` read here
+///
+/// Decoded from the reply rather than linking the transactions integrity
+/// zome (see `MatlComposite`).
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TransactionInfo {
+    pub transaction_hash: ActionHash,
+    pub transaction: TransactionTerms,
+}
+
+/// The fields of `transactions_integrity::Transaction` read here
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionTerms {
     pub buyer: AgentPubKey,
     pub seller: AgentPubKey,
-    pub transaction_value_cents: u64,
+    /// Total in minor units of `currency`
+    pub total_price_cents: u64,
+    #[serde(default = "default_currency")]
+    pub currency: String,
+    /// Total in the reference currency, None when priced in it
+    #[serde(default)]
+    pub reference_value_cents: Option<u64>,
+}
+
+fn default_currency() -> String {
+    money::REFERENCE_CURRENCY.to_string()
+}
+
+impl TransactionTerms {
+    /// Value MATL updates are weighted by (reference-currency cents)
+    pub fn matl_value_cents(&self) -> u64 {
+        self.reference_value_cents.unwrap_or(self.total_price_cents)
+    }
+
+    /// Convert an amount in the transaction's currency to the reference
+    /// currency, at the rate the transaction was valued at
+    pub fn reference_amount(&self, amount_minor: u64) -> u64 {
+        match self.reference_value_cents {
+            Some(reference) if self.total_price_cents > 0 => {
+                (amount_minor as u128 * reference as u128 / self.total_price_cents as u128) as u64
+            }
+            _ => amount_minor,
+        }
+    }
 }
 
+/// How a dispute went for an agent, as reported to the reputation zome
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeOutcome {
+    Won,
+    Lost,
+    ForfeitedStake,
+}
+
+/// Outcome reported to the reputation zome, backed by the arbitration result
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct UpdateMatlInput {
+pub struct DisputeOutcomeInput {
     pub agent: AgentPubKey,
-    pub successful: bool,
+    pub outcome: DisputeOutcome,
     pub transaction_value_cents: u64,
+    pub result_hash: ActionHash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArbitrationOutcomeInput {
+    pub arbitrator: AgentPubKey,
+    pub source: ArbitrationOutcomeSource,
+}
+
+/// What proves an arbitrator's panel seat (see the reputation zome)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ArbitrationOutcomeSource {
+    /// `ArbitrationResult` decided by the arbitrator's panel
+    Result(ActionHash),
+    /// Dispute update that replaced the arbitrator as non-responsive
+    Replacement(ActionHash),
 }
 
 
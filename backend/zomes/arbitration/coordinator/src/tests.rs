@@ -1,7 +1,8 @@
+use super::*;
+
 #[cfg(test)]
-mod tests {
+mod arbitration_tests {
     use super::*;
-    use arbitration_integrity::*;
 
     // Helper functions for tests
     fn mock_dispute() -> Dispute {
@@ -14,16 +15,28 @@ mod tests {
             evidence_cids: vec!["QmEvidence123456789012345678901234567890".to_string()],
             status: DisputeStatus::Filed,
             arbitrators: vec![],
+            panel_candidates: vec![],
+            panel_seed: None,
+            commit_deadline: None,
+            reveal_deadline: None,
+            replacement_rounds: vec![],
+            replacement_heads: vec![],
+            appeal_hash: None,
+            original_panel: vec![],
+            result_hash: None,
             created_at: Timestamp::from_micros(1000000),
             updated_at: Timestamp::from_micros(1000000),
         }
     }
 
-    fn mock_arbitration_vote(favor_buyer: bool) -> ArbitrationVote {
+    fn mock_arbitration_vote(refund_percent: u8) -> ArbitrationVote {
         ArbitrationVote {
             dispute_hash: ActionHash::from_raw_36(vec![1u8; 36]),
             arbitrator: AgentPubKey::from_raw_36(vec![4u8; 36]),
-            favor_buyer,
+            dispute_version_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+            commitment_hash: ActionHash::from_raw_36(vec![4u8; 36]),
+            refund_percent,
+            salt: "0123456789abcdef".to_string(),
             reasoning: "Based on evidence provided...".to_string(),
             arbitrator_matl_score: 0.85,
             voted_at: Timestamp::from_micros(1000000),
@@ -34,16 +47,18 @@ mod tests {
 
     #[test]
     fn test_dispute_status_states() {
-        let states = vec![
+        let states = [
             DisputeStatus::Filed,
             DisputeStatus::UnderReview,
+            DisputeStatus::Revealing,
             DisputeStatus::Voting,
             DisputeStatus::ResolvedBuyer,
             DisputeStatus::ResolvedSeller,
+            DisputeStatus::ResolvedSplit,
             DisputeStatus::Withdrawn,
         ];
 
-        assert_eq!(states.len(), 6);
+        assert_eq!(states.len(), 8);
     }
 
     #[test]
@@ -62,7 +77,7 @@ mod tests {
 
         // Reason must not be empty
         assert!(!dispute.reason.trim().is_empty());
-        assert!(dispute.reason.len() > 0);
+        assert!(!dispute.reason.is_empty());
         assert!(dispute.reason.len() <= 5000);
     }
 
@@ -76,6 +91,39 @@ mod tests {
         }
     }
 
+    // ===== Evidence Timeline Tests =====
+
+    fn mock_evidence(submitted_by: u8, micros: i64) -> DisputeEvidenceOutput {
+        DisputeEvidenceOutput {
+            evidence_hash: ActionHash::from_raw_36(vec![micros as u8; 36]),
+            evidence: DisputeEvidence {
+                dispute_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+                submitted_by: AgentPubKey::from_raw_36(vec![submitted_by; 36]),
+                statement: "Tracking shows the parcel was delivered".to_string(),
+                evidence_cids: vec![],
+                submitted_at: Timestamp::from_micros(micros),
+            },
+        }
+    }
+
+    #[test]
+    fn test_timeline_starts_with_filing_and_is_chronological() {
+        let dispute = mock_dispute();
+        let dispute_hash = ActionHash::from_raw_36(vec![1u8; 36]);
+
+        // Submitted out of order by seller (3) and buyer (2)
+        let evidence = vec![mock_evidence(3, 3_000_000), mock_evidence(2, 2_000_000)];
+
+        let events = build_dispute_timeline(&dispute_hash, &dispute, evidence);
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].kind, TimelineEventKind::Filing);
+        assert_eq!(events[0].statement, dispute.reason);
+        assert_eq!(events[1].submitted_by, dispute.buyer);
+        assert_eq!(events[2].submitted_by, dispute.seller);
+        assert!(events.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+    }
+
     // ===== MRC (Mutual Reputation Consensus) Tests =====
 
     #[test]
@@ -87,7 +135,10 @@ mod tests {
                 vote: ArbitrationVote {
                     dispute_hash: ActionHash::from_raw_36(vec![1u8; 36]),
                     arbitrator: AgentPubKey::from_raw_36(vec![1u8; 36]),
-                    favor_buyer: true,  // Vote for buyer
+                    dispute_version_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+                    commitment_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+                    refund_percent: 100,  // Vote for buyer
+                    salt: "0123456789abcdef".to_string(),
                     reasoning: "Evidence supports buyer".to_string(),
                     arbitrator_matl_score: 0.9, // High trust arbitrator
                     voted_at: Timestamp::from_micros(1000000),
@@ -98,7 +149,10 @@ mod tests {
                 vote: ArbitrationVote {
                     dispute_hash: ActionHash::from_raw_36(vec![1u8; 36]),
                     arbitrator: AgentPubKey::from_raw_36(vec![2u8; 36]),
-                    favor_buyer: false, // Vote for seller
+                    dispute_version_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+                    commitment_hash: ActionHash::from_raw_36(vec![2u8; 36]),
+                    refund_percent: 0, // Vote for seller
+                    salt: "0123456789abcdef".to_string(),
                     reasoning: "Seller provided proof".to_string(),
                     arbitrator_matl_score: 0.5, // Lower trust arbitrator
                     voted_at: Timestamp::from_micros(1000000),
@@ -111,7 +165,7 @@ mod tests {
         let mut total_weight = 0.0;
 
         for vote_output in &votes {
-            let vote_value = if vote_output.vote.favor_buyer { 1.0 } else { 0.0 };
+            let vote_value = vote_output.vote.refund_percent as f64 / 100.0;
             weighted_sum += vote_value * vote_output.vote.arbitrator_matl_score;
             total_weight += vote_output.vote.arbitrator_matl_score;
         }
@@ -125,20 +179,54 @@ mod tests {
         assert!((weighted_vote - 0.643).abs() < 0.01,
             "Weighted vote should be ~0.643, got {}", weighted_vote);
 
-        // Since 0.643 < 0.66 threshold, seller would win
-        assert!(weighted_vote < 0.66);
+        // The heavier arbitrator carries the weighted median
+        let median = weighted_median_refund(&[(100, 0.9), (0, 0.5)]);
+        assert_eq!(median, Some(100));
     }
 
     #[test]
-    fn test_mrc_threshold() {
-        // Buyer wins if weighted vote > 0.66 (66%)
-        const THRESHOLD: f64 = 0.66;
+    fn test_tally_and_resolution_follow_votes() {
+        let dispute = mock_dispute();
 
-        let buyer_wins_vote = 0.75;
-        let seller_wins_vote = 0.55;
+        let (mean, median) = tally_refunds(&[(100, 0.8), (100, 0.6), (0, 0.9)]).unwrap();
+        assert_eq!(median, 100);
+        assert!((mean - 1.4 / 2.3).abs() < 1e-9);
+        assert_eq!(tally_refunds(&[(50, 0.0)]), None);
+
+        assert_eq!(
+            resolution(&dispute, 100),
+            (
+                Some(dispute.buyer.clone()),
+                Some(dispute.seller.clone()),
+                DisputeStatus::ResolvedBuyer
+            )
+        );
+        assert_eq!(resolution(&dispute, 25), (None, None, DisputeStatus::ResolvedSplit));
+    }
 
-        assert!(buyer_wins_vote > THRESHOLD);
-        assert!(seller_wins_vote < THRESHOLD);
+    #[test]
+    fn test_weighted_median_refund() {
+        // Equal weights: middle vote decides
+        assert_eq!(
+            weighted_median_refund(&[(0, 0.8), (50, 0.8), (100, 0.8)]),
+            Some(50)
+        );
+
+        // A heavy minority can pull the median
+        assert_eq!(
+            weighted_median_refund(&[(25, 0.3), (25, 0.3), (75, 0.95)]),
+            Some(75)
+        );
+
+        // Order of votes does not matter
+        assert_eq!(
+            weighted_median_refund(&[(100, 0.8), (0, 0.8), (50, 0.8)]),
+            Some(50)
+        );
+
+        // No weight, no outcome
+        assert_eq!(weighted_median_refund(&[]), None);
+        assert_eq!(weighted_median_refund(&[(50, 0.0)]), None);
     }
 
     #[test]
@@ -152,7 +240,10 @@ mod tests {
                 vote: ArbitrationVote {
                     dispute_hash: ActionHash::from_raw_36(vec![1u8; 36]),
                     arbitrator: AgentPubKey::from_raw_36(vec![1u8; 36]),
-                    favor_buyer: true,
+                    dispute_version_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+                    commitment_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+                    refund_percent: 100,
+                    salt: "0123456789abcdef".to_string(),
                     reasoning: "Clear evidence".to_string(),
                     arbitrator_matl_score: 0.95, // Very high MATL
                     voted_at: Timestamp::from_micros(1000000),
@@ -164,7 +255,10 @@ mod tests {
                 vote: ArbitrationVote {
                     dispute_hash: ActionHash::from_raw_36(vec![1u8; 36]),
                     arbitrator: AgentPubKey::from_raw_36(vec![2u8; 36]),
-                    favor_buyer: false,
+                    dispute_version_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+                    commitment_hash: ActionHash::from_raw_36(vec![2u8; 36]),
+                    refund_percent: 0,
+                    salt: "0123456789abcdef".to_string(),
                     reasoning: "Disagree".to_string(),
                     arbitrator_matl_score: 0.3, // Low MATL
                     voted_at: Timestamp::from_micros(1000000),
@@ -176,7 +270,10 @@ mod tests {
                 vote: ArbitrationVote {
                     dispute_hash: ActionHash::from_raw_36(vec![1u8; 36]),
                     arbitrator: AgentPubKey::from_raw_36(vec![3u8; 36]),
-                    favor_buyer: false,
+                    dispute_version_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+                    commitment_hash: ActionHash::from_raw_36(vec![3u8; 36]),
+                    refund_percent: 0,
+                    salt: "0123456789abcdef".to_string(),
                     reasoning: "Also disagree".to_string(),
                     arbitrator_matl_score: 0.3, // Low MATL
                     voted_at: Timestamp::from_micros(1000000),
@@ -188,7 +285,7 @@ mod tests {
         let mut total_weight = 0.0;
 
         for vote_output in &votes {
-            let vote_value = if vote_output.vote.favor_buyer { 1.0 } else { 0.0 };
+            let vote_value = vote_output.vote.refund_percent as f64 / 100.0;
             weighted_sum += vote_value * vote_output.vote.arbitrator_matl_score;
             total_weight += vote_output.vote.arbitrator_matl_score;
         }
@@ -228,38 +325,210 @@ mod tests {
         assert_ne!(potential_arbitrator, dispute.filed_by);
     }
 
+    fn candidate(agent: AgentPubKey, matl_score: f64) -> ArbitratorCandidate {
+        ArbitratorCandidate {
+            registration_hash: ActionHash::from_raw_36(agent.get_raw_36().to_vec()),
+            score_hash: ActionHash::from_raw_36(agent.get_raw_36().to_vec()),
+            agent,
+            matl_score,
+        }
+    }
+
+    fn mock_candidates(count: u8) -> Vec<ArbitratorCandidate> {
+        (10u8..10 + count)
+            .map(|i| candidate(AgentPubKey::from_raw_36(vec![i; 36]), 0.71 + i as f64 / 100.0))
+            .collect()
+    }
+
+    #[test]
+    fn test_panel_pool_excludes_parties_and_low_matl() {
+        let mut dispute = mock_dispute();
+
+        dispute.panel_candidates = vec![
+            candidate(dispute.buyer.clone(), 0.95),
+            candidate(dispute.seller.clone(), 0.95),
+            candidate(AgentPubKey::from_raw_36(vec![10u8; 36]), 0.9),
+            candidate(AgentPubKey::from_raw_36(vec![11u8; 36]), 0.5), // Below threshold
+            candidate(AgentPubKey::from_raw_36(vec![12u8; 36]), 0.8),
+        ];
+
+        let eligible: Vec<AgentPubKey> = panel_pool(&dispute)
+            .into_iter()
+            .map(|c| c.agent)
+            .collect();
+
+        assert_eq!(
+            eligible,
+            vec![
+                AgentPubKey::from_raw_36(vec![10u8; 36]),
+                AgentPubKey::from_raw_36(vec![12u8; 36]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_corrected_candidates_add_missing_and_refresh_scores() {
+        let recorded = vec![candidate(AgentPubKey::from_raw_36(vec![12u8; 36]), 0.8)];
+
+        let mut refreshed = candidate(AgentPubKey::from_raw_36(vec![12u8; 36]), 0.9);
+        refreshed.score_hash = ActionHash::from_raw_36(vec![42u8; 36]);
+        let fresh = vec![
+            refreshed.clone(),
+            candidate(AgentPubKey::from_raw_36(vec![10u8; 36]), 0.75),
+        ];
+
+        let corrected = corrected_candidates(&recorded, fresh);
+
+        assert_eq!(
+            corrected,
+            vec![candidate(AgentPubKey::from_raw_36(vec![10u8; 36]), 0.75), refreshed]
+        );
+
+        // A fresh read that agrees with the snapshot changes nothing
+        assert_eq!(corrected_candidates(&corrected, corrected.clone()), corrected);
+    }
+
+    #[test]
+    fn test_draw_is_deterministic_and_honors_max() {
+        let dispute_hash = ActionHash::from_raw_36(vec![1u8; 36]);
+        let seed_head = ActionHash::from_raw_36(vec![7u8; 36]);
+        let candidates = mock_candidates(10);
+
+        let panel = draw_arbitrators(&dispute_hash, &seed_head, &candidates, 5);
+        let recomputed = draw_arbitrators(&dispute_hash, &seed_head, &candidates, 5);
+
+        assert_eq!(panel.len(), 5);
+        assert_eq!(panel, recomputed);
+
+        // No arbitrator is drawn twice
+        for (i, agent) in panel.iter().enumerate() {
+            assert!(!panel[i + 1..].contains(agent));
+        }
+    }
+
+    #[test]
+    fn test_draw_depends_on_seed() {
+        let dispute_hash = ActionHash::from_raw_36(vec![1u8; 36]);
+        let candidates = mock_candidates(10);
+
+        let panels: Vec<Vec<AgentPubKey>> = (0u8..8)
+            .map(|i| {
+                let seed_head = ActionHash::from_raw_36(vec![i; 36]);
+                draw_arbitrators(&dispute_hash, &seed_head, &candidates, 3)
+            })
+            .collect();
+
+        assert!(panels.iter().any(|panel| panel != &panels[0]));
+    }
+
+    #[test]
+    fn test_draw_with_small_pool_takes_everyone() {
+        let dispute_hash = ActionHash::from_raw_36(vec![1u8; 36]);
+        let seed_head = ActionHash::from_raw_36(vec![7u8; 36]);
+        let candidates = mock_candidates(2);
+
+        let panel = draw_arbitrators(&dispute_hash, &seed_head, &candidates, 5);
+
+        assert_eq!(panel.len(), 2);
+        assert!(panel.len() < MIN_ARBITRATORS);
+    }
+
+    #[test]
+    fn test_expected_panel_without_rounds_matches_draw() {
+        let dispute_hash = ActionHash::from_raw_36(vec![1u8; 36]);
+        let seed_head = ActionHash::from_raw_36(vec![7u8; 36]);
+        let candidates = mock_candidates(10);
+
+        assert_eq!(
+            expected_panel(&dispute_hash, &seed_head, &candidates, 3, &[]),
+            draw_arbitrators(&dispute_hash, &seed_head, &candidates, 3)
+        );
+    }
+
+    #[test]
+    fn test_replacement_round_keeps_responders_and_draws_new_members() {
+        let dispute_hash = ActionHash::from_raw_36(vec![1u8; 36]);
+        let seed_head = ActionHash::from_raw_36(vec![7u8; 36]);
+        let candidates = mock_candidates(10);
+
+        let initial = draw_arbitrators(&dispute_hash, &seed_head, &candidates, 3);
+        let removed = vec![initial[1].clone()];
+
+        let replaced = expected_panel(
+            &dispute_hash,
+            &seed_head,
+            &candidates,
+            3,
+            std::slice::from_ref(&removed),
+        );
+
+        assert_eq!(replaced.len(), 3);
+        assert_eq!(replaced[0], initial[0]);
+        assert_eq!(replaced[1], initial[2]);
+        // Replacement never re-seats a previous panel member
+        assert!(!initial.contains(&replaced[2]));
+    }
+
+    #[test]
+    fn test_replacement_with_exhausted_pool_shrinks_panel() {
+        let dispute_hash = ActionHash::from_raw_36(vec![1u8; 36]);
+        let seed_head = ActionHash::from_raw_36(vec![7u8; 36]);
+        let candidates = mock_candidates(3);
+
+        let initial = draw_arbitrators(&dispute_hash, &seed_head, &candidates, 3);
+        let replaced = expected_panel(
+            &dispute_hash,
+            &seed_head,
+            &candidates,
+            3,
+            &[vec![initial[0].clone()]],
+        );
+
+        assert_eq!(replaced.len(), 2);
+    }
+
+    #[test]
+    fn test_quorum_is_strict_majority() {
+        assert!(has_quorum(2, 3));
+        assert!(!has_quorum(1, 3));
+        assert!(has_quorum(3, 5));
+        assert!(!has_quorum(2, 4));
+        assert!(!has_quorum(0, 0));
+    }
+
     #[test]
     fn test_typical_arbitrator_count() {
         // Typically 3-5 arbitrators
         let arbitrator_count = 3;
 
-        assert!(arbitrator_count >= 3 && arbitrator_count <= 5);
+        assert!((3..=5).contains(&arbitrator_count));
     }
 
     // ===== Voting Tests =====
 
     #[test]
     fn test_arbitration_vote_structure() {
-        let vote = mock_arbitration_vote(true);
+        let vote = mock_arbitration_vote(100);
 
-        assert_eq!(vote.favor_buyer, true);
+        assert_eq!(vote.refund_percent, 100);
+        assert!(REFUND_PERCENT_STEPS.contains(&vote.refund_percent));
         assert!(!vote.reasoning.is_empty());
         assert!(vote.arbitrator_matl_score > 0.0 && vote.arbitrator_matl_score <= 1.0);
     }
 
     #[test]
     fn test_vote_reasoning_required() {
-        let vote = mock_arbitration_vote(false);
+        let vote = mock_arbitration_vote(0);
 
         // Reasoning must not be empty
         assert!(!vote.reasoning.trim().is_empty());
-        assert!(vote.reasoning.len() > 0);
+        assert!(!vote.reasoning.is_empty());
         assert!(vote.reasoning.len() <= 2000);
     }
 
     #[test]
     fn test_matl_score_range() {
-        let vote = mock_arbitration_vote(true);
+        let vote = mock_arbitration_vote(100);
 
         // MATL score must be valid
         assert!(vote.arbitrator_matl_score >= 0.0);
@@ -272,45 +541,315 @@ mod tests {
     fn test_arbitration_result_structure() {
         let result = ArbitrationResult {
             dispute_hash: ActionHash::from_raw_36(vec![1u8; 36]),
-            winner: AgentPubKey::from_raw_36(vec![2u8; 36]),
-            loser: AgentPubKey::from_raw_36(vec![3u8; 36]),
+            dispute_version_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+            winner: Some(AgentPubKey::from_raw_36(vec![2u8; 36])),
+            loser: Some(AgentPubKey::from_raw_36(vec![3u8; 36])),
             weighted_vote: 0.72,
+            refund_percent: 100,
             total_votes: 3,
+            vote_hashes: (0..3).map(|i| ActionHash::from_raw_36(vec![i; 36])).collect(),
             compensation_cents: Some(1999),
+            currency: "USD".to_string(),
+            appeal_hash: None,
+            stake_slashed: false,
             summary: "Resolved in favor of buyer".to_string(),
             finalized_at: Timestamp::from_micros(2000000),
         };
 
-        assert!(result.weighted_vote > 0.66); // Buyer won
+        assert_eq!(result.refund_percent, 100); // Buyer won
         assert_eq!(result.total_votes, 3);
         assert!(result.compensation_cents.is_some());
     }
 
     #[test]
-    fn test_buyer_wins_above_threshold() {
-        let weighted_vote = 0.75;
-        const THRESHOLD: f64 = 0.66;
+    fn test_refund_amount_from_median() {
+        assert_eq!(refund_amount_cents(10_000, 100), 10_000);
+        assert_eq!(refund_amount_cents(10_000, 75), 7_500);
+        assert_eq!(refund_amount_cents(10_000, 50), 5_000);
+        assert_eq!(refund_amount_cents(10_000, 0), 0);
+        assert_eq!(refund_amount_cents(1_999, 25), 499); // Rounds down
+    }
+
+    #[test]
+    fn test_transaction_terms_in_reference_currency() {
+        let mut terms = TransactionTerms {
+            buyer: AgentPubKey::from_raw_36(vec![2u8; 36]),
+            seller: AgentPubKey::from_raw_36(vec![3u8; 36]),
+            total_price_cents: 10_000,
+            currency: "USD".to_string(),
+            reference_value_cents: None,
+        };
+
+        // Reference-currency transactions need no conversion
+        assert_eq!(terms.matl_value_cents(), 10_000);
+        assert_eq!(terms.reference_amount(1_000), 1_000);
+
+        // 100.00 EUR valued at 108.00 USD
+        terms.currency = "EUR".to_string();
+        terms.reference_value_cents = Some(10_800);
+        assert_eq!(terms.matl_value_cents(), 10_800);
+        assert_eq!(terms.reference_amount(1_000), 1_080);
+
+        // Compensation and stakes stay in the transaction's currency
+        assert_eq!(refund_amount_cents(terms.total_price_cents, 50), 5_000);
+        assert_eq!(appeal_minimum_stake_cents(terms.total_price_cents, 10), 1_000);
+    }
+
+    #[test]
+    fn test_split_result_has_no_winner() {
+        let result = ArbitrationResult {
+            dispute_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+            dispute_version_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+            winner: None,
+            loser: None,
+            weighted_vote: 0.5,
+            refund_percent: 50,
+            total_votes: 3,
+            vote_hashes: (0..3).map(|i| ActionHash::from_raw_36(vec![i; 36])).collect(),
+            compensation_cents: Some(5_000),
+            currency: "USD".to_string(),
+            appeal_hash: None,
+            stake_slashed: false,
+            summary: "Resolved as a split".to_string(),
+            finalized_at: Timestamp::from_micros(2000000),
+        };
+
+        assert!(result.winner.is_none() && result.loser.is_none());
+        assert!(result.refund_percent > 0 && result.refund_percent < 100);
+    }
+
+    #[test]
+    fn test_new_dispute_is_a_fresh_filing() {
+        let filed = mock_dispute();
+        let seller = filed.seller.clone();
+        assert!(check_new_dispute(&filed, &filed.buyer).is_ok());
+
+        // Filed by its author only
+        assert!(check_new_dispute(&filed, &seller).is_err());
+
+        // No skipping ahead of the lifecycle
+        let mut resolved = filed.clone();
+        resolved.status = DisputeStatus::ResolvedBuyer;
+        assert!(check_new_dispute(&resolved, &filed.buyer).is_err());
+
+        // Panels and deadlines only arrive through validated updates
+        let mut seated = filed.clone();
+        seated.arbitrators = vec![AgentPubKey::from_raw_36(vec![10u8; 36])];
+        assert!(check_new_dispute(&seated, &filed.buyer).is_err());
+
+        let mut seeded = filed.clone();
+        seeded.panel_seed = Some(ActionHash::from_raw_36(vec![7u8; 36]));
+        assert!(check_new_dispute(&seeded, &filed.buyer).is_err());
+
+        let mut deadline = filed.clone();
+        deadline.reveal_deadline = Some(Timestamp::from_micros(3000000));
+        assert!(check_new_dispute(&deadline, &filed.buyer).is_err());
+
+        let mut appealed = filed.clone();
+        appealed.appeal_hash = Some(ActionHash::from_raw_36(vec![8u8; 36]));
+        assert!(check_new_dispute(&appealed, &filed.buyer).is_err());
+    }
+
+    #[test]
+    fn test_dispute_update_transitions() {
+        let filed = mock_dispute();
+        let buyer = filed.buyer.clone();
+        let seller = filed.seller.clone();
+        let arbitrator = AgentPubKey::from_raw_36(vec![10u8; 36]);
+        let outsider = AgentPubKey::from_raw_36(vec![99u8; 36]);
+
+        let mut drawn = filed.clone();
+        drawn.status = DisputeStatus::UnderReview;
+        drawn.arbitrators = vec![arbitrator.clone()];
+        drawn.panel_candidates = mock_candidates(1);
+        drawn.panel_seed = Some(ActionHash::from_raw_36(vec![7u8; 36]));
+
+        // Only the filer draws the panel
+        assert_eq!(
+            check_dispute_update(&filed, &drawn, &buyer),
+            Ok(DisputeChange::PanelDraw)
+        );
+        assert!(check_dispute_update(&filed, &drawn, &seller).is_err());
+
+        // Parties and the transaction are frozen
+        let mut reassigned = drawn.clone();
+        reassigned.seller = outsider.clone();
+        assert!(check_dispute_update(&filed, &reassigned, &buyer).is_err());
+
+        // Lifecycle moves need a party or panel member
+        let mut revealing = drawn.clone();
+        revealing.status = DisputeStatus::Revealing;
+        assert_eq!(
+            check_dispute_update(&drawn, &revealing, &arbitrator),
+            Ok(DisputeChange::Progress)
+        );
+        assert!(check_dispute_update(&drawn, &revealing, &outsider).is_err());
+
+        // No skipping straight to a resolution, and no swapping the panel
+        let mut resolved = drawn.clone();
+        resolved.status = DisputeStatus::ResolvedBuyer;
+        assert!(check_dispute_update(&drawn, &resolved, &buyer).is_err());
+
+        // Resolutions record the result they follow, and only they do
+        let mut voting = drawn.clone();
+        voting.status = DisputeStatus::Voting;
+        let mut settled = voting.clone();
+        settled.status = DisputeStatus::ResolvedSeller;
+        assert!(check_dispute_update(&voting, &settled, &buyer).is_err());
+
+        settled.result_hash = Some(ActionHash::from_raw_36(vec![5u8; 36]));
+        assert_eq!(
+            check_dispute_update(&voting, &settled, &buyer),
+            Ok(DisputeChange::Progress)
+        );
+
+        let mut cited = revealing.clone();
+        cited.result_hash = settled.result_hash.clone();
+        assert!(check_dispute_update(&drawn, &cited, &buyer).is_err());
+
+        let mut swapped = drawn.clone();
+        swapped.arbitrators = vec![outsider.clone()];
+        assert!(check_dispute_update(&drawn, &swapped, &buyer).is_err());
+
+        // Replacement rounds remove current panel members, citing a chain
+        // head of each removed member
+        let mut replaced = drawn.clone();
+        replaced.replacement_rounds = vec![vec![arbitrator.clone()]];
+        replaced.arbitrators = vec![];
+        assert!(check_dispute_update(&drawn, &replaced, &seller).is_err());
+
+        replaced.replacement_heads = vec![vec![ActionHash::from_raw_36(vec![20u8; 36])]];
+        assert_eq!(
+            check_dispute_update(&drawn, &replaced, &seller),
+            Ok(DisputeChange::Replacement)
+        );
+        replaced.replacement_rounds = vec![vec![outsider.clone()]];
+        assert!(check_dispute_update(&drawn, &replaced, &seller).is_err());
+
+        // Either party can correct the snapshot before any replacement
+        let mut corrected = drawn.clone();
+        corrected.panel_candidates = mock_candidates(2);
+        assert_eq!(
+            check_dispute_update(&drawn, &corrected, &seller),
+            Ok(DisputeChange::Correction)
+        );
+        assert!(check_dispute_update(&drawn, &corrected, &arbitrator).is_err());
+
+        let mut after_round = drawn.clone();
+        after_round.replacement_rounds = vec![vec![arbitrator.clone()]];
+        let mut late = after_round.clone();
+        late.panel_candidates = mock_candidates(2);
+        assert!(check_dispute_update(&after_round, &late, &seller).is_err());
+
+        let mut reseeded = corrected.clone();
+        reseeded.panel_seed = Some(ActionHash::from_raw_36(vec![9u8; 36]));
+        assert!(check_dispute_update(&drawn, &reseeded, &seller).is_err());
+
+        // Only the filer withdraws
+        let mut withdrawn = drawn.clone();
+        withdrawn.status = DisputeStatus::Withdrawn;
+        assert!(check_dispute_update(&drawn, &withdrawn, &buyer).is_ok());
+        assert!(check_dispute_update(&drawn, &withdrawn, &seller).is_err());
+    }
+
+    #[test]
+    fn test_reveal_opens_after_all_commitments_or_the_deadline() {
+        let mut dispute = mock_dispute();
+        dispute.status = DisputeStatus::UnderReview;
+        dispute.commit_deadline = Some(Timestamp::from_micros(2000000));
+
+        assert!(!reveal_open(&dispute, Timestamp::from_micros(2000000)));
+        assert!(reveal_open(&dispute, Timestamp::from_micros(2000001)));
+
+        dispute.status = DisputeStatus::Revealing;
+        assert!(reveal_open(&dispute, Timestamp::from_micros(1000000)));
+
+        dispute.status = DisputeStatus::Voting;
+        assert!(!reveal_open(&dispute, Timestamp::from_micros(2500000)));
+    }
+
+    #[test]
+    fn test_replacement_due_after_missed_deadline() {
+        let mut dispute = mock_dispute();
+        dispute.status = DisputeStatus::UnderReview;
+        dispute.commit_deadline = Some(Timestamp::from_micros(2000000));
+        dispute.reveal_deadline = Some(Timestamp::from_micros(3000000));
+
+        assert!(!replacement_due(&dispute, Timestamp::from_micros(2000000)));
+        assert!(replacement_due(&dispute, Timestamp::from_micros(2500000)));
+
+        // Once reveals are open only the reveal deadline counts
+        dispute.status = DisputeStatus::Revealing;
+        assert!(!replacement_due(&dispute, Timestamp::from_micros(2500000)));
+        assert!(replacement_due(&dispute, Timestamp::from_micros(3000001)));
+    }
+
+    // ===== Appeal Tests =====
+
+    #[test]
+    fn test_appeal_pool_requires_higher_tier_and_excludes_first_panel() {
+        let mut dispute = mock_dispute();
+        dispute.appeal_hash = Some(ActionHash::from_raw_36(vec![8u8; 36]));
+        dispute.original_panel = vec![AgentPubKey::from_raw_36(vec![10u8; 36])];
+
+        dispute.panel_candidates = vec![
+            candidate(AgentPubKey::from_raw_36(vec![10u8; 36]), 0.95), // Sat on first panel
+            candidate(AgentPubKey::from_raw_36(vec![11u8; 36]), 0.80), // Below appeal tier
+            candidate(AgentPubKey::from_raw_36(vec![12u8; 36]), 0.85), // Exactly at tier
+            candidate(AgentPubKey::from_raw_36(vec![13u8; 36]), 0.92),
+            candidate(dispute.seller.clone(), 0.99), // Party to the dispute
+        ];
+
+        let eligible = panel_pool(&dispute);
+
+        let agents: Vec<AgentPubKey> = eligible.into_iter().map(|c| c.agent).collect();
+        assert_eq!(
+            agents,
+            vec![
+                AgentPubKey::from_raw_36(vec![12u8; 36]),
+                AgentPubKey::from_raw_36(vec![13u8; 36]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_appeal_panel_is_larger() {
+        let mut dispute = mock_dispute();
+        assert_eq!(max_panel_size(&dispute, 5), 5);
 
-        let buyer_wins = weighted_vote > THRESHOLD;
-        assert!(buyer_wins);
+        dispute.appeal_hash = Some(ActionHash::from_raw_36(vec![9u8; 36]));
+        assert_eq!(max_panel_size(&dispute, 5), 5 + APPEAL_EXTRA_ARBITRATORS);
     }
 
     #[test]
-    fn test_seller_wins_below_threshold() {
-        let weighted_vote = 0.60;
-        const THRESHOLD: f64 = 0.66;
+    fn test_appeal_success_depends_on_appellant_side() {
+        // Buyer appeals a 25% refund
+        assert!(appeal_succeeded(true, 25, 50));
+        assert!(!appeal_succeeded(true, 25, 25));
+        assert!(!appeal_succeeded(true, 25, 0));
+
+        // Seller appeals a 100% refund
+        assert!(appeal_succeeded(false, 100, 75));
+        assert!(!appeal_succeeded(false, 100, 100));
+    }
 
-        let buyer_wins = weighted_vote > THRESHOLD;
-        assert!(!buyer_wins); // Seller wins
+    #[test]
+    fn test_appeal_minimum_stake() {
+        assert_eq!(appeal_minimum_stake_cents(10_000, 10), 1_000);
+        assert_eq!(appeal_minimum_stake_cents(1_999, 10), 199); // Rounds down
+        assert_eq!(appeal_minimum_stake_cents(u64::MAX, 100), u64::MAX);
     }
 
     #[test]
-    fn test_exactly_at_threshold() {
-        let weighted_vote = 0.66;
-        const THRESHOLD: f64 = 0.66;
+    fn test_file_appeal_input() {
+        let input = FileAppealInput {
+            dispute_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+            reason: "Panel ignored the tracking evidence".to_string(),
+            reputation_stake_cents: 1_000,
+        };
 
-        let buyer_wins = weighted_vote > THRESHOLD;
-        assert!(!buyer_wins); // Need STRICTLY greater than
+        assert!(!input.reason.is_empty());
+        assert!(input.reputation_stake_cents > 0);
     }
 
     // ===== MATL Impact Tests =====
@@ -318,13 +857,87 @@ mod tests {
     #[test]
     fn test_loser_gets_negative_matl_update() {
         // Losing a dispute should decrease MATL score
-        let update_input = UpdateMatlInput {
+        let update_input = DisputeOutcomeInput {
             agent: AgentPubKey::from_raw_36(vec![3u8; 36]),
-            successful: false, // Lost dispute
+            outcome: DisputeOutcome::Lost, // Lost dispute
             transaction_value_cents: 0,
+            result_hash: ActionHash::from_raw_36(vec![4u8; 36]),
         };
 
-        assert!(!update_input.successful);
+        assert_eq!(update_input.outcome, DisputeOutcome::Lost);
+    }
+
+    fn mock_result(winner: Option<u8>, loser: Option<u8>, stake_slashed: bool) -> ArbitrationResult {
+        ArbitrationResult {
+            dispute_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+            dispute_version_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+            winner: winner.map(|byte| AgentPubKey::from_raw_36(vec![byte; 36])),
+            loser: loser.map(|byte| AgentPubKey::from_raw_36(vec![byte; 36])),
+            weighted_vote: 0.72,
+            refund_percent: 100,
+            total_votes: 3,
+            vote_hashes: (0..3).map(|i| ActionHash::from_raw_36(vec![i; 36])).collect(),
+            compensation_cents: None,
+            currency: "USD".to_string(),
+            appeal_hash: None,
+            stake_slashed,
+            summary: "Resolved".to_string(),
+            finalized_at: Timestamp::from_micros(2000000),
+        }
+    }
+
+    fn mock_appeal(appellant: u8) -> Appeal {
+        Appeal {
+            dispute_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+            original_result_hash: ActionHash::from_raw_36(vec![5u8; 36]),
+            appellant: AgentPubKey::from_raw_36(vec![appellant; 36]),
+            reason: "Panel ignored the tracking evidence".to_string(),
+            reputation_stake_cents: 500,
+            filed_at: Timestamp::from_micros(3000000),
+        }
+    }
+
+    #[test]
+    fn test_dispute_updates_both_parties() {
+        let outcomes = dispute_outcomes(&mock_result(Some(2), Some(3), false), None);
+
+        assert_eq!(
+            outcomes,
+            vec![
+                (AgentPubKey::from_raw_36(vec![2u8; 36]), DisputeOutcome::Won),
+                (AgentPubKey::from_raw_36(vec![3u8; 36]), DisputeOutcome::Lost),
+            ]
+        );
+
+        // Split outcomes update nobody
+        assert!(dispute_outcomes(&mock_result(None, None, false), None).is_empty());
+    }
+
+    #[test]
+    fn test_upheld_appeal_only_costs_the_stake() {
+        let original = mock_result(Some(2), Some(3), false);
+        let upheld = mock_result(Some(2), Some(3), true);
+        let appeal = mock_appeal(3);
+
+        assert_eq!(
+            dispute_outcomes(&upheld, Some((&appeal, &original))),
+            vec![(appeal.appellant.clone(), DisputeOutcome::ForfeitedStake)]
+        );
+    }
+
+    #[test]
+    fn test_overturned_appeal_updates_the_new_sides() {
+        let original = mock_result(Some(2), Some(3), false);
+        let overturned = mock_result(Some(3), Some(2), false);
+        let appeal = mock_appeal(3);
+
+        assert_eq!(
+            dispute_outcomes(&overturned, Some((&appeal, &original))),
+            vec![
+                (AgentPubKey::from_raw_36(vec![3u8; 36]), DisputeOutcome::Won),
+                (AgentPubKey::from_raw_36(vec![2u8; 36]), DisputeOutcome::Lost),
+            ]
+        );
     }
 
     // ===== Edge Cases =====
@@ -332,14 +945,14 @@ mod tests {
     #[test]
     fn test_all_arbitrators_vote_same_way() {
         // Unanimous decision
-        let votes = vec![
-            mock_arbitration_vote(true),
-            mock_arbitration_vote(true),
-            mock_arbitration_vote(true),
+        let votes = [
+            mock_arbitration_vote(100),
+            mock_arbitration_vote(100),
+            mock_arbitration_vote(100),
         ];
 
-        let all_favor_buyer = votes.iter().all(|v| v.favor_buyer);
-        assert!(all_favor_buyer);
+        let all_full_refund = votes.iter().all(|v| v.refund_percent == 100);
+        assert!(all_full_refund);
     }
 
     #[test]
@@ -350,7 +963,10 @@ mod tests {
                 vote: ArbitrationVote {
                     dispute_hash: ActionHash::from_raw_36(vec![1u8; 36]),
                     arbitrator: AgentPubKey::from_raw_36(vec![1u8; 36]),
-                    favor_buyer: true,
+                    dispute_version_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+                    commitment_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+                    refund_percent: 100,
+                    salt: "0123456789abcdef".to_string(),
                     reasoning: "For buyer".to_string(),
                     arbitrator_matl_score: 0.8,
                     voted_at: Timestamp::from_micros(1000000),
@@ -361,7 +977,10 @@ mod tests {
                 vote: ArbitrationVote {
                     dispute_hash: ActionHash::from_raw_36(vec![1u8; 36]),
                     arbitrator: AgentPubKey::from_raw_36(vec![2u8; 36]),
-                    favor_buyer: true,
+                    dispute_version_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+                    commitment_hash: ActionHash::from_raw_36(vec![2u8; 36]),
+                    refund_percent: 100,
+                    salt: "0123456789abcdef".to_string(),
                     reasoning: "Also for buyer".to_string(),
                     arbitrator_matl_score: 0.6,
                     voted_at: Timestamp::from_micros(1000000),
@@ -372,7 +991,10 @@ mod tests {
                 vote: ArbitrationVote {
                     dispute_hash: ActionHash::from_raw_36(vec![1u8; 36]),
                     arbitrator: AgentPubKey::from_raw_36(vec![3u8; 36]),
-                    favor_buyer: false,
+                    dispute_version_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+                    commitment_hash: ActionHash::from_raw_36(vec![3u8; 36]),
+                    refund_percent: 0,
+                    salt: "0123456789abcdef".to_string(),
                     reasoning: "For seller".to_string(),
                     arbitrator_matl_score: 0.9, // Highest MATL but in minority
                     voted_at: Timestamp::from_micros(1000000),
@@ -384,7 +1006,7 @@ mod tests {
         let mut total_weight = 0.0;
 
         for vote_output in &votes {
-            let vote_value = if vote_output.vote.favor_buyer { 1.0 } else { 0.0 };
+            let vote_value = vote_output.vote.refund_percent as f64 / 100.0;
             weighted_sum += vote_value * vote_output.vote.arbitrator_matl_score;
             total_weight += vote_output.vote.arbitrator_matl_score;
         }
@@ -397,9 +1019,10 @@ mod tests {
 
         assert!((weighted_vote - 0.609).abs() < 0.01);
 
-        // Despite 2-to-1 vote for buyer, weighted vote is only 0.609
-        // Seller would win because < 0.66 threshold!
-        assert!(weighted_vote < 0.66);
+        // 2-to-1 for the buyer by count, and the weighted median agrees:
+        // 1.4 of 2.3 total weight voted for a full refund
+        let median = weighted_median_refund(&[(100, 0.8), (100, 0.6), (0, 0.9)]);
+        assert_eq!(median, Some(100));
     }
 
     // ===== Dispute Withdrawal Tests =====
@@ -431,15 +1054,57 @@ mod tests {
     }
 
     #[test]
-    fn test_submit_vote_input() {
-        let input = SubmitArbitrationVoteInput {
+    fn test_reveal_vote_input() {
+        let input = RevealArbitrationVoteInput {
             dispute_hash: ActionHash::from_raw_36(vec![1u8; 36]),
-            favor_buyer: true,
+            refund_percent: 100,
+            salt: "0123456789abcdef".to_string(),
             reasoning: "Evidence clearly supports buyer's claim".to_string(),
         };
 
-        assert!(input.favor_buyer);
+        assert_eq!(input.refund_percent, 100);
         assert!(input.reasoning.len() > 10);
+        assert!(input.salt.len() >= MIN_COMMITMENT_SALT_LEN);
+    }
+
+    // ===== Commit-Reveal Tests =====
+
+    #[test]
+    fn test_commitment_preimage_binds_decision_and_salt() {
+        let dispute_hash = ActionHash::from_raw_36(vec![1u8; 36]);
+        let arbitrator = AgentPubKey::from_raw_36(vec![4u8; 36]);
+
+        let buyer = vote_commitment_preimage(&dispute_hash, &arbitrator, 100, "saltsaltsaltsalt");
+        let seller = vote_commitment_preimage(&dispute_hash, &arbitrator, 0, "saltsaltsaltsalt");
+        let other_salt = vote_commitment_preimage(&dispute_hash, &arbitrator, 100, "pepperpepperpepp");
+
+        assert_ne!(buyer, seller);
+        assert_ne!(buyer, other_salt);
+        assert_eq!(
+            buyer,
+            vote_commitment_preimage(&dispute_hash, &arbitrator, 100, "saltsaltsaltsalt")
+        );
+    }
+
+    #[test]
+    fn test_commitment_preimage_binds_arbitrator() {
+        let dispute_hash = ActionHash::from_raw_36(vec![1u8; 36]);
+
+        let first = vote_commitment_preimage(
+            &dispute_hash,
+            &AgentPubKey::from_raw_36(vec![4u8; 36]),
+            100,
+            "saltsaltsaltsalt",
+        );
+        let second = vote_commitment_preimage(
+            &dispute_hash,
+            &AgentPubKey::from_raw_36(vec![5u8; 36]),
+            100,
+            "saltsaltsaltsalt",
+        );
+
+        // Copying another arbitrator's commitment does not work
+        assert_ne!(first, second);
     }
 
     // ===== Complete Flow Test =====
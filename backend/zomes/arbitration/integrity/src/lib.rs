@@ -28,6 +28,39 @@ pub struct Dispute {
     /// Assigned arbitrators (high MATL score agents)
     pub arbitrators: Vec<AgentPubKey>,
 
+    /// Registered arbitrators the panel was drawn from, ordered by agent
+    /// (see `panel_pool` for who can actually be drawn)
+    pub panel_candidates: Vec<ArbitratorCandidate>,
+
+    /// Seed of the panel draw: the `prev_action` of the update that drew
+    /// the panel (the filer's, or the appellant's for an appeal panel)
+    pub panel_seed: Option<ActionHash>,
+
+    /// After this time arbitrators may reveal even if not all have committed
+    pub commit_deadline: Option<Timestamp>,
+
+    /// After this time non-revealing arbitrators can be replaced,
+    /// or the dispute finalized on a quorum of votes
+    pub reveal_deadline: Option<Timestamp>,
+
+    /// Arbitrators removed for not responding, one list per replacement round
+    pub replacement_rounds: Vec<Vec<AgentPubKey>>,
+
+    /// Per replacement round, a chain head of each removed arbitrator
+    /// (same order) written after the deadline they missed
+    #[serde(default)]
+    pub replacement_heads: Vec<Vec<ActionHash>>,
+
+    /// Appeal against the first result, if one was filed
+    pub appeal_hash: Option<ActionHash>,
+
+    /// First-instance panel, excluded from the appeal panel
+    pub original_panel: Vec<AgentPubKey>,
+
+    /// Result the dispute was resolved by (the latest, after an appeal)
+    #[serde(default)]
+    pub result_hash: Option<ActionHash>,
+
     /// Creation timestamp
     pub created_at: Timestamp,
 
@@ -35,6 +68,43 @@ pub struct Dispute {
     pub updated_at: Timestamp,
 }
 
+/// Arbitrator candidate snapshot used for the panel draw
+///
+/// Recorded on the dispute so either party can recompute the panel.
+/// Validation checks the score and registration against the cited entries,
+/// which must predate the draw's seed. The party who did not draw can
+/// correct a snapshot that leaves arbitrators out or cites stale scores
+/// (see `DisputeChange::Correction`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ArbitratorCandidate {
+    /// Registered arbitrator
+    pub agent: AgentPubKey,
+
+    /// MATL composite score at draw time (draw weight)
+    pub matl_score: f64,
+
+    /// The agent's `ArbitratorRegistration`
+    pub registration_hash: ActionHash,
+
+    /// The MATL score entry `matl_score` was read from
+    pub score_hash: ActionHash,
+}
+
+/// The fields of `reputation_integrity::MatlScore` panel validation reads
+///
+/// Mirrored so this zome does not depend on the reputation integrity zome.
+/// Cited scores must also be that zome's `MatlScore` entries (see
+/// `is_matl_score`), whose validation ties every score to the outcomes it
+/// counts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, SerializedBytes)]
+pub struct MatlScoreTerms {
+    pub agent: AgentPubKey,
+    pub composite: f64,
+    /// Score version this one was computed from
+    #[serde(default)]
+    pub previous_score: Option<ActionHash>,
+}
+
 /// Dispute status lifecycle
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -42,10 +112,13 @@ pub enum DisputeStatus {
     /// Newly filed, awaiting arbitrator assignment
     Filed,
 
-    /// Arbitrators assigned, awaiting votes
+    /// Arbitrators assigned, awaiting vote commitments
     UnderReview,
 
-    /// All votes collected, awaiting finalization
+    /// Commitments collected (or deadline passed), awaiting reveals
+    Revealing,
+
+    /// All votes revealed, awaiting finalization
     Voting,
 
     /// Resolved in favor of buyer
@@ -54,11 +127,60 @@ pub enum DisputeStatus {
     /// Resolved in favor of seller
     ResolvedSeller,
 
+    /// Resolved with a partial refund to the buyer
+    ResolvedSplit,
+
     /// Withdrawn by filer
     Withdrawn,
 }
 
-/// Arbitration vote - an arbitrator's decision
+/// Dispute evidence - a statement from either party, with supporting files
+///
+/// Lets the responding party answer the filer, and either party add
+/// material while the dispute is still under review.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct DisputeEvidence {
+    /// Dispute this evidence belongs to
+    pub dispute_hash: ActionHash,
+
+    /// Party submitting the evidence (buyer or seller)
+    pub submitted_by: AgentPubKey,
+
+    /// Statement accompanying the evidence
+    pub statement: String,
+
+    /// IPFS CIDs of evidence (photos, documents, etc.)
+    pub evidence_cids: Vec<String>,
+
+    /// Timestamp
+    pub submitted_at: Timestamp,
+}
+
+/// Vote commitment - the sealed first phase of an arbitration vote
+///
+/// Arbitrators publish a salted hash of their decision first, so later
+/// arbitrators cannot see (and herd on) earlier votes.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct VoteCommitment {
+    /// Dispute being voted on
+    pub dispute_hash: ActionHash,
+
+    /// Arbitrator committing
+    pub arbitrator: AgentPubKey,
+
+    /// Dispute version whose panel the arbitrator sits on
+    pub dispute_version_hash: ActionHash,
+
+    /// BLAKE2b-256 of the vote preimage (see `vote_commitment_preimage`)
+    pub commitment: Vec<u8>,
+
+    /// Timestamp
+    pub committed_at: Timestamp,
+}
+
+/// Arbitration vote - an arbitrator's revealed decision
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct ArbitrationVote {
@@ -68,8 +190,18 @@ pub struct ArbitrationVote {
     /// Arbitrator who voted
     pub arbitrator: AgentPubKey,
 
-    /// Decision (true = favor buyer, false = favor seller)
-    pub favor_buyer: bool,
+    /// Dispute version whose panel the arbitrator sits on
+    pub dispute_version_hash: ActionHash,
+
+    /// Commitment this vote reveals
+    pub commitment_hash: ActionHash,
+
+    /// Decision: refund to the buyer as a percentage of the transaction
+    /// value (one of `REFUND_PERCENT_STEPS`; 100 = buyer, 0 = seller)
+    pub refund_percent: u8,
+
+    /// Salt used in the commitment
+    pub salt: String,
 
     /// Reasoning for decision
     pub reasoning: String,
@@ -88,21 +220,40 @@ pub struct ArbitrationResult {
     /// Dispute that was resolved
     pub dispute_hash: ActionHash,
 
-    /// Winner (buyer or seller)
-    pub winner: AgentPubKey,
+    /// Dispute version (in Voting) whose panel decided
+    pub dispute_version_hash: ActionHash,
 
-    /// Loser
-    pub loser: AgentPubKey,
+    /// Winner (buyer or seller), None for a split outcome
+    pub winner: Option<AgentPubKey>,
 
-    /// Weighted vote score (0.0-1.0, >0.66 = buyer wins)
+    /// Loser, None for a split outcome
+    pub loser: Option<AgentPubKey>,
+
+    /// Weighted mean refund fraction (0.0-1.0), for transparency
     pub weighted_vote: f64,
 
+    /// Weighted-median refund percentage decided by the panel
+    pub refund_percent: u8,
+
     /// Total votes cast
     pub total_votes: u32,
 
-    /// Optional compensation amount (in cents)
+    /// Revealed votes the outcome was tallied from
+    pub vote_hashes: Vec<ActionHash>,
+
+    /// Optional compensation amount, in minor units of `currency`
     pub compensation_cents: Option<u64>,
 
+    /// Currency of the compensation (the disputed transaction's)
+    #[serde(default = "default_currency")]
+    pub currency: String,
+
+    /// Appeal this result was decided on, None for first-instance results
+    pub appeal_hash: Option<ActionHash>,
+
+    /// Whether the appellant's reputation stake was forfeited (appeal failed)
+    pub stake_slashed: bool,
+
     /// Resolution summary
     pub summary: String,
 
@@ -110,6 +261,117 @@ pub struct ArbitrationResult {
     pub finalized_at: Timestamp,
 }
 
+fn default_currency() -> String {
+    "USD".to_string()
+}
+
+/// Minimum MATL composite score to sit on a dispute panel
+pub const MIN_ARBITRATOR_MATL: f64 = 0.7;
+
+/// Minimum panel size before a dispute moves to UnderReview
+pub const MIN_ARBITRATORS: usize = 3;
+
+/// Minimum MATL composite score to sit on an appeal panel
+pub const APPEAL_MIN_ARBITRATOR_MATL: f64 = 0.85;
+
+/// Appeal panels are this much larger than `max_arbitrators`
+pub const APPEAL_EXTRA_ARBITRATORS: usize = 2;
+
+/// Refund percentages an arbitrator may vote for
+pub const REFUND_PERCENT_STEPS: [u8; 5] = [0, 25, 50, 75, 100];
+
+/// Minimum salt length for vote commitments
+pub const MIN_COMMITMENT_SALT_LEN: usize = 16;
+
+/// Appeal - the losing party's request to rehear a dispute
+///
+/// Convenes a larger panel from a higher MATL tier. The appellant puts
+/// reputation at stake, not funds: nothing is held in escrow, and if the
+/// appeal does not move the outcome in their favor they take a MATL
+/// failure weighted by the stake (see `ArbitrationResult::stake_slashed`).
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Appeal {
+    /// Dispute being appealed
+    pub dispute_hash: ActionHash,
+
+    /// First-instance result being appealed
+    pub original_result_hash: ActionHash,
+
+    /// Party filing the appeal
+    pub appellant: AgentPubKey,
+
+    /// Grounds for appeal
+    pub reason: String,
+
+    /// Transaction value the appellant's MATL is risked against, in minor
+    /// units of the transaction's currency (no funds are held)
+    #[serde(alias = "stake_cents")]
+    pub reputation_stake_cents: u64,
+
+    /// Timestamp
+    pub filed_at: Timestamp,
+}
+
+/// Arbitrator registration - an agent opting in to the arbitration pool
+///
+/// Only registered agents are considered when a dispute panel is drawn.
+/// Their MATL score is checked at selection time, not at registration.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ArbitratorRegistration {
+    /// Agent volunteering to arbitrate
+    pub agent: AgentPubKey,
+
+    /// Registration timestamp
+    pub registered_at: Timestamp,
+}
+
+/// DNA properties relevant to arbitration (see dna.yaml)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, SerializedBytes)]
+pub struct ArbitrationProperties {
+    /// Days a dispute may stay open before timeout handling kicks in
+    pub arbitration_timeout_days: u32,
+
+    /// Upper bound on the size of a dispute panel
+    pub max_arbitrators: u32,
+
+    /// Days after a result during which the losing party may appeal
+    #[serde(default = "default_appeal_window_days")]
+    pub appeal_window_days: u32,
+
+    /// Minimum appeal reputation stake, as a percentage of the transaction value
+    #[serde(default = "default_appeal_stake_percent")]
+    pub appeal_stake_percent: u32,
+}
+
+fn default_appeal_window_days() -> u32 {
+    7
+}
+
+fn default_appeal_stake_percent() -> u32 {
+    10
+}
+
+impl Default for ArbitrationProperties {
+    fn default() -> Self {
+        Self {
+            arbitration_timeout_days: 14,
+            max_arbitrators: 5,
+            appeal_window_days: default_appeal_window_days(),
+            appeal_stake_percent: default_appeal_stake_percent(),
+        }
+    }
+}
+
+/// Read arbitration settings from the DNA properties
+///
+/// Falls back to the defaults when the properties are missing or malformed.
+pub fn arbitration_properties() -> ExternResult<ArbitrationProperties> {
+    let properties = dna_info()?.modifiers.properties;
+    Ok(ArbitrationProperties::try_from(properties).unwrap_or_default())
+}
+
 #[hdk_link_types]
 pub enum LinkTypes {
     /// Transaction -> Dispute
@@ -121,35 +383,326 @@ pub enum LinkTypes {
     /// Agent -> Disputes (as arbitrator)
     AgentToArbitrationOpportunities,
 
+    /// Dispute -> Evidence
+    DisputeToEvidence,
+
+    /// Dispute -> Vote commitments
+    DisputeToCommitments,
+
     /// Dispute -> Votes
     DisputeToVotes,
 
     /// Dispute -> Result
     DisputeToResult,
 
+    /// Dispute -> Appeal
+    DisputeToAppeal,
+
     /// All Disputes (for browsing)
     AllDisputes,
+
+    /// Path("arbitrator_pool") -> ArbitratorRegistration
+    ArbitratorPool,
+
+    /// Agent -> ArbitratorRegistration
+    AgentToArbitratorRegistration,
 }
 
 #[hdk_entry_types]
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
     Dispute(Dispute),
+    DisputeEvidence(DisputeEvidence),
+    VoteCommitment(VoteCommitment),
     ArbitrationVote(ArbitrationVote),
     ArbitrationResult(ArbitrationResult),
+    Appeal(Appeal),
+    ArbitratorRegistration(ArbitratorRegistration),
+}
+
+/// Check whether an agent may sit on a dispute's panel
+pub fn is_eligible_arbitrator(dispute: &Dispute, agent: &AgentPubKey, matl_score: f64) -> bool {
+    agent != &dispute.buyer
+        && agent != &dispute.seller
+        && agent != &dispute.filed_by
+        && matl_score > MIN_ARBITRATOR_MATL
+}
+
+/// Whether a candidate can be drawn onto a dispute's panel
+///
+/// First-instance panels draw eligible arbitrators (see
+/// `is_eligible_arbitrator`); appeal panels only the higher MATL tier,
+/// without the first panel.
+pub fn is_drawable(dispute: &Dispute, candidate: &ArbitratorCandidate) -> bool {
+    if !is_eligible_arbitrator(dispute, &candidate.agent, candidate.matl_score) {
+        return false;
+    }
+
+    match dispute.appeal_hash {
+        Some(_) => {
+            candidate.matl_score >= APPEAL_MIN_ARBITRATOR_MATL
+                && !dispute.original_panel.contains(&candidate.agent)
+        }
+        None => true,
+    }
+}
+
+/// Candidates a dispute's panel is drawn from, in snapshot order
+///
+/// A correction can lower a candidate's score below the tier, so the
+/// snapshot may list candidates who can no longer be drawn.
+pub fn panel_pool(dispute: &Dispute) -> Vec<ArbitratorCandidate> {
+    dispute
+        .panel_candidates
+        .iter()
+        .filter(|candidate| is_drawable(dispute, candidate))
+        .cloned()
+        .collect()
+}
+
+/// Draw a dispute panel from the eligible candidates
+///
+/// Weighted sampling without replacement, weighted by MATL composite.
+/// The PRNG is seeded from the dispute's ActionHash and the filer's chain
+/// head, so anyone holding the dispute entry can recompute the panel.
+pub fn draw_arbitrators(
+    dispute_hash: &ActionHash,
+    seed_head: &ActionHash,
+    candidates: &[ArbitratorCandidate],
+    max_arbitrators: usize,
+) -> Vec<AgentPubKey> {
+    let mut rng = PanelRng::new(dispute_hash, seed_head, 0);
+    weighted_draw(&mut rng, candidates.iter().collect(), max_arbitrators)
+}
+
+/// Recompute the current panel, including any replacement rounds
+///
+/// Each round drops the listed non-responsive arbitrators and draws the
+/// same number of replacements from candidates who have never sat on the
+/// panel, using a round-specific stream of the same seed.
+pub fn expected_panel(
+    dispute_hash: &ActionHash,
+    seed_head: &ActionHash,
+    candidates: &[ArbitratorCandidate],
+    max_arbitrators: usize,
+    replacement_rounds: &[Vec<AgentPubKey>],
+) -> Vec<AgentPubKey> {
+    let mut panel = draw_arbitrators(dispute_hash, seed_head, candidates, max_arbitrators);
+    let mut seated = panel.clone();
+
+    for (round, removed) in replacement_rounds.iter().enumerate() {
+        panel.retain(|agent| !removed.contains(agent));
+
+        let remaining: Vec<&ArbitratorCandidate> = candidates
+            .iter()
+            .filter(|c| !seated.contains(&c.agent))
+            .collect();
+
+        let mut rng = PanelRng::new(dispute_hash, seed_head, round as u32 + 1);
+        let replacements = weighted_draw(&mut rng, remaining, removed.len());
+
+        seated.extend(replacements.iter().cloned());
+        panel.extend(replacements);
+    }
+
+    panel
+}
+
+/// Weighted sampling without replacement
+fn weighted_draw(
+    rng: &mut PanelRng,
+    mut pool: Vec<&ArbitratorCandidate>,
+    count: usize,
+) -> Vec<AgentPubKey> {
+    let mut drawn = Vec::new();
+
+    while drawn.len() < count && !pool.is_empty() {
+        let total_weight: f64 = pool.iter().map(|c| c.matl_score).sum();
+        let mut target = rng.next_f64() * total_weight;
+
+        let mut index = pool.len() - 1;
+        for (i, candidate) in pool.iter().enumerate() {
+            if target < candidate.matl_score {
+                index = i;
+                break;
+            }
+            target -= candidate.matl_score;
+        }
+
+        drawn.push(pool.remove(index).agent.clone());
+    }
+
+    drawn
+}
+
+/// Weighted mean refund fraction and weighted-median refund percentage
+///
+/// Votes are (refund_percent, matl_weight) pairs. None when no vote
+/// carries weight.
+pub fn tally_refunds(votes: &[(u8, f64)]) -> Option<(f64, u8)> {
+    let total_weight: f64 = votes.iter().map(|(_, weight)| weight).sum();
+    let weighted_sum: f64 = votes
+        .iter()
+        .map(|(refund_percent, weight)| *refund_percent as f64 / 100.0 * weight)
+        .sum();
+
+    let median = weighted_median_refund(votes)?;
+    Some((weighted_sum / total_weight, median))
+}
+
+/// Weighted median of (refund_percent, matl_weight) pairs
+///
+/// The smallest refund at which the cumulative weight reaches half of
+/// the total. Returns None if there is no positive weight.
+pub fn weighted_median_refund(votes: &[(u8, f64)]) -> Option<u8> {
+    let total_weight: f64 = votes.iter().map(|(_, weight)| weight).sum();
+    if total_weight <= 0.0 {
+        return None;
+    }
+
+    let mut sorted = votes.to_vec();
+    sorted.sort_by_key(|(refund_percent, _)| *refund_percent);
+
+    let mut cumulative = 0.0;
+    for (refund_percent, weight) in sorted {
+        cumulative += weight;
+        if cumulative >= total_weight / 2.0 {
+            return Some(refund_percent);
+        }
+    }
+
+    None
+}
+
+/// Winner, loser and final status for a decided refund
+///
+/// A full refund resolves for the buyer, no refund for the seller, and
+/// anything in between is a split with neither.
+pub fn resolution(
+    dispute: &Dispute,
+    refund_percent: u8,
+) -> (Option<AgentPubKey>, Option<AgentPubKey>, DisputeStatus) {
+    match refund_percent {
+        100 => (
+            Some(dispute.buyer.clone()),
+            Some(dispute.seller.clone()),
+            DisputeStatus::ResolvedBuyer,
+        ),
+        0 => (
+            Some(dispute.seller.clone()),
+            Some(dispute.buyer.clone()),
+            DisputeStatus::ResolvedSeller,
+        ),
+        _ => (None, None, DisputeStatus::ResolvedSplit),
+    }
+}
+
+/// Whether enough panel members voted to finalize without the rest
+///
+/// A quorum is a strict majority of the current panel.
+pub fn has_quorum(votes: usize, panel_size: usize) -> bool {
+    panel_size > 0 && votes * 2 > panel_size
+}
+
+/// Bytes hashed to form a vote commitment
+///
+/// dispute hash || arbitrator key || refund percent byte || salt
+pub fn vote_commitment_preimage(
+    dispute_hash: &ActionHash,
+    arbitrator: &AgentPubKey,
+    refund_percent: u8,
+    salt: &str,
+) -> Vec<u8> {
+    let mut preimage = Vec::new();
+    preimage.extend_from_slice(dispute_hash.get_raw_39());
+    preimage.extend_from_slice(arbitrator.get_raw_39());
+    preimage.push(refund_percent);
+    preimage.extend_from_slice(salt.as_bytes());
+    preimage
+}
+
+/// Compute the commitment hash for a vote
+pub fn compute_vote_commitment(
+    dispute_hash: &ActionHash,
+    arbitrator: &AgentPubKey,
+    refund_percent: u8,
+    salt: &str,
+) -> ExternResult<Vec<u8>> {
+    // Same digest as the host's 32-byte blake2b, computable during validation
+    Ok(holo_hash::blake2b_256(&vote_commitment_preimage(
+        dispute_hash,
+        arbitrator,
+        refund_percent,
+        salt,
+    )))
+}
+
+/// Deterministic PRNG for panel draws (FNV-1a seed, SplitMix64 stream)
+struct PanelRng {
+    state: u64,
+}
+
+impl PanelRng {
+    fn new(dispute_hash: &ActionHash, seed_head: &ActionHash, round: u32) -> Self {
+        let mut state: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in dispute_hash
+            .get_raw_39()
+            .iter()
+            .chain(seed_head.get_raw_39().iter())
+            .chain(round.to_le_bytes().iter())
+        {
+            state ^= *byte as u64;
+            state = state.wrapping_mul(0x0100_0000_01b3);
+        }
+        Self { state }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform float in [0.0, 1.0)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
 }
 
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
     match op.flattened::<EntryTypes, LinkTypes>()? {
-        FlatOp::StoreEntry(store_entry) => match store_entry {
-            OpEntry::CreateEntry { app_entry, .. } => match app_entry {
-                EntryTypes::Dispute(dispute) => validate_dispute(&dispute),
-                EntryTypes::ArbitrationVote(vote) => validate_vote(&vote),
-                EntryTypes::ArbitrationResult(result) => validate_result(&result),
-            },
+        FlatOp::StoreEntry(OpEntry::CreateEntry { app_entry, action }) => match app_entry {
+            EntryTypes::Dispute(dispute) => validate_new_dispute(&dispute, &action),
+            EntryTypes::DisputeEvidence(evidence) => {
+                validate_dispute_evidence(&evidence, &action)
+            }
+            EntryTypes::VoteCommitment(commitment) => {
+                validate_vote_commitment(&commitment, &action)
+            }
+            EntryTypes::ArbitrationVote(vote) => validate_vote(&vote, &action),
+            EntryTypes::ArbitrationResult(result) => {
+                validate_result(&result, &action.author, action.timestamp)
+            }
+            EntryTypes::Appeal(appeal) => validate_appeal(&appeal, &action),
+            EntryTypes::ArbitratorRegistration(registration) => {
+                validate_arbitrator_registration(&registration, &action)
+            }
+        },
+        FlatOp::RegisterUpdate(OpUpdate::Entry { app_entry, action }) => match app_entry {
+            EntryTypes::Dispute(dispute) => validate_update_dispute(&dispute, &action),
+            // Appeal results supersede the first result via an update
+            EntryTypes::ArbitrationResult(result) => validate_update_result(&result, &action),
             _ => Ok(ValidateCallbackResult::Valid),
         },
+        FlatOp::RegisterCreateLink {
+            link_type: LinkTypes::DisputeToResult,
+            base_address,
+            target_address,
+            ..
+        } => validate_dispute_to_result(base_address, target_address),
         _ => Ok(ValidateCallbackResult::Valid),
     }
 }
@@ -196,48 +749,1191 @@ fn validate_dispute(dispute: &Dispute) -> ExternResult<ValidateCallbackResult> {
     Ok(ValidateCallbackResult::Valid)
 }
 
-fn validate_vote(vote: &ArbitrationVote) -> ExternResult<ValidateCallbackResult> {
-    // Reasoning must not be empty
-    if vote.reasoning.trim().is_empty() {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Vote reasoning cannot be empty".into(),
-        ));
+/// Check that a dispute entry is a fresh filing by its author
+///
+/// Panels, deadlines, replacements and appeals only arrive through
+/// validated updates, so a new dispute starts with none of them.
+pub fn check_new_dispute(dispute: &Dispute, author: &AgentPubKey) -> Result<(), String> {
+    if &dispute.filed_by != author {
+        return Err("Dispute must be authored by the filer".into());
     }
 
-    // Reasoning length limit
-    if vote.reasoning.len() > 2000 {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Vote reasoning too long (max 2000 characters)".into(),
-        ));
+    if dispute.status != DisputeStatus::Filed {
+        return Err("A new dispute starts as Filed".into());
     }
 
-    // MATL score must be valid
-    if vote.arbitrator_matl_score < 0.0 || vote.arbitrator_matl_score > 1.0 {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Invalid MATL score (must be 0.0-1.0)".into(),
-        ));
+    if !dispute.arbitrators.is_empty()
+        || !dispute.panel_candidates.is_empty()
+        || dispute.panel_seed.is_some()
+        || !dispute.original_panel.is_empty()
+        || !dispute.replacement_rounds.is_empty()
+        || !dispute.replacement_heads.is_empty()
+    {
+        return Err("A new dispute starts without a panel".into());
     }
 
-    Ok(ValidateCallbackResult::Valid)
+    if dispute.commit_deadline.is_some()
+        || dispute.reveal_deadline.is_some()
+        || dispute.appeal_hash.is_some()
+        || dispute.result_hash.is_some()
+    {
+        return Err("A new dispute starts without deadlines, an appeal or a result".into());
+    }
+
+    Ok(())
 }
 
-fn validate_result(result: &ArbitrationResult) -> ExternResult<ValidateCallbackResult> {
-    // Weighted vote must be valid
-    if result.weighted_vote < 0.0 || result.weighted_vote > 1.0 {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Invalid weighted vote (must be 0.0-1.0)".into(),
-        ));
+fn validate_new_dispute(dispute: &Dispute, action: &Create) -> ExternResult<ValidateCallbackResult> {
+    if let Err(reason) = check_new_dispute(dispute, &action.author) {
+        return Ok(ValidateCallbackResult::Invalid(reason));
     }
 
-    // Must have at least one vote
-    if result.total_votes == 0 {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Must have at least one vote".into(),
+    validate_dispute(dispute)
+}
+
+/// Kind of change a dispute update makes
+#[derive(Debug, Clone, PartialEq)]
+pub enum DisputeChange {
+    /// First panel drawn (Filed -> UnderReview)
+    PanelDraw,
+    /// Appeal panel drawn for a resolved dispute
+    Appeal,
+    /// Non-responsive arbitrators replaced
+    Replacement,
+    /// Candidate snapshot completed or refreshed, panel redrawn
+    Correction,
+    /// Status moved along the lifecycle, panel unchanged
+    Progress,
+}
+
+/// Whether a status is a final resolution
+pub fn is_resolved(status: &DisputeStatus) -> bool {
+    matches!(
+        status,
+        DisputeStatus::ResolvedBuyer | DisputeStatus::ResolvedSeller | DisputeStatus::ResolvedSplit
+    )
+}
+
+/// Check a dispute update against the previous version
+///
+/// Parties, transaction and filing are fixed. Each kind of update has
+/// its own transitions and authors:
+/// - Panel draw (Filed -> UnderReview): the filer
+/// - Appeal (resolved -> UnderReview): the appellant, checked against the
+///   appeal by validation
+/// - Replacement round (while UnderReview/Revealing): a party or panel member
+/// - Candidate correction (while UnderReview, before any replacement): a party
+/// - Withdrawal (from Filed/UnderReview): the filer
+/// - Reveal phase, voting and resolution: a party or panel member; a
+///   resolution records the result it follows, checked by validation
+///
+/// Only draws, appeals, replacements and corrections may touch the panel.
+pub fn check_dispute_update(
+    previous: &Dispute,
+    dispute: &Dispute,
+    author: &AgentPubKey,
+) -> Result<DisputeChange, String> {
+    use DisputeStatus::*;
+
+    if dispute.transaction_hash != previous.transaction_hash
+        || dispute.filed_by != previous.filed_by
+        || dispute.buyer != previous.buyer
+        || dispute.seller != previous.seller
+        || dispute.reason != previous.reason
+        || dispute.evidence_cids != previous.evidence_cids
+        || dispute.created_at != previous.created_at
+    {
+        return Err("Dispute parties, transaction and filing cannot change".into());
+    }
+
+    if dispute.updated_at < previous.updated_at {
+        return Err("Dispute updates cannot predate the previous version".into());
+    }
+
+    let resolving = previous.status == Voting && is_resolved(&dispute.status);
+    let new_result = dispute.result_hash.is_some() && dispute.result_hash != previous.result_hash;
+    if resolving != new_result || (!resolving && dispute.result_hash != previous.result_hash) {
+        return Err("A resolution records a new result, and only a resolution does".into());
+    }
+
+    let is_party = author == &previous.buyer || author == &previous.seller;
+    let on_panel = previous.arbitrators.contains(author);
+
+    if previous.appeal_hash.is_none() && dispute.appeal_hash.is_some() {
+        if !is_resolved(&previous.status) || dispute.status != UnderReview {
+            return Err("An appeal reopens a resolved dispute for review".into());
+        }
+
+        if dispute.original_panel != previous.arbitrators
+            || !dispute.replacement_rounds.is_empty()
+            || !dispute.replacement_heads.is_empty()
+        {
+            return Err("An appeal panel records the first panel and starts without replacements".into());
+        }
+
+        return Ok(DisputeChange::Appeal);
+    }
+
+    if previous.arbitrators.is_empty() && !dispute.arbitrators.is_empty() {
+        if previous.status != Filed || dispute.status != UnderReview {
+            return Err("A panel is drawn once, moving the dispute from Filed to UnderReview".into());
+        }
+
+        if author != &previous.filed_by {
+            return Err("Only the filer can draw the panel".into());
+        }
+
+        if !dispute.replacement_rounds.is_empty()
+            || !dispute.replacement_heads.is_empty()
+            || !dispute.original_panel.is_empty()
+        {
+            return Err("A new panel starts without replacements".into());
+        }
+
+        return Ok(DisputeChange::PanelDraw);
+    }
+
+    let panel_draw_unchanged = dispute.panel_candidates == previous.panel_candidates
+        && dispute.panel_seed == previous.panel_seed
+        && dispute.appeal_hash == previous.appeal_hash
+        && dispute.original_panel == previous.original_panel;
+
+    if dispute.replacement_rounds.len() == previous.replacement_rounds.len() + 1
+        && dispute.replacement_rounds.starts_with(&previous.replacement_rounds)
+    {
+        if !matches!(previous.status, UnderReview | Revealing)
+            || !matches!(dispute.status, UnderReview | Revealing)
+        {
+            return Err("Arbitrators are only replaced while the panel is voting".into());
+        }
+
+        let removed = &dispute.replacement_rounds[previous.replacement_rounds.len()];
+        if removed.is_empty() || removed.iter().any(|agent| !previous.arbitrators.contains(agent)) {
+            return Err("A replacement round removes members of the current panel".into());
+        }
+
+        let cited_heads = dispute.replacement_heads.len() == dispute.replacement_rounds.len()
+            && dispute.replacement_heads.starts_with(&previous.replacement_heads)
+            && dispute.replacement_heads.last().map(Vec::len) == Some(removed.len());
+        if !cited_heads {
+            return Err("A replacement round cites a chain head for each removed arbitrator".into());
+        }
+
+        if !is_party && !on_panel {
+            return Err("Only the parties or the panel can replace arbitrators".into());
+        }
+
+        if !panel_draw_unchanged {
+            return Err("A replacement round keeps the panel's draw".into());
+        }
+
+        return Ok(DisputeChange::Replacement);
+    }
+
+    let same_draw = dispute.panel_seed == previous.panel_seed
+        && dispute.appeal_hash == previous.appeal_hash
+        && dispute.original_panel == previous.original_panel
+        && dispute.replacement_rounds == previous.replacement_rounds
+        && dispute.replacement_heads == previous.replacement_heads
+        && dispute.commit_deadline == previous.commit_deadline
+        && dispute.reveal_deadline == previous.reveal_deadline;
+
+    if dispute.panel_candidates != previous.panel_candidates && same_draw {
+        if previous.status != UnderReview
+            || dispute.status != UnderReview
+            || !previous.replacement_rounds.is_empty()
+        {
+            return Err("Candidates are only corrected before the panel starts voting".into());
+        }
+
+        if !is_party {
+            return Err("Only the parties can correct the panel candidates".into());
+        }
+
+        return Ok(DisputeChange::Correction);
+    }
+
+    if !panel_draw_unchanged
+        || dispute.arbitrators != previous.arbitrators
+        || dispute.replacement_rounds != previous.replacement_rounds
+        || dispute.replacement_heads != previous.replacement_heads
+        || dispute.commit_deadline != previous.commit_deadline
+        || dispute.reveal_deadline != previous.reveal_deadline
+    {
+        return Err("Only a panel draw, replacement, correction or appeal can change the panel".into());
+    }
+
+    let allowed = match (&previous.status, &dispute.status) {
+        (UnderReview, Revealing) | (UnderReview, Voting) | (Revealing, Voting) => {
+            is_party || on_panel
+        }
+        (Voting, ResolvedBuyer) | (Voting, ResolvedSeller) | (Voting, ResolvedSplit) => {
+            is_party || on_panel
+        }
+        (Filed, Withdrawn) | (UnderReview, Withdrawn) => author == &previous.filed_by,
+        (from, to) => {
+            return Err(format!(
+                "Invalid dispute status transition from {:?} to {:?}",
+                from, to
+            ))
+        }
+    };
+
+    if !allowed {
+        return Err(format!(
+            "Agent is not allowed to move this dispute to {:?}",
+            dispute.status
         ));
     }
 
-    // Winner must be either buyer or seller
-    // (This would require access to the dispute entry, so we'll skip for now)
+    Ok(DisputeChange::Progress)
+}
+
+/// Whether a dispute version's panel may reveal votes at a given time
+///
+/// Reveals open once the whole panel has committed (Revealing), or once
+/// the commit deadline has passed with the dispute still under review.
+pub fn reveal_open(dispute: &Dispute, at: Timestamp) -> bool {
+    match dispute.status {
+        DisputeStatus::Revealing => true,
+        DisputeStatus::UnderReview => dispute.commit_deadline.is_some_and(|deadline| at > deadline),
+        _ => false,
+    }
+}
+
+/// Whether a panel may be replaced at a given time
+///
+/// Members who never committed can be replaced once the commit deadline
+/// has passed with the dispute still under review, and members who did
+/// not reveal once the reveal deadline has passed.
+pub fn replacement_due(previous: &Dispute, at: Timestamp) -> bool {
+    let passed = |deadline: Option<Timestamp>| deadline.is_some_and(|deadline| at > deadline);
+
+    passed(previous.reveal_deadline)
+        || (previous.status == DisputeStatus::UnderReview && passed(previous.commit_deadline))
+}
+
+/// Validate dispute updates
+///
+/// Updates are written against the previous version, which they are
+/// checked against (see `check_dispute_update`).
+///
+/// A newly drawn panel must match the seeded draw over the recorded
+/// candidates, so neither party can hand-pick arbitrators. The seed is
+/// the drawing update's own `prev_action`, and each candidate must cite
+/// their arbitrator registration and the MATL score entry their weight
+/// was read from, both written before the seed. Appeal panels are drawn
+/// by the appellant from the higher MATL tier, exclude the first panel
+/// and must be larger than it.
+///
+/// Validation cannot list the registry, so the other party checks the
+/// snapshot: until the commit deadline either party can add registered
+/// arbitrators it left out, or swap a cited score for a later version of
+/// it, and the panel is redrawn under the same seed.
+///
+/// Replacement rounds need a missed phase deadline (see `replacement_due`),
+/// and each removed arbitrator's chain must show they missed it (see
+/// `check_non_response`).
+fn validate_update_dispute(
+    dispute: &Dispute,
+    action: &Update,
+) -> ExternResult<ValidateCallbackResult> {
+    let data_validation = validate_dispute(dispute)?;
+    if let ValidateCallbackResult::Invalid(reason) = data_validation {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    let previous_record = must_get_valid_record(action.original_action_address.clone())?;
+    let previous: Dispute = match previous_record.entry().to_app_option() {
+        Ok(Some(previous)) => previous,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Updated entry must be a dispute".into(),
+            ))
+        }
+    };
+
+    let change = match check_dispute_update(&previous, dispute, &action.author) {
+        Ok(change) => change,
+        Err(reason) => return Ok(ValidateCallbackResult::Invalid(reason)),
+    };
+
+    let dispute_hash = original_action_hash(action.original_action_address.clone())?;
+
+    if change == DisputeChange::Appeal {
+        let appeal_hash = dispute.appeal_hash.clone().unwrap_or(dispute_hash.clone());
+        let appeal_record = must_get_valid_record(appeal_hash)?;
+        let appeal: Appeal = match appeal_record.entry().to_app_option() {
+            Ok(Some(appeal)) => appeal,
+            _ => {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Dispute must reference a valid appeal".into(),
+                ))
+            }
+        };
+
+        if appeal.dispute_hash != dispute_hash || appeal.appellant != action.author {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Only the appellant can draw the panel for their appeal".into(),
+            ));
+        }
+    }
+
+    if change == DisputeChange::Progress {
+        if let Some(result_hash) = &dispute.result_hash {
+            if dispute.result_hash != previous.result_hash {
+                let resolved = check_resolution(
+                    dispute,
+                    &dispute_hash,
+                    &action.original_action_address,
+                    result_hash,
+                )?;
+                if let Err(reason) = resolved {
+                    return Ok(ValidateCallbackResult::Invalid(reason));
+                }
+            }
+        }
+
+        return Ok(ValidateCallbackResult::Valid);
+    }
+
+    let seed_head = match &dispute.panel_seed {
+        Some(seed_head) => seed_head,
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Arbitrator panel is missing its draw seed".into(),
+            ))
+        }
+    };
+
+    let max_arbitrators = max_panel_size(dispute, arbitration_properties()?.max_arbitrators as usize);
+
+    if change == DisputeChange::Replacement {
+        // Replacements (and the penalties that follow them) need a missed deadline
+        if !replacement_due(&previous, action.timestamp) {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Arbitrators can only be replaced once a phase deadline has passed".into(),
+            ));
+        }
+
+        let round = dispute.replacement_rounds.len() - 1;
+        let removed = dispute.replacement_rounds[round].iter();
+        for (arbitrator, head) in removed.zip(&dispute.replacement_heads[round]) {
+            let missed =
+                check_non_response(&previous, &dispute_hash, arbitrator, head, action.timestamp)?;
+            if let Err(reason) = missed {
+                return Ok(ValidateCallbackResult::Invalid(reason));
+            }
+        }
+
+        // Draw and candidates were checked when the panel was drawn
+        let expected = expected_panel(
+            &dispute_hash,
+            seed_head,
+            &panel_pool(dispute),
+            max_arbitrators,
+            &dispute.replacement_rounds,
+        );
+
+        if expected.is_empty() || dispute.arbitrators != expected {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Replacement panel does not match the seeded draw".into(),
+            ));
+        }
+
+        return Ok(ValidateCallbackResult::Valid);
+    }
+
+    if change == DisputeChange::Correction {
+        // Commitments made to the first draw must not be overtaken
+        if dispute.commit_deadline.is_some_and(|deadline| action.timestamp > deadline) {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Panel candidates can only be corrected before the commit deadline".into(),
+            ));
+        }
+
+        if let Err(reason) =
+            check_candidate_correction(&previous.panel_candidates, &dispute.panel_candidates)?
+        {
+            return Ok(ValidateCallbackResult::Invalid(reason));
+        }
+    } else if seed_head != &action.prev_action {
+        // A fresh draw is seeded by the update itself
+        return Ok(ValidateCallbackResult::Invalid(
+            "Panel seed must be the drawing update's previous action".into(),
+        ));
+    }
+
+    let seed_time = must_get_action(seed_head.clone())?.action().timestamp();
+    if let Err(reason) = check_candidate_snapshot(&dispute.panel_candidates, seed_time)? {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    let min_arbitrators = match change {
+        DisputeChange::Appeal => dispute.original_panel.len() + 1,
+        _ => MIN_ARBITRATORS,
+    };
+
+    let panel = draw_arbitrators(&dispute_hash, seed_head, &panel_pool(dispute), max_arbitrators);
+
+    if panel.len() < min_arbitrators {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Arbitrator panel needs at least {} members",
+            min_arbitrators
+        )));
+    }
+
+    if dispute.arbitrators != panel {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Arbitrator panel does not match the seeded draw".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Check that a removed arbitrator missed the deadline they are replaced for
+///
+/// `head` must be an action on the arbitrator's chain written after the
+/// deadline. Their chain up to it, back to the dispute's filing, must
+/// hold no commitment for the dispute (or, once reveals have closed, no
+/// revealed vote).
+pub fn check_non_response(
+    previous: &Dispute,
+    dispute_hash: &ActionHash,
+    arbitrator: &AgentPubKey,
+    head: &ActionHash,
+    at: Timestamp,
+) -> ExternResult<Result<(), String>> {
+    let reveals_closed = previous.reveal_deadline.is_some_and(|deadline| at > deadline);
+    let deadline = if reveals_closed {
+        previous.reveal_deadline
+    } else {
+        previous.commit_deadline
+    };
+    let Some(deadline) = deadline else {
+        return Ok(Err("Dispute has no deadline to miss".into()));
+    };
+
+    let head_action = must_get_action(head.clone())?;
+    if head_action.action().author() != arbitrator || head_action.action().timestamp() <= deadline {
+        return Ok(Err(format!(
+            "Replacement must cite a chain head of {} written after the missed deadline",
+            arbitrator
+        )));
+    }
+
+    for entry in dispute_entries_on_chain(arbitrator, head, dispute_hash)? {
+        let responded = if reveals_closed {
+            ArbitrationVote::try_from(entry).is_ok_and(|vote| &vote.dispute_hash == dispute_hash)
+        } else {
+            VoteCommitment::try_from(entry).is_ok_and(|commitment| &commitment.dispute_hash == dispute_hash)
+        };
+        if responded {
+            return Ok(Err(format!(
+                "Arbitrator {} responded to the dispute and cannot be replaced",
+                arbitrator
+            )));
+        }
+    }
+
+    Ok(Ok(()))
+}
+
+/// App entries an agent created since a dispute was filed
+///
+/// Walks the agent's chain back from `chain_top` to the dispute's filing.
+fn dispute_entries_on_chain(
+    agent: &AgentPubKey,
+    chain_top: &ActionHash,
+    dispute_hash: &ActionHash,
+) -> ExternResult<Vec<Entry>> {
+    let filed_at = must_get_action(dispute_hash.clone())?.action().timestamp();
+    let filter = ChainFilter::new(chain_top.clone()).until_timestamp(filed_at);
+
+    let mut entries = Vec::new();
+    for activity in must_get_agent_activity(agent.clone(), filter)? {
+        if let Action::Create(create) = activity.action.action() {
+            if matches!(create.entry_type, EntryType::App(_)) {
+                entries.push(must_get_entry(create.entry_hash.clone())?.into_content());
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Check a resolution against the result it records
+///
+/// The result must decide the voting version being resolved (itself
+/// validated against the panel's votes), and the status must follow from
+/// its refund.
+fn check_resolution(
+    dispute: &Dispute,
+    dispute_hash: &ActionHash,
+    voting_version: &ActionHash,
+    result_hash: &ActionHash,
+) -> ExternResult<Result<(), String>> {
+    let result_record = must_get_valid_record(result_hash.clone())?;
+    let result: ArbitrationResult = match result_record.entry().to_app_option() {
+        Ok(Some(result)) => result,
+        _ => return Ok(Err("A resolution must record an arbitration result".into())),
+    };
+
+    if &result.dispute_hash != dispute_hash || &result.dispute_version_hash != voting_version {
+        return Ok(Err("The recorded result must decide the version being resolved".into()));
+    }
+
+    let (_, _, status) = resolution(dispute, result.refund_percent);
+    if dispute.status != status {
+        return Ok(Err(format!(
+            "A refund of {}% resolves the dispute as {:?}",
+            result.refund_percent, status
+        )));
+    }
+
+    Ok(Ok(()))
+}
+
+/// Check a candidate snapshot recorded for a draw seeded at `seed_time`
+///
+/// Candidates are ordered by agent, so each arbitrator appears once and
+/// the draw does not depend on who gathered them.
+fn check_candidate_snapshot(
+    candidates: &[ArbitratorCandidate],
+    seed_time: Timestamp,
+) -> ExternResult<Result<(), String>> {
+    if candidates.windows(2).any(|pair| pair[0].agent >= pair[1].agent) {
+        return Ok(Err("Panel candidates must be distinct and ordered by agent".into()));
+    }
+
+    for candidate in candidates {
+        if !(0.0..=1.0).contains(&candidate.matl_score) {
+            return Ok(Err(format!(
+                "Panel candidate {} has an invalid MATL score",
+                candidate.agent
+            )));
+        }
+
+        if let Err(reason) = check_candidate_entries(candidate, seed_time)? {
+            return Ok(Err(reason));
+        }
+    }
+
+    Ok(Ok(()))
+}
+
+/// Check that a corrected snapshot only adds candidates or refreshes scores
+///
+/// Every earlier candidate stays, with the same registration, and a
+/// changed score must be a later version of the one cited before.
+fn check_candidate_correction(
+    previous: &[ArbitratorCandidate],
+    corrected: &[ArbitratorCandidate],
+) -> ExternResult<Result<(), String>> {
+    for earlier in previous {
+        let Some(candidate) = corrected.iter().find(|c| c.agent == earlier.agent) else {
+            return Ok(Err("A correction keeps every earlier candidate".into()));
+        };
+
+        if candidate.registration_hash != earlier.registration_hash {
+            return Ok(Err("A correction keeps each candidate's registration".into()));
+        }
+
+        if candidate.score_hash != earlier.score_hash
+            && !is_later_score(&candidate.score_hash, &earlier.score_hash)?
+        {
+            return Ok(Err(format!(
+                "Corrected score for {} must be a later version of the cited one",
+                candidate.agent
+            )));
+        }
+    }
+
+    Ok(Ok(()))
+}
+
+/// Whether a MATL score was computed, directly or not, from `earlier`
+fn is_later_score(score_hash: &ActionHash, earlier: &ActionHash) -> ExternResult<bool> {
+    let mut current = score_hash.clone();
+    loop {
+        let record = must_get_valid_record(current)?;
+        if !is_matl_score(&record)? {
+            return Ok(false);
+        }
+        let previous = match record.entry().to_app_option::<MatlScoreTerms>() {
+            Ok(Some(score)) => score.previous_score,
+            _ => return Ok(false),
+        };
+
+        match previous {
+            Some(previous) if &previous == earlier => return Ok(true),
+            Some(previous) => current = previous,
+            None => return Ok(false),
+        }
+    }
+}
+
+/// Check a panel candidate against the entries they cite
+///
+/// The candidate must have registered as an arbitrator, and their draw
+/// weight must be the composite of a MATL score entry for them. Both
+/// entries must predate the draw's seed.
+fn check_candidate_entries(
+    candidate: &ArbitratorCandidate,
+    seed_time: Timestamp,
+) -> ExternResult<Result<(), String>> {
+    let registration_record = must_get_valid_record(candidate.registration_hash.clone())?;
+    let registered = matches!(
+        registration_record.entry().to_app_option::<ArbitratorRegistration>(),
+        Ok(Some(registration)) if registration.agent == candidate.agent
+    );
+    if !registered {
+        return Ok(Err(format!(
+            "Panel candidate {} is not a registered arbitrator",
+            candidate.agent
+        )));
+    }
+
+    let score_record = must_get_valid_record(candidate.score_hash.clone())?;
+    let scored = is_matl_score(&score_record)? && matches!(
+        score_record.entry().to_app_option::<MatlScoreTerms>(),
+        Ok(Some(score)) if score.agent == candidate.agent && score.composite == candidate.matl_score
+    );
+    if !scored {
+        return Ok(Err(format!(
+            "Panel candidate {} does not match their MATL score entry",
+            candidate.agent
+        )));
+    }
+
+    if registration_record.action().timestamp() > seed_time
+        || score_record.action().timestamp() > seed_time
+    {
+        return Ok(Err(format!(
+            "Panel candidate {} cites entries written after the draw",
+            candidate.agent
+        )));
+    }
+
+    Ok(Ok(()))
+}
+
+/// Integrity zome that defines `MatlScore`
+const REPUTATION_INTEGRITY_ZOME: &str = "reputation_integrity";
+
+/// Position of `MatlScore` in the reputation zome's `EntryTypes`
+const MATL_SCORE_ENTRY_INDEX: u8 = 0;
+
+/// Whether a record is a `MatlScore` written through the reputation zome
+fn is_matl_score(record: &Record) -> ExternResult<bool> {
+    let Some(EntryType::App(entry_def)) = record.action().entry_type() else {
+        return Ok(false);
+    };
+
+    let zome_names = dna_info()?.zome_names;
+    let from_reputation = zome_names
+        .get(entry_def.zome_index.0 as usize)
+        .is_some_and(|name| name.0 == REPUTATION_INTEGRITY_ZOME);
+
+    Ok(from_reputation && entry_def.entry_index.0 == MATL_SCORE_ENTRY_INDEX)
+}
+
+/// Get a dispute version whose panel includes an agent
+///
+/// The version must belong to the dispute (an update of it, or the
+/// dispute itself).
+fn panel_dispute(
+    dispute_hash: &ActionHash,
+    version_hash: &ActionHash,
+    agent: &AgentPubKey,
+) -> ExternResult<Result<Dispute, String>> {
+    if &original_action_hash(version_hash.clone())? != dispute_hash {
+        return Ok(Err("Dispute version belongs to a different dispute".into()));
+    }
+
+    let record = must_get_valid_record(version_hash.clone())?;
+    let dispute: Dispute = match record.entry().to_app_option() {
+        Ok(Some(dispute)) => dispute,
+        _ => return Ok(Err("Dispute version must be a dispute".into())),
+    };
+
+    if !dispute.arbitrators.contains(agent) {
+        return Ok(Err("Not an assigned arbitrator for this dispute".into()));
+    }
+
+    Ok(Ok(dispute))
+}
+
+/// Follow an update chain back to the action that created the entry
+fn original_action_hash(action_hash: ActionHash) -> ExternResult<ActionHash> {
+    let mut current = action_hash;
+    loop {
+        match must_get_action(current.clone())?.action() {
+            Action::Update(update) => current = update.original_action_address.clone(),
+            _ => return Ok(current),
+        }
+    }
+}
+
+fn validate_dispute_evidence(
+    evidence: &DisputeEvidence,
+    action: &Create,
+) -> ExternResult<ValidateCallbackResult> {
+    // Parties submit evidence for themselves only
+    if evidence.submitted_by != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Evidence must be authored by the submitting party".into(),
+        ));
+    }
+
+    // Statement must not be empty
+    if evidence.statement.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Evidence statement cannot be empty".into(),
+        ));
+    }
+
+    // Statement length limit
+    if evidence.statement.len() > 5000 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Evidence statement too long (max 5000 characters)".into(),
+        ));
+    }
+
+    // Evidence CIDs validation
+    if evidence.evidence_cids.len() > 10 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Maximum 10 evidence files per submission".into(),
+        ));
+    }
+
+    for cid in &evidence.evidence_cids {
+        if !is_valid_ipfs_cid(cid) {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Invalid IPFS CID: {}",
+                cid
+            )));
+        }
+    }
+
+    // Submitter must be a party to the dispute
+    let dispute_record = must_get_valid_record(evidence.dispute_hash.clone())?;
+    let dispute: Dispute = match dispute_record.entry().to_app_option() {
+        Ok(Some(dispute)) => dispute,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Evidence must reference a dispute".into(),
+            ))
+        }
+    };
+
+    if evidence.submitted_by != dispute.buyer && evidence.submitted_by != dispute.seller {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only buyer or seller can submit dispute evidence".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_appeal(appeal: &Appeal, action: &Create) -> ExternResult<ValidateCallbackResult> {
+    // Parties appeal for themselves only
+    if appeal.appellant != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Appeal must be authored by the appellant".into(),
+        ));
+    }
+
+    // Reason must not be empty
+    if appeal.reason.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Appeal reason cannot be empty".into(),
+        ));
+    }
+
+    // Reason length limit
+    if appeal.reason.len() > 5000 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Appeal reason too long (max 5000 characters)".into(),
+        ));
+    }
+
+    // An appeal must put something at risk
+    if appeal.reputation_stake_cents == 0 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Appeal reputation stake must be greater than zero".into(),
+        ));
+    }
+
+    // Appellant must be a party to the dispute
+    let dispute_record = must_get_valid_record(appeal.dispute_hash.clone())?;
+    let dispute: Dispute = match dispute_record.entry().to_app_option() {
+        Ok(Some(dispute)) => dispute,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Appeal must reference a dispute".into(),
+            ))
+        }
+    };
+
+    if appeal.appellant != dispute.buyer && appeal.appellant != dispute.seller {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only buyer or seller can appeal".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Largest panel a dispute may seat
+///
+/// Appeal panels may seat `APPEAL_EXTRA_ARBITRATORS` more than the
+/// `max_arbitrators` DNA property.
+pub fn max_panel_size(dispute: &Dispute, max_arbitrators: usize) -> usize {
+    match dispute.appeal_hash {
+        Some(_) => max_arbitrators + APPEAL_EXTRA_ARBITRATORS,
+        None => max_arbitrators,
+    }
+}
+
+/// Whether an appeal moved the outcome in the appellant's favor
+///
+/// Buyers win an appeal by raising the refund, sellers by lowering it.
+pub fn appeal_succeeded(appellant_is_buyer: bool, original_refund: u8, appeal_refund: u8) -> bool {
+    if appellant_is_buyer {
+        appeal_refund > original_refund
+    } else {
+        appeal_refund < original_refund
+    }
+}
+
+fn validate_vote_commitment(
+    commitment: &VoteCommitment,
+    action: &Create,
+) -> ExternResult<ValidateCallbackResult> {
+    // Arbitrators commit for themselves only
+    if commitment.arbitrator != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Vote commitment must be authored by the arbitrator".into(),
+        ));
+    }
+
+    // BLAKE2b-256 digest
+    if commitment.commitment.len() != 32 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Vote commitment must be a 32-byte hash".into(),
+        ));
+    }
+
+    // Only the panel commits, while the commit phase is open
+    let dispute = match panel_dispute(
+        &commitment.dispute_hash,
+        &commitment.dispute_version_hash,
+        &action.author,
+    )? {
+        Ok(dispute) => dispute,
+        Err(reason) => return Ok(ValidateCallbackResult::Invalid(reason)),
+    };
+
+    if dispute.status != DisputeStatus::UnderReview
+        || dispute.commit_deadline.is_some_and(|deadline| action.timestamp > deadline)
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Commit phase is closed for this dispute version".into(),
+        ));
+    }
+
+    // One commitment per arbitrator, so a reveal cannot pick between them
+    // (no dispute seats an arbitrator twice)
+    let earlier = dispute_entries_on_chain(&action.author, &action.prev_action, &commitment.dispute_hash)?;
+    let committed = earlier.into_iter().any(|entry| {
+        VoteCommitment::try_from(entry).is_ok_and(|earlier| earlier.dispute_hash == commitment.dispute_hash)
+    });
+    if committed {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Arbitrator has already committed a vote for this dispute".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_vote(vote: &ArbitrationVote, action: &Create) -> ExternResult<ValidateCallbackResult> {
+    // Arbitrators reveal for themselves only
+    if vote.arbitrator != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Vote must be authored by the arbitrator".into(),
+        ));
+    }
+
+    // Salt must be long enough to keep the commitment hiding
+    if vote.salt.len() < MIN_COMMITMENT_SALT_LEN {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Vote salt too short (min {} characters)",
+            MIN_COMMITMENT_SALT_LEN
+        )));
+    }
+
+    // Reasoning must not be empty
+    if vote.reasoning.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Vote reasoning cannot be empty".into(),
+        ));
+    }
+
+    // Reasoning length limit
+    if vote.reasoning.len() > 2000 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Vote reasoning too long (max 2000 characters)".into(),
+        ));
+    }
+
+    // MATL score must be valid
+    if vote.arbitrator_matl_score < 0.0 || vote.arbitrator_matl_score > 1.0 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Invalid MATL score (must be 0.0-1.0)".into(),
+        ));
+    }
+
+    // Refund must be one of the allowed steps
+    if !REFUND_PERCENT_STEPS.contains(&vote.refund_percent) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Refund percent must be one of {:?}",
+            REFUND_PERCENT_STEPS
+        )));
+    }
+
+    // Only the panel reveals, once the whole panel has committed
+    // (Revealing) or the commit deadline has passed
+    let dispute = match panel_dispute(&vote.dispute_hash, &vote.dispute_version_hash, &action.author)? {
+        Ok(dispute) => dispute,
+        Err(reason) => return Ok(ValidateCallbackResult::Invalid(reason)),
+    };
+
+    if !reveal_open(&dispute, action.timestamp) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Reveal phase is not open for this dispute version".into(),
+        ));
+    }
+
+    // The reveal must match the arbitrator's own earlier commitment
+    let commitment_record = must_get_valid_record(vote.commitment_hash.clone())?;
+    let commitment: VoteCommitment = match commitment_record.entry().to_app_option() {
+        Ok(Some(commitment)) => commitment,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Vote must reference a vote commitment".into(),
+            ))
+        }
+    };
+
+    if commitment_record.action().author() != &action.author
+        || commitment.dispute_hash != vote.dispute_hash
+        || commitment.arbitrator != vote.arbitrator
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Vote must reveal the arbitrator's own commitment for this dispute".into(),
+        ));
+    }
+
+    let expected = compute_vote_commitment(
+        &vote.dispute_hash,
+        &vote.arbitrator,
+        vote.refund_percent,
+        &vote.salt,
+    )?;
+    if expected != commitment.commitment {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Revealed vote does not match its commitment".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate an arbitration result
+///
+/// The result must be written by a party or a panel member of the
+/// Voting dispute version it cites, and its outcome must follow from the
+/// revealed votes it lists: every panel member's vote, or a quorum once
+/// the reveal deadline has passed.
+fn validate_result(
+    result: &ArbitrationResult,
+    author: &AgentPubKey,
+    timestamp: Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
+    // Weighted vote must be valid
+    if result.weighted_vote < 0.0 || result.weighted_vote > 1.0 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Invalid weighted vote (must be 0.0-1.0)".into(),
+        ));
+    }
+
+    // Must have at least one vote
+    if result.total_votes == 0 || result.total_votes as usize != result.vote_hashes.len() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Must have at least one vote, and count every listed vote".into(),
+        ));
+    }
+
+    // Refund percent must be valid
+    if result.refund_percent > 100 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Refund percent must be 0-100".into(),
+        ));
+    }
+
+    if original_action_hash(result.dispute_version_hash.clone())? != result.dispute_hash {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Dispute version belongs to a different dispute".into(),
+        ));
+    }
+
+    let dispute_record = must_get_valid_record(result.dispute_version_hash.clone())?;
+    let dispute: Dispute = match dispute_record.entry().to_app_option() {
+        Ok(Some(dispute)) => dispute,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Result must reference a dispute version".into(),
+            ))
+        }
+    };
+
+    if dispute.status != DisputeStatus::Voting || dispute.appeal_hash != result.appeal_hash {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Result must decide the dispute's current voting round".into(),
+        ));
+    }
+
+    if author != &dispute.buyer && author != &dispute.seller && !dispute.arbitrators.contains(author) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the parties or the panel can record a result".into(),
+        ));
+    }
+
+    // Tally the listed votes, one per panel member
+    let mut voters: Vec<AgentPubKey> = Vec::new();
+    let mut weighted_refunds: Vec<(u8, f64)> = Vec::new();
+    for vote_hash in &result.vote_hashes {
+        let vote_record = must_get_valid_record(vote_hash.clone())?;
+        let vote: ArbitrationVote = match vote_record.entry().to_app_option() {
+            Ok(Some(vote)) => vote,
+            _ => {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Result must list revealed votes".into(),
+                ))
+            }
+        };
+
+        if vote.dispute_hash != result.dispute_hash
+            || !dispute.arbitrators.contains(&vote.arbitrator)
+            || voters.contains(&vote.arbitrator)
+        {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Result votes must come from distinct members of the panel".into(),
+            ));
+        }
+
+        voters.push(vote.arbitrator);
+        weighted_refunds.push((vote.refund_percent, vote.arbitrator_matl_score));
+    }
+
+    let reveal_closed = dispute.reveal_deadline.is_some_and(|deadline| timestamp > deadline);
+    let complete = voters.len() == dispute.arbitrators.len();
+    let quorate = reveal_closed && has_quorum(voters.len(), dispute.arbitrators.len());
+    if !complete && !quorate {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Result needs every panel vote, or a quorum after the reveal deadline".into(),
+        ));
+    }
+
+    let expected = match tally_refunds(&weighted_refunds) {
+        Some(tally) => tally,
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "No valid votes (total weight is zero)".into(),
+            ))
+        }
+    };
+
+    if result.refund_percent != expected.1 || (result.weighted_vote - expected.0).abs() > 1e-9 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Result does not match the weighted tally of its votes".into(),
+        ));
+    }
+
+    let (winner, loser, _) = resolution(&dispute, result.refund_percent);
+    if result.winner != winner || result.loser != loser {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Winner and loser must follow from the refund decided".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate an appeal result, written as an update to the first result
+fn validate_update_result(
+    result: &ArbitrationResult,
+    action: &Update,
+) -> ExternResult<ValidateCallbackResult> {
+    let previous_record = must_get_valid_record(action.original_action_address.clone())?;
+    let previous: ArbitrationResult = match previous_record.entry().to_app_option() {
+        Ok(Some(previous)) => previous,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Updated entry must be an arbitration result".into(),
+            ))
+        }
+    };
+
+    if previous.dispute_hash != result.dispute_hash
+        || previous.appeal_hash.is_some()
+        || result.appeal_hash.is_none()
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only an appeal result can supersede the dispute's first result".into(),
+        ));
+    }
+
+    validate_result(result, &action.author, action.timestamp)
+}
+
+/// Validate a link from a dispute to its result
+fn validate_dispute_to_result(
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let (Some(dispute_hash), Some(result_hash)) =
+        (base_address.into_action_hash(), target_address.into_action_hash())
+    else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Dispute results link a dispute to a result action".into(),
+        ));
+    };
+
+    let record = must_get_valid_record(result_hash)?;
+    match record.entry().to_app_option::<ArbitrationResult>() {
+        Ok(Some(result)) if result.dispute_hash == dispute_hash => {
+            Ok(ValidateCallbackResult::Valid)
+        }
+        _ => Ok(ValidateCallbackResult::Invalid(
+            "Dispute result link must target a result for that dispute".into(),
+        )),
+    }
+}
+
+fn validate_arbitrator_registration(
+    registration: &ArbitratorRegistration,
+    action: &Create,
+) -> ExternResult<ValidateCallbackResult> {
+    // Agents can only register themselves
+    if registration.agent != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Arbitrator registration must be authored by the registering agent".into(),
+        ));
+    }
 
     Ok(ValidateCallbackResult::Valid)
 }
@@ -0,0 +1,562 @@
+//! Paginated listing browse
+//!
+//! Listings are linked from a bucket per creation day
+//! (`all_listings.YYYY.MM.DD`). The days that have listings are indexed
+//! as a calendar: `all_listings.years` links each year
+//! (`all_listings.YYYY`), a year links its months (`all_listings.YYYY.MM`)
+//! and a month links its day buckets. Every calendar link is tagged with
+//! the number of the day it was made for, which validation rebuilds the
+//! linked paths from. A page walks the days newest
+//! or oldest first, reading the calendar only as far as it gets and one
+//! bucket at a time, and stops once it has `limit` matches or has fetched
+//! `MAX_SCANNED_PER_PAGE` listings, so the work per page is bounded
+//! however many listings exist. The cursor records the day and the
+//! position in its bucket to resume from.
+//!
+//! Listings created before the day buckets existed are only on the
+//! `all_listings` anchor, and days bucketed before the calendar existed
+//! are missing from it, until `backfill_days` links them in. Only a
+//! listing's seller can link it into a bucket, so each seller backfills
+//! their own listings.
+
+use hdk::prelude::*;
+use listings_integrity::*;
+use mycelix_common::{error_handling, link_queries};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::index;
+
+/// Default page size
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+
+/// Largest page a caller can ask for
+pub const MAX_PAGE_SIZE: usize = 50;
+
+/// Most listings fetched (matching or not) while building one page
+pub const MAX_SCANNED_PER_PAGE: usize = 200;
+
+/// Order a browse walks listings in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum ListingSort {
+    /// Most recently created first
+    #[default]
+    Newest,
+    /// Earliest created first
+    Oldest,
+}
+
+/// Where the next page starts
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BrowseCursor {
+    /// Day bucket (days since the Unix epoch)
+    pub day: i64,
+
+    /// Links of that bucket already walked
+    pub position: usize,
+}
+
+/// Most `all_listings` links one backfill call checks
+pub const MAX_BACKFILL_PER_CALL: usize = 100;
+
+/// Link a new listing into its creation day's bucket
+///
+/// The first listing of a day also links the day into the calendar.
+pub fn index_listing_by_day(listing_hash: &ActionHash, created_at: Timestamp) -> ExternResult<()> {
+    let day = day_number(created_at);
+    let bucket = day_bucket_path(day).path_entry_hash()?;
+
+    let bucket_was_empty =
+        link_queries::get_links_local(bucket.clone(), LinkTypes::AllListings)?.is_empty();
+
+    create_link(bucket, listing_hash.clone(), LinkTypes::AllListings, ())?;
+
+    if bucket_was_empty {
+        index_day(day)?;
+    }
+
+    Ok(())
+}
+
+/// Link a day's bucket into the calendar, with its month and year if they
+/// are not there yet
+pub fn index_day(day: i64) -> ExternResult<()> {
+    let (year, month, _) = civil_date(day);
+    let levels = [
+        (CalendarLevel::Years, year),
+        (CalendarLevel::Months(year), i64::from(month)),
+        (CalendarLevel::Days(year, month), day),
+    ];
+
+    for ((level, number), (base, target)) in levels.into_iter().zip(calendar_links(day)) {
+        if !calendar_entries(level)?.contains(&number) {
+            create_link(
+                base.path_entry_hash()?,
+                target.path_entry_hash()?,
+                LinkTypes::AllListings,
+                day_tag(day),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A level of the calendar of days that have listings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalendarLevel {
+    Years,
+    Months(i64),
+    Days(i64, u32),
+}
+
+/// Numbers a calendar level links to, in ascending order
+///
+/// Years for `Years`, months for `Months` and day numbers for `Days`,
+/// read from the days the level's links are tagged with.
+pub fn calendar_entries(level: CalendarLevel) -> ExternResult<Vec<i64>> {
+    let path = match level {
+        CalendarLevel::Years => years_path(),
+        CalendarLevel::Months(year) => year_path(year),
+        CalendarLevel::Days(year, month) => month_path(year, month),
+    };
+
+    // Use shared utility for get_links
+    let links = link_queries::get_links_local(path.path_entry_hash()?, LinkTypes::AllListings)?;
+
+    let mut numbers: Vec<i64> = links
+        .iter()
+        .filter_map(|link| tagged_day(&link.tag))
+        .map(|day| match level {
+            CalendarLevel::Years => civil_date(day).0,
+            CalendarLevel::Months(_) => i64::from(civil_date(day).1),
+            CalendarLevel::Days(_, _) => day,
+        })
+        .collect();
+
+    numbers.sort_unstable();
+    numbers.dedup();
+
+    Ok(numbers)
+}
+
+/// The calendar of days that have listings, read lazily and cached
+///
+/// `load` reads one level of the calendar (ascending); each level is
+/// read at most once.
+pub struct ListingDays<F> {
+    load: F,
+    levels: BTreeMap<(i64, i64, i64), Vec<i64>>,
+}
+
+impl<F> ListingDays<F>
+where
+    F: FnMut(CalendarLevel) -> ExternResult<Vec<i64>>,
+{
+    pub fn new(load: F) -> Self {
+        ListingDays {
+            load,
+            levels: BTreeMap::new(),
+        }
+    }
+
+    /// The day with listings after `after` in `sort` order (the first day
+    /// when `after` is None)
+    ///
+    /// Only the years and months that can hold that day are read.
+    pub fn next_day(&mut self, after: Option<i64>, sort: ListingSort) -> ExternResult<Option<i64>> {
+        let after_date = after.map(civil_date);
+        // Whether the walk has already passed `value` on its way to `bound`
+        let passed = |value: i64, bound: i64| match sort {
+            ListingSort::Newest => value > bound,
+            ListingSort::Oldest => value < bound,
+        };
+
+        for year in self.entries(CalendarLevel::Years, sort)? {
+            if after_date.is_some_and(|(after_year, _, _)| passed(year, after_year)) {
+                continue;
+            }
+
+            for month in self.entries(CalendarLevel::Months(year), sort)? {
+                let month = month as u32;
+                let month_passed = after_date.is_some_and(|(after_year, after_month, _)| {
+                    year == after_year && passed(i64::from(month), i64::from(after_month))
+                });
+                if month_passed {
+                    continue;
+                }
+
+                for day in self.entries(CalendarLevel::Days(year, month), sort)? {
+                    if after.is_none_or(|after| day != after && !passed(day, after)) {
+                        return Ok(Some(day));
+                    }
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// One level of the calendar in walking order
+    fn entries(&mut self, level: CalendarLevel, sort: ListingSort) -> ExternResult<Vec<i64>> {
+        let key = match level {
+            CalendarLevel::Years => (0, 0, 0),
+            CalendarLevel::Months(year) => (1, year, 0),
+            CalendarLevel::Days(year, month) => (2, year, i64::from(month)),
+        };
+
+        let mut entries = match self.levels.get(&key) {
+            Some(entries) => entries.clone(),
+            None => {
+                let entries = (self.load)(level)?;
+                self.levels.insert(key, entries.clone());
+                entries
+            }
+        };
+
+        if sort == ListingSort::Newest {
+            entries.reverse();
+        }
+
+        Ok(entries)
+    }
+}
+
+/// Listings in a day's bucket, in `sort` order
+pub fn day_listings(day: i64, sort: ListingSort) -> ExternResult<Vec<ActionHash>> {
+    // Use shared utility for get_links
+    let mut links =
+        link_queries::get_links_local(day_bucket_path(day).path_entry_hash()?, LinkTypes::AllListings)?;
+
+    // Stable order so cursors stay valid between pages
+    links.sort_by(|a, b| {
+        b.timestamp
+            .cmp(&a.timestamp)
+            .then_with(|| a.create_link_hash.cmp(&b.create_link_hash))
+    });
+    if sort == ListingSort::Oldest {
+        links.reverse();
+    }
+
+    Ok(first_of_each(
+        links
+            .into_iter()
+            .filter_map(|link| link.target.into_action_hash())
+            .collect(),
+    ))
+}
+
+/// Drop repeats, keeping each item's first position
+///
+/// A listing can be linked into its bucket twice when backfills overlap.
+pub fn first_of_each<T: Ord + Clone>(items: Vec<T>) -> Vec<T> {
+    let mut seen = BTreeSet::new();
+    items
+        .into_iter()
+        .filter(|item| seen.insert(item.clone()))
+        .collect()
+}
+
+/// Link the caller's listings missing from their creation day's bucket,
+/// and those listings' days missing from the calendar
+///
+/// Walks the `all_listings` anchor oldest first, checking up to `limit`
+/// links from `position` and skipping other sellers' listings. Returns how many listings were linked and the
+/// position to continue from (None once the anchor has been walked).
+pub fn backfill_days(position: usize, limit: usize) -> ExternResult<(u32, Option<usize>)> {
+    let seller = agent_info()?.agent_initial_pubkey;
+
+    // Use shared utility for get_links
+    let mut links = link_queries::get_links_local(
+        all_listings_path().path_entry_hash()?,
+        LinkTypes::AllListings,
+    )?;
+
+    // Stable order so positions stay valid between calls
+    links.sort_by(|a, b| {
+        a.timestamp
+            .cmp(&b.timestamp)
+            .then_with(|| a.create_link_hash.cmp(&b.create_link_hash))
+    });
+
+    let total = links.len();
+    let end = position.saturating_add(limit).min(total);
+
+    let mut buckets: BTreeMap<i64, Vec<ActionHash>> = BTreeMap::new();
+    let mut linked = 0;
+
+    for link in links.into_iter().take(end).skip(position) {
+        let listing_hash = match index::get_listing_target(link)? {
+            Some(listing_hash) => listing_hash,
+            None => continue,
+        };
+        let record = match get(listing_hash.clone(), GetOptions::default())? {
+            Some(record) if record.action().author() == &seller => record,
+            _ => continue,
+        };
+        // Use shared utility for deserialization
+        let listing: Listing = error_handling::deserialize_entry(&record)?;
+
+        let day = day_number(listing.created_at);
+        let bucket = match buckets.entry(day) {
+            std::collections::btree_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::btree_map::Entry::Vacant(entry) => {
+                let bucket = day_listings(day, ListingSort::Newest)?;
+                if !bucket.is_empty() {
+                    index_day(day)?;
+                }
+                entry.insert(bucket)
+            }
+        };
+        if bucket.contains(&listing_hash) {
+            continue;
+        }
+
+        index_listing_by_day(&listing_hash, listing.created_at)?;
+        bucket.push(listing_hash);
+        linked += 1;
+    }
+
+    Ok((linked, (end < total).then_some(end)))
+}
+
+/// Walk day buckets in order, collecting up to `limit` matches
+///
+/// `next_day` gives the day after a day in walking order (the first day
+/// for None), `load` reads a day's bucket and `accept` fetches and
+/// filters one item. Stops after `max_scanned` items; returns the matches
+/// and, if the walk stopped early, the cursor to resume from.
+pub fn walk_days<T, R, N, L, A>(
+    cursor: Option<&BrowseCursor>,
+    limit: usize,
+    max_scanned: usize,
+    mut next_day: N,
+    mut load: L,
+    mut accept: A,
+) -> ExternResult<(Vec<R>, Option<BrowseCursor>)>
+where
+    N: FnMut(Option<i64>) -> ExternResult<Option<i64>>,
+    L: FnMut(i64) -> ExternResult<Vec<T>>,
+    A: FnMut(&T) -> ExternResult<Option<R>>,
+{
+    let mut matches = Vec::new();
+    let mut scanned = 0;
+
+    let mut next = match cursor {
+        Some(cursor) => Some(cursor.day),
+        None => next_day(None)?,
+    };
+
+    while let Some(day) = next {
+        let items = load(day)?;
+        let start = match cursor {
+            Some(cursor) if cursor.day == day => cursor.position,
+            _ => 0,
+        };
+
+        for (position, item) in items.iter().enumerate().skip(start) {
+            if matches.len() == limit || scanned == max_scanned {
+                return Ok((matches, Some(BrowseCursor { day, position })));
+            }
+
+            scanned += 1;
+            if let Some(found) = accept(item)? {
+                matches.push(found);
+            }
+        }
+
+        next = next_day(Some(day))?;
+    }
+
+    Ok((matches, None))
+}
+
+/// Whether a listing passes the browse filters (seller MATL aside)
+pub fn matches_filters(
+    listing: &Listing,
+    category: Option<&ListingCategory>,
+    currency: Option<&str>,
+    min_price_cents: Option<u64>,
+    max_price_cents: Option<u64>,
+) -> bool {
+    listing.status == ListingStatus::Active
+        && category.is_none_or(|category| &listing.category == category)
+        && currency.is_none_or(|currency| listing.currency == currency)
+        && min_price_cents.is_none_or(|min| listing.price_cents >= min)
+        && max_price_cents.is_none_or(|max| listing.price_cents <= max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_of_each_keeps_first_positions() {
+        assert_eq!(first_of_each(vec![3, 1, 3, 2, 1]), vec![3, 1, 2]);
+        assert_eq!(first_of_each::<u32>(vec![]), Vec::<u32>::new());
+    }
+
+    fn buckets() -> Vec<(i64, Vec<u32>)> {
+        vec![(30, vec![1, 2, 3]), (20, vec![4, 5]), (10, vec![6])]
+    }
+
+    fn walk(
+        cursor: Option<&BrowseCursor>,
+        limit: usize,
+        max_scanned: usize,
+        accept: impl Fn(u32) -> bool,
+    ) -> (Vec<u32>, Option<BrowseCursor>) {
+        let buckets = buckets();
+        let days: Vec<i64> = buckets.iter().map(|(day, _)| *day).collect();
+
+        walk_days(
+            cursor,
+            limit,
+            max_scanned,
+            |after| {
+                Ok(match after {
+                    Some(after) => days.iter().skip_while(|day| **day != after).nth(1).copied(),
+                    None => days.first().copied(),
+                })
+            },
+            |day| Ok(buckets.iter().find(|(d, _)| *d == day).unwrap().1.clone()),
+            |item| Ok(accept(*item).then_some(*item)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_walk_pages_across_days() {
+        let (page, cursor) = walk(None, 2, 100, |_| true);
+        assert_eq!(page, vec![1, 2]);
+        assert_eq!(cursor, Some(BrowseCursor { day: 30, position: 2 }));
+
+        let (page, cursor) = walk(cursor.as_ref(), 2, 100, |_| true);
+        assert_eq!(page, vec![3, 4]);
+        assert_eq!(cursor, Some(BrowseCursor { day: 20, position: 1 }));
+
+        let (page, cursor) = walk(cursor.as_ref(), 2, 100, |_| true);
+        assert_eq!(page, vec![5, 6]);
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn test_walk_stops_after_scan_budget() {
+        // Only even items match; the budget runs out before the page fills
+        let (page, cursor) = walk(None, 10, 3, |item| item % 2 == 0);
+        assert_eq!(page, vec![2]);
+        assert_eq!(cursor, Some(BrowseCursor { day: 20, position: 0 }));
+
+        let (page, cursor) = walk(cursor.as_ref(), 10, 3, |item| item % 2 == 0);
+        assert_eq!(page, vec![4, 6]);
+        assert_eq!(cursor, None);
+    }
+
+    /// Calendar over `days`, counting the levels read
+    fn calendar<'a>(
+        days: &[i64],
+        reads: &'a mut Vec<CalendarLevel>,
+    ) -> impl FnMut(CalendarLevel) -> ExternResult<Vec<i64>> + 'a {
+        let dates: Vec<(i64, i64, u32)> = days
+            .iter()
+            .map(|&day| {
+                let (year, month, _) = civil_date(day);
+                (day, year, month)
+            })
+            .collect();
+
+        move |level| {
+            reads.push(level);
+            let mut entries: Vec<i64> = dates
+                .iter()
+                .filter_map(|&(day, year, month)| match level {
+                    CalendarLevel::Years => Some(year),
+                    CalendarLevel::Months(y) => (year == y).then_some(i64::from(month)),
+                    CalendarLevel::Days(y, m) => (year == y && month == m).then_some(day),
+                })
+                .collect();
+            entries.sort_unstable();
+            entries.dedup();
+            Ok(entries)
+        }
+    }
+
+    #[test]
+    fn test_listing_days_cross_months_and_years_in_order() {
+        // 2024-12-31, 2025-01-01, 2025-10-01, 2025-10-17
+        let days = [20_088, 20_089, 20_362, 20_378];
+        let mut reads = Vec::new();
+        let mut calendar = ListingDays::new(calendar(&days, &mut reads));
+
+        let mut newest = Vec::new();
+        let mut day = calendar.next_day(None, ListingSort::Newest).unwrap();
+        while let Some(found) = day {
+            newest.push(found);
+            day = calendar.next_day(Some(found), ListingSort::Newest).unwrap();
+        }
+        assert_eq!(newest, vec![20_378, 20_362, 20_089, 20_088]);
+
+        let mut oldest = Vec::new();
+        let mut day = calendar.next_day(None, ListingSort::Oldest).unwrap();
+        while let Some(found) = day {
+            oldest.push(found);
+            day = calendar.next_day(Some(found), ListingSort::Oldest).unwrap();
+        }
+        assert_eq!(oldest, vec![20_088, 20_089, 20_362, 20_378]);
+    }
+
+    #[test]
+    fn test_listing_days_read_only_what_the_walk_reaches() {
+        let days = [20_088, 20_089, 20_362, 20_378];
+        let mut reads = Vec::new();
+        {
+            let mut calendar = ListingDays::new(calendar(&days, &mut reads));
+            assert_eq!(calendar.next_day(None, ListingSort::Newest).unwrap(), Some(20_378));
+            assert_eq!(calendar.next_day(Some(20_378), ListingSort::Newest).unwrap(), Some(20_362));
+        }
+
+        // Only 2025's October is read, once
+        assert_eq!(
+            reads,
+            vec![CalendarLevel::Years, CalendarLevel::Months(2025), CalendarLevel::Days(2025, 10)]
+        );
+    }
+
+    #[test]
+    fn test_filters() {
+        let listing = Listing {
+            title: "Lamp".to_string(),
+            description: "Brass desk lamp".to_string(),
+            price_cents: 4500,
+            currency: "USD".to_string(),
+            category: ListingCategory::HomeGarden,
+            photos_ipfs_cids: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            quantity_available: 1,
+            option_axes: vec![],
+            variants: vec![],
+            status: ListingStatus::Active,
+            publish_at: None,
+            expires_at: None,
+            price_changed_at: None,
+            epistemic: EpistemicClassification {
+                empirical: EmpiricalLevel::E1Testimonial,
+                normative: NormativeLevel::N0Personal,
+                materiality: MaterialityLevel::M1Temporal,
+            },
+            created_at: Timestamp::from_micros(0),
+            updated_at: Timestamp::from_micros(0),
+        };
+
+        assert!(matches_filters(&listing, None, None, None, None));
+        assert!(matches_filters(&listing, Some(&ListingCategory::HomeGarden), Some("USD"), Some(4500), Some(4500)));
+        assert!(!matches_filters(&listing, Some(&ListingCategory::Electronics), None, None, None));
+        assert!(!matches_filters(&listing, None, Some("EUR"), None, None));
+        assert!(!matches_filters(&listing, None, None, Some(5000), None));
+        assert!(!matches_filters(&listing, None, None, None, Some(4000)));
+
+        let sold = Listing {
+            status: ListingStatus::Sold,
+            ..listing
+        };
+        assert!(!matches_filters(&sold, None, None, None, None));
+    }
+}
@@ -0,0 +1,257 @@
+//! Listing revision history
+//!
+//! Every update to a listing is kept on the DHT; these helpers turn the
+//! versions into revisions with field-level diffs, and derive the series
+//! of prices the listing was offered at. Bookkeeping fields (`updated_at`,
+//! `price_changed_at`) are left out of the diffs.
+
+use hdk::prelude::*;
+use listings_integrity::*;
+
+/// One field that changed between two versions
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// One version of a listing and what changed from the version before
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ListingRevision {
+    pub version_hash: ActionHash,
+    pub revised_at: Timestamp,
+    pub listing: Listing,
+    /// Empty for the first version
+    pub changes: Vec<FieldChange>,
+}
+
+/// A price the listing was offered at, from `from` until the next point
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PricePoint {
+    pub version_hash: ActionHash,
+    pub price_cents: u64,
+    pub currency: String,
+    /// Unit price of each variant; empty for listings without variants
+    pub variant_prices: Vec<VariantPrice>,
+    pub from: Timestamp,
+}
+
+/// A variant's unit price at a price point
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VariantPrice {
+    pub sku: String,
+    pub price_cents: u64,
+}
+
+/// Fields that differ between two versions of a listing
+pub fn diff_listings(before: &Listing, after: &Listing) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    let mut compare = |field: &str, before: String, after: String| {
+        if before != after {
+            changes.push(FieldChange {
+                field: field.to_string(),
+                before,
+                after,
+            });
+        }
+    };
+
+    let time = |timestamp: Option<Timestamp>| {
+        timestamp
+            .map(|timestamp| timestamp.as_micros().to_string())
+            .unwrap_or_default()
+    };
+
+    compare("title", before.title.clone(), after.title.clone());
+    compare("description", before.description.clone(), after.description.clone());
+    compare("price_cents", before.price_cents.to_string(), after.price_cents.to_string());
+    compare("currency", before.currency.clone(), after.currency.clone());
+    compare("category", format!("{:?}", before.category), format!("{:?}", after.category));
+    compare(
+        "photos_ipfs_cids",
+        before.photos_ipfs_cids.join(","),
+        after.photos_ipfs_cids.join(","),
+    );
+    compare(
+        "quantity_available",
+        before.quantity_available.to_string(),
+        after.quantity_available.to_string(),
+    );
+    compare("option_axes", format!("{:?}", before.option_axes), format!("{:?}", after.option_axes));
+    compare("variants", format!("{:?}", before.variants), format!("{:?}", after.variants));
+    compare("status", format!("{:?}", before.status), format!("{:?}", after.status));
+    compare("publish_at", time(before.publish_at), time(after.publish_at));
+    compare("expires_at", time(before.expires_at), time(after.expires_at));
+
+    changes
+}
+
+/// Build revisions from a listing's versions (oldest first)
+pub fn revisions(versions: Vec<(ActionHash, Timestamp, Listing)>) -> Vec<ListingRevision> {
+    let mut revisions: Vec<ListingRevision> = Vec::with_capacity(versions.len());
+
+    for (version_hash, revised_at, listing) in versions {
+        let changes = revisions
+            .last()
+            .map(|previous| diff_listings(&previous.listing, &listing))
+            .unwrap_or_default();
+
+        revisions.push(ListingRevision {
+            version_hash,
+            revised_at,
+            listing,
+            changes,
+        });
+    }
+
+    revisions
+}
+
+/// The prices a listing was offered at, oldest first
+///
+/// The first version sets the opening price; after that only revisions
+/// that changed a price (the listing's, its currency or a variant's) add
+/// a point.
+pub fn price_history(revisions: &[ListingRevision]) -> Vec<PricePoint> {
+    revisions
+        .iter()
+        .enumerate()
+        .filter(|(i, revision)| {
+            *i == 0 || revision.listing.prices_differ(&revisions[i - 1].listing)
+        })
+        .map(|(_, revision)| {
+            let listing = &revision.listing;
+            PricePoint {
+                version_hash: revision.version_hash.clone(),
+                price_cents: listing.price_cents,
+                currency: listing.currency.clone(),
+                variant_prices: listing
+                    .variants
+                    .iter()
+                    .map(|variant| VariantPrice {
+                        sku: variant.sku.clone(),
+                        price_cents: listing.variant_price_cents(variant),
+                    })
+                    .collect(),
+                from: revision.revised_at,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_listing() -> Listing {
+        Listing {
+            title: "Lamp".to_string(),
+            description: "Brass desk lamp".to_string(),
+            price_cents: 4500,
+            currency: "USD".to_string(),
+            category: ListingCategory::HomeGarden,
+            photos_ipfs_cids: vec!["QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()],
+            quantity_available: 3,
+            option_axes: vec![],
+            variants: vec![],
+            status: ListingStatus::Active,
+            publish_at: None,
+            expires_at: None,
+            price_changed_at: None,
+            epistemic: EpistemicClassification {
+                empirical: EmpiricalLevel::E1Testimonial,
+                normative: NormativeLevel::N0Personal,
+                materiality: MaterialityLevel::M1Temporal,
+            },
+            created_at: Timestamp::from_micros(1000),
+            updated_at: Timestamp::from_micros(1000),
+        }
+    }
+
+    fn version(n: u8, at: i64, listing: Listing) -> (ActionHash, Timestamp, Listing) {
+        (ActionHash::from_raw_36(vec![n; 36]), Timestamp::from_micros(at), listing)
+    }
+
+    #[test]
+    fn test_diff_lists_changed_fields_only() {
+        let before = mock_listing();
+        let mut after = before.clone();
+        after.price_cents = 5200;
+        after.quantity_available = 2;
+        after.updated_at = Timestamp::from_micros(2000); // Bookkeeping, not a change
+        after.price_changed_at = Some(Timestamp::from_micros(2000));
+
+        assert_eq!(
+            diff_listings(&before, &after),
+            vec![
+                FieldChange {
+                    field: "price_cents".to_string(),
+                    before: "4500".to_string(),
+                    after: "5200".to_string(),
+                },
+                FieldChange {
+                    field: "quantity_available".to_string(),
+                    before: "3".to_string(),
+                    after: "2".to_string(),
+                },
+            ]
+        );
+        assert!(diff_listings(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn test_price_history_follows_price_changes() {
+        let opening = mock_listing();
+        let mut restocked = opening.clone();
+        restocked.quantity_available = 10;
+        let mut raised = restocked.clone();
+        raised.price_cents = 5200;
+        let mut repriced = raised.clone();
+        repriced.currency = "EUR".to_string();
+
+        let revisions = revisions(vec![
+            version(1, 1000, opening),
+            version(2, 2000, restocked),
+            version(3, 3000, raised),
+            version(4, 4000, repriced),
+        ]);
+
+        assert!(revisions[0].changes.is_empty());
+        assert_eq!(revisions[1].changes[0].field, "quantity_available");
+
+        let history = price_history(&revisions);
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].price_cents, 4500);
+        assert_eq!(history[1].price_cents, 5200);
+        assert_eq!(history[1].from, Timestamp::from_micros(3000));
+        assert_eq!(history[2].currency, "EUR");
+    }
+
+    #[test]
+    fn test_price_history_includes_variant_prices() {
+        let mut opening = mock_listing();
+        opening.variants = vec![ListingVariant {
+            sku: "TEE-S".to_string(),
+            options: vec!["S".to_string()],
+            price_cents: None,
+            quantity_available: 3,
+            photos_ipfs_cids: vec![],
+        }];
+        let mut repriced = opening.clone();
+        repriced.variants[0].price_cents = Some(4900);
+
+        let history = price_history(&revisions(vec![
+            version(1, 1000, opening),
+            version(2, 2000, repriced),
+        ]));
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            history[0].variant_prices,
+            vec![VariantPrice { sku: "TEE-S".to_string(), price_cents: 4500 }]
+        );
+        assert_eq!(history[1].variant_prices[0].price_cents, 4900);
+        assert_eq!(history[1].price_cents, 4500);
+    }
+}
@@ -0,0 +1,127 @@
+//! Discovery indexes for listings
+//!
+//! Every listing is linked, by its original action hash, from:
+//! - its seller (`AgentToListings`)
+//! - the `all_listings` anchor and its creation-day bucket (`AllListings`,
+//!   see `browse`)
+//! - `listings.status.{status}` (`StatusToListings`), whatever the status
+//! - `listings.category.{category}` (`CategoryToListings`), while buyers
+//!   can browse it (not a draft, scheduled or deleted)
+//!
+//! Only the seller can create these links, from the base the listing
+//! belongs under; status and category links are tagged with the version
+//! they index so validation can check the path against it.
+//!
+//! `reindex_listing` runs on every update: it deletes the listing's status
+//! and category links and recreates them from the new version, so the
+//! indexes always reflect the latest state. Links created before listings
+//! were indexed by action hash target the original entry instead; they
+//! are found through `get_listing_target` and replaced on the next update.
+
+use hdk::prelude::*;
+use listings_integrity::*;
+use mycelix_common::link_queries;
+
+use crate::browse;
+
+/// Link a new listing into every index
+pub fn index_listing(
+    listing_hash: &ActionHash,
+    seller: AgentPubKey,
+    listing: &Listing,
+) -> ExternResult<()> {
+    create_link(seller, listing_hash.clone(), LinkTypes::AgentToListings, ())?;
+
+    create_link(
+        all_listings_path().path_entry_hash()?,
+        listing_hash.clone(),
+        LinkTypes::AllListings,
+        (),
+    )?;
+    browse::index_listing_by_day(listing_hash, listing.created_at)?;
+
+    link_status_and_category(listing_hash, listing_hash, listing)
+}
+
+/// Replace a listing's status and category links after an update
+///
+/// `version_hash` is the update that wrote `current`.
+pub fn reindex_listing(
+    listing_hash: &ActionHash,
+    version_hash: &ActionHash,
+    previous: &Listing,
+    current: &Listing,
+) -> ExternResult<()> {
+    unlink(
+        status_path(&previous.status),
+        LinkTypes::StatusToListings,
+        listing_hash,
+    )?;
+    unlink(
+        category_path(&previous.category),
+        LinkTypes::CategoryToListings,
+        listing_hash,
+    )?;
+
+    link_status_and_category(listing_hash, version_hash, current)
+}
+
+/// Listing a link points at, as its original action hash
+///
+/// Older links target the listing's original entry; those resolve through
+/// the entry's creating action.
+pub fn get_listing_target(link: Link) -> ExternResult<Option<ActionHash>> {
+    if let Some(action_hash) = link.target.clone().into_action_hash() {
+        return Ok(Some(action_hash));
+    }
+
+    match link.target.into_entry_hash() {
+        Some(entry_hash) => Ok(get(entry_hash, GetOptions::default())?
+            .map(|record| record.action_address().clone())),
+        None => Ok(None),
+    }
+}
+
+fn link_status_and_category(
+    listing_hash: &ActionHash,
+    version_hash: &ActionHash,
+    listing: &Listing,
+) -> ExternResult<()> {
+    // Note: HDK 0.6.0 - Path.ensure() removed, paths auto-created
+    create_link(
+        status_path(&listing.status).path_entry_hash()?,
+        listing_hash.clone(),
+        LinkTypes::StatusToListings,
+        version_tag(version_hash),
+    )?;
+
+    if is_browsable(&listing.status) {
+        create_link(
+            category_path(&listing.category).path_entry_hash()?,
+            listing_hash.clone(),
+            LinkTypes::CategoryToListings,
+            version_tag(version_hash),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Delete the links from `path` to a listing (by action or original entry)
+fn unlink(path: Path, link_type: LinkTypes, listing_hash: &ActionHash) -> ExternResult<()> {
+    let original_entry_hash = get(listing_hash.clone(), GetOptions::default())?
+        .and_then(|record| record.action().entry_hash().cloned());
+
+    // Use shared utility for get_links
+    for link in link_queries::get_links_local(path.path_entry_hash()?, link_type)? {
+        let targets_entry = link.target.clone().into_entry_hash();
+        let targets_listing = link.target.clone().into_action_hash().as_ref() == Some(listing_hash)
+            || (targets_entry.is_some() && targets_entry == original_entry_hash);
+
+        if targets_listing {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,6 +1,17 @@
 use hdk::prelude::*;
 use listings_integrity::*;
-use mycelix_common::{error_handling, link_queries, time};
+use mycelix_common::{error_handling, link_queries, money, records, remote_calls, time};
+use std::collections::{BTreeMap, BTreeSet};
+
+mod browse;
+mod history;
+mod index;
+mod notify;
+
+const MICROS_PER_HOUR: i64 = 60 * 60 * 1_000_000;
+
+/// How long before expiry a sweep warns the seller
+const LISTING_EXPIRY_WARNING_HOURS: i64 = 48;
 
 /// Create a new listing
 ///
@@ -18,42 +29,32 @@ pub fn create_listing(input: CreateListingInput) -> ExternResult<ListingOutput>
     let sanitized_description = security::sanitize_user_input(&input.description);
 
     // Validate and sanitize IPFS CIDs
-    let mut sanitized_cids = Vec::new();
-    for cid in &input.photos_ipfs_cids {
-        match security::sanitize_ipfs_cid(cid) {
-            Ok(clean_cid) => sanitized_cids.push(clean_cid),
-            Err(e) => {
-                return Err(wasm_error!(WasmErrorInner::Guest(
-                    format!("Invalid IPFS CID: {}", e)
-                )));
-            }
-        }
-    }
+    let sanitized_cids = sanitize_cids(&input.photos_ipfs_cids)?;
 
     // Validate price and quantity
-    security::validate_price(input.price_cents).map_err(|e| {
-        wasm_error!(WasmErrorInner::Guest(format!(
-            "Invalid price: {}",
-            e
-        )))
-    })?;
+    let currency = input
+        .currency
+        .unwrap_or_else(|| money::REFERENCE_CURRENCY.to_string());
+    validate_listing_price(input.price_cents, &currency)?;
 
-    security::validate_quantity(input.quantity_available).map_err(|e| {
-        wasm_error!(WasmErrorInner::Guest(format!(
-            "Invalid quantity: {}",
-            e
-        )))
-    })?;
+    let now = time::now()?;
+    let status = initial_status(input.draft, input.publish_at, now);
 
     // Build listing with Epistemic Charter classification
-    let listing = Listing {
+    let mut listing = Listing {
         title: sanitized_title,
         description: sanitized_description,
         price_cents: input.price_cents,
+        currency,
         category: input.category,
         photos_ipfs_cids: sanitized_cids,
         quantity_available: input.quantity_available,
-        status: ListingStatus::Active,
+        option_axes: input.option_axes,
+        variants: input.variants,
+        status,
+        publish_at: input.publish_at,
+        expires_at: input.expires_at,
+        price_changed_at: None,
         epistemic: EpistemicClassification {
             // Seller's testimonial claim
             empirical: EmpiricalLevel::E1Testimonial,
@@ -62,55 +63,25 @@ pub fn create_listing(input: CreateListingInput) -> ExternResult<ListingOutput>
             // Temporal - prune when sold
             materiality: MaterialityLevel::M1Temporal,
         },
-        created_at: time::now()?,
-        updated_at: time::now()?,
+        created_at: now,
+        updated_at: now,
     };
 
+    apply_variants(&mut listing)?;
+
+    security::validate_quantity(listing.quantity_available).map_err(|e| {
+        wasm_error!(WasmErrorInner::Guest(format!(
+            "Invalid quantity: {}",
+            e
+        )))
+    })?;
+
     // Create entry on DHT
     let action_hash = create_entry(&EntryTypes::Listing(listing.clone()))?;
-    let entry_hash = hash_entry(&listing)?;
-
-    // Create discovery links
 
-    // 1. Agent -> Listing (seller's listings)
-    // Clone to avoid move since we use agent_initial_pubkey again later
+    // Create discovery links (agent, all, status, category)
     let agent_path = agent_info.agent_initial_pubkey.clone();
-    create_link(
-        agent_path.clone(), // Clone here since we use agent_path again for monitoring
-        entry_hash.clone(),
-        LinkTypes::AgentToListings,
-        (),
-    )?;
-
-    // 2. Category -> Listing (browse by category)
-    let category_path = Path::from(format!("listings.category.{:?}", listing.category));
-    // Note: HDK 0.6.0 - Path.ensure() removed, paths auto-created
-    create_link(
-        category_path.path_entry_hash()?,
-        entry_hash.clone(),
-        LinkTypes::CategoryToListings,
-        (),
-    )?;
-
-    // 3. Status -> Listing (filter by status)
-    let status_path = Path::from(format!("listings.status.{:?}", listing.status));
-    // Note: HDK 0.6.0 - Path.ensure() removed, paths auto-created
-    create_link(
-        status_path.path_entry_hash()?,
-        entry_hash.clone(),
-        LinkTypes::StatusToListings,
-        (),
-    )?;
-
-    // 4. All Listings anchor
-    let all_path = Path::from("all_listings");
-    // Note: HDK 0.6.0 - Path.ensure() removed, paths auto-created
-    create_link(
-        all_path.path_entry_hash()?,
-        entry_hash.clone(),
-        LinkTypes::AllListings,
-        (),
-    )?;
+    index::index_listing(&action_hash, agent_path.clone(), &listing)?;
 
     // Emit monitoring metric
     monitoring::emit_metric(
@@ -121,16 +92,46 @@ pub fn create_listing(input: CreateListingInput) -> ExternResult<ListingOutput>
     )?;
 
     Ok(ListingOutput {
-        listing_hash: action_hash.into(),
+        listing_hash: action_hash,
         listing,
-        seller_agent_id: agent_info.agent_initial_pubkey.into(),
+        seller_agent_id: agent_info.agent_initial_pubkey,
     })
 }
 
+/// Validate and sanitize photo IPFS CIDs
+fn sanitize_cids(cids: &[String]) -> ExternResult<Vec<String>> {
+    let mut sanitized_cids = Vec::new();
+    for cid in cids {
+        match security::sanitize_ipfs_cid(cid) {
+            Ok(clean_cid) => sanitized_cids.push(clean_cid),
+            Err(e) => {
+                return Err(wasm_error!(WasmErrorInner::Guest(
+                    format!("Invalid IPFS CID: {}", e)
+                )));
+            }
+        }
+    }
+    Ok(sanitized_cids)
+}
+
+/// Status a new listing starts in
+///
+/// Drafts stay drafts until the seller publishes them; a publish time in
+/// the future schedules the listing, otherwise it goes live at once.
+pub fn initial_status(draft: bool, publish_at: Option<Timestamp>, now: Timestamp) -> ListingStatus {
+    match publish_at {
+        _ if draft => ListingStatus::Draft,
+        Some(publish_at) if publish_at > now => ListingStatus::Scheduled,
+        _ => ListingStatus::Active,
+    }
+}
+
 /// Get a specific listing by hash
+///
+/// Returns the latest version of the listing.
 #[hdk_extern]
 pub fn get_listing(listing_hash: ActionHash) -> ExternResult<Option<ListingOutput>> {
-    let record = get(listing_hash.clone(), GetOptions::default())?;
+    let record = records::get_latest_record(listing_hash.clone())?;
 
     match record {
         Some(record) => {
@@ -138,9 +139,9 @@ pub fn get_listing(listing_hash: ActionHash) -> ExternResult<Option<ListingOutpu
             let listing: Listing = error_handling::deserialize_entry(&record)?;
 
             Ok(Some(ListingOutput {
-                listing_hash: listing_hash.into(),
+                listing_hash,
                 listing,
-                seller_agent_id: record.action().author().clone().into(),
+                seller_agent_id: record.action().author().clone(),
             }))
         }
         None => Ok(None),
@@ -148,19 +149,21 @@ pub fn get_listing(listing_hash: ActionHash) -> ExternResult<Option<ListingOutpu
 }
 
 /// Get all listings in the marketplace
+///
+/// Reads every listing; prefer `browse_listings` for pages.
 #[hdk_extern]
 pub fn get_all_listings(_: ()) -> ExternResult<ListingsResponse> {
-    let path = Path::from("all_listings");
+    let path = all_listings_path();
     // Use shared utility for get_links
     let links = link_queries::get_links_local(path.path_entry_hash()?, LinkTypes::AllListings)?;
 
     let mut listings = Vec::new();
 
     for link in links {
-        if let Some(action_hash) = link.target.into_action_hash() {
+        if let Some(action_hash) = index::get_listing_target(link)? {
             if let Some(listing_output) = get_listing(action_hash)? {
-                // Filter out deleted/inactive if needed
-                if listing_output.listing.status != ListingStatus::Deleted {
+                // Filter out deleted and unpublished listings
+                if is_browsable(&listing_output.listing.status) {
                     listings.push(listing_output);
                 }
             }
@@ -170,18 +173,118 @@ pub fn get_all_listings(_: ()) -> ExternResult<ListingsResponse> {
     Ok(ListingsResponse { listings })
 }
 
+/// Browse active listings a page at a time
+///
+/// Filters by category, currency, price range and the seller's MATL
+/// composite score, sorts newest or oldest first, and returns the cursor
+/// for the next page (None on the last page). A cursor resumes the sort
+/// it was returned for. See `browse` for how pages are bounded.
+#[hdk_extern]
+pub fn browse_listings(input: BrowseListingsInput) -> ExternResult<BrowsePage> {
+    let limit = input
+        .limit
+        .map(|limit| limit as usize)
+        .unwrap_or(browse::DEFAULT_PAGE_SIZE)
+        .clamp(1, browse::MAX_PAGE_SIZE);
+
+    if input.min_seller_matl.is_some_and(|floor| !(0.0..=1.0).contains(&floor)) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Seller MATL floor must be between 0 and 1".into()
+        )));
+    }
+
+    // One MATL lookup per seller per page
+    let mut seller_scores: BTreeMap<AgentPubKey, f64> = BTreeMap::new();
+
+    let mut days = browse::ListingDays::new(browse::calendar_entries);
+    let (listings, next_cursor) = browse::walk_days(
+        input.cursor.as_ref(),
+        limit,
+        browse::MAX_SCANNED_PER_PAGE,
+        |after| days.next_day(after, input.sort),
+        |day| browse::day_listings(day, input.sort),
+        |listing_hash| {
+            let output = match get_listing(listing_hash.clone())? {
+                Some(output) => output,
+                None => return Ok(None),
+            };
+
+            let matches = browse::matches_filters(
+                &output.listing,
+                input.category.as_ref(),
+                input.currency.as_deref(),
+                input.min_price_cents,
+                input.max_price_cents,
+            );
+            if !matches {
+                return Ok(None);
+            }
+
+            if let Some(floor) = input.min_seller_matl {
+                let score = match seller_scores.get(&output.seller_agent_id) {
+                    Some(score) => *score,
+                    None => {
+                        let score = get_matl_composite(output.seller_agent_id.clone())?;
+                        seller_scores.insert(output.seller_agent_id.clone(), score);
+                        score
+                    }
+                };
+                if score < floor {
+                    return Ok(None);
+                }
+            }
+
+            Ok(Some(output))
+        },
+    )?;
+
+    Ok(BrowsePage {
+        listings,
+        next_cursor,
+    })
+}
+
+/// Link the caller's listings created before day-bucketed browsing into
+/// their buckets
+///
+/// Only a listing's seller may link it, so every seller runs this for
+/// their own listings. Checks a batch of the `all_listings` anchor per call; call again with
+/// `next_position` until it comes back None.
+#[hdk_extern]
+pub fn backfill_listing_days(input: BackfillListingDaysInput) -> ExternResult<BackfillListingDaysOutput> {
+    let limit = input
+        .limit
+        .map(|limit| limit as usize)
+        .unwrap_or(browse::MAX_BACKFILL_PER_CALL)
+        .clamp(1, browse::MAX_BACKFILL_PER_CALL);
+
+    let (linked, next_position) = browse::backfill_days(input.position as usize, limit)?;
+
+    Ok(BackfillListingDaysOutput {
+        linked,
+        next_position: next_position.map(|position| position as u32),
+    })
+}
+
 /// Get listings by seller
+///
+/// Sellers see all their listings; others do not see drafts or
+/// scheduled listings.
 #[hdk_extern]
 pub fn get_listings_by_seller(agent_id: AgentPubKey) -> ExternResult<ListingsResponse> {
+    let own_listings = agent_id == agent_info()?.agent_initial_pubkey;
+
     // Use shared utility for get_links
     let links = link_queries::get_links_local(agent_id, LinkTypes::AgentToListings)?;
 
     let mut listings = Vec::new();
 
     for link in links {
-        if let Some(action_hash) = link.target.into_action_hash() {
+        if let Some(action_hash) = index::get_listing_target(link)? {
             if let Some(listing_output) = get_listing(action_hash)? {
-                listings.push(listing_output);
+                if own_listings || is_published(&listing_output.listing.status) {
+                    listings.push(listing_output);
+                }
             }
         }
     }
@@ -199,16 +302,49 @@ pub fn get_my_listings(_: ()) -> ExternResult<ListingsResponse> {
 /// Get listings by category
 #[hdk_extern]
 pub fn get_listings_by_category(category: ListingCategory) -> ExternResult<ListingsResponse> {
-    let path = Path::from(format!("listings.category.{:?}", category));
+    let path = category_path(&category);
     // Use shared utility for get_links
     let links = link_queries::get_links_local(path.path_entry_hash()?, LinkTypes::CategoryToListings)?;
 
     let mut listings = Vec::new();
 
     for link in links {
-        if let Some(action_hash) = link.target.into_action_hash() {
+        if let Some(action_hash) = index::get_listing_target(link)? {
+            if let Some(listing_output) = get_listing(action_hash)? {
+                // Links are rebuilt on update, but skip any that lag behind
+                let listing = &listing_output.listing;
+                if listing.category == category && is_browsable(&listing.status) {
+                    listings.push(listing_output);
+                }
+            }
+        }
+    }
+
+    Ok(ListingsResponse { listings })
+}
+
+/// Get listings by status
+///
+/// Drafts and scheduled listings are only returned to their seller.
+#[hdk_extern]
+pub fn get_listings_by_status(status: ListingStatus) -> ExternResult<ListingsResponse> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let path = status_path(&status);
+    // Use shared utility for get_links
+    let links = link_queries::get_links_local(path.path_entry_hash()?, LinkTypes::StatusToListings)?;
+
+    let mut listings = Vec::new();
+
+    for link in links {
+        if let Some(action_hash) = index::get_listing_target(link)? {
             if let Some(listing_output) = get_listing(action_hash)? {
-                listings.push(listing_output);
+                // Links are rebuilt on update, but skip any that lag behind
+                if listing_output.listing.status != status {
+                    continue;
+                }
+                if is_published(&status) || listing_output.seller_agent_id == me {
+                    listings.push(listing_output);
+                }
             }
         }
     }
@@ -217,60 +353,175 @@ pub fn get_listings_by_category(category: ListingCategory) -> ExternResult<Listi
 }
 
 /// Update a listing
+///
+/// Updates are applied to the latest version and recorded against it, so
+/// stock changes made in between are not lost and validation can compare
+/// each version with the one before. Text and photo CIDs are sanitized as
+/// on create, and only legal status changes are accepted (restocking a
+/// sold listing puts it back on offer). The listing's status and category
+/// links are rebuilt from the new version.
 #[hdk_extern]
 pub fn update_listing(input: UpdateListingInput) -> ExternResult<ListingOutput> {
-    // Get the original listing
-    let original_record = get(input.listing_hash.clone(), GetOptions::default())?
+    let original_hash = records::get_original_action_hash(input.listing_hash.clone())?;
+
+    // Get the latest version of the listing
+    let latest_record = records::get_latest_record(original_hash.clone())?
         .ok_or(wasm_error!(WasmErrorInner::Guest(
             "Listing not found".into()
         )))?;
 
     // Use shared utility for deserialization
-    let mut listing: Listing = error_handling::deserialize_entry(&original_record)?;
+    let mut listing: Listing = error_handling::deserialize_entry(&latest_record)?;
+    let previous = listing.clone();
 
     // Verify ownership
     let agent_info = agent_info()?;
-    if original_record.action().author() != &agent_info.agent_initial_pubkey {
+    if latest_record.action().author() != &agent_info.agent_initial_pubkey {
         return Err(wasm_error!(WasmErrorInner::Guest(
             "Only the seller can update their listing".into()
         )));
     }
 
-    // Apply updates
+    // Apply updates, sanitized as on create
     if let Some(title) = input.title {
-        listing.title = title;
+        listing.title = security::sanitize_user_input(&title);
     }
     if let Some(description) = input.description {
-        listing.description = description;
+        listing.description = security::sanitize_user_input(&description);
     }
     if let Some(price_cents) = input.price_cents {
         listing.price_cents = price_cents;
     }
+    if let Some(currency) = input.currency {
+        listing.currency = currency;
+    }
     if let Some(category) = input.category {
         listing.category = category;
     }
     if let Some(photos_ipfs_cids) = input.photos_ipfs_cids {
-        listing.photos_ipfs_cids = photos_ipfs_cids;
+        listing.photos_ipfs_cids = sanitize_cids(&photos_ipfs_cids)?;
     }
     if let Some(quantity_available) = input.quantity_available {
         listing.quantity_available = quantity_available;
     }
+    if let Some(option_axes) = input.option_axes {
+        listing.option_axes = option_axes;
+    }
+    if let Some(publish_at) = input.publish_at {
+        listing.publish_at = Some(publish_at);
+    }
+    if let Some(expires_at) = input.expires_at {
+        listing.expires_at = Some(expires_at);
+    }
+    if let Some(variants) = input.variants {
+        listing.variants = variants;
+    }
     if let Some(status) = input.status {
         listing.status = status;
     }
 
+    validate_listing_price(listing.price_cents, &listing.currency)?;
+    apply_variants(&mut listing)?;
+
+    if previous.status == ListingStatus::Sold
+        && listing.status == ListingStatus::Sold
+        && listing.quantity_available > 0
+    {
+        listing.status = ListingStatus::Active;
+    }
+
     listing.updated_at = time::now()?;
 
+    // Record price changes, at most one per MIN_PRICE_CHANGE_INTERVAL_HOURS
+    if listing.prices_differ(&previous) {
+        listing.price_changed_at = Some(listing.updated_at);
+    }
+    check_price_change(&previous, &listing, listing.updated_at)
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?;
+    check_status_change(&previous, &listing, listing.updated_at)
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?;
+
     // Create update
-    let action_hash = update_entry(input.listing_hash, &EntryTypes::Listing(listing.clone()))?;
+    let action_hash = update_entry(
+        latest_record.action_address().clone(),
+        &EntryTypes::Listing(listing.clone()),
+    )?;
+
+    // Keep the status and category indexes on the latest version
+    index::reindex_listing(&original_hash, &action_hash, &previous, &listing)?;
 
     Ok(ListingOutput {
-        listing_hash: action_hash.into(),
+        listing_hash: action_hash,
         listing,
-        seller_agent_id: agent_info.agent_initial_pubkey.into(),
+        seller_agent_id: agent_info.agent_initial_pubkey,
     })
 }
 
+/// Get every revision of a listing, oldest first, with field-level diffs
+#[hdk_extern]
+pub fn get_listing_history(listing_hash: ActionHash) -> ExternResult<Vec<history::ListingRevision>> {
+    let original_hash = records::get_original_action_hash(listing_hash)?;
+
+    let mut versions = Vec::new();
+    for record in records::get_all_versions(original_hash)? {
+        // Use shared utility for deserialization
+        let listing: Listing = error_handling::deserialize_entry(&record)?;
+        versions.push((record.action_address().clone(), record.action().timestamp(), listing));
+    }
+
+    Ok(history::revisions(versions))
+}
+
+/// Get the prices a listing has been offered at, oldest first
+#[hdk_extern]
+pub fn get_price_history(listing_hash: ActionHash) -> ExternResult<Vec<history::PricePoint>> {
+    let revisions = get_listing_history(listing_hash)?;
+    Ok(history::price_history(&revisions))
+}
+
+/// Check a price against the cap for its currency
+///
+/// Custom currencies registered in the DNA properties carry their own cap;
+/// ISO-4217 currencies use the caps in `security::validate_price`.
+fn validate_listing_price(price_minor: u64, currency: &str) -> ExternResult<()> {
+    let custom_currencies = money::registered_currencies()?;
+
+    let result = match money::find_custom_currency(&custom_currencies, currency) {
+        Some(custom) => {
+            security::validate_price_with_limit(price_minor, currency, custom.max_price_minor)
+        }
+        None => security::validate_price(price_minor, currency),
+    };
+
+    result
+        .map(|_| ())
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Invalid price: {}", e))))
+}
+
+/// Check variant prices and total the variants' stock
+///
+/// For listings with variants, stock is kept per variant and
+/// `quantity_available` is always their total.
+fn apply_variants(listing: &mut Listing) -> ExternResult<()> {
+    if listing.variants.is_empty() {
+        return Ok(());
+    }
+
+    for variant in &listing.variants {
+        validate_listing_price(listing.variant_price_cents(variant), &listing.currency)?;
+    }
+
+    listing.quantity_available = listing
+        .variants
+        .iter()
+        .try_fold(0u32, |total, variant| total.checked_add(variant.quantity_available))
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Variant stock overflows".into()
+        )))?;
+
+    Ok(())
+}
+
 /// Delete a listing (soft delete by setting status to Deleted)
 #[hdk_extern]
 pub fn delete_listing(listing_hash: ActionHash) -> ExternResult<()> {
@@ -279,15 +530,580 @@ pub fn delete_listing(listing_hash: ActionHash) -> ExternResult<()> {
         title: None,
         description: None,
         price_cents: None,
+        currency: None,
         category: None,
         photos_ipfs_cids: None,
         quantity_available: None,
+        option_axes: None,
+        variants: None,
+        publish_at: None,
+        expires_at: None,
         status: Some(ListingStatus::Deleted),
     })?;
 
     Ok(())
 }
 
+/// Renew a listing until a new expiry time (seller)
+///
+/// Active listings get the new expiry; listings that expired (or were
+/// paused) go live again.
+#[hdk_extern]
+pub fn renew_listing(input: RenewListingInput) -> ExternResult<ListingOutput> {
+    let listing = get_listing(input.listing_hash.clone())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("Listing not found".into())
+    ))?;
+
+    if input.expires_at <= time::now()? {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Renewed listings must expire in the future".into()
+        )));
+    }
+
+    let status = match listing.listing.status {
+        ListingStatus::Active => None,
+        ListingStatus::Inactive => Some(ListingStatus::Active),
+        other => {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Cannot renew a listing with status {:?}",
+                other
+            ))))
+        }
+    };
+
+    update_listing(UpdateListingInput {
+        listing_hash: input.listing_hash,
+        title: None,
+        description: None,
+        price_cents: None,
+        currency: None,
+        category: None,
+        photos_ipfs_cids: None,
+        quantity_available: None,
+        option_axes: None,
+        variants: None,
+        publish_at: None,
+        expires_at: Some(input.expires_at),
+        status,
+    })
+}
+
+/// Publish the caller's due scheduled listings and expire their lapsed ones
+///
+/// - Scheduled listings whose publish time has passed go live
+/// - Active listings whose expiry has passed move to `Inactive` (and
+///   under the inactive status path); the seller is notified
+/// - Active listings expiring within `LISTING_EXPIRY_WARNING_HOURS` raise
+///   an expiry warning so the seller can renew them
+/// - Stock committed to a transaction that was since cancelled goes back
+///   into the listing (a buyer who cancels cannot restock it themselves)
+///
+/// Besides the signals, the seller gets a `ListingExpiring` notification
+/// from the notifications zome: one warning per listing and expiry, and
+/// one when the listing expires (see `notify`). A listing the sweep cannot
+/// act on is reported in `failed` and the sweep moves on to the next.
+#[hdk_extern]
+pub fn sweep_listings(_: ()) -> ExternResult<ListingSweepOutput> {
+    let now = time::now()?;
+    let mut output = ListingSweepOutput::default();
+    let sent_notifications = notify::sent_notification_ids()?;
+
+    for listing_hash in my_listing_hashes()? {
+        let swept = sweep_listing(&listing_hash, now, &sent_notifications, &mut output);
+        if let Err(error) = swept {
+            output.failed.push(ListingSweepFailure {
+                listing_hash,
+                error: error.to_string(),
+            });
+        }
+    }
+
+    Ok(output)
+}
+
+/// Act on one listing for `sweep_listings`
+fn sweep_listing(
+    listing_hash: &ActionHash,
+    now: Timestamp,
+    sent_notifications: &BTreeSet<String>,
+    output: &mut ListingSweepOutput,
+) -> ExternResult<()> {
+    output
+        .restocked
+        .extend(restore_cancelled_stock(listing_hash.clone())?);
+
+    let listing = match get_listing(listing_hash.clone())? {
+        Some(output) => output.listing,
+        None => return Ok(()),
+    };
+
+    let status = match listing_sweep_action(&listing, now) {
+        Some(ListingSweepAction::Publish) => ListingStatus::Active,
+        Some(ListingSweepAction::Expire) => ListingStatus::Inactive,
+        Some(ListingSweepAction::WarnExpiring) => {
+            let notification = notify::expiry_notification(
+                notify::ExpiryEvent::Expiring,
+                listing_hash,
+                &listing.title,
+                listing.expires_at,
+                now,
+            );
+            if !sent_notifications.contains(&notification.id) {
+                notify::send(notification);
+            }
+
+            emit_signal(ListingSignal::ListingExpiring {
+                listing_hash: listing_hash.clone(),
+                title: listing.title,
+                expires_at: listing.expires_at,
+            })?;
+            output.expiring.push(listing_hash.clone());
+            return Ok(());
+        }
+        None => return Ok(()),
+    };
+
+    update_listing(UpdateListingInput {
+        listing_hash: listing_hash.clone(),
+        title: None,
+        description: None,
+        price_cents: None,
+        currency: None,
+        category: None,
+        photos_ipfs_cids: None,
+        quantity_available: None,
+        option_axes: None,
+        variants: None,
+        publish_at: None,
+        expires_at: None,
+        status: Some(status.clone()),
+    })?;
+
+    if status == ListingStatus::Inactive {
+        notify::send(notify::expiry_notification(
+            notify::ExpiryEvent::Expired,
+            listing_hash,
+            &listing.title,
+            listing.expires_at,
+            now,
+        ));
+
+        emit_signal(ListingSignal::ListingExpired {
+            listing_hash: listing_hash.clone(),
+            title: listing.title,
+            expires_at: listing.expires_at,
+        })?;
+        output.expired.push(listing_hash.clone());
+    } else {
+        output.published.push(listing_hash.clone());
+    }
+
+    Ok(())
+}
+
+/// What a sweep does with a listing at `now`, if anything
+pub fn listing_sweep_action(listing: &Listing, now: Timestamp) -> Option<ListingSweepAction> {
+    match listing.status {
+        ListingStatus::Scheduled => match listing.publish_at {
+            Some(publish_at) if publish_at <= now => Some(ListingSweepAction::Publish),
+            _ => None,
+        },
+        ListingStatus::Active => match listing.expires_at {
+            Some(expires_at) if expires_at <= now => Some(ListingSweepAction::Expire),
+            Some(expires_at)
+                if expires_at.as_micros()
+                    <= now.as_micros() + LISTING_EXPIRY_WARNING_HOURS * MICROS_PER_HOUR =>
+            {
+                Some(ListingSweepAction::WarnExpiring)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Reserve stock for a new transaction (buyer)
+///
+/// Called by the transactions zome when a buyer creates a transaction.
+/// Fails if the listing is not active or does not have enough
+/// unreserved stock, so two buyers cannot both take the last unit.
+/// Listings with variants are reserved per variant.
+#[hdk_extern]
+pub fn reserve_stock(input: ReserveStockInput) -> ExternResult<ReservationOutput> {
+    let buyer = agent_info()?.agent_initial_pubkey;
+    let listing_hash = records::get_original_action_hash(input.listing_hash)?;
+
+    let listing = get_listing(listing_hash.clone())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("Listing not found".into())
+    ))?;
+
+    if listing.listing.status != ListingStatus::Active {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Cannot buy from listing with status {:?}",
+            listing.listing.status
+        ))));
+    }
+
+    let stock = variant_stock(&listing.listing, input.variant_sku.as_deref())
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?;
+
+    let now = time::now()?;
+    let reservations = get_listing_reservations(listing_hash.clone())?;
+    let available = available_stock(
+        stock,
+        reservations
+            .iter()
+            .map(|r| &r.reservation)
+            .filter(|r| r.variant_sku == input.variant_sku),
+        now,
+    );
+
+    if input.quantity == 0 || input.quantity > available {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Only {} units available",
+            available
+        ))));
+    }
+
+    let reservation = InventoryReservation {
+        listing_hash: listing_hash.clone(),
+        transaction_hash: input.transaction_hash.clone(),
+        buyer,
+        quantity: input.quantity,
+        variant_sku: input.variant_sku,
+        status: ReservationStatus::Reserved,
+        expires_at: Timestamp::from_micros(now.as_micros() + RESERVATION_TTL_HOURS * MICROS_PER_HOUR),
+        created_at: now,
+        updated_at: now,
+    };
+
+    let action_hash = create_entry(&EntryTypes::InventoryReservation(reservation.clone()))?;
+
+    create_link(
+        listing_hash,
+        action_hash.clone(),
+        LinkTypes::ListingToReservations,
+        (),
+    )?;
+
+    create_link(
+        input.transaction_hash,
+        action_hash.clone(),
+        LinkTypes::TransactionToReservation,
+        (),
+    )?;
+
+    Ok(ReservationOutput {
+        reservation_hash: action_hash,
+        reservation,
+    })
+}
+
+/// Commit a transaction's reservations (seller)
+///
+/// Takes each line's reserved units out of `quantity_available` (and out
+/// of the reserved variant's stock). When stock reaches zero the listing
+/// moves to `Sold`. An expired reservation can still be committed if the
+/// stock has not been taken by someone else. Returns the updated listings.
+#[hdk_extern]
+pub fn commit_reservation(transaction_hash: ActionHash) -> ExternResult<Vec<ListingOutput>> {
+    let reservations = get_transaction_reservations(transaction_hash)?;
+    if reservations.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "No stock reservation for this transaction".into()
+        )));
+    }
+
+    reservations
+        .into_iter()
+        .map(|(reservation_hash, reservation)| commit_one_reservation(reservation_hash, reservation))
+        .collect()
+}
+
+/// Commit one reservation for `commit_reservation`
+fn commit_one_reservation(
+    reservation_hash: ActionHash,
+    mut reservation: InventoryReservation,
+) -> ExternResult<ListingOutput> {
+    if reservation.status != ReservationStatus::Reserved {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Cannot commit reservation with status {:?}",
+            reservation.status
+        ))));
+    }
+
+    let listing = get_listing(reservation.listing_hash.clone())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("Listing not found".into())
+    ))?;
+
+    let stock = variant_stock(&listing.listing, reservation.variant_sku.as_deref())
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?;
+
+    // Stock must still cover this reservation after all the others
+    let now = time::now()?;
+    let other_reservations = get_listing_reservations(reservation.listing_hash.clone())?
+        .into_iter()
+        .filter(|r| {
+            r.reservation.transaction_hash != reservation.transaction_hash
+                && r.reservation.variant_sku == reservation.variant_sku
+        })
+        .collect::<Vec<_>>();
+    let available = available_stock(
+        stock,
+        other_reservations.iter().map(|r| &r.reservation),
+        now,
+    );
+
+    if reservation.quantity > available {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Only {} units left to commit",
+            available
+        ))));
+    }
+
+    let remaining = listing.listing.quantity_available - reservation.quantity;
+
+    let variants = match &reservation.variant_sku {
+        Some(sku) => {
+            let mut variants = listing.listing.variants.clone();
+            for variant in variants.iter_mut().filter(|v| &v.sku == sku) {
+                variant.quantity_available -= reservation.quantity;
+            }
+            Some(variants)
+        }
+        None => None,
+    };
+
+    // Only the seller can update the listing
+    let updated = update_listing(UpdateListingInput {
+        listing_hash: reservation.listing_hash.clone(),
+        title: None,
+        description: None,
+        price_cents: None,
+        currency: None,
+        category: None,
+        photos_ipfs_cids: None,
+        quantity_available: Some(remaining),
+        option_axes: None,
+        variants,
+        publish_at: None,
+        expires_at: None,
+        status: if remaining == 0 {
+            Some(ListingStatus::Sold)
+        } else {
+            None
+        },
+    })?;
+
+    reservation.status = ReservationStatus::Committed;
+    reservation.updated_at = now;
+    update_entry(reservation_hash, &EntryTypes::InventoryReservation(reservation))?;
+
+    Ok(updated)
+}
+
+/// Release a transaction's reservations
+///
+/// A reservation still holding stock is dropped (buyer or seller). A
+/// committed one has its units put back into the listing, moving a `Sold`
+/// listing back to `Active`; only the seller can restock, so for anyone
+/// else a committed reservation is left for the seller's `sweep_listings`
+/// to release. Reservations already released are skipped. Returns the
+/// reservations released.
+#[hdk_extern]
+pub fn release_reservation(transaction_hash: ActionHash) -> ExternResult<Vec<ReservationOutput>> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    let mut released = Vec::new();
+
+    for (reservation_hash, mut reservation) in get_transaction_reservations(transaction_hash)? {
+        match reservation.status {
+            ReservationStatus::Reserved => {}
+            ReservationStatus::Committed => {
+                let listing = get_listing(reservation.listing_hash.clone())?.ok_or(wasm_error!(
+                    WasmErrorInner::Guest("Listing not found".into())
+                ))?;
+
+                if listing.seller_agent_id != caller {
+                    continue;
+                }
+
+                let input = restock_input(&listing.listing, &reservation)
+                    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?;
+                update_listing(input)?;
+            }
+            ReservationStatus::Released => continue,
+        }
+
+        reservation.status = ReservationStatus::Released;
+        reservation.updated_at = time::now()?;
+
+        let action_hash = update_entry(
+            reservation_hash,
+            &EntryTypes::InventoryReservation(reservation.clone()),
+        )?;
+
+        released.push(ReservationOutput {
+            reservation_hash: action_hash,
+            reservation,
+        });
+    }
+
+    Ok(released)
+}
+
+/// Restock a listing's reservations committed to cancelled transactions
+/// (seller), returns the transactions restocked
+fn restore_cancelled_stock(listing_hash: ActionHash) -> ExternResult<Vec<ActionHash>> {
+    let mut restocked = Vec::new();
+
+    for output in get_listing_reservations(listing_hash)? {
+        if output.reservation.status != ReservationStatus::Committed {
+            continue;
+        }
+
+        let transaction_hash = output.reservation.transaction_hash.clone();
+        let status = get_transaction_status(transaction_hash.clone())?;
+
+        if needs_restock(&output.reservation, status.as_ref())
+            && !release_reservation(transaction_hash.clone())?.is_empty()
+        {
+            restocked.push(transaction_hash);
+        }
+    }
+
+    Ok(restocked)
+}
+
+/// Whether a reservation's stock must go back into the listing: it was
+/// committed to a transaction that has been cancelled
+pub fn needs_restock(
+    reservation: &InventoryReservation,
+    transaction_status: Option<&TransactionStatus>,
+) -> bool {
+    reservation.status == ReservationStatus::Committed
+        && transaction_status == Some(&TransactionStatus::Cancelled)
+}
+
+/// Get the stock of a listing that is not held by a reservation
+#[hdk_extern]
+pub fn get_available_stock(listing_hash: ActionHash) -> ExternResult<u32> {
+    let listing_hash = records::get_original_action_hash(listing_hash)?;
+
+    let listing = get_listing(listing_hash.clone())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("Listing not found".into())
+    ))?;
+    let reservations = get_listing_reservations(listing_hash)?;
+
+    Ok(available_stock(
+        listing.listing.quantity_available,
+        reservations.iter().map(|r| &r.reservation),
+        time::now()?,
+    ))
+}
+
+/// Get the stock of one variant that is not held by a reservation
+#[hdk_extern]
+pub fn get_available_variant_stock(input: VariantStockInput) -> ExternResult<u32> {
+    let listing_hash = records::get_original_action_hash(input.listing_hash)?;
+
+    let listing = get_listing(listing_hash.clone())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("Listing not found".into())
+    ))?;
+    let stock = variant_stock(&listing.listing, Some(input.variant_sku.as_str()))
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?;
+    let reservations = get_listing_reservations(listing_hash)?;
+
+    Ok(available_stock(
+        stock,
+        reservations
+            .iter()
+            .map(|r| &r.reservation)
+            .filter(|r| r.variant_sku.as_ref() == Some(&input.variant_sku)),
+        time::now()?,
+    ))
+}
+
+/// Stock of the variant a purchase targets
+///
+/// Listings with variants must be bought by SKU; listings without
+/// variants must not be.
+pub fn variant_stock(listing: &Listing, variant_sku: Option<&str>) -> Result<u32, String> {
+    match variant_sku {
+        Some(sku) => listing
+            .variant(sku)
+            .map(|variant| variant.quantity_available)
+            .ok_or(format!("Listing has no variant {}", sku)),
+        None if listing.variants.is_empty() => Ok(listing.quantity_available),
+        None => Err("Choose a variant of this listing".into()),
+    }
+}
+
+/// Listing update putting a committed reservation's units back in stock
+///
+/// A listing that sold out goes back to `Active`; any other status is kept.
+pub fn restock_input(
+    listing: &Listing,
+    reservation: &InventoryReservation,
+) -> Result<UpdateListingInput, String> {
+    let overflow = || "Restocked quantity overflows".to_string();
+
+    let quantity_available = listing
+        .quantity_available
+        .checked_add(reservation.quantity)
+        .ok_or_else(overflow)?;
+
+    let variants = match &reservation.variant_sku {
+        Some(sku) => {
+            let mut variants = listing.variants.clone();
+            let variant = variants
+                .iter_mut()
+                .find(|v| &v.sku == sku)
+                .ok_or(format!("Listing has no variant {}", sku))?;
+            variant.quantity_available = variant
+                .quantity_available
+                .checked_add(reservation.quantity)
+                .ok_or_else(overflow)?;
+            Some(variants)
+        }
+        None => None,
+    };
+
+    Ok(UpdateListingInput {
+        listing_hash: reservation.listing_hash.clone(),
+        title: None,
+        description: None,
+        price_cents: None,
+        currency: None,
+        category: None,
+        photos_ipfs_cids: None,
+        quantity_available: Some(quantity_available),
+        option_axes: None,
+        variants,
+        publish_at: None,
+        expires_at: None,
+        status: if listing.status == ListingStatus::Sold {
+            Some(ListingStatus::Active)
+        } else {
+            None
+        },
+    })
+}
+
+/// Stock left after unexpired reservations
+pub fn available_stock<'a>(
+    quantity_available: u32,
+    reservations: impl IntoIterator<Item = &'a InventoryReservation>,
+    now: Timestamp,
+) -> u32 {
+    let reserved: u32 = reservations
+        .into_iter()
+        .filter(|r| r.status == ReservationStatus::Reserved && r.expires_at > now)
+        .map(|r| r.quantity)
+        .sum();
+
+    quantity_available.saturating_sub(reserved)
+}
+
 /// Search listings by text query (simple implementation)
 #[hdk_extern]
 pub fn search_listings(query: String) -> ExternResult<ListingsResponse> {
@@ -308,6 +1124,118 @@ pub fn search_listings(query: String) -> ExternResult<ListingsResponse> {
     })
 }
 
+// ===== Helper Functions =====
+
+/// Whether buyers can see a listing with this status
+fn is_published(status: &ListingStatus) -> bool {
+    !matches!(status, ListingStatus::Draft | ListingStatus::Scheduled)
+}
+
+/// Mirror of the `composite` field of the reputation zome's `MatlScore`
+#[derive(Serialize, Deserialize, Debug)]
+struct MatlComposite {
+    composite: f64,
+}
+
+/// An agent's MATL composite score (0 for agents with no score yet)
+fn get_matl_composite(agent: AgentPubKey) -> ExternResult<f64> {
+    // Use shared utility for remote calls
+    let score: Option<MatlComposite> =
+        remote_calls::call_zome("reputation", "get_agent_matl_score", agent)?;
+
+    Ok(score.map(|score| score.composite).unwrap_or(0.0))
+}
+
+/// Mirror of `transactions_integrity::TransactionStatus`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionStatus {
+    Pending,
+    Confirmed,
+    Shipped,
+    Delivered,
+    Completed,
+    Disputed,
+    Cancelled,
+    ReturnRequested,
+    ReturnApproved,
+    ReturnShipped,
+    Refunded,
+}
+
+/// Mirror of the `status` field of the transactions zome's `Transaction`
+#[derive(Serialize, Deserialize, Debug)]
+struct TransactionState {
+    status: TransactionStatus,
+}
+
+/// Mirror of the transactions zome's `TransactionOutput`
+#[derive(Serialize, Deserialize, Debug)]
+struct TransactionStateOutput {
+    transaction: TransactionState,
+}
+
+/// Current status of a transaction (None if it cannot be found)
+fn get_transaction_status(transaction_hash: ActionHash) -> ExternResult<Option<TransactionStatus>> {
+    // Use shared utility for remote calls
+    let output: Option<TransactionStateOutput> =
+        remote_calls::call_zome("transactions", "get_transaction", transaction_hash)?;
+
+    Ok(output.map(|output| output.transaction.status))
+}
+
+/// Original action hashes of the listings on the caller's source chain
+fn my_listing_hashes() -> ExternResult<Vec<ActionHash>> {
+    let filter = ChainQueryFilter::new()
+        .entry_type(UnitEntryTypes::Listing.try_into()?)
+        .action_type(ActionType::Create);
+
+    Ok(query(filter)?
+        .into_iter()
+        .map(|record| record.action_address().clone())
+        .collect())
+}
+
+/// Get all reservations for a listing (latest versions)
+fn get_listing_reservations(listing_hash: ActionHash) -> ExternResult<Vec<ReservationOutput>> {
+    let links = link_queries::get_links_local(listing_hash, LinkTypes::ListingToReservations)?;
+
+    let mut reservations = Vec::new();
+    for link in links {
+        if let Some(action_hash) = link.target.into_action_hash() {
+            if let Some(record) = records::get_latest_record(action_hash)? {
+                // Use shared utility for deserialization
+                reservations.push(ReservationOutput {
+                    reservation_hash: record.action_address().clone(),
+                    reservation: error_handling::deserialize_entry(&record)?,
+                });
+            }
+        }
+    }
+
+    Ok(reservations)
+}
+
+/// Get the latest versions of a transaction's reservations (one per line)
+fn get_transaction_reservations(
+    transaction_hash: ActionHash,
+) -> ExternResult<Vec<(ActionHash, InventoryReservation)>> {
+    let links = link_queries::get_links_local(transaction_hash, LinkTypes::TransactionToReservation)?;
+
+    let mut reservations = Vec::new();
+    for link in links {
+        if let Some(action_hash) = link.target.into_action_hash() {
+            if let Some(record) = records::get_latest_record(action_hash)? {
+                // Use shared utility for deserialization
+                let reservation: InventoryReservation = error_handling::deserialize_entry(&record)?;
+                reservations.push((record.action_address().clone(), reservation));
+            }
+        }
+    }
+
+    Ok(reservations)
+}
+
 // ===== Input/Output Types =====
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -315,9 +1243,25 @@ pub struct CreateListingInput {
     pub title: String,
     pub description: String,
     pub price_cents: u64,
+    /// Defaults to USD
+    #[serde(default)]
+    pub currency: Option<String>,
     pub category: ListingCategory,
     pub photos_ipfs_cids: Vec<String>,
+    /// Ignored when variants are given (stock is their total)
     pub quantity_available: u32,
+    #[serde(default)]
+    pub option_axes: Vec<OptionAxis>,
+    #[serde(default)]
+    pub variants: Vec<ListingVariant>,
+    /// Save as a draft instead of publishing
+    #[serde(default)]
+    pub draft: bool,
+    /// Schedule the listing to go live later
+    #[serde(default)]
+    pub publish_at: Option<Timestamp>,
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -326,12 +1270,73 @@ pub struct UpdateListingInput {
     pub title: Option<String>,
     pub description: Option<String>,
     pub price_cents: Option<u64>,
+    #[serde(default)]
+    pub currency: Option<String>,
     pub category: Option<ListingCategory>,
     pub photos_ipfs_cids: Option<Vec<String>>,
+    /// Ignored for listings with variants (stock is their total)
     pub quantity_available: Option<u32>,
+    #[serde(default)]
+    pub option_axes: Option<Vec<OptionAxis>>,
+    #[serde(default)]
+    pub variants: Option<Vec<ListingVariant>>,
+    #[serde(default)]
+    pub publish_at: Option<Timestamp>,
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>,
     pub status: Option<ListingStatus>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RenewListingInput {
+    pub listing_hash: ActionHash,
+    pub expires_at: Timestamp,
+}
+
+/// What a listing sweep does with a listing
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ListingSweepAction {
+    Publish,
+    Expire,
+    WarnExpiring,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ListingSweepOutput {
+    pub published: Vec<ActionHash>,
+    pub expired: Vec<ActionHash>,
+    pub expiring: Vec<ActionHash>,
+    /// Cancelled transactions whose committed stock was put back
+    #[serde(default)]
+    pub restocked: Vec<ActionHash>,
+    /// Listings the sweep could not act on
+    #[serde(default)]
+    pub failed: Vec<ListingSweepFailure>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ListingSweepFailure {
+    pub listing_hash: ActionHash,
+    pub error: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum ListingSignal {
+    /// A listing expires soon: renew it to keep it live
+    ListingExpiring {
+        listing_hash: ActionHash,
+        title: String,
+        expires_at: Option<Timestamp>,
+    },
+    /// A listing expired and was moved to Inactive
+    ListingExpired {
+        listing_hash: ActionHash,
+        title: String,
+        expires_at: Option<Timestamp>,
+    },
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ListingOutput {
     pub listing_hash: ActionHash,
@@ -344,6 +1349,78 @@ pub struct ListingsResponse {
     pub listings: Vec<ListingOutput>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BrowseListingsInput {
+    #[serde(default)]
+    pub category: Option<ListingCategory>,
+    /// Only listings priced in this currency
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// Price range (minor units, inclusive)
+    #[serde(default)]
+    pub min_price_cents: Option<u64>,
+    #[serde(default)]
+    pub max_price_cents: Option<u64>,
+    /// Lowest seller MATL composite score (0.0 - 1.0)
+    #[serde(default)]
+    pub min_seller_matl: Option<f64>,
+    /// Creation order of the page (newest first by default)
+    #[serde(default)]
+    pub sort: browse::ListingSort,
+    /// Page size (default 20, max 50)
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// Cursor returned with the previous page
+    #[serde(default)]
+    pub cursor: Option<browse::BrowseCursor>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BrowsePage {
+    pub listings: Vec<ListingOutput>,
+    pub next_cursor: Option<browse::BrowseCursor>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackfillListingDaysInput {
+    /// Where in the `all_listings` anchor to start (0 on the first call)
+    #[serde(default)]
+    pub position: u32,
+    /// Links to check (default and max 100)
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackfillListingDaysOutput {
+    /// Listings linked into their day bucket by this call
+    pub linked: u32,
+    /// Where the next call starts; None when the backfill is done
+    pub next_position: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReserveStockInput {
+    pub listing_hash: ActionHash,
+    pub transaction_hash: ActionHash,
+    pub quantity: u32,
+    /// Required for listings with variants
+    #[serde(default)]
+    pub variant_sku: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VariantStockInput {
+    pub listing_hash: ActionHash,
+    pub variant_sku: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReservationOutput {
+    pub reservation_hash: ActionHash,
+    pub reservation: InventoryReservation,
+}
+
 
 // ===== Tests =====
 #[cfg(test)]
@@ -0,0 +1,207 @@
+//! Seller notifications for expiring listings
+//!
+//! Sweeps notify the seller through the notifications zome, as
+//! `ListingExpiring`, when a listing enters the warning window and again
+//! when it expires. The notification types are mirrored here rather than
+//! depending on the notifications zome. A notification's id names the
+//! event, the listing and its expiry, so a sweep can tell which warnings
+//! the seller already has and renewing a listing arms a fresh one.
+
+use hdk::prelude::*;
+use mycelix_common::remote_calls;
+use std::collections::{BTreeSet, HashMap};
+
+/// Mirror of `notifications_integrity::NotificationType` (the variant sent here)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum NotificationType {
+    ListingExpiring,
+}
+
+/// Mirror of `notifications_integrity::NotificationPriority` (the levels sent here)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum NotificationPriority {
+    Normal,
+    High,
+}
+
+/// Mirror of `notifications_integrity::Notification`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub id: String,
+    pub notification_type: NotificationType,
+    pub priority: NotificationPriority,
+    pub title: String,
+    pub message: String,
+    pub action_link: Option<String>,
+    pub related_entity: Option<ActionHash>,
+    pub from_agent: Option<AgentPubKey>,
+    pub created_at: u64,
+    pub expires_at: Option<u64>,
+    pub read: bool,
+    pub dismissed: bool,
+    pub read_at: Option<u64>,
+    pub metadata: HashMap<String, String>,
+}
+
+/// Mirror of the notifications zome's `NotificationFilter`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct NotificationFilter {
+    only_unread: Option<bool>,
+    notification_types: Option<Vec<NotificationType>>,
+    min_priority: Option<NotificationPriority>,
+    include_dismissed: Option<bool>,
+    limit: Option<u32>,
+}
+
+/// The part of the notifications zome's `NotificationListResponse` read here
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct NotificationList {
+    notifications: Vec<NotificationWithHash>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct NotificationWithHash {
+    notification: Notification,
+}
+
+/// What the seller is told about a listing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExpiryEvent {
+    /// Expires within `LISTING_EXPIRY_WARNING_HOURS`
+    Expiring,
+    /// Expired and moved to `Inactive`
+    Expired,
+}
+
+/// Id of the notification for an event on one expiry of a listing
+pub fn notification_id(
+    event: ExpiryEvent,
+    listing_hash: &ActionHash,
+    expires_at: Option<Timestamp>,
+) -> String {
+    let event = match event {
+        ExpiryEvent::Expiring => "listing_expiring",
+        ExpiryEvent::Expired => "listing_expired",
+    };
+
+    format!(
+        "{}:{}:{}",
+        event,
+        listing_hash,
+        expires_at.map_or(0, |expires_at| expires_at.as_micros())
+    )
+}
+
+/// Notification telling the seller about an expiry event
+pub fn expiry_notification(
+    event: ExpiryEvent,
+    listing_hash: &ActionHash,
+    title: &str,
+    expires_at: Option<Timestamp>,
+    now: Timestamp,
+) -> Notification {
+    let (priority, heading, message) = match event {
+        ExpiryEvent::Expiring => (
+            NotificationPriority::Normal,
+            "Listing expiring soon",
+            format!("\"{}\" expires soon. Renew it to keep it live.", title),
+        ),
+        ExpiryEvent::Expired => (
+            NotificationPriority::High,
+            "Listing expired",
+            format!("\"{}\" has expired and is no longer offered. Renew it to relist.", title),
+        ),
+    };
+
+    Notification {
+        id: notification_id(event, listing_hash, expires_at),
+        notification_type: NotificationType::ListingExpiring,
+        priority,
+        title: heading.to_string(),
+        message,
+        action_link: None,
+        related_entity: Some(listing_hash.clone()),
+        from_agent: None,
+        created_at: now.as_micros() as u64,
+        expires_at: None,
+        read: false,
+        dismissed: false,
+        read_at: None,
+        metadata: HashMap::from([("listing_title".to_string(), title.to_string())]),
+    }
+}
+
+/// Ids of the expiry notifications the caller already has
+pub fn sent_notification_ids() -> ExternResult<BTreeSet<String>> {
+    // Use shared utility for remote calls
+    let list: NotificationList = remote_calls::call_zome(
+        "notifications",
+        "get_my_notifications",
+        NotificationFilter {
+            only_unread: None,
+            notification_types: Some(vec![NotificationType::ListingExpiring]),
+            min_priority: None,
+            include_dismissed: Some(true),
+            limit: None,
+        },
+    )?;
+
+    Ok(list
+        .notifications
+        .into_iter()
+        .map(|sent| sent.notification.id)
+        .collect())
+}
+
+/// Send the caller a notification
+///
+/// The notifications zome refuses types the seller turned off or
+/// priorities below their floor; that is their choice, not a failed sweep.
+pub fn send(notification: Notification) {
+    let _ = remote_calls::call_zome_void("notifications", "create_notification", notification);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_ids_name_event_listing_and_expiry() {
+        let listing_hash = ActionHash::from_raw_36(vec![1u8; 36]);
+        let expires_at = Some(Timestamp::from_micros(5_000_000));
+
+        let expiring = notification_id(ExpiryEvent::Expiring, &listing_hash, expires_at);
+        assert_ne!(expiring, notification_id(ExpiryEvent::Expired, &listing_hash, expires_at));
+
+        // Renewing moves the expiry, so the next warning is a new notification
+        let renewed = Some(Timestamp::from_micros(9_000_000));
+        assert_ne!(expiring, notification_id(ExpiryEvent::Expiring, &listing_hash, renewed));
+
+        let other_listing = ActionHash::from_raw_36(vec![2u8; 36]);
+        assert_ne!(expiring, notification_id(ExpiryEvent::Expiring, &other_listing, expires_at));
+    }
+
+    #[test]
+    fn test_expiry_notification() {
+        let listing_hash = ActionHash::from_raw_36(vec![1u8; 36]);
+        let now = Timestamp::from_micros(2_000_000);
+        let expires_at = Some(Timestamp::from_micros(5_000_000));
+
+        let warning =
+            expiry_notification(ExpiryEvent::Expiring, &listing_hash, "T-shirt", expires_at, now);
+        assert_eq!(warning.notification_type, NotificationType::ListingExpiring);
+        assert_eq!(warning.priority, NotificationPriority::Normal);
+        assert_eq!(warning.related_entity, Some(listing_hash.clone()));
+        assert_eq!(warning.created_at, 2_000_000);
+        assert!(warning.message.contains("T-shirt"));
+        assert_eq!(
+            warning.id,
+            notification_id(ExpiryEvent::Expiring, &listing_hash, expires_at)
+        );
+
+        let expired =
+            expiry_notification(ExpiryEvent::Expired, &listing_hash, "T-shirt", expires_at, now);
+        assert_eq!(expired.notification_type, NotificationType::ListingExpiring);
+        assert_eq!(expired.priority, NotificationPriority::High);
+    }
+}
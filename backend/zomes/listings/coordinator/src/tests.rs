@@ -1,8 +1,8 @@
+use super::*;
+
 #[cfg(test)]
-mod tests {
+mod listings_tests {
     use super::*;
-    use hdk::prelude::*;
-    use listings_integrity::*;
 
     // Helper functions for tests
     fn mock_listing_input() -> CreateListingInput {
@@ -10,9 +10,15 @@ mod tests {
             title: "Test Product".to_string(),
             description: "A great test product".to_string(),
             price_cents: 1999,
+            currency: None,
             category: ListingCategory::Electronics,
             photos_ipfs_cids: vec!["QmTest123456789012345678901234567890123456".to_string()],
             quantity_available: 10,
+            option_axes: vec![],
+            variants: vec![],
+            draft: false,
+            publish_at: None,
+            expires_at: None,
         }
     }
 
@@ -49,7 +55,7 @@ mod tests {
 
         // Valid title
         input.title = "Valid Product Name".to_string();
-        assert!(input.title.len() >= 1 && input.title.len() <= 200);
+        assert!(!input.title.is_empty() && input.title.len() <= 200);
     }
 
     #[test]
@@ -77,7 +83,7 @@ mod tests {
 
         // Valid CIDv0 (Qm...)
         input.photos_ipfs_cids = vec![
-            "QmTest123456789012345678901234567890123456".to_string()
+            "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string()
         ];
         assert!(input.photos_ipfs_cids[0].starts_with("Qm"));
         assert_eq!(input.photos_ipfs_cids[0].len(), 46);
@@ -109,13 +115,11 @@ mod tests {
 
         // Valid photo count
         input.photos_ipfs_cids = vec!["QmTest123456789012345678901234567890123456".to_string(); 5];
-        assert!(input.photos_ipfs_cids.len() >= 1 && input.photos_ipfs_cids.len() <= 10);
+        assert!(!input.photos_ipfs_cids.is_empty() && input.photos_ipfs_cids.len() <= 10);
     }
 
     #[test]
     fn test_epistemic_classification() {
-        let mut input = mock_listing_input();
-
         // Test that seller claims start at E1 (Testimonial)
         let classification = EpistemicClassification {
             empirical: EmpiricalLevel::E1Testimonial,
@@ -133,22 +137,24 @@ mod tests {
         // Test all category variants exist
         let categories = vec![
             ListingCategory::Electronics,
-            ListingCategory::Clothing,
-            ListingCategory::Home,
-            ListingCategory::Books,
-            ListingCategory::Toys,
-            ListingCategory::Sports,
-            ListingCategory::Food,
+            ListingCategory::Fashion,
+            ListingCategory::HomeGarden,
+            ListingCategory::SportsOutdoors,
+            ListingCategory::BooksMedia,
+            ListingCategory::ToysGames,
+            ListingCategory::HealthBeauty,
+            ListingCategory::Automotive,
+            ListingCategory::ArtCollectibles,
             ListingCategory::Other,
         ];
 
-        assert_eq!(categories.len(), 8);
+        assert_eq!(categories.len(), 10);
     }
 
     #[test]
     fn test_listing_status_transitions() {
         // Test valid status transitions
-        let statuses = vec![
+        let statuses = [
             ListingStatus::Active,
             ListingStatus::Sold,
             ListingStatus::Deleted,
@@ -171,7 +177,12 @@ mod tests {
         ];
 
         for (input, expected) in queries {
-            let sanitized = input.trim().to_lowercase().replace("<", "").replace(">", "");
+            let sanitized: String = input
+                .trim()
+                .to_lowercase()
+                .chars()
+                .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+                .collect();
             assert_eq!(sanitized, expected);
         }
     }
@@ -189,6 +200,234 @@ mod tests {
         assert!(input.quantity_available > 0);
     }
 
+    fn mock_reservation(quantity: u32, status: ReservationStatus, expires_at: i64) -> InventoryReservation {
+        InventoryReservation {
+            listing_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+            transaction_hash: ActionHash::from_raw_36(vec![2u8; 36]),
+            buyer: mock_agent_pub_key(),
+            quantity,
+            variant_sku: None,
+            status,
+            expires_at: Timestamp::from_micros(expires_at),
+            created_at: Timestamp::from_micros(1000000),
+            updated_at: Timestamp::from_micros(1000000),
+        }
+    }
+
+    #[test]
+    fn test_available_stock_subtracts_active_reservations() {
+        let now = Timestamp::from_micros(2000000);
+        let reservations = vec![
+            mock_reservation(3, ReservationStatus::Reserved, 5000000),
+            mock_reservation(2, ReservationStatus::Reserved, 5000000),
+        ];
+
+        assert_eq!(available_stock(10, &reservations, now), 5);
+    }
+
+    #[test]
+    fn test_available_stock_ignores_settled_and_expired_reservations() {
+        let now = Timestamp::from_micros(2000000);
+        let reservations = vec![
+            mock_reservation(3, ReservationStatus::Committed, 5000000), // Already deducted
+            mock_reservation(2, ReservationStatus::Released, 5000000),
+            mock_reservation(4, ReservationStatus::Reserved, 1500000), // Expired
+        ];
+
+        assert_eq!(available_stock(10, &reservations, now), 10);
+    }
+
+    #[test]
+    fn test_last_unit_can_only_be_reserved_once() {
+        let now = Timestamp::from_micros(2000000);
+        let first_buyer = vec![mock_reservation(1, ReservationStatus::Reserved, 5000000)];
+
+        assert_eq!(available_stock(1, &[], now), 1);
+        assert_eq!(available_stock(1, &first_buyer, now), 0);
+    }
+
+    fn mock_variant_listing() -> Listing {
+        Listing {
+            title: "T-shirt".to_string(),
+            description: "Organic cotton".to_string(),
+            price_cents: 2500,
+            currency: "USD".to_string(),
+            category: ListingCategory::Fashion,
+            photos_ipfs_cids: vec!["QmTest123456789012345678901234567890123456".to_string()],
+            quantity_available: 5,
+            option_axes: vec![OptionAxis {
+                name: "Size".to_string(),
+                values: vec!["S".to_string(), "M".to_string()],
+            }],
+            variants: vec![
+                ListingVariant {
+                    sku: "TS-S".to_string(),
+                    options: vec!["S".to_string()],
+                    price_cents: None,
+                    quantity_available: 2,
+                    photos_ipfs_cids: vec![],
+                },
+                ListingVariant {
+                    sku: "TS-M".to_string(),
+                    options: vec!["M".to_string()],
+                    price_cents: Some(2800),
+                    quantity_available: 3,
+                    photos_ipfs_cids: vec![],
+                },
+            ],
+            status: ListingStatus::Active,
+            publish_at: None,
+            expires_at: None,
+            price_changed_at: None,
+            epistemic: EpistemicClassification {
+                empirical: EmpiricalLevel::E1Testimonial,
+                normative: NormativeLevel::N0Personal,
+                materiality: MaterialityLevel::M1Temporal,
+            },
+            created_at: Timestamp::from_micros(1000000),
+            updated_at: Timestamp::from_micros(1000000),
+        }
+    }
+
+    #[test]
+    fn test_variant_stock_requires_sku_for_variant_listings() {
+        let listing = mock_variant_listing();
+
+        assert_eq!(variant_stock(&listing, Some("TS-M")), Ok(3));
+        assert!(variant_stock(&listing, Some("TS-XL")).is_err());
+        assert!(variant_stock(&listing, None).is_err());
+    }
+
+    #[test]
+    fn test_variant_stock_rejects_sku_for_plain_listings() {
+        let mut listing = mock_variant_listing();
+        listing.option_axes.clear();
+        listing.variants.clear();
+
+        assert_eq!(variant_stock(&listing, None), Ok(5));
+        assert!(variant_stock(&listing, Some("TS-S")).is_err());
+    }
+
+    #[test]
+    fn test_reservations_only_hold_their_own_variant() {
+        let now = Timestamp::from_micros(2000000);
+        let mut small = mock_reservation(2, ReservationStatus::Reserved, 5000000);
+        small.variant_sku = Some("TS-S".to_string());
+        let reservations = [small];
+
+        let small_held = reservations
+            .iter()
+            .filter(|r| r.variant_sku.as_deref() == Some("TS-S"));
+        let medium_held = reservations
+            .iter()
+            .filter(|r| r.variant_sku.as_deref() == Some("TS-M"));
+
+        assert_eq!(available_stock(2, small_held, now), 0);
+        assert_eq!(available_stock(3, medium_held, now), 3);
+    }
+
+    #[test]
+    fn test_restock_returns_units_and_reopens_sold_listings() {
+        let mut listing = mock_variant_listing();
+        listing.variants[1].quantity_available = 0;
+        listing.quantity_available = 2;
+        let mut reservation = mock_reservation(2, ReservationStatus::Committed, 5000000);
+        reservation.variant_sku = Some("TS-M".to_string());
+
+        let input = restock_input(&listing, &reservation).unwrap();
+        let variants = input.variants.unwrap();
+        assert_eq!(input.quantity_available, Some(4));
+        assert_eq!(variants[0].quantity_available, 2);
+        assert_eq!(variants[1].quantity_available, 2);
+        assert_eq!(input.status, None);
+
+        listing.option_axes.clear();
+        listing.variants.clear();
+        listing.quantity_available = 0;
+        listing.status = ListingStatus::Sold;
+        reservation.variant_sku = None;
+
+        let input = restock_input(&listing, &reservation).unwrap();
+        assert_eq!(input.quantity_available, Some(2));
+        assert!(input.variants.is_none());
+        assert_eq!(input.status, Some(ListingStatus::Active));
+
+        reservation.variant_sku = Some("TS-XL".to_string());
+        assert!(restock_input(&listing, &reservation).is_err());
+    }
+
+    #[test]
+    fn test_only_committed_stock_of_cancelled_transactions_is_restocked() {
+        let committed = mock_reservation(2, ReservationStatus::Committed, 5000000);
+        assert!(needs_restock(&committed, Some(&TransactionStatus::Cancelled)));
+        assert!(!needs_restock(&committed, Some(&TransactionStatus::Confirmed)));
+        assert!(!needs_restock(&committed, Some(&TransactionStatus::Completed)));
+        assert!(!needs_restock(&committed, None));
+
+        let reserved = mock_reservation(2, ReservationStatus::Reserved, 5000000);
+        assert!(!needs_restock(&reserved, Some(&TransactionStatus::Cancelled)));
+
+        let released = mock_reservation(2, ReservationStatus::Released, 5000000);
+        assert!(!needs_restock(&released, Some(&TransactionStatus::Cancelled)));
+    }
+
+    #[test]
+    fn test_initial_status() {
+        let now = Timestamp::from_micros(2000000);
+
+        assert_eq!(initial_status(false, None, now), ListingStatus::Active);
+        assert_eq!(initial_status(true, None, now), ListingStatus::Draft);
+        assert_eq!(
+            initial_status(false, Some(Timestamp::from_micros(3000000)), now),
+            ListingStatus::Scheduled
+        );
+        assert_eq!(
+            initial_status(false, Some(Timestamp::from_micros(1000000)), now),
+            ListingStatus::Active
+        );
+        // Drafts stay drafts whatever the publish time
+        assert_eq!(
+            initial_status(true, Some(Timestamp::from_micros(3000000)), now),
+            ListingStatus::Draft
+        );
+    }
+
+    #[test]
+    fn test_sweep_publishes_due_scheduled_listings() {
+        let now = Timestamp::from_micros(2000000);
+        let mut listing = mock_variant_listing();
+        listing.status = ListingStatus::Scheduled;
+
+        listing.publish_at = Some(Timestamp::from_micros(3000000));
+        assert_eq!(listing_sweep_action(&listing, now), None);
+
+        listing.publish_at = Some(Timestamp::from_micros(2000000));
+        assert_eq!(listing_sweep_action(&listing, now), Some(ListingSweepAction::Publish));
+    }
+
+    #[test]
+    fn test_sweep_expires_and_warns_active_listings() {
+        let hour: i64 = 60 * 60 * 1_000_000;
+        let now = Timestamp::from_micros(100 * hour);
+        let mut listing = mock_variant_listing();
+
+        assert_eq!(listing_sweep_action(&listing, now), None); // No expiry
+
+        listing.expires_at = Some(Timestamp::from_micros(100 * hour));
+        assert_eq!(listing_sweep_action(&listing, now), Some(ListingSweepAction::Expire));
+
+        listing.expires_at = Some(Timestamp::from_micros(110 * hour));
+        assert_eq!(listing_sweep_action(&listing, now), Some(ListingSweepAction::WarnExpiring));
+
+        listing.expires_at = Some(Timestamp::from_micros(200 * hour));
+        assert_eq!(listing_sweep_action(&listing, now), None);
+
+        // Paused listings are left alone
+        listing.status = ListingStatus::Inactive;
+        listing.expires_at = Some(Timestamp::from_micros(100 * hour));
+        assert_eq!(listing_sweep_action(&listing, now), None);
+    }
+
     #[test]
     fn test_description_length() {
         let mut input = mock_listing_input();
@@ -203,7 +442,7 @@ mod tests {
 
         // Valid description
         input.description = "This is a valid product description.".to_string();
-        assert!(input.description.len() >= 1 && input.description.len() <= 5000);
+        assert!(!input.description.is_empty() && input.description.len() <= 5000);
     }
 
     #[test]
@@ -213,7 +452,14 @@ mod tests {
             title: Some("Updated Title".to_string()),
             description: Some("Updated description".to_string()),
             price_cents: Some(2999),
+            currency: Some("EUR".to_string()),
+            category: None,
+            photos_ipfs_cids: None,
             quantity_available: Some(5),
+            option_axes: None,
+            variants: None,
+            publish_at: None,
+            expires_at: None,
             status: Some(ListingStatus::Active),
         };
 
@@ -236,8 +482,8 @@ mod tests {
     fn test_category_filter() {
         // Test that filtering by category works
         let electronics = ListingCategory::Electronics;
-        let clothing = ListingCategory::Clothing;
+        let fashion = ListingCategory::Fashion;
 
-        assert_ne!(electronics, clothing);
+        assert_ne!(electronics, fashion);
     }
 }
@@ -1,4 +1,5 @@
 use hdi::prelude::*;
+use std::collections::BTreeSet;
 
 /// Listing entry type - core marketplace data structure
 ///
@@ -13,10 +14,14 @@ pub struct Listing {
     /// Detailed description (1-5000 characters)
     pub description: String,
 
-    /// Price in USD cents (to avoid floating point issues)
+    /// Price in minor units of `currency` (to avoid floating point issues)
     /// Example: $19.99 = 1999 cents
     pub price_cents: u64,
 
+    /// ISO-4217 or registered custom currency code
+    #[serde(default = "default_currency")]
+    pub currency: String,
+
     /// Product category for filtering
     pub category: ListingCategory,
 
@@ -24,11 +29,35 @@ pub struct Listing {
     pub photos_ipfs_cids: Vec<String>,
 
     /// Number of items available (inventory)
+    ///
+    /// For listings with variants this is the total of the variants' stock.
     pub quantity_available: u32,
 
+    /// Option axes the variants are built from (e.g. Size, Color)
+    #[serde(default)]
+    pub option_axes: Vec<OptionAxis>,
+
+    /// Purchasable SKUs, one per combination of option values
+    ///
+    /// Empty for listings sold as a single item.
+    #[serde(default)]
+    pub variants: Vec<ListingVariant>,
+
     /// Current listing status
     pub status: ListingStatus,
 
+    /// When a scheduled listing goes live
+    #[serde(default)]
+    pub publish_at: Option<Timestamp>,
+
+    /// When the listing stops being offered (moved to `Inactive`)
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>,
+
+    /// When the price (or currency) last changed; None if it never has
+    #[serde(default)]
+    pub price_changed_at: Option<Timestamp>,
+
     /// Epistemic classification (Epistemic Charter v2.0)
     /// This listing is a claim about a product existing at this price
     pub epistemic: EpistemicClassification,
@@ -40,6 +69,144 @@ pub struct Listing {
     pub updated_at: Timestamp,
 }
 
+fn default_currency() -> String {
+    "USD".to_string()
+}
+
+impl Listing {
+    /// Find a variant by SKU
+    pub fn variant(&self, sku: &str) -> Option<&ListingVariant> {
+        self.variants.iter().find(|variant| variant.sku == sku)
+    }
+
+    /// Unit price of a variant, falling back to the listing price
+    pub fn variant_price_cents(&self, variant: &ListingVariant) -> u64 {
+        variant.price_cents.unwrap_or(self.price_cents)
+    }
+
+    /// Whether any price buyers are offered differs from another version's
+    ///
+    /// Compares the listing price and currency, and each variant's unit
+    /// price with the same SKU's in `other` (or `other`'s listing price
+    /// for SKUs it does not have, so re-adding a variant is no way around
+    /// the price change interval).
+    pub fn prices_differ(&self, other: &Listing) -> bool {
+        self.price_cents != other.price_cents
+            || self.currency != other.currency
+            || self.variants.iter().any(|variant| {
+                let before = other
+                    .variant(&variant.sku)
+                    .map(|earlier| other.variant_price_cents(earlier))
+                    .unwrap_or(other.price_cents);
+                self.variant_price_cents(variant) != before
+            })
+    }
+}
+
+/// An option buyers choose from, e.g. "Size" with S, M and L
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OptionAxis {
+    /// Axis name (1-50 characters, unique within the listing)
+    pub name: String,
+
+    /// Allowed values (1-50 characters each, unique within the axis)
+    pub values: Vec<String>,
+}
+
+/// A purchasable combination of option values (a SKU)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ListingVariant {
+    /// Seller's stock-keeping unit (1-64 characters, unique within the listing)
+    pub sku: String,
+
+    /// One value per option axis, in axis order
+    pub options: Vec<String>,
+
+    /// Overrides the listing price when set
+    pub price_cents: Option<u64>,
+
+    /// Units of this variant available
+    pub quantity_available: u32,
+
+    /// Photos of this variant; a subset of the listing's photos
+    pub photos_ipfs_cids: Vec<String>,
+}
+
+/// Maximum option axes per listing
+pub const MAX_OPTION_AXES: usize = 3;
+
+/// Maximum values per option axis
+pub const MAX_OPTION_VALUES: usize = 20;
+
+/// Maximum variants per listing
+pub const MAX_VARIANTS: usize = 100;
+
+/// Maximum units per variant
+pub const MAX_VARIANT_QUANTITY: u32 = 1_000_000;
+
+/// Minimum time between two price changes of a listing
+pub const MIN_PRICE_CHANGE_INTERVAL_HOURS: i64 = 24;
+
+/// How far `price_changed_at` may trail the update that changed the price
+pub const PRICE_CHANGE_CLOCK_SKEW_MINUTES: i64 = 5;
+
+/// Maximum listing price (minor units)
+pub const MAX_PRICE_CENTS: u64 = 10_000_000_000;
+
+/// Inventory reservation - stock held for a pending transaction
+///
+/// Created by the buyer when a transaction is created (one per line of
+/// the transaction), committed by the seller on confirmation (decrementing `quantity_available`), or
+/// released on cancellation. Reservations that are still `Reserved`
+/// stop counting against stock once they expire.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct InventoryReservation {
+    /// Listing the stock is reserved from (original action)
+    pub listing_hash: ActionHash,
+
+    /// Transaction holding the reservation (original action)
+    pub transaction_hash: ActionHash,
+
+    /// Buyer holding the reservation
+    pub buyer: AgentPubKey,
+
+    /// Units reserved
+    pub quantity: u32,
+
+    /// Variant the units are reserved from, for listings with variants
+    #[serde(default)]
+    pub variant_sku: Option<String>,
+
+    /// Reservation state
+    pub status: ReservationStatus,
+
+    /// Reserved stock is released automatically after this time
+    pub expires_at: Timestamp,
+
+    /// Creation timestamp
+    pub created_at: Timestamp,
+
+    /// Last update timestamp
+    pub updated_at: Timestamp,
+}
+
+/// Reservation lifecycle
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReservationStatus {
+    /// Holding stock for a pending transaction
+    Reserved,
+    /// Stock taken from the listing (seller confirmed)
+    Committed,
+    /// Stock returned (transaction cancelled, timed out or refunded after
+    /// a return); a committed reservation's units go back into the listing
+    Released,
+}
+
+/// How long a reservation holds stock before the seller confirms
+pub const RESERVATION_TTL_HOURS: i64 = 72;
+
 /// Product categories for marketplace
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "PascalCase")]
@@ -66,11 +233,15 @@ pub enum ListingCategory {
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ListingStatus {
+    /// Being prepared by the seller, not visible to buyers
+    Draft,
+    /// Waiting for `publish_at`, not visible to buyers
+    Scheduled,
     /// Active and available for purchase
     Active,
     /// Sold out (inventory = 0)
     Sold,
-    /// Seller paused listing
+    /// Seller paused listing, or it expired
     Inactive,
     /// Soft-deleted (hidden from UI)
     Deleted,
@@ -140,20 +311,136 @@ pub enum MaterialityLevel {
 #[hdk_link_types]
 pub enum LinkTypes {
     /// Links from agent to their listings
-    /// Base: AgentPubKey, Target: Listing EntryHash
+    /// Base: AgentPubKey, Target: Listing ActionHash (original)
     AgentToListings,
 
-    /// Links from category path to listings
-    /// Base: Path("listings.{category}"), Target: Listing EntryHash
+    /// Links from category path to browsable listings
+    /// Base: Path("listings.category.{category}"), Target: Listing ActionHash (original)
+    /// Tag: the listing version indexed (`version_tag`)
     CategoryToListings,
 
     /// Links from status path to listings
-    /// Base: Path("listings.status.{status}"), Target: Listing EntryHash
+    /// Base: Path("listings.status.{status}"), Target: Listing ActionHash (original)
+    /// Tag: the listing version indexed (`version_tag`)
     StatusToListings,
 
-    /// All listings anchor
-    /// Base: Path("all_listings"), Target: Listing EntryHash
+    /// All listings anchor, creation-day buckets and their calendar
+    /// Base: Path("all_listings") or a day bucket, Target: Listing ActionHash (original)
+    /// Calendar links (`calendar_links`) join path anchors, tagged with a day number
     AllListings,
+
+    /// Links from a listing to its inventory reservations
+    /// Base: Listing ActionHash, Target: InventoryReservation ActionHash
+    ListingToReservations,
+
+    /// Links from a transaction to its inventory reservations
+    /// Base: Transaction ActionHash, Target: InventoryReservation ActionHash
+    TransactionToReservation,
+}
+
+const MICROS_PER_DAY: i64 = 24 * 60 * 60 * 1_000_000;
+
+/// Path a category's listings are linked from
+pub fn category_path(category: &ListingCategory) -> Path {
+    Path::from(format!("listings.category.{:?}", category))
+}
+
+/// Path a status's listings are linked from
+pub fn status_path(status: &ListingStatus) -> Path {
+    Path::from(format!("listings.status.{:?}", status))
+}
+
+/// Path every listing is linked from
+pub fn all_listings_path() -> Path {
+    Path::from("all_listings")
+}
+
+/// Whether buyers can browse a listing with this status
+///
+/// Only browsable listings are linked under their category.
+pub fn is_browsable(status: &ListingStatus) -> bool {
+    !matches!(
+        status,
+        ListingStatus::Draft | ListingStatus::Scheduled | ListingStatus::Deleted
+    )
+}
+
+/// Days since the Unix epoch a timestamp falls on
+pub fn day_number(timestamp: Timestamp) -> i64 {
+    timestamp.as_micros().div_euclid(MICROS_PER_DAY)
+}
+
+/// Calendar date (year, month, day) of a day number
+///
+/// Uses the proleptic Gregorian calendar (Howard Hinnant's `civil_from_days`).
+pub fn civil_date(day: i64) -> (i64, u32, u32) {
+    let z = day + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day_of_month = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day_of_month)
+}
+
+/// Bucket path the listings created on a day are linked from
+pub fn day_bucket_path(day: i64) -> Path {
+    let (year, month, day_of_month) = civil_date(day);
+    Path::from(format!("all_listings.{:04}.{:02}.{:02}", year, month, day_of_month))
+}
+
+/// Path the years that have listings are linked from
+pub fn years_path() -> Path {
+    Path::from("all_listings.years")
+}
+
+/// Path a year's months with listings are linked from
+pub fn year_path(year: i64) -> Path {
+    Path::from(format!("all_listings.{:04}", year))
+}
+
+/// Path a month's day buckets are linked from
+pub fn month_path(year: i64, month: u32) -> Path {
+    Path::from(format!("all_listings.{:04}.{:02}", year, month))
+}
+
+/// Calendar links a day with listings sits under, as (base, target)
+///
+/// The years anchor links the day's year, the year its month and the
+/// month the day's bucket. Each link is tagged with the day number
+/// (`day_tag`), which is all validation needs to rebuild both paths.
+pub fn calendar_links(day: i64) -> [(Path, Path); 3] {
+    let (year, month, _) = civil_date(day);
+    [
+        (years_path(), year_path(year)),
+        (year_path(year), month_path(year, month)),
+        (month_path(year, month), day_bucket_path(day)),
+    ]
+}
+
+/// Tag of a calendar link: the day it was made for
+pub fn day_tag(day: i64) -> LinkTag {
+    LinkTag::new(day.to_string())
+}
+
+/// Day a calendar link's tag names
+pub fn tagged_day(tag: &LinkTag) -> Option<i64> {
+    String::from_utf8(tag.clone().into_inner()).ok()?.parse().ok()
+}
+
+/// Tag of a status or category link: the listing version it indexes
+pub fn version_tag(version_hash: &ActionHash) -> LinkTag {
+    LinkTag::new(version_hash.get_raw_39().to_vec())
+}
+
+/// Listing version a status or category link's tag names
+pub fn tagged_version(tag: &LinkTag) -> Option<ActionHash> {
+    ActionHash::try_from_raw_39(tag.clone().into_inner()).ok()
 }
 
 /// Entry types for this integrity zome
@@ -161,6 +448,7 @@ pub enum LinkTypes {
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
     Listing(Listing),
+    InventoryReservation(InventoryReservation),
 }
 
 /// Validation function for Listing entries
@@ -173,19 +461,25 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
         FlatOp::StoreEntry(store_entry) => match store_entry {
             OpEntry::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::Listing(listing) => validate_create_listing(&listing, &action),
+                EntryTypes::InventoryReservation(reservation) => {
+                    validate_create_reservation(&reservation, &action)
+                }
             },
             OpEntry::UpdateEntry {
                 app_entry, action, ..
             } => match app_entry {
                 EntryTypes::Listing(listing) => validate_update_listing(&listing, &action),
+                EntryTypes::InventoryReservation(reservation) => {
+                    validate_update_reservation(&reservation, &action)
+                }
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
-        FlatOp::RegisterUpdate(update_entry) => match update_entry {
-            OpUpdate::Entry { app_entry, action } => match app_entry {
-                EntryTypes::Listing(listing) => validate_update_listing(&listing, &action),
-            },
-            _ => Ok(ValidateCallbackResult::Valid),
+        FlatOp::RegisterUpdate(OpUpdate::Entry { app_entry, action }) => match app_entry {
+            EntryTypes::Listing(listing) => validate_update_listing(&listing, &action),
+            EntryTypes::InventoryReservation(reservation) => {
+                validate_update_reservation(&reservation, &action)
+            }
         },
         FlatOp::RegisterDelete(_delete_entry) => {
             // Allow sellers to delete their own listings
@@ -193,19 +487,22 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
         }
         FlatOp::RegisterCreateLink {
             link_type,
-            base_address: _,
-            target_address: _,
-            tag: _,
-            action: _,
-        } => {
-            // Validate that links are created by the listing owner
-            match link_type {
-                LinkTypes::AgentToListings => Ok(ValidateCallbackResult::Valid),
-                LinkTypes::CategoryToListings => Ok(ValidateCallbackResult::Valid),
-                LinkTypes::StatusToListings => Ok(ValidateCallbackResult::Valid),
-                LinkTypes::AllListings => Ok(ValidateCallbackResult::Valid),
+            base_address,
+            target_address,
+            tag,
+            action,
+        } => match link_type {
+            // Discovery links are created by the listing owner, from the
+            // base the listing belongs under
+            LinkTypes::AgentToListings
+            | LinkTypes::CategoryToListings
+            | LinkTypes::StatusToListings
+            | LinkTypes::AllListings => {
+                validate_create_listing_link(link_type, &base_address, &target_address, &tag, &action)
             }
-        }
+            LinkTypes::ListingToReservations => Ok(ValidateCallbackResult::Valid),
+            LinkTypes::TransactionToReservation => Ok(ValidateCallbackResult::Valid),
+        },
         FlatOp::RegisterDeleteLink { .. } => Ok(ValidateCallbackResult::Valid),
         _ => Ok(ValidateCallbackResult::Valid),
     }
@@ -236,10 +533,23 @@ fn validate_listing_data(
         ));
     }
 
-    // Prevent unrealistic prices (> $1,000,000)
-    if listing.price_cents > 100_000_000_00 {
+    // Prevent unrealistic prices (per-currency caps are applied by
+    // security::validate_price when the listing is written)
+    if listing.price_cents > MAX_PRICE_CENTS {
         return Ok(ValidateCallbackResult::Invalid(
-            "Price exceeds maximum allowed ($1,000,000)".into(),
+            "Price exceeds maximum allowed".into(),
+        ));
+    }
+
+    // ISO-4217 (three letters) or custom code (up to eight letters/digits)
+    let currency_ok = (3..=8).contains(&listing.currency.len())
+        && listing
+            .currency
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+    if !currency_ok {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Currency must be an ISO-4217 or registered currency code".into(),
         ));
     }
 
@@ -266,13 +576,26 @@ fn validate_listing_data(
         }
     }
 
-    // Quantity validation
-    if listing.quantity_available == 0 {
+    // Quantity validation (only listings off sale may reach zero)
+    if listing.quantity_available == 0
+        && !matches!(
+            listing.status,
+            ListingStatus::Sold | ListingStatus::Deleted | ListingStatus::Inactive
+        )
+    {
         return Ok(ValidateCallbackResult::Invalid(
             "Quantity must be at least 1".into(),
         ));
     }
 
+    if let Err(reason) = check_variants(listing) {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    if let Err(reason) = check_schedule(listing) {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
     // Epistemic validation: Ensure listings start with proper classification
     // New listings should be E1 (testimonial), N0 (personal), M1 or M2
     match listing.epistemic.empirical {
@@ -292,30 +615,269 @@ fn validate_listing_data(
     Ok(ValidateCallbackResult::Valid)
 }
 
+/// Check a listing's option axes and variants
+///
+/// Every variant picks one value from each axis, no two variants share a
+/// combination or SKU, variant photos come from the listing's photos, and
+/// the listing's stock is the sum of the variants' stock.
+pub fn check_variants(listing: &Listing) -> Result<(), String> {
+    if listing.option_axes.is_empty() != listing.variants.is_empty() {
+        return Err("Option axes and variants must be given together".into());
+    }
+
+    if listing.option_axes.len() > MAX_OPTION_AXES {
+        return Err(format!("Maximum {} option axes allowed", MAX_OPTION_AXES));
+    }
+
+    let mut axis_names = BTreeSet::new();
+    for axis in &listing.option_axes {
+        if axis.name.is_empty() || axis.name.len() > 50 {
+            return Err("Option names must be 1-50 characters".into());
+        }
+        if !axis_names.insert(axis.name.as_str()) {
+            return Err(format!("Duplicate option {}", axis.name));
+        }
+
+        if axis.values.is_empty() || axis.values.len() > MAX_OPTION_VALUES {
+            return Err(format!(
+                "Option {} must have 1-{} values",
+                axis.name, MAX_OPTION_VALUES
+            ));
+        }
+
+        let mut values = BTreeSet::new();
+        for value in &axis.values {
+            if value.is_empty() || value.len() > 50 {
+                return Err("Option values must be 1-50 characters".into());
+            }
+            if !values.insert(value.as_str()) {
+                return Err(format!("Duplicate value {} for option {}", value, axis.name));
+            }
+        }
+    }
+
+    if listing.variants.len() > MAX_VARIANTS {
+        return Err(format!("Maximum {} variants allowed", MAX_VARIANTS));
+    }
+
+    let mut skus = BTreeSet::new();
+    let mut combinations = BTreeSet::new();
+    let mut total_quantity: u32 = 0;
+    for variant in &listing.variants {
+        if variant.sku.is_empty() || variant.sku.len() > 64 {
+            return Err("SKUs must be 1-64 characters".into());
+        }
+        if !skus.insert(variant.sku.as_str()) {
+            return Err(format!("Duplicate SKU {}", variant.sku));
+        }
+
+        if variant.options.len() != listing.option_axes.len() {
+            return Err(format!(
+                "Variant {} must pick one value per option",
+                variant.sku
+            ));
+        }
+        for (axis, value) in listing.option_axes.iter().zip(&variant.options) {
+            if !axis.values.contains(value) {
+                return Err(format!(
+                    "Variant {} uses unknown {} value {}",
+                    variant.sku, axis.name, value
+                ));
+            }
+        }
+        if !combinations.insert(variant.options.clone()) {
+            return Err(format!(
+                "Variant {} repeats another variant's options",
+                variant.sku
+            ));
+        }
+
+        if let Some(price_cents) = variant.price_cents {
+            if price_cents == 0 || price_cents > MAX_PRICE_CENTS {
+                return Err(format!(
+                    "Variant {} price must be between 1 and {}",
+                    variant.sku, MAX_PRICE_CENTS
+                ));
+            }
+        }
+
+        if variant.quantity_available > MAX_VARIANT_QUANTITY {
+            return Err(format!(
+                "Variant {} quantity exceeds {}",
+                variant.sku, MAX_VARIANT_QUANTITY
+            ));
+        }
+
+        for cid in &variant.photos_ipfs_cids {
+            if !listing.photos_ipfs_cids.contains(cid) {
+                return Err(format!(
+                    "Variant {} photo {} is not one of the listing's photos",
+                    variant.sku, cid
+                ));
+            }
+        }
+
+        total_quantity = total_quantity
+            .checked_add(variant.quantity_available)
+            .ok_or("Variant stock overflows")?;
+    }
+
+    if !listing.variants.is_empty() && total_quantity != listing.quantity_available {
+        return Err(format!(
+            "Quantity available must be the variants' total of {}",
+            total_quantity
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check a listing's publish and expiry times
+///
+/// Scheduled listings need a publish time, and a listing cannot expire
+/// before it is published or created.
+pub fn check_schedule(listing: &Listing) -> Result<(), String> {
+    if listing.status == ListingStatus::Scheduled && listing.publish_at.is_none() {
+        return Err("Scheduled listings need a publish time".into());
+    }
+
+    if let Some(expires_at) = listing.expires_at {
+        let starts_at = listing.publish_at.unwrap_or(listing.created_at);
+        if expires_at <= starts_at {
+            return Err("Listing must expire after it is published".into());
+        }
+    }
+
+    Ok(())
+}
+
 /// Validate listing creation
 fn validate_create_listing(
     listing: &Listing,
     _action: &Create,
 ) -> ExternResult<ValidateCallbackResult> {
+    // New listings always have stock
+    if listing.quantity_available == 0 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Quantity must be at least 1".into(),
+        ));
+    }
+
+    // The first price is not a price change
+    if listing.price_changed_at.is_some() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "New listings cannot have a price change".into(),
+        ));
+    }
+
+    // New listings start as drafts, scheduled or live
+    if !matches!(
+        listing.status,
+        ListingStatus::Draft | ListingStatus::Scheduled | ListingStatus::Active
+    ) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "New listings cannot start as {:?}",
+            listing.status
+        )));
+    }
+
     validate_listing_data(listing)
 }
 
+/// Check a listing's price change against the version it updates
+///
+/// A price (or currency, or variant price) change must record when it
+/// happened, at the time of the update, and come at least
+/// `MIN_PRICE_CHANGE_INTERVAL_HOURS` after the previous change. Updates
+/// that keep the prices keep the record.
+pub fn check_price_change(
+    previous: &Listing,
+    listing: &Listing,
+    updated_at: Timestamp,
+) -> Result<(), String> {
+    let price_changed = listing.prices_differ(previous);
+
+    if !price_changed {
+        if listing.price_changed_at != previous.price_changed_at {
+            return Err("Price change time can only move when the price changes".into());
+        }
+        return Ok(());
+    }
+
+    let skew = PRICE_CHANGE_CLOCK_SKEW_MINUTES * 60 * 1_000_000;
+    let recorded_now = listing.price_changed_at.is_some_and(|changed_at| {
+        changed_at <= updated_at && changed_at.as_micros() + skew >= updated_at.as_micros()
+    });
+    if !recorded_now {
+        return Err("A price change must record when it was made".into());
+    }
+
+    if let Some(last_change) = previous.price_changed_at {
+        let interval = MIN_PRICE_CHANGE_INTERVAL_HOURS * 60 * 60 * 1_000_000;
+        if updated_at.as_micros() < last_change.as_micros() + interval {
+            return Err(format!(
+                "The price can change at most once every {} hours",
+                MIN_PRICE_CHANGE_INTERVAL_HOURS
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check a listing's status change against the version it updates
+///
+/// Deleted listings stay deleted and published ones never return to
+/// draft. A listing is only `Sold` once its stock runs out, and leaves
+/// `Sold` only when restocked (or deleted). A scheduled listing goes live
+/// no earlier than its publish time.
+pub fn check_status_change(
+    previous: &Listing,
+    listing: &Listing,
+    updated_at: Timestamp,
+) -> Result<(), String> {
+    if previous.status == ListingStatus::Deleted && listing.status != ListingStatus::Deleted {
+        return Err("Cannot reactivate a deleted listing - create a new one instead".into());
+    }
+
+    if previous.status != ListingStatus::Draft && listing.status == ListingStatus::Draft {
+        return Err("A published listing cannot return to draft".into());
+    }
+
+    if listing.status == ListingStatus::Sold && listing.quantity_available > 0 {
+        return Err("A listing with stock left cannot be marked sold".into());
+    }
+
+    if previous.status == ListingStatus::Sold
+        && !matches!(listing.status, ListingStatus::Sold | ListingStatus::Deleted)
+        && listing.quantity_available == 0
+    {
+        return Err("A sold listing must be restocked before it is offered again".into());
+    }
+
+    if previous.status == ListingStatus::Scheduled
+        && listing.status == ListingStatus::Active
+        && listing.publish_at.is_some_and(|publish_at| publish_at > updated_at)
+    {
+        return Err("A scheduled listing cannot go live before its publish time".into());
+    }
+
+    Ok(())
+}
+
 /// Validate listing updates
+///
+/// Each update is made against the previous version, so the rules that
+/// depend on what changed compare against that version.
 fn validate_update_listing(
     listing: &Listing,
     action: &Update,
 ) -> ExternResult<ValidateCallbackResult> {
-    // 1. Run same data validations as create
-    let data_validation = validate_listing_data(listing)?;
-    if let ValidateCallbackResult::Invalid(reason) = data_validation {
-        return Ok(ValidateCallbackResult::Invalid(reason));
-    }
-
-    // 2. Authorization check: Only the original seller can update their listing
-    // The Update action contains the original_action_hash which created the entry
-    // We need to verify the author of the update matches the original author
+    // 1. Authorization check: Only the original seller can update their listing
+    // The Update action references the previous version, written by the
+    // seller (back to the action that created the listing)
 
-    // Get the original action that created this listing
+    // Get the previous version's action
     let original_action_hash = action.original_action_address.clone();
 
     // The author of the current update action
@@ -339,36 +901,346 @@ fn validate_update_listing(
         ));
     }
 
-    // 3. Validate that certain fields cannot be changed (seller protection)
-    // The created_at timestamp should never change on updates
-    let original_entry = must_get_entry(action.original_entry_address.clone())?;
+    // Each version is updated once, so the price change record (and the
+    // interval it enforces) cannot be sidestepped by forking the history
+    if is_forked_update(action)? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "This listing version has already been updated".into(),
+        ));
+    }
 
-    if let Entry::App(app_entry) = original_entry.as_content() {
-        // Try to deserialize the original listing
-        if let Ok(original_listing) = Listing::try_from(app_entry.clone().into_sb()) {
-            // created_at should never change
-            if listing.created_at != original_listing.created_at {
-                return Ok(ValidateCallbackResult::Invalid(
-                    "Cannot modify the creation timestamp".into()
-                ));
+    // 2. Check the new version against the one it updates
+    let previous_entry = must_get_entry(action.original_entry_address.clone())?;
+
+    if let Entry::App(app_entry) = previous_entry.as_content() {
+        // Try to deserialize the previous version
+        if let Ok(previous_listing) = Listing::try_from(app_entry.clone().into_sb()) {
+            return check_listing_update(&previous_listing, listing, action.timestamp);
+        }
+    }
+
+    validate_listing_data(listing)
+}
+
+/// Check an updated listing's data against the version it updates
+///
+/// Runs the same data validations as create, then the rules on what an
+/// update may change: the creation time is fixed, the update time never
+/// goes back, and status and price changes follow `check_status_change`
+/// and `check_price_change`.
+pub fn check_listing_update(
+    previous: &Listing,
+    listing: &Listing,
+    updated_at: Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
+    let data_validation = validate_listing_data(listing)?;
+    if let ValidateCallbackResult::Invalid(reason) = data_validation {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    // created_at should never change
+    if listing.created_at != previous.created_at {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Cannot modify the creation timestamp".into()
+        ));
+    }
+
+    // updated_at should be greater than or equal to the previous version's
+    if listing.updated_at < previous.updated_at {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Update timestamp cannot be earlier than the previous version".into()
+        ));
+    }
+
+    // Only legal status transitions
+    if let Err(reason) = check_status_change(previous, listing, updated_at) {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    // Cap how often the price changes
+    if let Err(reason) = check_price_change(previous, listing, updated_at) {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Whether the seller already updated the version an update references
+///
+/// Only the seller updates a listing, so any earlier update of the same
+/// version sits on their chain between that version and this action.
+fn is_forked_update(action: &Update) -> ExternResult<bool> {
+    let filter = ChainFilter::new(action.prev_action.clone())
+        .until_hash(action.original_action_address.clone());
+
+    for activity in must_get_agent_activity(action.author.clone(), filter)? {
+        if let Action::Update(update) = activity.action.action() {
+            if update.original_action_address == action.original_action_address {
+                return Ok(true);
             }
+        }
+    }
+
+    Ok(false)
+}
 
-            // updated_at should be greater than or equal to original
-            if listing.updated_at < original_listing.updated_at {
+/// Validate a discovery link to a listing
+///
+/// The link must target the listing's create action and be made by its
+/// seller, from the seller's key (`AgentToListings`), the `all_listings`
+/// anchor or the listing's creation-day bucket (`AllListings`), or the
+/// status or category path of the listing version its tag names. Any
+/// version of the listing may be named, since validation cannot know
+/// which one is latest. `AllListings` links between calendar anchors
+/// are checked by `validate_calendar_link` instead.
+fn validate_create_listing_link(
+    link_type: LinkTypes,
+    base_address: &AnyLinkableHash,
+    target_address: &AnyLinkableHash,
+    tag: &LinkTag,
+    action: &CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+    if link_type == LinkTypes::AllListings {
+        if let Some(anchor) = target_address.clone().into_entry_hash() {
+            return validate_calendar_link(base_address, &anchor, tag);
+        }
+    }
+
+    let listing_hash = match target_address.clone().into_action_hash() {
+        Some(listing_hash) => listing_hash,
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Listing links must target the listing's create action".into(),
+            ))
+        }
+    };
+
+    let record = must_get_valid_record(listing_hash.clone())?;
+    let listing: Listing = match (record.action(), record.entry().to_app_option()) {
+        (Action::Create(_), Ok(Some(listing))) => listing,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Link target is not a listing's create action".into(),
+            ))
+        }
+    };
+
+    if record.action().author() != &action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the seller can link their listing".into(),
+        ));
+    }
+
+    let allowed_bases: Vec<EntryHash> = match link_type {
+        LinkTypes::AgentToListings => {
+            if base_address != &AnyLinkableHash::from(action.author.clone()) {
                 return Ok(ValidateCallbackResult::Invalid(
-                    "Update timestamp cannot be earlier than original".into()
+                    "Listings can only be linked from their seller".into(),
                 ));
             }
+            return Ok(ValidateCallbackResult::Valid);
+        }
+        LinkTypes::AllListings => vec![
+            all_listings_path().path_entry_hash()?,
+            day_bucket_path(day_number(listing.created_at)).path_entry_hash()?,
+        ],
+        _ => {
+            let version = match tagged_version(tag) {
+                Some(version) => version,
+                None => {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "Status and category links must name the listing version they index".into(),
+                    ))
+                }
+            };
+            let indexed = match get_listing_version(&version, &listing_hash)? {
+                Ok(indexed) => indexed,
+                Err(reason) => return Ok(ValidateCallbackResult::Invalid(reason)),
+            };
 
-            // Prevent resurrection of deleted listings
-            if original_listing.status == ListingStatus::Deleted
-                && listing.status != ListingStatus::Deleted
-            {
+            if link_type == LinkTypes::StatusToListings {
+                vec![status_path(&indexed.status).path_entry_hash()?]
+            } else if is_browsable(&indexed.status) {
+                vec![category_path(&indexed.category).path_entry_hash()?]
+            } else {
                 return Ok(ValidateCallbackResult::Invalid(
-                    "Cannot reactivate a deleted listing - create a new one instead".into()
+                    "Only listings buyers can browse are linked under their category".into(),
                 ));
             }
         }
+    };
+
+    if !allowed_bases
+        .into_iter()
+        .any(|base| base_address == &AnyLinkableHash::from(base))
+    {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "{:?} link base does not match the listing",
+            link_type
+        )));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// A listing version, checked to descend from the listing's create action
+fn get_listing_version(
+    version_hash: &ActionHash,
+    listing_hash: &ActionHash,
+) -> ExternResult<Result<Listing, String>> {
+    let record = must_get_valid_record(version_hash.clone())?;
+    let listing: Listing = match record.entry().to_app_option() {
+        Ok(Some(listing)) => listing,
+        _ => return Ok(Err("Indexed version is not a listing".into())),
+    };
+
+    // Follow the updates back to the action that created the listing
+    let mut action = record.action().clone();
+    let mut action_hash = version_hash.clone();
+    while let Action::Update(update) = action {
+        action_hash = update.original_action_address.clone();
+        action = must_get_action(action_hash.clone())?.action().clone();
+    }
+
+    if &action_hash != listing_hash {
+        return Ok(Err("Indexed version belongs to another listing".into()));
+    }
+
+    Ok(Ok(listing))
+}
+
+/// Validate an `AllListings` link between calendar anchors
+///
+/// Its tag names a day, and it must be one of the links `calendar_links`
+/// gives for that day.
+fn validate_calendar_link(
+    base_address: &AnyLinkableHash,
+    anchor: &EntryHash,
+    tag: &LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let day = match tagged_day(tag) {
+        Some(day) => day,
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Calendar links must be tagged with a day number".into(),
+            ))
+        }
+    };
+
+    for (base, target) in calendar_links(day) {
+        if base_address == &AnyLinkableHash::from(base.path_entry_hash()?)
+            && anchor == &target.path_entry_hash()?
+        {
+            return Ok(ValidateCallbackResult::Valid);
+        }
+    }
+
+    Ok(ValidateCallbackResult::Invalid(format!(
+        "Calendar link does not join the anchors of day {}",
+        day
+    )))
+}
+
+/// Validate reservation creation
+fn validate_create_reservation(
+    reservation: &InventoryReservation,
+    action: &Create,
+) -> ExternResult<ValidateCallbackResult> {
+    // Buyers reserve for themselves
+    if reservation.buyer != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Reservation must be authored by the buyer".into(),
+        ));
+    }
+
+    if reservation.quantity == 0 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Reservation quantity must be at least 1".into(),
+        ));
+    }
+
+    if reservation.status != ReservationStatus::Reserved {
+        return Ok(ValidateCallbackResult::Invalid(
+            "New reservations must be in Reserved state".into(),
+        ));
+    }
+
+    if reservation.expires_at <= reservation.created_at {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Reservation must expire after it is created".into(),
+        ));
+    }
+
+    // Sellers cannot reserve their own stock
+    let listing_action = must_get_action(reservation.listing_hash.clone())?;
+    if listing_action.action().author() == &action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Sellers cannot reserve their own listing".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate reservation updates
+///
+/// Updates are made against the previous version. Only the status may
+/// change: Reserved → Committed (seller), Reserved → Released (buyer or
+/// seller), or Committed → Released (seller, restocking the listing).
+fn validate_update_reservation(
+    reservation: &InventoryReservation,
+    action: &Update,
+) -> ExternResult<ValidateCallbackResult> {
+    let previous_record = must_get_valid_record(action.original_action_address.clone())?;
+    let previous: InventoryReservation = match previous_record.entry().to_app_option() {
+        Ok(Some(previous)) => previous,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Updated entry must be a reservation".into(),
+            ))
+        }
+    };
+
+    if reservation.listing_hash != previous.listing_hash
+        || reservation.transaction_hash != previous.transaction_hash
+        || reservation.buyer != previous.buyer
+        || reservation.quantity != previous.quantity
+        || reservation.variant_sku != previous.variant_sku
+        || reservation.expires_at != previous.expires_at
+        || reservation.created_at != previous.created_at
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the reservation status can change".into(),
+        ));
+    }
+
+    if previous.status == ReservationStatus::Released {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Reservation has already been released".into(),
+        ));
+    }
+
+    let seller = must_get_action(reservation.listing_hash.clone())?
+        .action()
+        .author()
+        .clone();
+
+    // Releasing a committed reservation puts its units back into the
+    // listing, which only the seller can do
+    let allowed = match (&previous.status, &reservation.status) {
+        (ReservationStatus::Reserved, ReservationStatus::Committed) => action.author == seller,
+        (ReservationStatus::Reserved, ReservationStatus::Released) => {
+            action.author == seller || action.author == reservation.buyer
+        }
+        (ReservationStatus::Committed, ReservationStatus::Released) => action.author == seller,
+        _ => false,
+    };
+
+    if !allowed {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Agent is not allowed to move a reservation to {:?}",
+            reservation.status
+        )));
     }
 
     Ok(ValidateCallbackResult::Valid)
@@ -409,4 +1281,337 @@ mod tests {
         assert!(!is_valid_ipfs_cid("notacid"));
         assert!(!is_valid_ipfs_cid(""));
     }
+
+    const PHOTO: &str = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+
+    fn variant(sku: &str, options: &[&str], quantity_available: u32) -> ListingVariant {
+        ListingVariant {
+            sku: sku.to_string(),
+            options: options.iter().map(|o| o.to_string()).collect(),
+            price_cents: None,
+            quantity_available,
+            photos_ipfs_cids: vec![],
+        }
+    }
+
+    fn listing_with_variants(variants: Vec<ListingVariant>) -> Listing {
+        Listing {
+            title: "T-shirt".to_string(),
+            description: "Organic cotton".to_string(),
+            price_cents: 2500,
+            currency: "USD".to_string(),
+            category: ListingCategory::Fashion,
+            photos_ipfs_cids: vec![PHOTO.to_string()],
+            quantity_available: variants.iter().map(|v| v.quantity_available).sum(),
+            option_axes: vec![
+                OptionAxis {
+                    name: "Size".to_string(),
+                    values: vec!["S".to_string(), "M".to_string()],
+                },
+                OptionAxis {
+                    name: "Color".to_string(),
+                    values: vec!["Red".to_string(), "Blue".to_string()],
+                },
+            ],
+            variants,
+            status: ListingStatus::Active,
+            publish_at: None,
+            expires_at: None,
+            price_changed_at: None,
+            epistemic: EpistemicClassification {
+                empirical: EmpiricalLevel::E1Testimonial,
+                normative: NormativeLevel::N0Personal,
+                materiality: MaterialityLevel::M1Temporal,
+            },
+            created_at: Timestamp::from_micros(0),
+            updated_at: Timestamp::from_micros(0),
+        }
+    }
+
+    #[test]
+    fn test_variants_accept_distinct_combinations() {
+        let mut listing = listing_with_variants(vec![
+            variant("TS-S-RED", &["S", "Red"], 3),
+            variant("TS-M-BLUE", &["M", "Blue"], 2),
+        ]);
+        listing.variants[1].price_cents = Some(2800);
+        listing.variants[1].photos_ipfs_cids = vec![PHOTO.to_string()];
+
+        assert_eq!(check_variants(&listing), Ok(()));
+        assert_eq!(listing.quantity_available, 5);
+        assert_eq!(listing.variant_price_cents(&listing.variants[0]), 2500);
+        assert_eq!(listing.variant_price_cents(&listing.variants[1]), 2800);
+    }
+
+    #[test]
+    fn test_variants_reject_repeated_combination_or_sku() {
+        let listing = listing_with_variants(vec![
+            variant("A", &["S", "Red"], 1),
+            variant("B", &["S", "Red"], 1),
+        ]);
+        assert!(check_variants(&listing).is_err());
+
+        let listing = listing_with_variants(vec![
+            variant("A", &["S", "Red"], 1),
+            variant("A", &["M", "Red"], 1),
+        ]);
+        assert!(check_variants(&listing).is_err());
+    }
+
+    #[test]
+    fn test_variants_reject_unknown_values_and_missing_axes() {
+        let listing = listing_with_variants(vec![variant("A", &["XL", "Red"], 1)]);
+        assert!(check_variants(&listing).is_err());
+
+        let listing = listing_with_variants(vec![variant("A", &["S"], 1)]);
+        assert!(check_variants(&listing).is_err());
+    }
+
+    #[test]
+    fn test_variants_enforce_per_variant_limits() {
+        let mut listing = listing_with_variants(vec![variant("A", &["S", "Red"], 1)]);
+        listing.variants[0].price_cents = Some(0);
+        assert!(check_variants(&listing).is_err());
+
+        let mut listing = listing_with_variants(vec![variant("A", &["S", "Red"], 1)]);
+        listing.variants[0].photos_ipfs_cids = vec!["QmSomeOtherPhoto".to_string()];
+        assert!(check_variants(&listing).is_err());
+
+        let mut listing = listing_with_variants(vec![variant("A", &["S", "Red"], 1)]);
+        listing.quantity_available = 4;
+        assert!(check_variants(&listing).is_err());
+    }
+
+    #[test]
+    fn test_schedule_requires_publish_time_before_expiry() {
+        let mut listing = listing_with_variants(vec![variant("A", &["S", "Red"], 1)]);
+        listing.created_at = Timestamp::from_micros(1000);
+        assert_eq!(check_schedule(&listing), Ok(()));
+
+        listing.status = ListingStatus::Scheduled;
+        assert!(check_schedule(&listing).is_err());
+
+        listing.publish_at = Some(Timestamp::from_micros(5000));
+        listing.expires_at = Some(Timestamp::from_micros(9000));
+        assert_eq!(check_schedule(&listing), Ok(()));
+
+        listing.expires_at = Some(Timestamp::from_micros(5000));
+        assert!(check_schedule(&listing).is_err());
+
+        listing.status = ListingStatus::Active;
+        listing.publish_at = None;
+        listing.expires_at = Some(Timestamp::from_micros(500));
+        assert!(check_schedule(&listing).is_err());
+    }
+
+    const HOUR: i64 = 60 * 60 * 1_000_000;
+
+    #[test]
+    fn test_price_change_must_be_recorded() {
+        let previous = listing_with_variants(vec![variant("A", &["S", "Red"], 1)]);
+        let at = Timestamp::from_micros(100 * HOUR);
+
+        // Unchanged price, unchanged record
+        assert_eq!(check_price_change(&previous, &previous, at), Ok(()));
+
+        let mut listing = previous.clone();
+        listing.price_cents = 2700;
+        assert!(check_price_change(&previous, &listing, at).is_err()); // Not recorded
+
+        listing.price_changed_at = Some(Timestamp::from_micros(10 * HOUR));
+        assert!(check_price_change(&previous, &listing, at).is_err()); // Backdated
+
+        listing.price_changed_at = Some(at);
+        assert_eq!(check_price_change(&previous, &listing, at), Ok(()));
+
+        // The record cannot move without a price change
+        let mut touched = previous.clone();
+        touched.price_changed_at = Some(at);
+        assert!(check_price_change(&previous, &touched, at).is_err());
+    }
+
+    #[test]
+    fn test_variant_prices_count_as_price_changes() {
+        let previous = listing_with_variants(vec![
+            variant("A", &["S", "Red"], 1),
+            variant("B", &["M", "Red"], 1),
+        ]);
+
+        // Stock moves are not price changes
+        let mut restocked = previous.clone();
+        restocked.variants[0].quantity_available = 5;
+        assert!(!restocked.prices_differ(&previous));
+
+        // Overriding at the listing price changes nothing buyers pay
+        let mut same_price = previous.clone();
+        same_price.variants[1].price_cents = Some(2500);
+        assert!(!same_price.prices_differ(&previous));
+
+        let mut repriced = previous.clone();
+        repriced.variants[1].price_cents = Some(2800);
+        assert!(repriced.prices_differ(&previous));
+
+        let at = Timestamp::from_micros(100 * HOUR);
+        assert!(check_price_change(&previous, &repriced, at).is_err()); // Not recorded
+        repriced.price_changed_at = Some(at);
+        assert_eq!(check_price_change(&previous, &repriced, at), Ok(()));
+
+        // Dropping a variant and adding it back at a new price is a change
+        let dropped = listing_with_variants(vec![variant("A", &["S", "Red"], 1)]);
+        let mut readded = previous.clone();
+        readded.variants[1].price_cents = Some(2800);
+        assert!(readded.prices_differ(&dropped));
+    }
+
+    #[test]
+    fn test_price_changes_are_rate_limited() {
+        let mut previous = listing_with_variants(vec![variant("A", &["S", "Red"], 1)]);
+        previous.price_changed_at = Some(Timestamp::from_micros(100 * HOUR));
+
+        let mut listing = previous.clone();
+        listing.currency = "EUR".to_string();
+
+        let soon = Timestamp::from_micros(110 * HOUR);
+        listing.price_changed_at = Some(soon);
+        assert!(check_price_change(&previous, &listing, soon).is_err());
+
+        let later = Timestamp::from_micros((100 + MIN_PRICE_CHANGE_INTERVAL_HOURS) * HOUR);
+        listing.price_changed_at = Some(later);
+        assert_eq!(check_price_change(&previous, &listing, later), Ok(()));
+    }
+
+    fn with_status(listing: &Listing, status: ListingStatus, quantity_available: u32) -> Listing {
+        let mut listing = listing.clone();
+        listing.variants.clear();
+        listing.status = status;
+        listing.quantity_available = quantity_available;
+        listing
+    }
+
+    #[test]
+    fn test_status_changes_follow_stock() {
+        let base = listing_with_variants(vec![]);
+        let at = Timestamp::from_micros(100 * HOUR);
+
+        let active = with_status(&base, ListingStatus::Active, 3);
+        let sold_out = with_status(&base, ListingStatus::Sold, 0);
+        assert!(check_status_change(&active, &with_status(&base, ListingStatus::Sold, 3), at).is_err());
+        assert_eq!(check_status_change(&active, &sold_out, at), Ok(()));
+
+        // Un-selling needs stock back
+        assert!(check_status_change(&sold_out, &with_status(&base, ListingStatus::Active, 0), at).is_err());
+        assert!(check_status_change(&sold_out, &with_status(&base, ListingStatus::Inactive, 0), at).is_err());
+        assert_eq!(check_status_change(&sold_out, &with_status(&base, ListingStatus::Active, 2), at), Ok(()));
+        assert_eq!(check_status_change(&sold_out, &with_status(&base, ListingStatus::Deleted, 0), at), Ok(()));
+
+        let deleted = with_status(&base, ListingStatus::Deleted, 3);
+        assert!(check_status_change(&deleted, &active, at).is_err());
+        assert!(check_status_change(&active, &with_status(&base, ListingStatus::Draft, 3), at).is_err());
+    }
+
+    fn is_valid(result: ExternResult<ValidateCallbackResult>) -> bool {
+        matches!(result, Ok(ValidateCallbackResult::Valid))
+    }
+
+    #[test]
+    fn test_listings_off_sale_can_update_with_no_stock() {
+        let mut base = listing_with_variants(vec![]);
+        base.option_axes.clear();
+        let at = Timestamp::from_micros(100 * HOUR);
+
+        let active = with_status(&base, ListingStatus::Active, 3);
+        let sold_out = with_status(&base, ListingStatus::Sold, 0);
+        let deleted = with_status(&base, ListingStatus::Deleted, 0);
+        let inactive = with_status(&base, ListingStatus::Inactive, 0);
+
+        assert!(is_valid(check_listing_update(&active, &sold_out, at)));
+        assert!(is_valid(check_listing_update(&sold_out, &deleted, at)));
+        assert!(is_valid(check_listing_update(&inactive, &deleted, at)));
+        assert!(is_valid(check_listing_update(&with_status(&base, ListingStatus::Inactive, 3), &inactive, at)));
+
+        // Listings on sale still need stock
+        assert!(!is_valid(check_listing_update(&active, &with_status(&base, ListingStatus::Active, 0), at)));
+        assert!(!is_valid(check_listing_update(&active, &with_status(&base, ListingStatus::Scheduled, 0), at)));
+    }
+
+    #[test]
+    fn test_scheduled_listings_go_live_at_publish_time() {
+        let mut scheduled = with_status(&listing_with_variants(vec![]), ListingStatus::Scheduled, 3);
+        scheduled.publish_at = Some(Timestamp::from_micros(100 * HOUR));
+        let mut live = scheduled.clone();
+        live.status = ListingStatus::Active;
+
+        assert!(check_status_change(&scheduled, &live, Timestamp::from_micros(99 * HOUR)).is_err());
+        assert_eq!(check_status_change(&scheduled, &live, Timestamp::from_micros(100 * HOUR)), Ok(()));
+
+        // Publishing early means moving the publish time
+        live.publish_at = Some(Timestamp::from_micros(99 * HOUR));
+        assert_eq!(check_status_change(&scheduled, &live, Timestamp::from_micros(99 * HOUR)), Ok(()));
+    }
+
+    #[test]
+    fn test_index_paths() {
+        assert_eq!(
+            category_path(&ListingCategory::HomeGarden),
+            Path::from("listings.category.HomeGarden")
+        );
+        assert_eq!(
+            status_path(&ListingStatus::Scheduled),
+            Path::from("listings.status.Scheduled")
+        );
+    }
+
+    #[test]
+    fn test_browsable_statuses() {
+        assert!(is_browsable(&ListingStatus::Active));
+        assert!(is_browsable(&ListingStatus::Sold));
+        assert!(is_browsable(&ListingStatus::Inactive));
+        assert!(!is_browsable(&ListingStatus::Draft));
+        assert!(!is_browsable(&ListingStatus::Scheduled));
+        assert!(!is_browsable(&ListingStatus::Deleted));
+    }
+
+    #[test]
+    fn test_civil_date() {
+        assert_eq!(civil_date(0), (1970, 1, 1));
+        assert_eq!(civil_date(59), (1970, 3, 1));
+        assert_eq!(civil_date(11_016), (2000, 2, 29));
+        assert_eq!(civil_date(20_378), (2025, 10, 17));
+        assert_eq!(civil_date(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn test_day_bucket_path() {
+        let created_at = Timestamp::from_micros(20_378 * MICROS_PER_DAY + 5_000_000);
+
+        assert_eq!(day_number(created_at), 20_378);
+        assert_eq!(
+            day_bucket_path(day_number(created_at)),
+            Path::from("all_listings.2025.10.17")
+        );
+    }
+
+    #[test]
+    fn test_calendar_links_join_a_days_anchors() {
+        assert_eq!(
+            calendar_links(20_378),
+            [
+                (Path::from("all_listings.years"), Path::from("all_listings.2025")),
+                (Path::from("all_listings.2025"), Path::from("all_listings.2025.10")),
+                (Path::from("all_listings.2025.10"), Path::from("all_listings.2025.10.17")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_link_tags_round_trip() {
+        assert_eq!(tagged_day(&day_tag(20_378)), Some(20_378));
+        assert_eq!(tagged_day(&day_tag(-1)), Some(-1));
+        assert_eq!(tagged_day(&LinkTag::new("October")), None);
+
+        let version = ActionHash::from_raw_36(vec![7; 36]);
+        assert_eq!(tagged_version(&version_tag(&version)), Some(version));
+        assert_eq!(tagged_version(&LinkTag::new(Vec::<u8>::new())), None);
+        assert_eq!(tagged_version(&day_tag(20_378)), None);
+    }
 }
@@ -1,12 +1,12 @@
-/// Messaging Coordinator Zome
-///
-/// Provides P2P encrypted messaging for the Mycelix-Marketplace.
-/// This coordinator implements the business logic for:
-/// - Sending and receiving messages
-/// - Managing conversations
-/// - Read receipts and typing indicators
-/// - Spam prevention via MATL gating
-/// - Message search and filtering
+//! Messaging Coordinator Zome
+//!
+//! Provides P2P encrypted messaging for the Mycelix-Marketplace.
+//! This coordinator implements the business logic for:
+//! - Sending and receiving messages
+//! - Managing conversations
+//! - Read receipts and typing indicators
+//! - Spam prevention via MATL gating
+//! - Message search and filtering
 
 use hdk::prelude::*;
 use messaging_integrity::*;
@@ -207,7 +207,7 @@ pub fn start_conversation(input: StartConversationInput) -> ExternResult<Convers
     Ok(ConversationOutput {
         conversation_hash,
         conversation,
-        first_message: first_message,
+        first_message,
     })
 }
 
@@ -2,8 +2,6 @@ use super::*;
 
 #[cfg(test)]
 mod messaging_tests {
-    use super::*;
-
     // ===== Message Creation Tests =====
 
     #[test]
@@ -263,7 +261,10 @@ mod messaging_tests {
 
 // ===== Test Helpers =====
 
+/// Fixtures for the message and conversation tests above once they drive
+/// the zome through a mocked HDK
 #[cfg(test)]
+#[allow(dead_code)]
 mod test_helpers {
     use super::*;
 
@@ -279,7 +280,7 @@ mod test_helpers {
             encrypted_content: content.to_string(),
             listing_hash: None,
             transaction_hash: None,
-            conversation_id: ActionHash::from_raw_39(vec![0; 39]).unwrap(),
+            conversation_id: ActionHash::from_raw_39(vec![0; 39]),
             sent_at: 1000,
             read_at: None,
             message_type: MessageType::Text,
@@ -301,8 +302,8 @@ mod test_helpers {
             listing_hash: None,
             transaction_hash: None,
             subject,
-            first_message_hash: ActionHash::from_raw_39(vec![0; 39]).unwrap(),
-            last_message_hash: ActionHash::from_raw_39(vec![0; 39]).unwrap(),
+            first_message_hash: ActionHash::from_raw_39(vec![0; 39]),
+            last_message_hash: ActionHash::from_raw_39(vec![0; 39]),
             message_count: 0,
             unread_counts: Vec::new(),
             started_at: 1000,
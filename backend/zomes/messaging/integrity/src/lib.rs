@@ -1,13 +1,13 @@
-/// Messaging Integrity Zome
-///
-/// This zome provides P2P encrypted messaging for the Mycelix-Marketplace.
-/// Features:
-/// - End-to-end encrypted messages
-/// - Conversation threading (by listing or transaction)
-/// - Rich media attachments (IPFS)
-/// - Read receipts and typing indicators
-/// - MATL-gated messaging (spam prevention)
-/// - Message search and filtering
+//! Messaging Integrity Zome
+//!
+//! This zome provides P2P encrypted messaging for the Mycelix-Marketplace.
+//! Features:
+//! - End-to-end encrypted messages
+//! - Conversation threading (by listing or transaction)
+//! - Rich media attachments (IPFS)
+//! - Read receipts and typing indicators
+//! - MATL-gated messaging (spam prevention)
+//! - Message search and filtering
 
 use hdi::prelude::*;
 
@@ -255,7 +255,7 @@ pub enum LinkTypes {
     TransactionToConversations,
 }
 
-/// Validation rules for messages
+// Validation rules for messages
 
 /// Validate message creation
 pub fn validate_create_message(message: Message, action: &Create) -> ExternResult<ValidateCallbackResult> {
@@ -360,13 +360,10 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
-        FlatOp::RegisterUpdate(update_entry) => match update_entry {
-            OpUpdate::Entry { app_entry, .. } => match app_entry {
-                EntryTypes::Message(_message) => Ok(ValidateCallbackResult::Valid),
-                EntryTypes::Conversation(_conversation) => Ok(ValidateCallbackResult::Valid),
-                EntryTypes::ReadReceipt(_receipt) => Ok(ValidateCallbackResult::Valid),
-            },
-            _ => Ok(ValidateCallbackResult::Valid),
+        FlatOp::RegisterUpdate(OpUpdate::Entry { app_entry, .. }) => match app_entry {
+            EntryTypes::Message(_message) => Ok(ValidateCallbackResult::Valid),
+            EntryTypes::Conversation(_conversation) => Ok(ValidateCallbackResult::Valid),
+            EntryTypes::ReadReceipt(_receipt) => Ok(ValidateCallbackResult::Valid),
         },
         FlatOp::RegisterDelete(_delete_entry) => {
             // Allow deletion of messages, conversations, receipts
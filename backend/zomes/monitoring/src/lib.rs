@@ -1,7 +1,7 @@
-/// Monitoring and Metrics System for Mycelix-Marketplace
-///
-/// This module provides real-time monitoring, Byzantine attack detection,
-/// and operational visibility for the marketplace.
+//! Monitoring and Metrics System for Mycelix-Marketplace
+//!
+//! This module provides real-time monitoring, Byzantine attack detection,
+//! and operational visibility for the marketplace.
 
 use hdk::prelude::*;
 use std::collections::VecDeque;
@@ -158,6 +158,12 @@ pub struct MarketplaceMetrics {
     pub active_alerts: Vec<Alert>,
 }
 
+impl Default for MarketplaceMetrics {
+    fn default() -> Self {
+        Self::new(1000) // Keep last 1000 events
+    }
+}
+
 impl MarketplaceMetrics {
     pub fn new(max_recent_events: usize) -> Self {
         Self {
@@ -175,10 +181,6 @@ impl MarketplaceMetrics {
         }
     }
 
-    pub fn default() -> Self {
-        Self::new(1000) // Keep last 1000 events
-    }
-
     /// Record a metric event
     pub fn record_event(&mut self, event: MetricEvent) -> ExternResult<()> {
         // Update counters
@@ -325,6 +327,12 @@ pub struct PerformanceMetrics {
     pub cache_misses: u64,
 }
 
+impl Default for PerformanceMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PerformanceMetrics {
     pub fn new() -> Self {
         Self {
@@ -361,22 +369,14 @@ static mut GLOBAL_PERF_METRICS: Option<PerformanceMetrics> = None;
 
 /// Get or initialize global metrics
 pub fn get_metrics() -> &'static mut MarketplaceMetrics {
-    unsafe {
-        if GLOBAL_METRICS.is_none() {
-            GLOBAL_METRICS = Some(MarketplaceMetrics::default());
-        }
-        GLOBAL_METRICS.as_mut().unwrap()
-    }
+    // Zome calls run single-threaded, so nothing else holds the metrics
+    unsafe { (*std::ptr::addr_of_mut!(GLOBAL_METRICS)).get_or_insert_with(MarketplaceMetrics::default) }
 }
 
 /// Get or initialize performance metrics
 pub fn get_perf_metrics() -> &'static mut PerformanceMetrics {
-    unsafe {
-        if GLOBAL_PERF_METRICS.is_none() {
-            GLOBAL_PERF_METRICS = Some(PerformanceMetrics::new());
-        }
-        GLOBAL_PERF_METRICS.as_mut().unwrap()
-    }
+    // Zome calls run single-threaded, so nothing else holds the metrics
+    unsafe { (*std::ptr::addr_of_mut!(GLOBAL_PERF_METRICS)).get_or_insert_with(PerformanceMetrics::new) }
 }
 
 /// Emit metric (public API)
@@ -408,8 +408,18 @@ pub fn get_active_alerts() -> Vec<Alert> {
 mod tests {
     use super::*;
 
+    /// Serve `sys_time` from a fixed clock, as tests run without a conductor
+    fn mock_clock() {
+        let mut mock_hdk = MockHdkT::new();
+        mock_hdk
+            .expect_sys_time()
+            .returning(|_| Ok(Timestamp::from_micros(1_000_000)));
+        set_hdk(mock_hdk);
+    }
+
     #[test]
     fn test_metric_event_creation() {
+        mock_clock();
         let event = MetricEvent::new(
             MetricType::TransactionCreated,
             1.0,
@@ -424,6 +434,7 @@ mod tests {
 
     #[test]
     fn test_metrics_recording() {
+        mock_clock();
         let mut metrics = MarketplaceMetrics::default();
 
         let event1 = MetricEvent::new(MetricType::TransactionCreated, 1.0, None, None).unwrap();
@@ -439,6 +450,7 @@ mod tests {
 
     #[test]
     fn test_success_rate_calculation() {
+        mock_clock();
         let mut metrics = MarketplaceMetrics::default();
 
         // 7 out of 10 completed
@@ -458,6 +470,7 @@ mod tests {
 
     #[test]
     fn test_dispute_rate_calculation() {
+        mock_clock();
         let mut metrics = MarketplaceMetrics::default();
 
         // 2 out of 10 disputed
@@ -476,6 +489,7 @@ mod tests {
 
     #[test]
     fn test_matl_average_calculation() {
+        mock_clock();
         let mut metrics = MarketplaceMetrics::default();
 
         let scores = vec![0.8, 0.7, 0.9, 0.6];
@@ -491,6 +505,7 @@ mod tests {
 
     #[test]
     fn test_byzantine_spike_alert() {
+        mock_clock();
         let count = 150;
         let alert = Alert::byzantine_spike(count).unwrap();
 
@@ -500,6 +515,7 @@ mod tests {
 
     #[test]
     fn test_high_dispute_rate_alert() {
+        mock_clock();
         let alert = Alert::high_dispute_rate(0.15).unwrap();
 
         assert_eq!(alert.severity, AlertSeverity::Warning);
@@ -508,6 +524,7 @@ mod tests {
 
     #[test]
     fn test_network_compromised_alert() {
+        mock_clock();
         let alert = Alert::network_compromised(0.3).unwrap();
 
         assert_eq!(alert.severity, AlertSeverity::Critical);
@@ -516,6 +533,7 @@ mod tests {
 
     #[test]
     fn test_anomaly_detection() {
+        mock_clock();
         let mut metrics = MarketplaceMetrics::default();
 
         // Simulate Byzantine attack spike
@@ -525,7 +543,7 @@ mod tests {
         }
 
         // Should generate alert
-        assert!(metrics.active_alerts.len() > 0);
+        assert!(!metrics.active_alerts.is_empty());
         assert!(metrics
             .active_alerts
             .iter()
@@ -534,6 +552,7 @@ mod tests {
 
     #[test]
     fn test_dashboard_generation() {
+        mock_clock();
         let mut metrics = MarketplaceMetrics::default();
 
         // Add some data
@@ -564,6 +583,7 @@ mod tests {
 
     #[test]
     fn test_recent_events_size_limit() {
+        mock_clock();
         let mut metrics = MarketplaceMetrics::new(10); // Max 10 events
 
         // Add 20 events
@@ -36,7 +36,7 @@ pub struct NotificationFilter {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 pub enum NotificationSignal {
-    NewNotification { notification_hash: ActionHash, notification: Notification },
+    NewNotification { notification_hash: ActionHash, notification: Box<Notification> },
     NotificationRead { notification_hash: ActionHash },
     NotificationDismissed { notification_hash: ActionHash },
     AllNotificationsRead,
@@ -80,7 +80,7 @@ pub fn create_notification(notification: Notification) -> ExternResult<ActionHas
 
     emit_signal(NotificationSignal::NewNotification {
         notification_hash: notification_hash.clone(),
-        notification,
+        notification: Box::new(notification),
     })?;
 
     Ok(notification_hash)
@@ -104,7 +104,7 @@ pub fn get_my_notifications(filter: NotificationFilter) -> ExternResult<Notifica
         }
     }
 
-    notifications.sort_by(|a, b| b.notification.created_at.cmp(&a.notification.created_at));
+    notifications.sort_by_key(|n| std::cmp::Reverse(n.notification.created_at));
     if let Some(limit) = filter.limit {
         notifications.truncate(limit as usize);
     }
@@ -224,9 +224,11 @@ fn get_or_create_preferences(agent: AgentPubKey) -> ExternResult<NotificationPre
     let links = get_agent_preferences_links(agent.clone())?;
 
     if links.is_empty() {
-        let mut prefs = NotificationPreferences::default();
-        prefs.agent = agent.clone();
-        prefs.updated_at = now_micros()?;
+        let prefs = NotificationPreferences {
+            agent: agent.clone(),
+            updated_at: now_micros()?,
+            ..Default::default()
+        };
         let prefs_hash = create_entry(EntryTypes::NotificationPreferences(prefs.clone()))?;
         create_link(agent, prefs_hash, LinkTypes::AgentToPreferences, ())?;
         Ok(prefs)
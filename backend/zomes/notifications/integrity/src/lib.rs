@@ -107,19 +107,17 @@ pub enum LinkTypes {
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
     match op.flattened::<EntryTypes, LinkTypes>()? {
-        FlatOp::StoreEntry(OpEntry::CreateEntry { app_entry, .. }) => {
-            match app_entry {
-                EntryTypes::Notification(n) => {
-                    if n.title.is_empty() {
-                        return Ok(ValidateCallbackResult::Invalid("Notification title required".into()));
-                    }
-                    if n.message.is_empty() {
-                        return Ok(ValidateCallbackResult::Invalid("Notification message required".into()));
-                    }
-                    Ok(ValidateCallbackResult::Valid)
-                }
-                _ => Ok(ValidateCallbackResult::Valid),
+        FlatOp::StoreEntry(OpEntry::CreateEntry {
+            app_entry: EntryTypes::Notification(n),
+            ..
+        }) => {
+            if n.title.is_empty() {
+                return Ok(ValidateCallbackResult::Invalid("Notification title required".into()));
             }
+            if n.message.is_empty() {
+                return Ok(ValidateCallbackResult::Invalid("Notification message required".into()));
+            }
+            Ok(ValidateCallbackResult::Valid)
         }
         _ => Ok(ValidateCallbackResult::Valid),
     }
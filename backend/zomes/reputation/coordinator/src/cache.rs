@@ -1,12 +1,12 @@
-/// MATL Score Caching System
-///
-/// This module provides intelligent caching for MATL scores to achieve
-/// 10x-100x performance improvements on reputation queries.
-///
-/// Cache Strategy:
-/// - TTL (Time-To-Live): 5 minutes for most queries
-/// - Invalidation: On MATL score updates
-/// - Size: LRU eviction when > 10,000 entries
+//! MATL Score Caching System
+//!
+//! This module provides intelligent caching for MATL scores to achieve
+//! 10x-100x performance improvements on reputation queries.
+//!
+//! Cache Strategy:
+//! - TTL (Time-To-Live): 5 minutes for most queries
+//! - Invalidation: On MATL score updates
+//! - Size: LRU eviction when > 10,000 entries
 
 use hdk::prelude::*;
 use std::collections::HashMap;
@@ -48,6 +48,12 @@ pub struct MatlCache {
     default_ttl: u64,
 }
 
+impl Default for MatlCache {
+    fn default() -> Self {
+        Self::new(10_000, 300) // 10k entries, 5 min TTL
+    }
+}
+
 impl MatlCache {
     pub fn new(max_size: usize, default_ttl: u64) -> Self {
         Self {
@@ -57,10 +63,6 @@ impl MatlCache {
         }
     }
 
-    pub fn default() -> Self {
-        Self::new(10_000, 300) // 10k entries, 5 min TTL
-    }
-
     /// Get cached score if valid, otherwise compute
     pub fn get_or_compute<F>(
         &mut self,
@@ -138,27 +140,7 @@ impl MatlCache {
 
     /// Create default score for new agent
     fn create_default_score(&self, agent: AgentPubKey) -> ExternResult<MatlScore> {
-        Ok(MatlScore {
-            agent,
-            pogq: ProofOfGradientQuality {
-                quality: 0.5,
-                consistency: 0.5,
-                entropy: 0.0,
-                timestamp: time::now()?,
-            },
-            reputation: 0.5,
-            composite: 0.5,
-            transaction_count: 0,
-            total_value_cents: 0,
-            updated_at: time::now()?,
-            flags: ByzantineFlags {
-                cartel_detected: false,
-                volatile_reputation: false,
-                gradient_poisoning: false,
-                sybil_suspected: false,
-                risk_score: 0.0,
-            },
-        })
+        Ok(neutral_matl_score(agent, time::now()?))
     }
 }
 
@@ -180,12 +162,8 @@ static mut GLOBAL_MATL_CACHE: Option<MatlCache> = None;
 
 /// Get or initialize global cache
 pub fn get_cache() -> &'static mut MatlCache {
-    unsafe {
-        if GLOBAL_MATL_CACHE.is_none() {
-            GLOBAL_MATL_CACHE = Some(MatlCache::default());
-        }
-        GLOBAL_MATL_CACHE.as_mut().unwrap()
-    }
+    // Zome calls run single-threaded, so nothing else holds the cache
+    unsafe { (*std::ptr::addr_of_mut!(GLOBAL_MATL_CACHE)).get_or_insert_with(MatlCache::default) }
 }
 
 /// Cached MATL score lookup
@@ -234,6 +212,15 @@ pub fn get_cache_stats() -> CacheStats {
 mod tests {
     use super::*;
 
+    /// Serve `sys_time` from a fixed clock, as tests run without a conductor
+    fn mock_clock() {
+        let mut mock_hdk = MockHdkT::new();
+        mock_hdk
+            .expect_sys_time()
+            .returning(|_| Ok(Timestamp::from_micros(1_000_000)));
+        set_hdk(mock_hdk);
+    }
+
     fn mock_matl_score(agent: AgentPubKey) -> MatlScore {
         MatlScore {
             agent,
@@ -255,11 +242,15 @@ mod tests {
                 sybil_suspected: false,
                 risk_score: 0.0,
             },
+            arbitration: ArbitrationRecord::default(),
+            previous_score: None,
+            outcome: None,
         }
     }
 
     #[test]
     fn test_cache_entry_creation() {
+        mock_clock();
         let agent = AgentPubKey::from_raw_36(vec![1u8; 36]);
         let score = mock_matl_score(agent);
 
@@ -271,6 +262,7 @@ mod tests {
 
     #[test]
     fn test_cache_put_and_get() {
+        mock_clock();
         let mut cache = MatlCache::new(100, 300);
         let agent = AgentPubKey::from_raw_36(vec![1u8; 36]);
         let score = mock_matl_score(agent.clone());
@@ -286,6 +278,7 @@ mod tests {
 
     #[test]
     fn test_cache_invalidation() {
+        mock_clock();
         let mut cache = MatlCache::new(100, 300);
         let agent = AgentPubKey::from_raw_36(vec![1u8; 36]);
         let score = mock_matl_score(agent.clone());
@@ -305,6 +298,7 @@ mod tests {
 
     #[test]
     fn test_cache_max_size_eviction() {
+        mock_clock();
         let mut cache = MatlCache::new(3, 300); // Max 3 entries
 
         let agent1 = AgentPubKey::from_raw_36(vec![1u8; 36]);
@@ -326,6 +320,7 @@ mod tests {
 
     #[test]
     fn test_cache_stats() {
+        mock_clock();
         let mut cache = MatlCache::new(100, 300);
         let agent1 = AgentPubKey::from_raw_36(vec![1u8; 36]);
         let agent2 = AgentPubKey::from_raw_36(vec![2u8; 36]);
@@ -342,6 +337,7 @@ mod tests {
 
     #[test]
     fn test_cache_clear() {
+        mock_clock();
         let mut cache = MatlCache::new(100, 300);
         let agent = AgentPubKey::from_raw_36(vec![1u8; 36]);
 
@@ -356,6 +352,7 @@ mod tests {
 
     #[test]
     fn test_default_score_creation() {
+        mock_clock();
         let cache = MatlCache::default();
         let agent = AgentPubKey::from_raw_36(vec![1u8; 36]);
 
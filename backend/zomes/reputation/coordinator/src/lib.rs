@@ -2,7 +2,10 @@ use hdk::prelude::*;
 use reputation_integrity::*;
 use mycelix_common::{error_handling, link_queries, time};
 
-mod cache;
+pub mod cache;
+mod outcomes;
+
+use outcomes::{DisputeOutcome, ScoreOutcome, ScoreStep};
 
 /// Get or initialize MATL score for an agent
 ///
@@ -10,18 +13,30 @@ mod cache;
 /// New agents start with neutral reputation (0.5).
 #[hdk_extern]
 pub fn get_agent_matl_score(agent: AgentPubKey) -> ExternResult<Option<MatlScore>> {
-    let path = agent.clone();
+    Ok(get_agent_matl_score_record(agent)?.map(|record| record.score))
+}
+
+/// Get an agent's current MATL score together with its action hash
+///
+/// Lets other zomes cite the exact score entry they relied on, e.g.
+/// arbitration panels, whose validation checks each candidate's score.
+/// Returns None until the agent's first score is stored.
+#[hdk_extern]
+pub fn get_agent_matl_score_record(agent: AgentPubKey) -> ExternResult<Option<MatlScoreRecord>> {
     // Use shared utility for get_links
-    let links = link_queries::get_links_local(path, LinkTypes::AgentToScore)?;
-
-    if let Some(link) = links.first() {
-        if let Some(action_hash) = link.target.clone().into_action_hash() {
-            let record = get(action_hash, GetOptions::default())?;
-            if let Some(record) = record {
-                // Use shared utility for deserialization
-                let score: MatlScore = error_handling::deserialize_entry(&record)?;
-                return Ok(Some(score));
-            }
+    let links = link_queries::get_links_local(agent, LinkTypes::AgentToScore)?;
+
+    // Each update links a new score version; the latest link is current
+    let latest = links.into_iter().max_by_key(|link| link.timestamp);
+
+    if let Some(action_hash) = latest.and_then(|link| link.target.into_action_hash()) {
+        if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
+            // Use shared utility for deserialization
+            let score: MatlScore = error_handling::deserialize_entry(&record)?;
+            return Ok(Some(MatlScoreRecord {
+                score_hash: action_hash,
+                score,
+            }));
         }
     }
 
@@ -38,75 +53,112 @@ pub fn get_agent_matl_score_fast(agent: AgentPubKey) -> ExternResult<MatlScore>
     cache::get_agent_matl_score_cached(agent)
 }
 
-/// Update MATL score after a transaction
+/// Update MATL score after a completed transaction
 ///
-/// This implements the core MATL algorithm:
-/// 1. Compute PoGQ (quality, consistency, entropy)
-/// 2. Update reputation based on transaction outcome
-/// 3. Detect Byzantine patterns
-/// 4. Calculate composite score
+/// Only accepted with the transaction's countersigned receipt: both
+/// signatures must verify, the agent must be a party, the value must be
+/// the receipt's, and each transaction counts once per agent. Buyer and
+/// seller are each updated with the same receipt.
 #[hdk_extern]
 pub fn update_matl_score(input: UpdateMatlInput) -> ExternResult<MatlScore> {
-    let agent = input.agent.clone();
+    let receipt = outcomes::get_verified_receipt(input.receipt_hash.clone())?;
+
+    outcomes::check_receipt(
+        &receipt.terms,
+        &input.agent,
+        input.successful,
+        input.transaction_value_cents,
+    )
+    .map_err(|reason| wasm_error!(WasmErrorInner::Guest(reason)))?;
+
+    // Counted per transaction, so a second receipt for it counts nothing
+    let tag = outcomes::outcome_tag("receipt", &input.agent);
+    outcomes::ensure_not_counted(receipt.terms.transaction_hash.clone(), &tag)?;
+
+    let (score_hash, score) = apply_transaction_outcome(
+        input.agent,
+        input.successful,
+        input.transaction_value_cents,
+        ScoreOutcome::Receipt(input.receipt_hash.clone()),
+    )?;
 
-    // Get existing score or create new one
-    let mut score = match get_agent_matl_score(agent.clone())? {
-        Some(existing) => existing,
-        None => {
-            // Initialize new agent with neutral score
-            MatlScore {
-                agent: agent.clone(),
-                pogq: ProofOfGradientQuality {
-                    quality: 0.5,
-                    consistency: 0.5,
-                    entropy: 0.0,
-                    timestamp: time::now()?,
-                },
-                reputation: 0.5, // Neutral starting point
-                composite: 0.5,
-                transaction_count: 0,
-                total_value_cents: 0,
-                updated_at: time::now()?,
-                flags: ByzantineFlags {
-                    cartel_detected: false,
-                    volatile_reputation: false,
-                    gradient_poisoning: false,
-                    sybil_suspected: false,
-                    risk_score: 0.0,
-                },
-            }
-        }
-    };
+    outcomes::mark_counted(receipt.terms.transaction_hash, score_hash, tag)?;
+
+    Ok(score)
+}
 
-    // Update transaction stats
-    score.transaction_count += 1;
-    score.total_value_cents += input.transaction_value_cents;
+/// Update an agent's MATL score with how a dispute went for them
+///
+/// Winners count as a successful transaction, losers (and appellants who
+/// forfeit their stake) as a failed one. Only accepted with the
+/// arbitration result that decided it, and each outcome counts once per
+/// result.
+#[hdk_extern]
+pub fn record_dispute_outcome(input: DisputeOutcomeInput) -> ExternResult<MatlScore> {
+    let (result, appeal) = outcomes::get_arbitration_result(input.result_hash.clone())?;
+
+    outcomes::check_dispute_outcome(&result, appeal.as_ref(), &input.agent, &input.outcome)
+        .map_err(|reason| wasm_error!(WasmErrorInner::Guest(reason)))?;
+
+    let tag = outcomes::outcome_tag(input.outcome.kind(), &input.agent);
+    outcomes::ensure_not_counted(input.result_hash.clone(), &tag)?;
+
+    let (score_hash, score) = apply_transaction_outcome(
+        input.agent,
+        input.outcome.successful(),
+        input.transaction_value_cents,
+        ScoreOutcome::Dispute {
+            result_hash: input.result_hash.clone(),
+            outcome: input.outcome.clone(),
+        },
+    )?;
 
-    // Compute new PoGQ based on transaction outcome
-    score.pogq = compute_pogq(&score, &input)?;
+    outcomes::mark_counted(input.result_hash, score_hash, tag)?;
 
-    // Update reputation with exponential moving average
-    let alpha = 0.3; // Learning rate
-    let transaction_quality = if input.successful { 1.0 } else { 0.0 };
-    score.reputation = alpha * transaction_quality + (1.0 - alpha) * score.reputation;
+    Ok(score)
+}
 
-    // Detect Byzantine patterns
-    score.flags = detect_byzantine_patterns(&score)?;
+/// Count a cancelled transaction as a failure for the party at fault
+///
+/// Takes the transaction update that cancelled it; the party at fault
+/// and the value are read from the transaction, not the caller.
+#[hdk_extern]
+pub fn record_cancellation(cancellation_hash: ActionHash) -> ExternResult<MatlScore> {
+    let (previous, cancelled_by, cancelled_at) =
+        outcomes::get_cancellation(cancellation_hash.clone())?;
 
-    // Calculate composite score (MATL formula)
-    score.composite = compute_composite_score(&score.pogq, score.reputation);
+    let agent = outcomes::cancellation_at_fault(&previous, &cancelled_by, cancelled_at);
 
-    // Update timestamp
-    score.updated_at = time::now()?;
+    let tag = outcomes::outcome_tag("cancelled", &agent);
+    outcomes::ensure_not_counted(cancellation_hash.clone(), &tag)?;
 
-    // Save score
-    let action_hash = create_entry(&EntryTypes::MatlScore(score.clone()))?;
+    let (score_hash, score) = apply_transaction_outcome(
+        agent,
+        false,
+        previous.matl_value_cents(),
+        ScoreOutcome::Cancellation(cancellation_hash.clone()),
+    )?;
 
-    // Create/update link
-    create_link(agent.clone(), action_hash, LinkTypes::AgentToScore, ())?;
+    outcomes::mark_counted(cancellation_hash, score_hash, tag)?;
 
-    // Invalidate cache after update
-    cache::invalidate_matl_cache(&agent);
+    Ok(score)
+}
+
+/// Fold one transaction outcome into an agent's MATL score
+///
+/// See `apply_outcome` for the MATL algorithm; validation recomputes the
+/// stored score from the previous version and the outcome.
+fn apply_transaction_outcome(
+    agent: AgentPubKey,
+    successful: bool,
+    transaction_value_cents: u64,
+    outcome: ScoreOutcome,
+) -> ExternResult<(ActionHash, MatlScore)> {
+    let step = ScoreStep::Transaction {
+        successful,
+        value_cents: transaction_value_cents,
+    };
+    let (score_hash, score) = count_outcome(agent.clone(), &step, outcome)?;
 
     // Emit monitoring metric
     monitoring::emit_metric(
@@ -127,101 +179,94 @@ pub fn update_matl_score(input: UpdateMatlInput) -> ExternResult<MatlScore> {
         )?;
     }
 
-    Ok(score)
+    Ok((score_hash, score))
 }
 
-/// Compute Proof of Gradient Quality
+/// Record how an arbitrator performed on a finalized (or abandoned) panel
+///
+/// Feeds the arbitration-reliability component of the MATL composite:
+/// agreeing with the final weighted outcome and revealing before the
+/// deadline raise it, missing a deadline lowers it. Since panels are
+/// drawn weighted by composite score, reliable arbitrators are drawn
+/// more often.
 ///
-/// This measures the quality and consistency of an agent's behavior.
-/// Higher quality + higher consistency = higher trust.
-fn compute_pogq(
-    score: &MatlScore,
-    input: &UpdateMatlInput,
-) -> ExternResult<ProofOfGradientQuality> {
-    // Quality: weighted by transaction value and outcome
-    let transaction_quality = if input.successful {
-        // Successful transaction increases quality
-        0.8 + (input.transaction_value_cents as f64 / 1_000_000.0).min(0.2)
-    } else {
-        // Failed transaction decreases quality
-        0.2
+/// The seat is read from the result or replacement the input cites, and
+/// counts once per arbitrator.
+#[hdk_extern]
+pub fn record_arbitration_outcome(input: ArbitrationOutcomeInput) -> ExternResult<MatlScore> {
+    let agent = input.arbitrator.clone();
+
+    let (outcome_hash, seat, outcome) = match input.source {
+        ArbitrationOutcomeSource::Result(result_hash) => {
+            let seat = outcomes::get_panel_seat(result_hash.clone(), &agent)?;
+            (result_hash.clone(), seat, ScoreOutcome::PanelSeat(result_hash))
+        }
+        ArbitrationOutcomeSource::Replacement(replacement_hash) => {
+            outcomes::get_replacement(replacement_hash.clone(), &agent)?;
+            (
+                replacement_hash.clone(),
+                outcomes::ArbitrationSeat::missed(),
+                ScoreOutcome::Replacement(replacement_hash),
+            )
+        }
     };
 
-    // Exponential moving average for quality
-    let alpha = 0.2;
-    let new_quality = alpha * transaction_quality + (1.0 - alpha) * score.pogq.quality;
+    let tag = outcomes::outcome_tag("arbitration", &agent);
+    outcomes::ensure_not_counted(outcome_hash.clone(), &tag)?;
 
-    // Consistency: measure variance in quality over time
-    // Low variance = high consistency
-    let quality_diff = (transaction_quality - score.pogq.quality).abs();
-    let new_consistency = (1.0 - quality_diff.min(1.0)) * 0.7 + score.pogq.consistency * 0.3;
+    let (score_hash, score) = count_outcome(agent.clone(), &ScoreStep::Seat(seat), outcome)?;
+    outcomes::mark_counted(outcome_hash, score_hash, tag)?;
 
-    // Entropy: measure of unpredictability
-    // Lower entropy = more predictable (good)
-    let new_entropy = compute_entropy(score)?;
+    // Emit monitoring metric
+    monitoring::emit_metric(
+        monitoring::MetricType::MatlScoreUpdated,
+        score.composite,
+        Some(agent),
+        Some(format!(
+            "arbitration_reliability:{:.2},assignments:{}",
+            score.arbitration.reliability().unwrap_or(0.0),
+            score.arbitration.assignments
+        )),
+    )?;
 
-    Ok(ProofOfGradientQuality {
-        quality: new_quality.clamp(0.0, 1.0),
-        consistency: new_consistency.clamp(0.0, 1.0),
-        entropy: new_entropy,
-        timestamp: time::now()?,
-    })
+    Ok(score)
 }
 
-/// Compute entropy of agent behavior
+/// Count an outcome into the agent's latest score and store the new version
 ///
-/// Low entropy = predictable, consistent behavior (trustworthy)
-/// High entropy = erratic, unpredictable behavior (suspicious)
-fn compute_entropy(score: &MatlScore) -> ExternResult<f64> {
-    // Simplified entropy calculation
-    // In production, this would analyze transaction patterns over time
-    let quality_variance = (0.5 - score.pogq.quality).abs();
-    let consistency_penalty = 1.0 - score.pogq.consistency;
-
-    Ok((quality_variance + consistency_penalty) / 2.0)
+/// Agents without a score start from the neutral one.
+fn count_outcome(
+    agent: AgentPubKey,
+    step: &ScoreStep,
+    outcome: ScoreOutcome,
+) -> ExternResult<(ActionHash, MatlScore)> {
+    let now = time::now()?;
+
+    let (previous_score, previous) = match get_agent_matl_score_record(agent.clone())? {
+        Some(record) => (Some(record.score_hash), record.score),
+        None => (None, neutral_matl_score(agent, now)),
+    };
+
+    let mut score = apply_outcome(&previous, step, now);
+    score.previous_score = previous_score;
+    score.outcome = Some(outcome);
+
+    let score_hash = store_matl_score(&score)?;
+    Ok((score_hash, score))
 }
 
-/// Detect Byzantine attack patterns
-///
-/// This implements the key innovation for 45% Byzantine tolerance:
-/// detecting coordinated attacks, Sybil identities, and malicious behavior.
-fn detect_byzantine_patterns(score: &MatlScore) -> ExternResult<ByzantineFlags> {
-    let mut flags = score.flags.clone();
-
-    // 1. Volatile Reputation Detection
-    // Rapid changes in reputation suggest manipulation
-    flags.volatile_reputation = score.pogq.entropy > 0.7;
-
-    // 2. Sybil Detection (simplified)
-    // Multiple accounts with identical patterns
-    // TODO: Implement full Sybil detection via graph analysis
-    flags.sybil_suspected = score.transaction_count < 3 && score.composite > 0.8;
-
-    // 3. Quality Inconsistency
-    // High quality but low consistency = suspicious
-    let inconsistency = score.pogq.quality - score.pogq.consistency;
-    if inconsistency > 0.3 {
-        flags.risk_score = (flags.risk_score + 0.2).min(1.0);
-    }
+/// Save a new score version and link it to the agent
+fn store_matl_score(score: &MatlScore) -> ExternResult<ActionHash> {
+    // Save score
+    let action_hash = create_entry(&EntryTypes::MatlScore(score.clone()))?;
 
-    // 4. Compute overall Byzantine risk score
-    let mut risk: f64 = 0.0;
-    if flags.cartel_detected {
-        risk += 0.4;
-    }
-    if flags.volatile_reputation {
-        risk += 0.2;
-    }
-    if flags.gradient_poisoning {
-        risk += 0.3;
-    }
-    if flags.sybil_suspected {
-        risk += 0.1;
-    }
+    // Create/update link
+    create_link(score.agent.clone(), action_hash.clone(), LinkTypes::AgentToScore, ())?;
 
-    flags.risk_score = risk.min(1.0);
+    // Invalidate cache after update
+    cache::invalidate_matl_cache(&score.agent);
 
-    Ok(flags)
+    Ok(action_hash)
 }
 
 /// Compute composite MATL score
@@ -233,13 +278,15 @@ fn detect_byzantine_patterns(score: &MatlScore) -> ExternResult<ByzantineFlags>
 /// - Quality (0.4): Most important - what they do
 /// - Consistency (0.3): Important - reliability over time
 /// - Reputation (0.3): Important - historical track record
-pub fn compute_composite_score(pogq: &ProofOfGradientQuality, reputation: f64) -> f64 {
-    const W_QUALITY: f64 = 0.4;
-    const W_CONSISTENCY: f64 = 0.3;
-    const W_REPUTATION: f64 = 0.3;
-
-    (W_QUALITY * pogq.quality + W_CONSISTENCY * pogq.consistency + W_REPUTATION * reputation)
-        .clamp(0.0, 1.0)
+///
+/// Agents with an arbitration record blend in their arbitration
+/// reliability at `W_ARBITRATION_RELIABILITY` (0.2).
+pub fn compute_composite_score(
+    pogq: &ProofOfGradientQuality,
+    reputation: f64,
+    arbitration_reliability: Option<f64>,
+) -> f64 {
+    expected_composite(pogq, reputation, arbitration_reliability).clamp(0.0, 1.0)
 }
 
 /// Check if agent is Byzantine (above risk threshold)
@@ -324,11 +371,12 @@ pub fn submit_review(input: SubmitReviewInput) -> ExternResult<ReviewOutput> {
     )?;
 
     // Update seller's MATL score based on review
-    update_matl_score(UpdateMatlInput {
-        agent: input.seller.clone(),
-        successful: input.rating >= 4, // 4-5 stars = successful
-        transaction_value_cents: 0,    // Value tracked elsewhere
-    })?;
+    apply_transaction_outcome(
+        input.seller.clone(),
+        input.rating >= 4, // 4-5 stars = successful
+        0,                 // Value tracked elsewhere
+        ScoreOutcome::Review(action_hash.clone()),
+    )?;
 
     // Emit monitoring metric
     monitoring::emit_metric(
@@ -381,6 +429,38 @@ pub struct UpdateMatlInput {
     pub agent: AgentPubKey,
     pub successful: bool,
     pub transaction_value_cents: u64,
+    /// Countersigned `TransactionReceipt` backing the update
+    pub receipt_hash: ActionHash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DisputeOutcomeInput {
+    pub agent: AgentPubKey,
+    pub outcome: DisputeOutcome,
+    pub transaction_value_cents: u64,
+    /// `ArbitrationResult` that decided the outcome
+    pub result_hash: ActionHash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArbitrationOutcomeInput {
+    pub arbitrator: AgentPubKey,
+    pub source: ArbitrationOutcomeSource,
+}
+
+/// What proves an arbitrator's panel seat
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ArbitrationOutcomeSource {
+    /// `ArbitrationResult` decided by the arbitrator's panel
+    Result(ActionHash),
+    /// Dispute update that replaced the arbitrator as non-responsive
+    Replacement(ActionHash),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MatlScoreRecord {
+    pub score_hash: ActionHash,
+    pub score: MatlScore,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
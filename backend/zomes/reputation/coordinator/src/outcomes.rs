@@ -0,0 +1,182 @@
+//! Proof behind a MATL update
+//!
+//! Scores only move on outcomes other agents can check:
+//! - A completed transaction, proven by its countersigned `TransactionReceipt`
+//! - A cancelled transaction, proven by the cancelling transaction update
+//! - A won or lost dispute, or a forfeited appeal stake, proven by the
+//!   `ArbitrationResult`
+//! - A panel seat, proven by the `ArbitrationResult` the panel decided
+//!   (and the votes it lists), or by the dispute update that replaced a
+//!   non-responsive arbitrator
+//!
+//! Each outcome counts at most once per agent; counted outcomes are linked
+//! to the score they fed, tagged with the agent.
+//!
+//! The checks and entry mirrors live in `reputation_integrity::outcomes`,
+//! which validation uses to prove each stored score again; the helpers
+//! here load the cited entries to check a request before writing.
+
+use hdk::prelude::*;
+use reputation_integrity::LinkTypes;
+use mycelix_common::link_queries;
+
+pub use reputation_integrity::outcomes::*;
+
+/// Load a countersigned receipt and verify both signatures
+pub fn get_verified_receipt(receipt_hash: ActionHash) -> ExternResult<TransactionReceipt> {
+    let receipt: TransactionReceipt = get_app_entry(receipt_hash, "Transaction receipt")?;
+
+    let signatures = [
+        (&receipt.terms.buyer, &receipt.buyer_signature),
+        (&receipt.terms.seller, &receipt.seller_signature),
+    ];
+    for (party, signature) in signatures {
+        if !verify_signature(party.clone(), signature.clone(), receipt.terms.clone())? {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Receipt signatures do not verify".into()
+            )));
+        }
+    }
+
+    Ok(receipt)
+}
+
+/// Load an arbitration result and, for appeal results, the appeal
+pub fn get_arbitration_result(
+    result_hash: ActionHash,
+) -> ExternResult<(ArbitrationResult, Option<Appeal>)> {
+    let result: ArbitrationResult = get_app_entry(result_hash, "Arbitration result")?;
+
+    let appeal = match &result.appeal_hash {
+        Some(appeal_hash) => Some(get_app_entry(appeal_hash.clone(), "Appeal")?),
+        None => None,
+    };
+
+    Ok((result, appeal))
+}
+
+/// Load the panel behind a result and score an arbitrator's seat on it
+pub fn get_panel_seat(result_hash: ActionHash, arbitrator: &AgentPubKey) -> ExternResult<ArbitrationSeat> {
+    let (result, _) = get_arbitration_result(result_hash)?;
+    let dispute: Dispute = get_app_entry(result.dispute_version_hash.clone(), "Dispute")?;
+
+    let mut counted_vote = None;
+    for vote_hash in &result.vote_hashes {
+        let record = get(vote_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
+            WasmErrorInner::Guest("Arbitration vote not found".into())
+        ))?;
+        let vote: ArbitrationVote = get_app_entry(vote_hash.clone(), "Arbitration vote")?;
+
+        if &vote.arbitrator == arbitrator && vote.dispute_hash == result.dispute_hash {
+            counted_vote = Some((vote, record.action().timestamp()));
+            break;
+        }
+    }
+
+    panel_seat(
+        &dispute,
+        &result,
+        arbitrator,
+        counted_vote.as_ref().map(|(vote, revealed_at)| (vote, *revealed_at)),
+    )
+    .map_err(|reason| wasm_error!(WasmErrorInner::Guest(reason)))
+}
+
+/// Load a replacing dispute update and the version it replaced
+pub fn get_replacement(replacement_hash: ActionHash, arbitrator: &AgentPubKey) -> ExternResult<()> {
+    let record = get(replacement_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("Dispute update not found".into())
+    ))?;
+
+    let previous_hash = match record.action() {
+        Action::Update(update) => update.original_action_address.clone(),
+        _ => {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Replacement must be a dispute update".into()
+            )))
+        }
+    };
+
+    let dispute: Dispute = get_app_entry(replacement_hash, "Dispute")?;
+    let previous: Dispute = get_app_entry(previous_hash, "Dispute")?;
+
+    check_replacement(&previous, &dispute, arbitrator)
+        .map_err(|reason| wasm_error!(WasmErrorInner::Guest(reason)))
+}
+
+/// Load a cancelling transaction update and the version it cancelled
+///
+/// Returns the cancelled-from version, who cancelled and when.
+pub fn get_cancellation(
+    cancellation_hash: ActionHash,
+) -> ExternResult<(TransactionInfo, AgentPubKey, Timestamp)> {
+    let record = get(cancellation_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("Cancellation not found".into())
+    ))?;
+
+    let cancelled: TransactionInfo = get_app_entry(cancellation_hash, "Transaction")?;
+    if cancelled.status != "cancelled" {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Transaction update is not a cancellation".into()
+        )));
+    }
+
+    let previous_hash = match record.action() {
+        Action::Update(update) => update.original_action_address.clone(),
+        _ => {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Cancellation must be a transaction update".into()
+            )))
+        }
+    };
+    let previous: TransactionInfo = get_app_entry(previous_hash, "Transaction")?;
+
+    Ok((
+        previous,
+        record.action().author().clone(),
+        record.action().timestamp(),
+    ))
+}
+
+/// Tag identifying who an outcome was counted for
+pub fn outcome_tag(kind: &str, agent: &AgentPubKey) -> LinkTag {
+    LinkTag::new(format!("{}:{}", kind, agent))
+}
+
+/// Fail if an outcome was already counted under `tag`
+pub fn ensure_not_counted(outcome_hash: ActionHash, tag: &LinkTag) -> ExternResult<()> {
+    // Use shared utility for get_links
+    let links = link_queries::get_links_local(outcome_hash, LinkTypes::OutcomeToScoreUpdates)?;
+
+    if links.iter().any(|link| &link.tag == tag) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Outcome has already been counted for this agent".into()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Record that an outcome fed a score update
+pub fn mark_counted(outcome_hash: ActionHash, score_hash: ActionHash, tag: LinkTag) -> ExternResult<()> {
+    create_link(outcome_hash, score_hash, LinkTypes::OutcomeToScoreUpdates, tag)?;
+    Ok(())
+}
+
+fn get_app_entry<T>(action_hash: ActionHash, what: &str) -> ExternResult<T>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let record = get(action_hash, GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest(format!("{} not found", what))
+    ))?;
+
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Deserialization error: {:?}", e))))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "{} could not be deserialized",
+            what
+        ))))
+}
@@ -1,7 +1,8 @@
+use super::*;
+
 #[cfg(test)]
-mod tests {
+mod reputation_tests {
     use super::*;
-    use reputation_integrity::*;
 
     // Helper functions for tests
     fn mock_pogq() -> ProofOfGradientQuality {
@@ -29,6 +30,9 @@ mod tests {
                 sybil_suspected: false,
                 risk_score: 0.0,
             },
+            arbitration: ArbitrationRecord::default(),
+            previous_score: None,
+            outcome: None,
         }
     }
 
@@ -39,7 +43,7 @@ mod tests {
         let pogq = mock_pogq();
         let reputation = 0.75;
 
-        let composite = compute_composite_score(&pogq, reputation);
+        let composite = compute_composite_score(&pogq, reputation, None);
 
         // composite = 0.4 * quality + 0.3 * consistency + 0.3 * reputation
         // composite = 0.4 * 0.8 + 0.3 * 0.7 + 0.3 * 0.75
@@ -69,7 +73,7 @@ mod tests {
             timestamp: Timestamp::from_micros(1000000),
         };
 
-        let composite = compute_composite_score(&pogq_high, 1.5);
+        let composite = compute_composite_score(&pogq_high, 1.5, None);
 
         // Should be clamped to 1.0
         assert!(composite <= 1.0, "Composite should be clamped to 1.0");
@@ -95,10 +99,97 @@ mod tests {
             timestamp: Timestamp::from_micros(1000000),
         };
 
-        let composite = compute_composite_score(&pogq, new_agent_reputation);
+        let composite = compute_composite_score(&pogq, new_agent_reputation, None);
         assert!((composite - 0.5).abs() < 0.01, "New agent composite should be ~0.5");
     }
 
+    #[test]
+    fn test_outcomes_step_from_the_previous_score() {
+        let agent = AgentPubKey::from_raw_36(vec![0u8; 36]);
+        let at = Timestamp::from_micros(2000000);
+        let neutral = neutral_matl_score(agent.clone(), at);
+
+        // A success raises the score, a failure lowers it
+        let success = ScoreStep::Transaction { successful: true, value_cents: 5000 };
+        let failure = ScoreStep::Transaction { successful: false, value_cents: 5000 };
+        let raised = apply_outcome(&neutral, &success, at);
+        let lowered = apply_outcome(&neutral, &failure, at);
+        assert!(raised.composite > neutral.composite);
+        assert!(lowered.composite < neutral.composite);
+        assert_eq!(raised.transaction_count, 1);
+        assert_eq!(raised.total_value_cents, 5000);
+        assert_eq!(raised.updated_at, at);
+
+        // The same outcome on the same score always gives the same score,
+        // which is what validation recomputes
+        assert_eq!(raised, apply_outcome(&neutral, &success, at));
+
+        // Panel seats only touch the arbitration record
+        let seated = apply_outcome(&raised, &ScoreStep::Seat(outcome(true, true, true)), at);
+        assert_eq!(seated.pogq, raised.pogq);
+        assert_eq!(seated.reputation, raised.reputation);
+        assert_eq!(seated.transaction_count, raised.transaction_count);
+        assert_eq!(seated.arbitration.assignments, 1);
+    }
+
+    // ===== Arbitration Reliability Tests =====
+
+    fn outcome(voted: bool, agreed_with_outcome: bool, on_time: bool) -> outcomes::ArbitrationSeat {
+        outcomes::ArbitrationSeat {
+            voted,
+            agreed_with_outcome,
+            on_time,
+        }
+    }
+
+    #[test]
+    fn test_arbitration_reliability_without_record() {
+        // Non-arbitrators keep the base formula
+        let record = ArbitrationRecord::default();
+        assert_eq!(record.reliability(), None);
+
+        let composite = compute_composite_score(&mock_pogq(), 0.75, record.reliability());
+        assert!((composite - 0.755).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_arbitration_outcomes_update_record() {
+        let mut record = ArbitrationRecord::default();
+
+        apply_arbitration_outcome(&mut record, &outcome(true, true, true));
+        apply_arbitration_outcome(&mut record, &outcome(true, false, true));
+        apply_arbitration_outcome(&mut record, &outcome(false, true, true)); // Missed: no credit
+
+        assert_eq!(record.assignments, 3);
+        assert_eq!(record.agreed_votes, 1);
+        assert_eq!(record.on_time_votes, 2);
+
+        // 0.5 * (1/3) + 0.5 * (2/3) = 0.5
+        assert!((record.reliability().unwrap() - 0.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_reliable_arbitrators_score_higher() {
+        let pogq = mock_pogq();
+
+        let mut reliable = ArbitrationRecord::default();
+        let mut unreliable = ArbitrationRecord::default();
+        for _ in 0..5 {
+            apply_arbitration_outcome(&mut reliable, &outcome(true, true, true));
+            apply_arbitration_outcome(&mut unreliable, &outcome(false, false, false));
+        }
+
+        let base = compute_composite_score(&pogq, 0.75, None);
+        let reliable_score = compute_composite_score(&pogq, 0.75, reliable.reliability());
+        let unreliable_score = compute_composite_score(&pogq, 0.75, unreliable.reliability());
+
+        assert!(reliable_score > base);
+        assert!(unreliable_score < base);
+
+        // (1 - 0.2) * 0.755 + 0.2 * 1.0 = 0.804
+        assert!((reliable_score - 0.804).abs() < 0.01);
+    }
+
     // ===== Byzantine Detection Tests =====
 
     #[test]
@@ -121,7 +212,7 @@ mod tests {
     #[test]
     fn test_high_risk_score_calculation() {
         // Test risk score accumulation
-        let mut risk = 0.0;
+        let mut risk: f64 = 0.0;
 
         // Cartel detected
         risk += 0.4;
@@ -132,7 +223,7 @@ mod tests {
 
         let total_risk = risk.min(1.0);
 
-        assert_eq!(total_risk, 0.7);
+        assert!((total_risk - 0.7).abs() < 1e-9);
         assert!(total_risk > 0.5); // Above Byzantine threshold
     }
 
@@ -220,14 +311,14 @@ mod tests {
         let malicious_agents = 4;
 
         // New malicious agents start with low reputation (0.5)
-        let malicious_reputation = 0.5;
+        let malicious_reputation: f64 = 0.5;
 
         // Byzantine power
         let byzantine_power = (malicious_agents as f64) * malicious_reputation.powi(2);
         // byzantine_power = 4 * 0.25 = 1.0
 
         // Honest power (assuming average reputation of 0.8)
-        let honest_reputation = 0.8;
+        let honest_reputation: f64 = 0.8;
         let honest_power = (honest_agents as f64) * honest_reputation.powi(2);
         // honest_power = 6 * 0.64 = 3.84
 
@@ -245,16 +336,15 @@ mod tests {
     #[test]
     fn test_45_percent_byzantine_tolerance() {
         // Test the theoretical 45% limit
-        let total_agents = 100;
         let malicious_agents = 45; // 45%
         let honest_agents = 55;
 
         // Malicious agents start with neutral reputation
-        let malicious_rep = 0.5;
+        let malicious_rep: f64 = 0.5;
         let byzantine_power = (malicious_agents as f64) * malicious_rep.powi(2);
 
         // Honest agents have good reputation
-        let honest_rep = 0.8;
+        let honest_rep: f64 = 0.8;
         let honest_power = (honest_agents as f64) * honest_rep.powi(2);
 
         let threshold = honest_power / 3.0;
@@ -291,12 +381,13 @@ mod tests {
         let valid_ratings = vec![1, 2, 3, 4, 5];
 
         for rating in valid_ratings {
-            assert!(rating >= 1 && rating <= 5);
+            assert!((1..=5).contains(&rating));
         }
 
-        // Invalid ratings
-        assert!(0 < 1); // Too low
-        assert!(6 > 5); // Too high
+        // Invalid ratings: too low, too high
+        for rating in [0, 6] {
+            assert!(!(1..=5).contains(&rating));
+        }
     }
 
     #[test]
@@ -330,7 +421,7 @@ mod tests {
     #[test]
     fn test_exponential_moving_average() {
         // Test EMA formula: new = α * transaction + (1-α) * old
-        let alpha = 0.3;
+        let alpha: f64 = 0.3;
         let old_reputation = 0.7;
         let transaction_quality = 1.0; // Successful
 
@@ -343,7 +434,7 @@ mod tests {
 
     #[test]
     fn test_failed_transaction_impact() {
-        let alpha = 0.3;
+        let alpha: f64 = 0.3;
         let old_reputation = 0.7;
         let transaction_quality = 0.0; // Failed
 
@@ -369,6 +460,235 @@ mod tests {
         assert!(large_boost > small_boost);
     }
 
+    // ===== Outcome Proof Tests =====
+
+    fn mock_terms() -> outcomes::ReceiptTerms {
+        outcomes::ReceiptTerms {
+            transaction_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+            buyer: AgentPubKey::from_raw_36(vec![2u8; 36]),
+            seller: AgentPubKey::from_raw_36(vec![3u8; 36]),
+            listing_hash: ActionHash::from_raw_36(vec![4u8; 36]),
+            listing_version_hash: ActionHash::from_raw_36(vec![4u8; 36]),
+            quantity: 1,
+            total_price_cents: 1999,
+            currency: "USD".to_string(),
+            matl_value_cents: 1999,
+        }
+    }
+
+    #[test]
+    fn test_receipt_backs_party_updates_only() {
+        let terms = mock_terms();
+
+        // Both sides of the market are updated from the same receipt
+        assert!(outcomes::check_receipt(&terms, &terms.seller, true, 1999).is_ok());
+        assert!(outcomes::check_receipt(&terms, &terms.buyer, true, 1999).is_ok());
+
+        // Outsiders and inflated values are rejected
+        let outsider = AgentPubKey::from_raw_36(vec![9u8; 36]);
+        assert!(outcomes::check_receipt(&terms, &outsider, true, 1999).is_err());
+        assert!(outcomes::check_receipt(&terms, &terms.seller, true, 1_000_000).is_err());
+
+        // A countersigned receipt cannot be turned against the other party
+        assert!(outcomes::check_receipt(&terms, &terms.buyer, false, 1999).is_err());
+    }
+
+    #[test]
+    fn test_dispute_outcomes_need_the_result() {
+        let loser = AgentPubKey::from_raw_36(vec![2u8; 36]);
+        let winner = AgentPubKey::from_raw_36(vec![3u8; 36]);
+        let result = outcomes::ArbitrationResult {
+            dispute_hash: ActionHash::from_raw_36(vec![5u8; 36]),
+            dispute_version_hash: ActionHash::from_raw_36(vec![5u8; 36]),
+            winner: Some(winner.clone()),
+            loser: Some(loser.clone()),
+            refund_percent: 100,
+            vote_hashes: vec![ActionHash::from_raw_36(vec![7u8; 36])],
+            appeal_hash: None,
+            stake_slashed: false,
+        };
+
+        assert!(outcomes::check_dispute_outcome(&result, None, &loser, &outcomes::DisputeOutcome::Lost).is_ok());
+        assert!(outcomes::check_dispute_outcome(&result, None, &winner, &outcomes::DisputeOutcome::Won).is_ok());
+        assert!(outcomes::check_dispute_outcome(&result, None, &winner, &outcomes::DisputeOutcome::Lost).is_err());
+        assert!(outcomes::check_dispute_outcome(&result, None, &loser, &outcomes::DisputeOutcome::Won).is_err());
+        assert!(outcomes::check_dispute_outcome(
+            &result,
+            None,
+            &loser,
+            &outcomes::DisputeOutcome::ForfeitedStake
+        )
+        .is_err());
+
+        // A failed appeal forfeits the appellant's stake
+        let appeal = outcomes::Appeal { appellant: loser.clone(), reputation_stake_cents: 200 };
+        let appeal_result = outcomes::ArbitrationResult {
+            appeal_hash: Some(ActionHash::from_raw_36(vec![6u8; 36])),
+            stake_slashed: true,
+            ..result
+        };
+        assert!(outcomes::check_dispute_outcome(
+            &appeal_result,
+            Some(&appeal),
+            &loser,
+            &outcomes::DisputeOutcome::ForfeitedStake
+        )
+        .is_ok());
+    }
+
+    fn mock_panel() -> (outcomes::Dispute, outcomes::ArbitrationResult) {
+        let dispute = outcomes::Dispute {
+            transaction_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+            buyer: AgentPubKey::from_raw_36(vec![2u8; 36]),
+            seller: AgentPubKey::from_raw_36(vec![3u8; 36]),
+            arbitrators: vec![
+                AgentPubKey::from_raw_36(vec![10u8; 36]),
+                AgentPubKey::from_raw_36(vec![11u8; 36]),
+            ],
+            reveal_deadline: Some(Timestamp::from_micros(1_000_000)),
+            replacement_rounds: vec![],
+            replacement_heads: vec![],
+        };
+        let result = outcomes::ArbitrationResult {
+            dispute_hash: ActionHash::from_raw_36(vec![5u8; 36]),
+            dispute_version_hash: ActionHash::from_raw_36(vec![5u8; 36]),
+            winner: None,
+            loser: None,
+            refund_percent: 50,
+            vote_hashes: vec![ActionHash::from_raw_36(vec![7u8; 36])],
+            appeal_hash: None,
+            stake_slashed: false,
+        };
+        (dispute, result)
+    }
+
+    #[test]
+    fn test_panel_seat_follows_the_result() {
+        let (dispute, result) = mock_panel();
+        let arbitrator = AgentPubKey::from_raw_36(vec![10u8; 36]);
+        let vote = |refund_percent| outcomes::ArbitrationVote {
+            dispute_hash: result.dispute_hash.clone(),
+            arbitrator: arbitrator.clone(),
+            refund_percent,
+        };
+
+        let agreed = vote(50);
+        let seat = outcomes::panel_seat(
+            &dispute,
+            &result,
+            &arbitrator,
+            Some((&agreed, Timestamp::from_micros(1_000_000))),
+        )
+        .unwrap();
+        assert!(seat.voted && seat.agreed_with_outcome && seat.on_time);
+
+        let dissent = vote(100);
+        let late = outcomes::panel_seat(
+            &dispute,
+            &result,
+            &arbitrator,
+            Some((&dissent, Timestamp::from_micros(1_000_001))),
+        )
+        .unwrap();
+        assert!(late.voted && !late.agreed_with_outcome && !late.on_time);
+
+        // Panel members the result lists no vote for missed the seat
+        let missed = AgentPubKey::from_raw_36(vec![11u8; 36]);
+        assert_eq!(
+            outcomes::panel_seat(&dispute, &result, &missed, None),
+            Ok(outcomes::ArbitrationSeat::missed())
+        );
+
+        // Agents off the panel have no seat to score
+        let outsider = AgentPubKey::from_raw_36(vec![12u8; 36]);
+        assert!(outcomes::panel_seat(&dispute, &result, &outsider, None).is_err());
+    }
+
+    #[test]
+    fn test_replacement_needs_a_new_round() {
+        let (previous, _) = mock_panel();
+        let arbitrator = AgentPubKey::from_raw_36(vec![10u8; 36]);
+
+        let mut replaced = previous.clone();
+        replaced.replacement_rounds = vec![vec![arbitrator.clone()]];
+
+        // The round must cite the arbitrator's chain head
+        assert!(outcomes::check_replacement(&previous, &replaced, &arbitrator).is_err());
+        replaced.replacement_heads = vec![vec![ActionHash::from_raw_36(vec![20u8; 36])]];
+        assert!(outcomes::check_replacement(&previous, &replaced, &arbitrator).is_ok());
+
+        // Later versions carry the round forward without replacing anyone
+        assert!(outcomes::check_replacement(&replaced, &replaced, &arbitrator).is_err());
+
+        let other = AgentPubKey::from_raw_36(vec![11u8; 36]);
+        assert!(outcomes::check_replacement(&previous, &replaced, &other).is_err());
+    }
+
+    #[test]
+    fn test_dispute_outcome_success() {
+        assert!(outcomes::DisputeOutcome::Won.successful());
+        assert!(!outcomes::DisputeOutcome::Lost.successful());
+        assert!(!outcomes::DisputeOutcome::ForfeitedStake.successful());
+    }
+
+    fn mock_transaction_info(status: &str, deadline: Option<i64>) -> outcomes::TransactionInfo {
+        outcomes::TransactionInfo {
+            buyer: AgentPubKey::from_raw_36(vec![2u8; 36]),
+            seller: AgentPubKey::from_raw_36(vec![3u8; 36]),
+            total_price_cents: 1999,
+            reference_value_cents: None,
+            status: status.to_string(),
+            deadline: deadline.map(Timestamp::from_micros),
+        }
+    }
+
+    #[test]
+    fn test_cancellation_counts_against_party_who_backed_out() {
+        let pending = mock_transaction_info("pending", Some(5_000_000));
+        let before_deadline = Timestamp::from_micros(4_000_000);
+
+        assert_eq!(
+            outcomes::cancellation_at_fault(&pending, &pending.buyer, before_deadline),
+            pending.buyer
+        );
+
+        let confirmed = mock_transaction_info("confirmed", None);
+        assert_eq!(
+            outcomes::cancellation_at_fault(&confirmed, &confirmed.seller, before_deadline),
+            confirmed.seller
+        );
+    }
+
+    #[test]
+    fn test_lapsed_confirmation_counts_against_seller() {
+        let pending = mock_transaction_info("pending", Some(5_000_000));
+        let after_deadline = Timestamp::from_micros(6_000_000);
+
+        // The buyer's sweep cancels, but the seller never confirmed
+        assert_eq!(
+            outcomes::cancellation_at_fault(&pending, &pending.buyer, after_deadline),
+            pending.seller
+        );
+    }
+
+    #[test]
+    fn test_transaction_info_value_prefers_reference_currency() {
+        let mut info = mock_transaction_info("cancelled", None);
+        assert_eq!(info.matl_value_cents(), 1999);
+
+        info.reference_value_cents = Some(2158);
+        assert_eq!(info.matl_value_cents(), 2158);
+    }
+
+    #[test]
+    fn test_outcome_tags_separate_agents_and_outcomes() {
+        let agent = AgentPubKey::from_raw_36(vec![2u8; 36]);
+        let other = AgentPubKey::from_raw_36(vec![3u8; 36]);
+
+        assert_ne!(outcomes::outcome_tag("receipt", &agent), outcomes::outcome_tag("receipt", &other));
+        assert_ne!(outcomes::outcome_tag("lost", &agent), outcomes::outcome_tag("forfeited_stake", &agent));
+    }
+
     // ===== Integration Tests =====
 
     #[test]
@@ -385,13 +705,17 @@ mod tests {
         score.total_value_cents += transaction_value;
 
         // Update reputation
-        let alpha = 0.3;
+        let alpha: f64 = 0.3;
         let old_reputation = score.reputation;
         let transaction_quality = if transaction_successful { 1.0 } else { 0.0 };
         score.reputation = alpha * transaction_quality + (1.0 - alpha) * old_reputation;
 
         // Recalculate composite
-        score.composite = compute_composite_score(&score.pogq, score.reputation);
+        score.composite = compute_composite_score(
+            &score.pogq,
+            score.reputation,
+            score.arbitration.reliability(),
+        );
 
         // Verify updates
         assert_eq!(score.transaction_count, 11);
@@ -1,5 +1,9 @@
 use hdi::prelude::*;
 
+pub mod outcomes;
+
+use outcomes::{ArbitrationSeat, ScoreOutcome, ScoreStep};
+
 /// MATL Score Entry - Mycelix Adaptive Trust Layer
 ///
 /// This implements the breakthrough 45% Byzantine fault tolerance
@@ -22,7 +26,9 @@ pub struct MatlScore {
     pub reputation: f64,
 
     /// Composite trust score (weighted combination)
-    /// Formula: 0.4 * quality + 0.3 * consistency + 0.3 * reputation
+    /// Formula: 0.4 * quality + 0.3 * consistency + 0.3 * reputation,
+    /// blended with arbitration reliability once the agent has served
+    /// on a dispute panel (see `expected_composite`)
     pub composite: f64,
 
     /// Number of transactions completed
@@ -36,6 +42,19 @@ pub struct MatlScore {
 
     /// Byzantine detection flags
     pub flags: ByzantineFlags,
+
+    /// Track record as a dispute arbitrator
+    #[serde(default)]
+    pub arbitration: ArbitrationRecord,
+
+    /// Score version this one was computed from, None for the agent's first
+    #[serde(default)]
+    pub previous_score: Option<ActionHash>,
+
+    /// Outcome this version counts, None only for the neutral starting
+    /// score, which is never stored
+    #[serde(default)]
+    pub outcome: Option<ScoreOutcome>,
 }
 
 /// Proof of Gradient Quality - Core Trust Mechanism
@@ -81,6 +100,252 @@ pub struct ByzantineFlags {
     pub risk_score: f64,
 }
 
+/// Arbitration Record - accountability for dispute arbitrators
+///
+/// Updated whenever a panel the agent sat on is finalized, or when the
+/// agent is replaced for missing a deadline.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ArbitrationRecord {
+    /// Panel seats the agent has been scored on
+    pub assignments: u32,
+
+    /// Votes that matched the final weighted outcome
+    pub agreed_votes: u32,
+
+    /// Votes revealed before the reveal deadline
+    pub on_time_votes: u32,
+}
+
+impl ArbitrationRecord {
+    /// Arbitration reliability [0.0, 1.0], None without any assignments
+    ///
+    /// Half accuracy (agreement with the final outcome), half punctuality.
+    /// A missed deadline counts against both.
+    pub fn reliability(&self) -> Option<f64> {
+        if self.assignments == 0 {
+            return None;
+        }
+
+        let assignments = self.assignments as f64;
+        let accuracy = self.agreed_votes as f64 / assignments;
+        let punctuality = self.on_time_votes as f64 / assignments;
+
+        Some((0.5 * accuracy + 0.5 * punctuality).clamp(0.0, 1.0))
+    }
+}
+
+/// Weight of arbitration reliability in the composite score
+///
+/// Only applies to agents with an arbitration record; everyone else
+/// keeps the base formula.
+pub const W_ARBITRATION_RELIABILITY: f64 = 0.2;
+
+/// Composite score from its components
+///
+/// base = 0.4 * quality + 0.3 * consistency + 0.3 * reputation
+/// composite = base, or (1 - w) * base + w * reliability for arbitrators
+pub fn expected_composite(
+    pogq: &ProofOfGradientQuality,
+    reputation: f64,
+    arbitration_reliability: Option<f64>,
+) -> f64 {
+    let base = 0.4 * pogq.quality + 0.3 * pogq.consistency + 0.3 * reputation;
+
+    match arbitration_reliability {
+        Some(reliability) => {
+            (1.0 - W_ARBITRATION_RELIABILITY) * base + W_ARBITRATION_RELIABILITY * reliability
+        }
+        None => base,
+    }
+}
+
+/// Starting score for an agent without one: neutral (0.5) everywhere
+pub fn neutral_matl_score(agent: AgentPubKey, at: Timestamp) -> MatlScore {
+    MatlScore {
+        agent,
+        pogq: ProofOfGradientQuality {
+            quality: 0.5,
+            consistency: 0.5,
+            entropy: 0.0,
+            timestamp: at,
+        },
+        reputation: 0.5, // Neutral starting point
+        composite: 0.5,
+        transaction_count: 0,
+        total_value_cents: 0,
+        updated_at: at,
+        flags: ByzantineFlags {
+            cartel_detected: false,
+            volatile_reputation: false,
+            gradient_poisoning: false,
+            sybil_suspected: false,
+            risk_score: 0.0,
+        },
+        arbitration: ArbitrationRecord::default(),
+        previous_score: None,
+        outcome: None,
+    }
+}
+
+/// Score after counting one outcome, as of `at`
+///
+/// This implements the core MATL algorithm for transactions:
+/// 1. Compute PoGQ (quality, consistency, entropy)
+/// 2. Update reputation based on transaction outcome
+/// 3. Detect Byzantine patterns
+/// 4. Calculate composite score
+///
+/// Panel seats only move the arbitration record (and so the composite).
+/// The caller fills in `previous_score` and `outcome`; validation
+/// recomputes every stored score this way.
+pub fn apply_outcome(previous: &MatlScore, step: &ScoreStep, at: Timestamp) -> MatlScore {
+    let mut score = previous.clone();
+
+    match step {
+        ScoreStep::Transaction {
+            successful,
+            value_cents,
+        } => {
+            // Update transaction stats
+            score.transaction_count = score.transaction_count.saturating_add(1);
+            score.total_value_cents = score.total_value_cents.saturating_add(*value_cents);
+
+            // Compute new PoGQ based on transaction outcome
+            score.pogq = compute_pogq(previous, *successful, *value_cents, at);
+
+            // Update reputation with exponential moving average
+            let alpha = 0.3; // Learning rate
+            let transaction_quality = if *successful { 1.0 } else { 0.0 };
+            score.reputation = alpha * transaction_quality + (1.0 - alpha) * score.reputation;
+
+            // Detect Byzantine patterns
+            score.flags = detect_byzantine_patterns(&score);
+        }
+        ScoreStep::Seat(seat) => apply_arbitration_outcome(&mut score.arbitration, seat),
+    }
+
+    // Calculate composite score (MATL formula)
+    score.composite = expected_composite(
+        &score.pogq,
+        score.reputation,
+        score.arbitration.reliability(),
+    )
+    .clamp(0.0, 1.0);
+    score.updated_at = at;
+
+    score
+}
+
+/// Add one panel seat to an arbitration record
+pub fn apply_arbitration_outcome(record: &mut ArbitrationRecord, seat: &ArbitrationSeat) {
+    record.assignments += 1;
+
+    if seat.voted {
+        if seat.agreed_with_outcome {
+            record.agreed_votes += 1;
+        }
+        if seat.on_time {
+            record.on_time_votes += 1;
+        }
+    }
+}
+
+/// Compute Proof of Gradient Quality
+///
+/// This measures the quality and consistency of an agent's behavior.
+/// Higher quality + higher consistency = higher trust.
+fn compute_pogq(
+    score: &MatlScore,
+    successful: bool,
+    transaction_value_cents: u64,
+    at: Timestamp,
+) -> ProofOfGradientQuality {
+    // Quality: weighted by transaction value and outcome
+    let transaction_quality = if successful {
+        // Successful transaction increases quality
+        0.8 + (transaction_value_cents as f64 / 1_000_000.0).min(0.2)
+    } else {
+        // Failed transaction decreases quality
+        0.2
+    };
+
+    // Exponential moving average for quality
+    let alpha = 0.2;
+    let new_quality = alpha * transaction_quality + (1.0 - alpha) * score.pogq.quality;
+
+    // Consistency: measure variance in quality over time
+    // Low variance = high consistency
+    let quality_diff = (transaction_quality - score.pogq.quality).abs();
+    let new_consistency = (1.0 - quality_diff.min(1.0)) * 0.7 + score.pogq.consistency * 0.3;
+
+    // Entropy: measure of unpredictability
+    // Lower entropy = more predictable (good)
+    let new_entropy = compute_entropy(score);
+
+    ProofOfGradientQuality {
+        quality: new_quality.clamp(0.0, 1.0),
+        consistency: new_consistency.clamp(0.0, 1.0),
+        entropy: new_entropy,
+        timestamp: at,
+    }
+}
+
+/// Compute entropy of agent behavior
+///
+/// Low entropy = predictable, consistent behavior (trustworthy)
+/// High entropy = erratic, unpredictable behavior (suspicious)
+fn compute_entropy(score: &MatlScore) -> f64 {
+    // Simplified entropy calculation
+    // In production, this would analyze transaction patterns over time
+    let quality_variance = (0.5 - score.pogq.quality).abs();
+    let consistency_penalty = 1.0 - score.pogq.consistency;
+
+    (quality_variance + consistency_penalty) / 2.0
+}
+
+/// Detect Byzantine attack patterns
+///
+/// This implements the key innovation for 45% Byzantine tolerance:
+/// detecting coordinated attacks, Sybil identities, and malicious behavior.
+pub fn detect_byzantine_patterns(score: &MatlScore) -> ByzantineFlags {
+    let mut flags = score.flags.clone();
+
+    // 1. Volatile Reputation Detection
+    // Rapid changes in reputation suggest manipulation
+    flags.volatile_reputation = score.pogq.entropy > 0.7;
+
+    // 2. Sybil Detection (simplified)
+    // Multiple accounts with identical patterns
+    // TODO: Implement full Sybil detection via graph analysis
+    flags.sybil_suspected = score.transaction_count < 3 && score.composite > 0.8;
+
+    // 3. Quality Inconsistency
+    // High quality but low consistency = suspicious
+    let inconsistency = score.pogq.quality - score.pogq.consistency;
+    if inconsistency > 0.3 {
+        flags.risk_score = (flags.risk_score + 0.2).min(1.0);
+    }
+
+    // 4. Compute overall Byzantine risk score
+    let mut risk: f64 = 0.0;
+    if flags.cartel_detected {
+        risk += 0.4;
+    }
+    if flags.volatile_reputation {
+        risk += 0.2;
+    }
+    if flags.gradient_poisoning {
+        risk += 0.3;
+    }
+    if flags.sybil_suspected {
+        risk += 0.1;
+    }
+
+    flags.risk_score = risk.min(1.0);
+
+    flags
+}
+
 /// Review Entry - Verifiable feedback from transactions
 ///
 /// Reviews upgrade listings from E1 (seller claim) to E2 (buyer verified)
@@ -160,6 +425,10 @@ pub enum LinkTypes {
 
     /// Transaction -> Review
     TransactionToReview,
+
+    /// Counted outcome (receipt's transaction or arbitration result) -> MatlScore,
+    /// tagged with the agent it was counted for
+    OutcomeToScoreUpdates,
 }
 
 #[hdk_entry_types]
@@ -173,18 +442,25 @@ pub enum EntryTypes {
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
     match op.flattened::<EntryTypes, LinkTypes>()? {
-        FlatOp::StoreEntry(store_entry) => match store_entry {
-            OpEntry::CreateEntry { app_entry, .. } => match app_entry {
-                EntryTypes::MatlScore(score) => validate_matl_score(&score),
-                EntryTypes::Review(review) => validate_review(&review),
-            },
-            _ => Ok(ValidateCallbackResult::Valid),
+        FlatOp::StoreEntry(OpEntry::CreateEntry { app_entry, action }) => match app_entry {
+            EntryTypes::MatlScore(score) => validate_matl_score(&score, &action),
+            EntryTypes::Review(review) => validate_review(&review),
         },
         _ => Ok(ValidateCallbackResult::Valid),
     }
 }
 
-fn validate_matl_score(score: &MatlScore) -> ExternResult<ValidateCallbackResult> {
+/// Validate a MATL score version
+///
+/// Besides the ranges and the composite formula, a score must be the
+/// version it names as previous (or the neutral score) with its outcome
+/// applied (see `apply_outcome`). The outcome must prove out for the
+/// agent, must not be counted earlier in the agent's score history, and
+/// the author must have taken part in it.
+///
+/// Checking for an earlier count walks the score history back to the
+/// first version.
+fn validate_matl_score(score: &MatlScore, action: &Create) -> ExternResult<ValidateCallbackResult> {
     // Validate score ranges
     if score.pogq.quality < 0.0
         || score.pogq.quality > 1.0
@@ -200,10 +476,21 @@ fn validate_matl_score(score: &MatlScore) -> ExternResult<ValidateCallbackResult
         ));
     }
 
+    // Arbitration counters cannot exceed the seats they describe
+    if score.arbitration.agreed_votes > score.arbitration.assignments
+        || score.arbitration.on_time_votes > score.arbitration.assignments
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Arbitration record counts exceed assignments".into(),
+        ));
+    }
+
     // Verify composite score calculation
-    let expected_composite = 0.4 * score.pogq.quality
-        + 0.3 * score.pogq.consistency
-        + 0.3 * score.reputation;
+    let expected_composite = expected_composite(
+        &score.pogq,
+        score.reputation,
+        score.arbitration.reliability(),
+    );
 
     let diff = (score.composite - expected_composite).abs();
     if diff > 0.01 {
@@ -213,9 +500,77 @@ fn validate_matl_score(score: &MatlScore) -> ExternResult<ValidateCallbackResult
         ));
     }
 
+    if score.updated_at > action.timestamp {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Score cannot postdate its action".into(),
+        ));
+    }
+
+    let outcome = match &score.outcome {
+        Some(outcome) => outcome,
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Score must name the outcome it counts".into(),
+            ))
+        }
+    };
+
+    let proven = match outcomes::prove_outcome(outcome, &score.agent)? {
+        Ok(proven) => proven,
+        Err(reason) => return Ok(ValidateCallbackResult::Invalid(reason)),
+    };
+
+    if !proven.participants.contains(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only a participant in the outcome can record it".into(),
+        ));
+    }
+
+    let previous = match &score.previous_score {
+        Some(previous_hash) => match score_version(previous_hash)? {
+            Some(previous) if previous.agent == score.agent => previous,
+            _ => {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Previous score must be a score for the same agent".into(),
+                ))
+            }
+        },
+        None => neutral_matl_score(score.agent.clone(), score.updated_at),
+    };
+
+    // Earlier versions must not have counted the same outcome
+    let mut earlier = Some(previous.clone());
+    while let Some(version) = earlier {
+        if version.outcome.as_ref() == Some(outcome) {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Outcome has already been counted for this agent".into(),
+            ));
+        }
+
+        earlier = match &version.previous_score {
+            Some(previous_hash) => score_version(previous_hash)?,
+            None => None,
+        };
+    }
+
+    let mut expected = apply_outcome(&previous, &proven.step, score.updated_at);
+    expected.previous_score = score.previous_score.clone();
+    expected.outcome = score.outcome.clone();
+    if &expected != score {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Score does not follow from the previous score and its outcome".into(),
+        ));
+    }
+
     Ok(ValidateCallbackResult::Valid)
 }
 
+/// Load a stored score version
+fn score_version(score_hash: &ActionHash) -> ExternResult<Option<MatlScore>> {
+    let record = must_get_valid_record(score_hash.clone())?;
+    Ok(record.entry().to_app_option::<MatlScore>().ok().flatten())
+}
+
 fn validate_review(review: &Review) -> ExternResult<ValidateCallbackResult> {
     // Rating must be 1-5
     if review.rating < 1 || review.rating > 5 {
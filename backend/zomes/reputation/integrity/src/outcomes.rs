@@ -0,0 +1,644 @@
+//! Proof behind a MATL update
+//!
+//! Scores only move on outcomes other agents can check:
+//! - A completed transaction, proven by its countersigned `TransactionReceipt`
+//! - A cancelled transaction, proven by the cancelling transaction update
+//! - A won or lost dispute, or a forfeited appeal reputation stake, proven by the
+//!   `ArbitrationResult`
+//! - A review of a seller, proven by the buyer's `Review`
+//! - A panel seat, proven by the `ArbitrationResult` the panel decided
+//!   (and the votes it lists), or by the dispute update that replaced a
+//!   non-responsive arbitrator
+//!
+//! Every stored score names the outcome it counts, and validation proves
+//! that outcome again (see `prove_outcome`), so a score can only be
+//! written by someone who took part in it.
+//!
+//! The structs below mirror the transactions and arbitration entries, so
+//! this zome does not depend on their integrity zomes. `ReceiptTerms` must
+//! serialize exactly like the signed original for signatures to verify.
+
+use hdi::prelude::*;
+
+use crate::Review;
+
+/// Mirror of `transactions_integrity::ReceiptTerms`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReceiptTerms {
+    pub transaction_hash: ActionHash,
+    pub buyer: AgentPubKey,
+    pub seller: AgentPubKey,
+    pub listing_hash: ActionHash,
+    pub listing_version_hash: ActionHash,
+    pub quantity: u32,
+    pub total_price_cents: u64,
+    pub currency: String,
+    pub matl_value_cents: u64,
+}
+
+/// Mirror of `transactions_integrity::TransactionReceipt`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, SerializedBytes)]
+pub struct TransactionReceipt {
+    pub terms: ReceiptTerms,
+    pub buyer_signature: Signature,
+    pub seller_signature: Signature,
+    pub countersigned_at: Timestamp,
+}
+
+/// The fields of `transactions_integrity::Transaction` checked here
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, SerializedBytes)]
+pub struct TransactionInfo {
+    pub buyer: AgentPubKey,
+    pub seller: AgentPubKey,
+    pub total_price_cents: u64,
+    #[serde(default)]
+    pub reference_value_cents: Option<u64>,
+    /// Lowercase `TransactionStatus`
+    pub status: String,
+    #[serde(default)]
+    pub deadline: Option<Timestamp>,
+}
+
+impl TransactionInfo {
+    /// Value MATL updates are weighted by (reference-currency cents)
+    pub fn matl_value_cents(&self) -> u64 {
+        self.reference_value_cents.unwrap_or(self.total_price_cents)
+    }
+
+    /// Convert an amount in the transaction's currency to the reference
+    /// currency, at the rate the transaction was valued at
+    pub fn reference_amount(&self, amount_minor: u64) -> u64 {
+        match self.reference_value_cents {
+            Some(reference) if self.total_price_cents > 0 => {
+                (amount_minor as u128 * reference as u128 / self.total_price_cents as u128) as u64
+            }
+            _ => amount_minor,
+        }
+    }
+}
+
+/// The fields of `arbitration_integrity::ArbitrationResult` checked here
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, SerializedBytes)]
+pub struct ArbitrationResult {
+    pub dispute_hash: ActionHash,
+    pub dispute_version_hash: ActionHash,
+    pub winner: Option<AgentPubKey>,
+    pub loser: Option<AgentPubKey>,
+    pub refund_percent: u8,
+    pub vote_hashes: Vec<ActionHash>,
+    pub appeal_hash: Option<ActionHash>,
+    pub stake_slashed: bool,
+}
+
+/// The fields of `arbitration_integrity::Dispute` checked here
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, SerializedBytes)]
+pub struct Dispute {
+    pub transaction_hash: ActionHash,
+    pub buyer: AgentPubKey,
+    pub seller: AgentPubKey,
+    pub arbitrators: Vec<AgentPubKey>,
+    pub reveal_deadline: Option<Timestamp>,
+    pub replacement_rounds: Vec<Vec<AgentPubKey>>,
+    #[serde(default)]
+    pub replacement_heads: Vec<Vec<ActionHash>>,
+}
+
+impl Dispute {
+    /// Agents who may write the outcomes of this dispute version
+    fn participants(&self) -> Vec<AgentPubKey> {
+        let mut participants = vec![self.buyer.clone(), self.seller.clone()];
+        participants.extend(self.arbitrators.iter().cloned());
+        participants
+    }
+}
+
+/// The fields of `arbitration_integrity::ArbitrationVote` checked here
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, SerializedBytes)]
+pub struct ArbitrationVote {
+    pub dispute_hash: ActionHash,
+    pub arbitrator: AgentPubKey,
+    pub refund_percent: u8,
+}
+
+/// How an arbitrator served on one panel
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ArbitrationSeat {
+    /// False if the arbitrator never revealed a counted vote
+    pub voted: bool,
+    /// Vote matched the final weighted outcome
+    pub agreed_with_outcome: bool,
+    /// Vote was revealed before the reveal deadline
+    pub on_time: bool,
+}
+
+impl ArbitrationSeat {
+    /// A seat the arbitrator did not respond on
+    pub fn missed() -> Self {
+        ArbitrationSeat {
+            voted: false,
+            agreed_with_outcome: false,
+            on_time: false,
+        }
+    }
+}
+
+/// The fields of `arbitration_integrity::Appeal` checked here
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, SerializedBytes)]
+pub struct Appeal {
+    pub appellant: AgentPubKey,
+    #[serde(alias = "stake_cents")]
+    pub reputation_stake_cents: u64,
+}
+
+/// How a dispute went for an agent
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeOutcome {
+    /// Won the dispute
+    Won,
+    /// Lost the dispute
+    Lost,
+    /// Lost an appeal and forfeited the reputation stake
+    ForfeitedStake,
+}
+
+impl DisputeOutcome {
+    /// Whether the outcome counts as a successful transaction
+    pub fn successful(&self) -> bool {
+        matches!(self, DisputeOutcome::Won)
+    }
+
+    /// Tag prefix the outcome is counted under
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DisputeOutcome::Won => "won",
+            DisputeOutcome::Lost => "lost",
+            DisputeOutcome::ForfeitedStake => "forfeited_stake",
+        }
+    }
+}
+
+/// The outcome a score version counts
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ScoreOutcome {
+    /// Countersigned `TransactionReceipt` of a completed transaction
+    Receipt(ActionHash),
+    /// Transaction update that cancelled it, counted against the party at fault
+    Cancellation(ActionHash),
+    /// The buyer's `Review` of the seller (4-5 stars count as a success)
+    Review(ActionHash),
+    /// `ArbitrationResult` the agent won, lost or forfeited a stake on
+    Dispute {
+        result_hash: ActionHash,
+        outcome: DisputeOutcome,
+    },
+    /// `ArbitrationResult` decided by a panel the agent sat on
+    PanelSeat(ActionHash),
+    /// Dispute update that replaced the agent as a non-responsive arbitrator
+    Replacement(ActionHash),
+}
+
+/// How an outcome moves a score
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ScoreStep {
+    /// A transaction, weighted by its value in reference-currency cents
+    Transaction { successful: bool, value_cents: u64 },
+    /// A panel seat, feeding the arbitration record
+    Seat(ArbitrationSeat),
+}
+
+/// An outcome checked against the entries it cites
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProvenOutcome {
+    /// What the outcome does to the agent's score
+    pub step: ScoreStep,
+    /// Agents who took part, and so may record it
+    pub participants: Vec<AgentPubKey>,
+}
+
+/// Check that a receipt backs a MATL update for `agent`
+///
+/// Both parties signed off on the transaction, so a receipt only ever
+/// backs a successful update.
+pub fn check_receipt(
+    terms: &ReceiptTerms,
+    agent: &AgentPubKey,
+    successful: bool,
+    transaction_value_cents: u64,
+) -> Result<(), String> {
+    if !successful {
+        return Err("A countersigned receipt backs only successful updates".into());
+    }
+
+    if agent != &terms.buyer && agent != &terms.seller {
+        return Err("Agent is not a party to the receipt".into());
+    }
+
+    if transaction_value_cents != terms.matl_value_cents {
+        return Err(format!(
+            "Transaction value must be the receipt's {} cents",
+            terms.matl_value_cents
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check that an arbitration result (and its appeal) backs `outcome` for `agent`
+pub fn check_dispute_outcome(
+    result: &ArbitrationResult,
+    appeal: Option<&Appeal>,
+    agent: &AgentPubKey,
+    outcome: &DisputeOutcome,
+) -> Result<(), String> {
+    match outcome {
+        DisputeOutcome::Won => {
+            if result.winner.as_ref() != Some(agent) {
+                return Err("Agent did not win this dispute".into());
+            }
+        }
+        DisputeOutcome::Lost => {
+            if result.loser.as_ref() != Some(agent) {
+                return Err("Agent did not lose this dispute".into());
+            }
+        }
+        DisputeOutcome::ForfeitedStake => {
+            let forfeited = result.stake_slashed
+                && appeal.map(|appeal| &appeal.appellant) == Some(agent);
+            if !forfeited {
+                return Err("Agent did not forfeit an appeal stake on this result".into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Score an arbitrator's seat on the panel that decided a result
+///
+/// `vote` is the arbitrator's counted vote, if the result lists one, with
+/// the time it was revealed. Panel members without one are scored as
+/// non-responsive.
+pub fn panel_seat(
+    dispute: &Dispute,
+    result: &ArbitrationResult,
+    arbitrator: &AgentPubKey,
+    vote: Option<(&ArbitrationVote, Timestamp)>,
+) -> Result<ArbitrationSeat, String> {
+    if !dispute.arbitrators.contains(arbitrator) {
+        return Err("Agent did not sit on the panel that decided this result".into());
+    }
+
+    Ok(match vote {
+        Some((vote, revealed_at)) => ArbitrationSeat {
+            voted: true,
+            agreed_with_outcome: vote.refund_percent == result.refund_percent,
+            on_time: dispute
+                .reveal_deadline
+                .is_none_or(|deadline| revealed_at <= deadline),
+        },
+        None => ArbitrationSeat::missed(),
+    })
+}
+
+/// Check that a dispute update replaced an arbitrator
+///
+/// The update must add a replacement round naming the arbitrator, and
+/// cite the chain head that shows they missed the deadline. Arbitration
+/// validation only accepts rounds after a missed deadline, walking each
+/// removed arbitrator's chain back from their cited head.
+pub fn check_replacement(
+    previous: &Dispute,
+    dispute: &Dispute,
+    arbitrator: &AgentPubKey,
+) -> Result<(), String> {
+    let added_round = dispute.replacement_rounds.len() == previous.replacement_rounds.len() + 1;
+    let position = match dispute.replacement_rounds.last() {
+        Some(round) if added_round => round.iter().position(|agent| agent == arbitrator),
+        _ => None,
+    };
+    let Some(position) = position else {
+        return Err("Dispute update did not replace this arbitrator".into());
+    };
+
+    let cited_head = dispute.replacement_heads.len() == dispute.replacement_rounds.len()
+        && dispute
+            .replacement_heads
+            .last()
+            .is_some_and(|heads| heads.len() > position);
+    if !cited_head {
+        return Err("Replacement does not show the arbitrator missed a deadline".into());
+    }
+
+    Ok(())
+}
+
+/// The party a cancellation counts against
+///
+/// A seller who let the confirmation window lapse is at fault, whoever
+/// cancelled; otherwise the party who backed out is.
+pub fn cancellation_at_fault(
+    previous: &TransactionInfo,
+    cancelled_by: &AgentPubKey,
+    cancelled_at: Timestamp,
+) -> AgentPubKey {
+    let lapsed = previous.status == "pending"
+        && previous.deadline.is_some_and(|deadline| cancelled_at > deadline);
+
+    if lapsed {
+        previous.seller.clone()
+    } else {
+        cancelled_by.clone()
+    }
+}
+
+/// Prove an outcome for `agent` from the entries it cites
+///
+/// Returns the step it applies to the agent's score and who took part in
+/// it, or why it does not count for the agent.
+pub fn prove_outcome(
+    outcome: &ScoreOutcome,
+    agent: &AgentPubKey,
+) -> ExternResult<Result<ProvenOutcome, String>> {
+    match outcome {
+        ScoreOutcome::Receipt(receipt_hash) => prove_receipt(receipt_hash, agent),
+        ScoreOutcome::Cancellation(cancellation_hash) => prove_cancellation(cancellation_hash, agent),
+        ScoreOutcome::Review(review_hash) => prove_review(review_hash, agent),
+        ScoreOutcome::Dispute {
+            result_hash,
+            outcome,
+        } => prove_dispute_outcome(result_hash, agent, outcome),
+        ScoreOutcome::PanelSeat(result_hash) => prove_panel_seat(result_hash, agent),
+        ScoreOutcome::Replacement(replacement_hash) => prove_replacement(replacement_hash, agent),
+    }
+}
+
+fn prove_receipt(
+    receipt_hash: &ActionHash,
+    agent: &AgentPubKey,
+) -> ExternResult<Result<ProvenOutcome, String>> {
+    let receipt: TransactionReceipt = match app_entry(receipt_hash, "Transaction receipt")? {
+        Ok((receipt, _)) => receipt,
+        Err(reason) => return Ok(Err(reason)),
+    };
+
+    let signatures = [
+        (&receipt.terms.buyer, &receipt.buyer_signature),
+        (&receipt.terms.seller, &receipt.seller_signature),
+    ];
+    for (party, signature) in signatures {
+        if !verify_signature(party.clone(), signature.clone(), receipt.terms.clone())? {
+            return Ok(Err("Receipt signatures do not verify".into()));
+        }
+    }
+
+    let value_cents = receipt.terms.matl_value_cents;
+    if let Err(reason) = check_receipt(&receipt.terms, agent, true, value_cents) {
+        return Ok(Err(reason));
+    }
+
+    Ok(Ok(ProvenOutcome {
+        step: ScoreStep::Transaction {
+            successful: true,
+            value_cents,
+        },
+        participants: vec![receipt.terms.buyer, receipt.terms.seller],
+    }))
+}
+
+fn prove_cancellation(
+    cancellation_hash: &ActionHash,
+    agent: &AgentPubKey,
+) -> ExternResult<Result<ProvenOutcome, String>> {
+    let (cancelled, record): (TransactionInfo, Record) =
+        match app_entry(cancellation_hash, "Transaction")? {
+            Ok(cancelled) => cancelled,
+            Err(reason) => return Ok(Err(reason)),
+        };
+
+    if cancelled.status != "cancelled" {
+        return Ok(Err("Transaction update is not a cancellation".into()));
+    }
+
+    let previous_hash = match record.action() {
+        Action::Update(update) => update.original_action_address.clone(),
+        _ => return Ok(Err("Cancellation must be a transaction update".into())),
+    };
+    let previous: TransactionInfo = match app_entry(&previous_hash, "Transaction")? {
+        Ok((previous, _)) => previous,
+        Err(reason) => return Ok(Err(reason)),
+    };
+
+    let at_fault = cancellation_at_fault(
+        &previous,
+        record.action().author(),
+        record.action().timestamp(),
+    );
+    if &at_fault != agent {
+        return Ok(Err("Cancellation does not count against this agent".into()));
+    }
+
+    Ok(Ok(ProvenOutcome {
+        step: ScoreStep::Transaction {
+            successful: false,
+            value_cents: previous.matl_value_cents(),
+        },
+        participants: vec![previous.buyer, previous.seller],
+    }))
+}
+
+fn prove_review(
+    review_hash: &ActionHash,
+    agent: &AgentPubKey,
+) -> ExternResult<Result<ProvenOutcome, String>> {
+    let (review, record): (Review, Record) = match app_entry(review_hash, "Review")? {
+        Ok(review) => review,
+        Err(reason) => return Ok(Err(reason)),
+    };
+
+    if record.action().author() != &review.reviewer || &review.seller != agent {
+        return Ok(Err("Review must be the reviewer's own review of this agent".into()));
+    }
+
+    let transaction: TransactionInfo = match app_entry(&review.transaction_hash, "Transaction")? {
+        Ok((transaction, _)) => transaction,
+        Err(reason) => return Ok(Err(reason)),
+    };
+
+    if transaction.buyer != review.reviewer || transaction.seller != review.seller {
+        return Ok(Err("Only the buyer can review the seller of a transaction".into()));
+    }
+
+    Ok(Ok(ProvenOutcome {
+        step: ScoreStep::Transaction {
+            successful: review.rating >= 4,
+            value_cents: 0,
+        },
+        participants: vec![review.reviewer],
+    }))
+}
+
+fn prove_dispute_outcome(
+    result_hash: &ActionHash,
+    agent: &AgentPubKey,
+    outcome: &DisputeOutcome,
+) -> ExternResult<Result<ProvenOutcome, String>> {
+    let (result, dispute) = match decided_result(result_hash)? {
+        Ok(decided) => decided,
+        Err(reason) => return Ok(Err(reason)),
+    };
+
+    let appeal: Option<Appeal> = match &result.appeal_hash {
+        Some(appeal_hash) => match app_entry(appeal_hash, "Appeal")? {
+            Ok((appeal, _)) => Some(appeal),
+            Err(reason) => return Ok(Err(reason)),
+        },
+        None => None,
+    };
+
+    if let Err(reason) = check_dispute_outcome(&result, appeal.as_ref(), agent, outcome) {
+        return Ok(Err(reason));
+    }
+
+    // Weighed like the disputed transaction, or the stake for a forfeit
+    let transaction: TransactionInfo = match app_entry(&dispute.transaction_hash, "Transaction")? {
+        Ok((transaction, _)) => transaction,
+        Err(reason) => return Ok(Err(reason)),
+    };
+    let value_cents = match (outcome, &appeal) {
+        (DisputeOutcome::ForfeitedStake, Some(appeal)) => {
+            transaction.reference_amount(appeal.reputation_stake_cents)
+        }
+        _ => transaction.matl_value_cents(),
+    };
+
+    Ok(Ok(ProvenOutcome {
+        step: ScoreStep::Transaction {
+            successful: outcome.successful(),
+            value_cents,
+        },
+        participants: dispute.participants(),
+    }))
+}
+
+fn prove_panel_seat(
+    result_hash: &ActionHash,
+    arbitrator: &AgentPubKey,
+) -> ExternResult<Result<ProvenOutcome, String>> {
+    let (result, dispute) = match decided_result(result_hash)? {
+        Ok(decided) => decided,
+        Err(reason) => return Ok(Err(reason)),
+    };
+
+    let mut counted_vote = None;
+    for vote_hash in &result.vote_hashes {
+        let (vote, record): (ArbitrationVote, Record) =
+            match app_entry(vote_hash, "Arbitration vote")? {
+                Ok(vote) => vote,
+                Err(reason) => return Ok(Err(reason)),
+            };
+
+        if &vote.arbitrator == arbitrator && vote.dispute_hash == result.dispute_hash {
+            counted_vote = Some((vote, record.action().timestamp()));
+            break;
+        }
+    }
+
+    let seat = match panel_seat(
+        &dispute,
+        &result,
+        arbitrator,
+        counted_vote.as_ref().map(|(vote, revealed_at)| (vote, *revealed_at)),
+    ) {
+        Ok(seat) => seat,
+        Err(reason) => return Ok(Err(reason)),
+    };
+
+    Ok(Ok(ProvenOutcome {
+        step: ScoreStep::Seat(seat),
+        participants: dispute.participants(),
+    }))
+}
+
+fn prove_replacement(
+    replacement_hash: &ActionHash,
+    arbitrator: &AgentPubKey,
+) -> ExternResult<Result<ProvenOutcome, String>> {
+    let (dispute, record): (Dispute, Record) = match app_entry(replacement_hash, "Dispute")? {
+        Ok(dispute) => dispute,
+        Err(reason) => return Ok(Err(reason)),
+    };
+
+    // Only arbitration validation checks the removed arbitrators' chains
+    if !is_arbitration_dispute(&record)? {
+        return Ok(Err("Replacement must be an arbitration dispute update".into()));
+    }
+
+    let previous_hash = match record.action() {
+        Action::Update(update) => update.original_action_address.clone(),
+        _ => return Ok(Err("Replacement must be a dispute update".into())),
+    };
+    let previous: Dispute = match app_entry(&previous_hash, "Dispute")? {
+        Ok((previous, _)) => previous,
+        Err(reason) => return Ok(Err(reason)),
+    };
+
+    if let Err(reason) = check_replacement(&previous, &dispute, arbitrator) {
+        return Ok(Err(reason));
+    }
+
+    Ok(Ok(ProvenOutcome {
+        step: ScoreStep::Seat(ArbitrationSeat::missed()),
+        participants: previous.participants(),
+    }))
+}
+
+/// Integrity zome that defines `Dispute`
+const ARBITRATION_INTEGRITY_ZOME: &str = "arbitration_integrity";
+
+/// Position of `Dispute` in the arbitration zome's `EntryTypes`
+const DISPUTE_ENTRY_INDEX: u8 = 0;
+
+/// Whether a record is a `Dispute` written through the arbitration zome
+fn is_arbitration_dispute(record: &Record) -> ExternResult<bool> {
+    let Some(EntryType::App(entry_def)) = record.action().entry_type() else {
+        return Ok(false);
+    };
+
+    let zome_names = dna_info()?.zome_names;
+    let from_arbitration = zome_names
+        .get(entry_def.zome_index.0 as usize)
+        .is_some_and(|name| name.0 == ARBITRATION_INTEGRITY_ZOME);
+
+    Ok(from_arbitration && entry_def.entry_index.0 == DISPUTE_ENTRY_INDEX)
+}
+
+/// Load a result and the dispute version whose panel decided it
+fn decided_result(
+    result_hash: &ActionHash,
+) -> ExternResult<Result<(ArbitrationResult, Dispute), String>> {
+    let result: ArbitrationResult = match app_entry(result_hash, "Arbitration result")? {
+        Ok((result, _)) => result,
+        Err(reason) => return Ok(Err(reason)),
+    };
+
+    let dispute: Dispute = match app_entry(&result.dispute_version_hash, "Dispute")? {
+        Ok((dispute, _)) => dispute,
+        Err(reason) => return Ok(Err(reason)),
+    };
+
+    Ok(Ok((result, dispute)))
+}
+
+/// Load a valid record and decode its app entry
+fn app_entry<T>(action_hash: &ActionHash, what: &str) -> ExternResult<Result<(T, Record), String>>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let record = must_get_valid_record(action_hash.clone())?;
+
+    match record.entry().to_app_option::<T>() {
+        Ok(Some(entry)) => Ok(Ok((entry, record))),
+        _ => Ok(Err(format!("{} could not be deserialized", what))),
+    }
+}
@@ -324,6 +324,3 @@ pub fn delete_search_index(entity_hash: ActionHash) -> ExternResult<()> {
 
     Ok(())
 }
-
-#[cfg(test)]
-mod tests;
@@ -87,11 +87,8 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
-        FlatOp::RegisterUpdate(update_entry) => match update_entry {
-            OpUpdate::Entry { app_entry, .. } => match app_entry {
-                EntryTypes::SearchIndexEntry(entry) => validate_search_index_entry(&entry),
-            },
-            _ => Ok(ValidateCallbackResult::Valid),
+        FlatOp::RegisterUpdate(OpUpdate::Entry { app_entry, .. }) => match app_entry {
+            EntryTypes::SearchIndexEntry(entry) => validate_search_index_entry(&entry),
         },
         FlatOp::RegisterCreateLink { link_type, .. } => {
             // All link types are valid for search indexing
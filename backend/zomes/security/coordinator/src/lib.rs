@@ -246,10 +246,10 @@ pub fn get_security_stats(_: ()) -> ExternResult<SecurityStatsOutput> {
 
     // Convert to sorted vectors
     let mut events_by_type: Vec<(String, u32)> = event_type_counts.into_iter().collect();
-    events_by_type.sort_by(|a, b| b.1.cmp(&a.1));
+    events_by_type.sort_by_key(|entry| std::cmp::Reverse(entry.1));
 
     let mut top_offenders: Vec<(AgentPubKey, u32)> = agent_counts.into_iter().collect();
-    top_offenders.sort_by(|a, b| b.1.cmp(&a.1));
+    top_offenders.sort_by_key(|entry| std::cmp::Reverse(entry.1));
     top_offenders.truncate(10); // Top 10 only
 
     Ok(SecurityStatsOutput {
@@ -260,6 +260,3 @@ pub fn get_security_stats(_: ()) -> ExternResult<SecurityStatsOutput> {
         top_offenders,
     })
 }
-
-#[cfg(test)]
-mod tests;
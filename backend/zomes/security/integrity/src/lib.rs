@@ -86,11 +86,8 @@ pub enum EntryTypes {
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
     match op.flattened::<EntryTypes, LinkTypes>()? {
-        FlatOp::StoreEntry(store_entry) => match store_entry {
-            OpEntry::CreateEntry { app_entry, .. } => match app_entry {
-                EntryTypes::SecurityLog(log) => validate_security_log(&log),
-            },
-            _ => Ok(ValidateCallbackResult::Valid),
+        FlatOp::StoreEntry(OpEntry::CreateEntry { app_entry, .. }) => match app_entry {
+            EntryTypes::SecurityLog(log) => validate_security_log(&log),
         },
         FlatOp::RegisterCreateLink { link_type, .. } => {
             // All link types are valid for security logging
@@ -1,7 +1,7 @@
-/// Security utilities for Mycelix-Marketplace
-///
-/// This module provides comprehensive input sanitization and validation
-/// to prevent XSS, injection attacks, and other security vulnerabilities.
+//! Security utilities for Mycelix-Marketplace
+//!
+//! This module provides comprehensive input sanitization and validation
+//! to prevent XSS, injection attacks, and other security vulnerabilities.
 
 use std::collections::HashSet;
 
@@ -133,18 +133,61 @@ pub fn normalize_unicode(input: &str) -> String {
         .collect()
 }
 
-/// Validate price (in cents)
-pub fn validate_price(price_cents: u64) -> Result<u64, String> {
-    if price_cents == 0 {
+/// Price caps for supported ISO-4217 currencies, in minor units
+///
+/// Each cap is roughly $1,000,000 worth of the currency.
+const ISO_PRICE_LIMITS: &[(&str, u64)] = &[
+    ("USD", 100_000_000),       // $1,000,000.00
+    ("EUR", 100_000_000),       // €1,000,000.00
+    ("GBP", 80_000_000),        // £800,000.00
+    ("CHF", 100_000_000),       // CHF 1,000,000.00
+    ("CAD", 140_000_000),       // C$1,400,000.00
+    ("AUD", 150_000_000),       // A$1,500,000.00
+    ("JPY", 150_000_000),       // ¥150,000,000 (no minor unit)
+    ("CNY", 700_000_000),       // ¥7,000,000.00
+    ("INR", 8_000_000_000),     // ₹80,000,000.00
+    ("BRL", 500_000_000),       // R$5,000,000.00
+    ("MXN", 1_700_000_000),     // MX$17,000,000.00
+    ("SEK", 1_000_000_000),     // 10,000,000.00 kr
+];
+
+/// Price cap for an ISO-4217 currency (in minor units)
+pub fn max_price_minor(currency: &str) -> Option<u64> {
+    ISO_PRICE_LIMITS
+        .iter()
+        .find(|(code, _)| *code == currency)
+        .map(|(_, limit)| *limit)
+}
+
+/// Validate price (in minor units of `currency`)
+///
+/// Custom currencies have their own caps and go through
+/// `validate_price_with_limit`.
+pub fn validate_price(amount_minor: u64, currency: &str) -> Result<u64, String> {
+    let limit = max_price_minor(currency)
+        .ok_or_else(|| format!("Unsupported currency: {}", currency))?;
+
+    validate_price_with_limit(amount_minor, currency, limit)
+}
+
+/// Validate price against an explicit cap (in minor units)
+pub fn validate_price_with_limit(
+    amount_minor: u64,
+    currency: &str,
+    max_amount_minor: u64,
+) -> Result<u64, String> {
+    if amount_minor == 0 {
         return Err("Price must be greater than zero".to_string());
     }
 
-    if price_cents > 100_000_000 {
-        // Max $1,000,000
-        return Err("Price too high (max $1,000,000)".to_string());
+    if amount_minor > max_amount_minor {
+        return Err(format!(
+            "Price too high (max {} minor units of {})",
+            max_amount_minor, currency
+        ));
     }
 
-    Ok(price_cents)
+    Ok(amount_minor)
 }
 
 /// Validate quantity
@@ -217,11 +260,9 @@ impl RateLimiter {
             .unwrap_or(0);
 
         // Calculate when the oldest action will fall outside the window
-        let wait_time = oldest_in_window
+        oldest_in_window
             .saturating_add(self.window_seconds)
-            .saturating_sub(current_time);
-
-        wait_time
+            .saturating_sub(current_time)
     }
 
     // Default rate limiters for different actions
@@ -334,7 +375,7 @@ pub fn contains_profanity(text: &str) -> bool {
     let lowercase = text.to_lowercase();
 
     // Basic profanity list (expand in production)
-    let profanity_list = vec![
+    let profanity_list = [
         "spam", "scam", "fraud", "fake", "phishing",
         // Add more as needed
     ];
@@ -462,12 +503,24 @@ mod tests {
     #[test]
     fn test_validate_price() {
         // Valid prices
-        assert!(validate_price(100).is_ok()); // $1.00
-        assert!(validate_price(999999).is_ok()); // $9,999.99
+        assert!(validate_price(100, "USD").is_ok()); // $1.00
+        assert!(validate_price(999999, "USD").is_ok()); // $9,999.99
 
         // Invalid prices
-        assert!(validate_price(0).is_err());
-        assert!(validate_price(100_000_001).is_err()); // Over $1M
+        assert!(validate_price(0, "USD").is_err());
+        assert!(validate_price(100_000_001, "USD").is_err()); // Over $1M
+    }
+
+    #[test]
+    fn test_validate_price_per_currency() {
+        // Caps differ by currency
+        assert!(validate_price(100_000_000, "JPY").is_ok()); // ¥100M
+        assert!(validate_price(90_000_000, "GBP").is_err()); // Over £800k
+
+        // Unknown currencies need an explicit cap
+        assert!(validate_price(100, "MYC").is_err());
+        assert!(validate_price_with_limit(100, "MYC", 1_000).is_ok());
+        assert!(validate_price_with_limit(1_001, "MYC", 1_000).is_err());
     }
 
     #[test]
@@ -680,8 +733,8 @@ mod tests {
     fn test_security_error_messages() {
         let xss_error = SecurityError::xss_attempt();
         assert!(xss_error.message.contains("dangerous"));
-        assert!(xss_error.suggestion.len() > 0);
-        assert!(xss_error.learn_more.len() > 0);
+        assert!(!xss_error.suggestion.is_empty());
+        assert!(!xss_error.learn_more.is_empty());
 
         let cid_error = SecurityError::invalid_ipfs_cid();
         assert!(cid_error.message.contains("IPFS"));
@@ -0,0 +1,348 @@
+//! Escrow for marketplace transactions
+//!
+//! Buyer funds are held when a transaction is created and leave escrow
+//! either as a release to the seller or a refund to the buyer.
+//!
+//! Value moves through a `Settlement` adapter. Every movement is also
+//! recorded as an `EscrowEntry` linked from the transaction, and those
+//! entries are the source of truth for how much is still held.
+//!
+//! Adapters:
+//! - `LocalLedger`: settles on the DHT ledger itself (default, and used in tests)
+//! - External payment rails can implement `Settlement` and return their
+//!   own transaction references
+
+use hdk::prelude::*;
+use transactions_integrity::*;
+use mycelix_common::{error_handling, link_queries, time};
+
+/// A way of moving escrowed value
+pub trait Settlement {
+    /// Lock the buyer's payment, returns a settlement reference
+    fn hold(&mut self, payer: &AgentPubKey, amount_cents: u64) -> ExternResult<String>;
+
+    /// Pay held funds out to the seller
+    fn release(&mut self, payee: &AgentPubKey, amount_cents: u64) -> ExternResult<String>;
+
+    /// Return held funds to the buyer
+    fn refund(&mut self, payee: &AgentPubKey, amount_cents: u64) -> ExternResult<String>;
+
+    /// Funds still held
+    fn held_cents(&self) -> u64;
+}
+
+/// Settlement on the DHT ledger of escrow entries
+///
+/// Balances are rebuilt from a transaction's existing escrow entries,
+/// so a hold can be paid out at most once in total.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LocalLedger {
+    transaction_hash: Option<ActionHash>,
+    held_cents: u64,
+    released_cents: u64,
+    refunded_cents: u64,
+    movements: u32,
+}
+
+impl LocalLedger {
+    /// Rebuild the ledger for a transaction from its escrow entries
+    pub fn from_entries(transaction_hash: ActionHash, entries: &[EscrowEntry]) -> Self {
+        let mut ledger = LocalLedger {
+            transaction_hash: Some(transaction_hash),
+            ..Default::default()
+        };
+
+        for entry in entries {
+            match entry.kind {
+                EscrowKind::Hold => ledger.held_cents += entry.amount_cents,
+                EscrowKind::Release => ledger.released_cents += entry.amount_cents,
+                EscrowKind::Refund => ledger.refunded_cents += entry.amount_cents,
+            }
+            ledger.movements += 1;
+        }
+
+        ledger
+    }
+
+    /// Total the buyer paid into escrow
+    pub fn funded_cents(&self) -> u64 {
+        self.held_cents
+    }
+
+    /// Total released to the seller
+    pub fn released_cents(&self) -> u64 {
+        self.released_cents
+    }
+
+    /// Total refunded to the buyer
+    pub fn refunded_cents(&self) -> u64 {
+        self.refunded_cents
+    }
+
+    fn next_reference(&mut self) -> String {
+        self.movements += 1;
+        match &self.transaction_hash {
+            Some(hash) => format!("local:{}:{}", hash, self.movements),
+            None => format!("local:{}", self.movements),
+        }
+    }
+
+    fn pay_out(&mut self, amount_cents: u64) -> ExternResult<()> {
+        if amount_cents == 0 {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Escrow amount must be greater than zero".into()
+            )));
+        }
+
+        if amount_cents > self.held_cents() {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Only {} cents are held in escrow",
+                self.held_cents()
+            ))));
+        }
+
+        Ok(())
+    }
+}
+
+impl Settlement for LocalLedger {
+    fn hold(&mut self, _payer: &AgentPubKey, amount_cents: u64) -> ExternResult<String> {
+        if self.held_cents > 0 || self.movements > 0 {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Escrow is already funded".into()
+            )));
+        }
+
+        if amount_cents == 0 {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Escrow amount must be greater than zero".into()
+            )));
+        }
+
+        self.held_cents = amount_cents;
+        Ok(self.next_reference())
+    }
+
+    fn release(&mut self, _payee: &AgentPubKey, amount_cents: u64) -> ExternResult<String> {
+        self.pay_out(amount_cents)?;
+        self.released_cents += amount_cents;
+        Ok(self.next_reference())
+    }
+
+    fn refund(&mut self, _payee: &AgentPubKey, amount_cents: u64) -> ExternResult<String> {
+        self.pay_out(amount_cents)?;
+        self.refunded_cents += amount_cents;
+        Ok(self.next_reference())
+    }
+
+    fn held_cents(&self) -> u64 {
+        self.held_cents
+            .saturating_sub(self.released_cents)
+            .saturating_sub(self.refunded_cents)
+    }
+}
+
+/// Get all escrow entries for a transaction
+///
+/// Entries are fetched from the network, since the other party may have
+/// recorded movements this node has not seen yet.
+pub fn get_escrow_entries(transaction_hash: ActionHash) -> ExternResult<Vec<EscrowEntry>> {
+    // Use shared utility for get_links
+    let links = link_queries::get_links_network(transaction_hash, LinkTypes::TransactionToEscrow)?;
+
+    let mut entries = Vec::new();
+    for link in links {
+        if let Some(action_hash) = link.target.into_action_hash() {
+            if let Some(record) = get(action_hash, GetOptions::default())? {
+                // Use shared utility for deserialization
+                entries.push(error_handling::deserialize_entry(&record)?);
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Load the ledger for a transaction
+pub fn load_ledger(transaction_hash: ActionHash) -> ExternResult<LocalLedger> {
+    let entries = get_escrow_entries(transaction_hash.clone())?;
+    Ok(LocalLedger::from_entries(transaction_hash, &entries))
+}
+
+/// Load the ledger of a transaction whose escrow must already be funded
+///
+/// Every transaction holds the buyer's payment when it is created, so a
+/// missing hold means it has not been found yet and nothing can be paid out.
+pub fn load_funded_ledger(transaction_hash: ActionHash) -> ExternResult<LocalLedger> {
+    let ledger = load_ledger(transaction_hash)?;
+
+    if ledger.funded_cents() == 0 {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Escrow hold for this transaction was not found".into()
+        )));
+    }
+
+    Ok(ledger)
+}
+
+/// What authorizes an escrow movement (checked by validation)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Authorization {
+    /// Holds need no authorization
+    None,
+    /// A dispute's arbitration result
+    Result(ActionHash),
+    /// A Completed, Cancelled or Refunded version of the transaction
+    Version(ActionHash),
+}
+
+/// Move funds through the adapter and record the movement
+pub fn record_movement<S: Settlement>(
+    settlement: &mut S,
+    transaction_hash: ActionHash,
+    kind: EscrowKind,
+    party: AgentPubKey,
+    amount_cents: u64,
+    authorization: Authorization,
+) -> ExternResult<EscrowEntry> {
+    let settlement_reference = match kind {
+        EscrowKind::Hold => settlement.hold(&party, amount_cents)?,
+        EscrowKind::Release => settlement.release(&party, amount_cents)?,
+        EscrowKind::Refund => settlement.refund(&party, amount_cents)?,
+    };
+
+    let (arbitration_result_hash, transaction_version_hash) = match authorization {
+        Authorization::None => (None, None),
+        Authorization::Result(result_hash) => (Some(result_hash), None),
+        Authorization::Version(version_hash) => (None, Some(version_hash)),
+    };
+
+    let entry = EscrowEntry {
+        transaction_hash: transaction_hash.clone(),
+        kind,
+        amount_cents,
+        party,
+        settlement_reference,
+        arbitration_result_hash,
+        transaction_version_hash,
+        recorded_at: time::now()?,
+    };
+
+    let action_hash = create_entry(&EntryTypes::EscrowEntry(entry.clone()))?;
+
+    create_link(
+        transaction_hash,
+        action_hash,
+        LinkTypes::TransactionToEscrow,
+        (),
+    )?;
+
+    Ok(entry)
+}
+
+/// Split held funds into (refund to buyer, release to seller)
+///
+/// The refund is capped at what is still held; the seller gets the rest.
+pub fn split_settlement(held_cents: u64, refund_cents: u64) -> (u64, u64) {
+    let refund = refund_cents.min(held_cents);
+    (refund, held_cents - refund)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction_hash() -> ActionHash {
+        ActionHash::from_raw_36(vec![1u8; 36])
+    }
+
+    fn buyer() -> AgentPubKey {
+        AgentPubKey::from_raw_36(vec![2u8; 36])
+    }
+
+    fn seller() -> AgentPubKey {
+        AgentPubKey::from_raw_36(vec![3u8; 36])
+    }
+
+    fn entry(kind: EscrowKind, amount_cents: u64) -> EscrowEntry {
+        EscrowEntry {
+            transaction_hash: transaction_hash(),
+            kind,
+            amount_cents,
+            party: buyer(),
+            settlement_reference: "local:test".to_string(),
+            arbitration_result_hash: None,
+            transaction_version_hash: None,
+            recorded_at: Timestamp::from_micros(1000000),
+        }
+    }
+
+    #[test]
+    fn test_hold_then_release() {
+        let mut ledger = LocalLedger::from_entries(transaction_hash(), &[]);
+
+        ledger.hold(&buyer(), 1999).unwrap();
+        assert_eq!(ledger.held_cents(), 1999);
+
+        ledger.release(&seller(), 1999).unwrap();
+        assert_eq!(ledger.held_cents(), 0);
+        assert_eq!(ledger.released_cents(), 1999);
+    }
+
+    #[test]
+    fn test_cannot_hold_twice() {
+        let mut ledger = LocalLedger::from_entries(transaction_hash(), &[]);
+
+        ledger.hold(&buyer(), 1999).unwrap();
+        assert!(ledger.hold(&buyer(), 1999).is_err());
+    }
+
+    #[test]
+    fn test_cannot_pay_out_more_than_held() {
+        let mut ledger = LocalLedger::from_entries(transaction_hash(), &[]);
+        ledger.hold(&buyer(), 1000).unwrap();
+
+        ledger.refund(&buyer(), 600).unwrap();
+        assert!(ledger.release(&seller(), 500).is_err());
+        ledger.release(&seller(), 400).unwrap();
+
+        assert_eq!(ledger.held_cents(), 0);
+        assert!(ledger.refund(&buyer(), 1).is_err());
+    }
+
+    #[test]
+    fn test_ledger_rebuilt_from_entries() {
+        let ledger = LocalLedger::from_entries(
+            transaction_hash(),
+            &[entry(EscrowKind::Hold, 1000), entry(EscrowKind::Refund, 250)],
+        );
+
+        assert_eq!(ledger.held_cents(), 750);
+        assert_eq!(ledger.refunded_cents(), 250);
+        // Payouts leave the funded total as it was
+        assert_eq!(ledger.funded_cents(), 1000);
+
+        // Without a hold nothing was ever funded
+        let unfunded = LocalLedger::from_entries(transaction_hash(), &[entry(EscrowKind::Refund, 250)]);
+        assert_eq!(unfunded.funded_cents(), 0);
+    }
+
+    #[test]
+    fn test_settlement_references_are_unique() {
+        let mut ledger = LocalLedger::from_entries(transaction_hash(), &[]);
+
+        let hold = ledger.hold(&buyer(), 1000).unwrap();
+        let refund = ledger.refund(&buyer(), 500).unwrap();
+        let release = ledger.release(&seller(), 500).unwrap();
+
+        assert_ne!(hold, refund);
+        assert_ne!(refund, release);
+    }
+
+    #[test]
+    fn test_split_settlement() {
+        assert_eq!(split_settlement(1000, 250), (250, 750));
+        assert_eq!(split_settlement(1000, 0), (0, 1000));
+        assert_eq!(split_settlement(1000, 5000), (1000, 0)); // Capped at held
+    }
+}
@@ -1,26 +1,70 @@
 use hdk::prelude::*;
 use transactions_integrity::*;
-use mycelix_common::{error_handling, link_queries, remote_calls, time};
+use mycelix_common::{error_handling, link_queries, money, records, remote_calls, time};
+
+mod escrow;
+mod statement;
+
+use escrow::Settlement;
+
+const EXCHANGE_RATES_PATH: &str = "exchange_rates";
 
 /// Create a new transaction (buyer initiates purchase)
 ///
 /// This starts the transaction lifecycle. The buyer creates the transaction
 /// in Pending state, and the seller must confirm it.
+/// The buyer's payment is held in escrow until the transaction settles.
+///
+/// The seller and total price are taken from the current version of the
+/// listing, never from the buyer.
 #[hdk_extern]
 pub fn create_transaction(input: CreateTransactionInput) -> ExternResult<TransactionOutput> {
+    let (line, currency) = price_listing(input.listing_hash, input.variant_sku, input.quantity)?;
+    open_transaction(vec![line], currency, None)
+}
+
+/// Create a Pending transaction for one seller's priced lines, reserve
+/// their stock and hold the buyer's payment
+///
+/// The first line is the listing the transaction names; a transaction
+/// with more than one line records them all and totals them.
+fn open_transaction(
+    lines: Vec<OrderLine>,
+    currency: String,
+    order_hash: Option<ActionHash>,
+) -> ExternResult<TransactionOutput> {
     let agent_info = agent_info()?;
+    let now = time::now()?;
+
+    let first = lines.first().cloned().ok_or(wasm_error!(WasmErrorInner::Guest(
+        "A transaction needs at least one line".into()
+    )))?;
+    let total_price_cents = lines
+        .iter()
+        .try_fold(0u64, |total, line| total.checked_add(line.total_price_cents))
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Transaction total overflows".into()
+        )))?;
+
+    // Value non-USD purchases in USD for MATL weighting
+    let (exchange_rate_hash, reference_value_cents) =
+        value_in_reference_currency(total_price_cents, &currency)?;
 
     // Create transaction entry
     let transaction = Transaction {
         buyer: agent_info.agent_initial_pubkey.clone(),
-        seller: input.seller.clone(),
-        listing_hash: input.listing_hash.clone(),
-        quantity: input.quantity,
-        total_price_cents: input.total_price_cents,
+        seller: first.seller,
+        listing_hash: first.listing_hash,
+        listing_version_hash: first.listing_version_hash,
+        quantity: first.quantity,
+        total_price_cents,
+        currency,
+        exchange_rate_hash,
+        reference_value_cents,
         status: TransactionStatus::Pending,
-        created_at: time::now()?,
-        updated_at: time::now()?,
-        tracking_info: None,
+        created_at: now,
+        updated_at: now,
+        shipment_hash: None,
         epistemic: EpistemicClassification {
             // Transaction starts as testimonial (E1)
             empirical: EmpiricalLevel::E1Testimonial,
@@ -29,10 +73,20 @@ pub fn create_transaction(input: CreateTransactionInput) -> ExternResult<Transac
             // Temporal during transaction (M1)
             materiality: MaterialityLevel::M1Temporal,
         },
+        order_hash,
+        // Seller has until the confirmation timeout to accept
+        deadline: state_deadline(&TransactionStatus::Pending, now, &transaction_properties()?),
+        return_request: None,
+        receipt_hash: None,
+        variant_sku: first.variant_sku,
+        lines: if lines.len() > 1 { lines } else { Vec::new() },
     };
 
     let action_hash = create_entry(&EntryTypes::Transaction(transaction.clone()))?;
 
+    // Reserve stock so the last unit cannot be sold twice
+    reserve_lines(&action_hash, &transaction)?;
+
     // Create links for discovery
     create_link(
         transaction.buyer.clone(),
@@ -48,17 +102,30 @@ pub fn create_transaction(input: CreateTransactionInput) -> ExternResult<Transac
         (),
     )?;
 
-    create_link(
-        transaction.listing_hash.clone(),
+    for line in transaction.purchase_lines() {
+        create_link(
+            line.listing_hash,
+            action_hash.clone(),
+            LinkTypes::ListingToTransactions,
+            (),
+        )?;
+    }
+
+    // Hold the buyer's payment
+    let mut ledger = escrow::LocalLedger::from_entries(action_hash.clone(), &[]);
+    escrow::record_movement(
+        &mut ledger,
         action_hash.clone(),
-        LinkTypes::ListingToTransactions,
-        (),
+        EscrowKind::Hold,
+        transaction.buyer.clone(),
+        transaction.total_price_cents,
+        escrow::Authorization::None,
     )?;
 
     // Emit monitoring metric
     monitoring::emit_metric(
         monitoring::MetricType::TransactionCreated,
-        transaction.total_price_cents as f64,
+        transaction.matl_value_cents() as f64,
         Some(transaction.buyer.clone()),
         Some(format!("seller:{:?},quantity:{}", transaction.seller, transaction.quantity)),
     )?;
@@ -69,12 +136,42 @@ pub fn create_transaction(input: CreateTransactionInput) -> ExternResult<Transac
     })
 }
 
+/// Reserve the stock of every line of a new transaction
+///
+/// The listings zome writes each reservation in its own call, so they
+/// outlive a failed transaction: if a line cannot be reserved, the lines
+/// reserved before it are released before failing.
+fn reserve_lines(transaction_hash: &ActionHash, transaction: &Transaction) -> ExternResult<()> {
+    for line in transaction.purchase_lines() {
+        // Use shared utility for remote calls
+        let reserved: ExternResult<ReservedStock> = remote_calls::call_zome(
+            "listings",
+            "reserve_stock",
+            ReserveStockInput {
+                listing_hash: line.listing_hash,
+                transaction_hash: transaction_hash.clone(),
+                quantity: line.quantity,
+                variant_sku: line.variant_sku,
+            },
+        );
+
+        if let Err(error) = reserved {
+            remote_calls::call_zome_void("listings", "release_reservation", transaction_hash.clone())?;
+            return Err(error);
+        }
+    }
+
+    Ok(())
+}
+
 /// Get a transaction by hash
+///
+/// Returns the latest version of the transaction.
 #[hdk_extern]
 pub fn get_transaction(
     transaction_hash: ActionHash,
 ) -> ExternResult<Option<TransactionOutput>> {
-    let record = get(transaction_hash.clone(), GetOptions::default())?;
+    let record = records::get_latest_record(transaction_hash.clone())?;
 
     match record {
         Some(record) => {
@@ -131,136 +228,291 @@ pub fn get_my_transactions(_: ()) -> ExternResult<TransactionsResponse> {
 /// Seller confirms the transaction
 ///
 /// State transition: Pending → Confirmed
+/// Commits the reserved stock, decrementing the listing's inventory
 #[hdk_extern]
 pub fn confirm_transaction(transaction_hash: ActionHash) -> ExternResult<TransactionOutput> {
-    update_transaction_status(
-        transaction_hash,
-        TransactionStatus::Confirmed,
-        None,
-        vec![TransactionStatus::Pending],
-    )
+    let original_hash = records::get_original_action_hash(transaction_hash.clone())?;
+
+    let output = update_transaction_status(transaction_hash, TransactionStatus::Confirmed)?;
+
+    // Use shared utility for remote calls
+    remote_calls::call_zome_void("listings", "commit_reservation", original_hash)?;
+
+    Ok(output)
 }
 
 /// Seller marks transaction as shipped
 ///
 /// State transition: Confirmed → Shipped
+/// Records a `Shipment` with the carrier details and attaches it to the
+/// transaction.
 #[hdk_extern]
 pub fn mark_shipped(input: MarkShippedInput) -> ExternResult<TransactionOutput> {
-    update_transaction_status(
+    let original_hash = records::get_original_action_hash(input.transaction_hash.clone())?;
+
+    // Check the transition before recording anything
+    let (_, current) = get_latest_transaction(input.transaction_hash.clone())?;
+    verify_transition(&current, &TransactionStatus::Shipped)?;
+
+    let now = time::now()?;
+    let shipment = Shipment {
+        transaction_hash: original_hash.clone(),
+        carrier: input.carrier,
+        tracking_number: input.tracking_number,
+        shipping_method: input.shipping_method,
+        estimated_delivery_start: input.estimated_delivery_start,
+        estimated_delivery_end: input.estimated_delivery_end,
+        events: Vec::new(),
+        created_at: now,
+        updated_at: now,
+    };
+
+    let shipment_hash = create_entry(&EntryTypes::Shipment(shipment))?;
+
+    create_link(
+        original_hash,
+        shipment_hash.clone(),
+        LinkTypes::TransactionToShipment,
+        (),
+    )?;
+
+    update_transaction_with(
         input.transaction_hash,
         TransactionStatus::Shipped,
-        input.tracking_info,
-        vec![TransactionStatus::Confirmed],
+        |transaction| transaction.shipment_hash = Some(shipment_hash),
     )
 }
 
+/// Seller appends a tracking event to a transaction's shipment
+#[hdk_extern]
+pub fn add_tracking_event(input: AddTrackingEventInput) -> ExternResult<ShipmentOutput> {
+    let original_hash = records::get_original_action_hash(input.transaction_hash)?;
+
+    let shipment_hash = get_shipment_hash(original_hash)?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("Transaction has not been shipped".into())
+    ))?;
+
+    let record = records::get_latest_record(shipment_hash.clone())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("Shipment not found".into())
+    ))?;
+
+    if record.action().author() != &agent_info()?.agent_initial_pubkey {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the seller can add tracking events".into()
+        )));
+    }
+
+    // Use shared utility for deserialization
+    let mut shipment: Shipment = error_handling::deserialize_entry(&record)?;
+
+    if shipment.events.len() >= MAX_TRACKING_EVENTS {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Shipment cannot have more than {} tracking events",
+            MAX_TRACKING_EVENTS
+        ))));
+    }
+
+    shipment.events.push(input.event);
+    shipment.updated_at = time::now()?;
+
+    // Update against the previous version, so validation can see it
+    update_entry(record.action_address().clone(), &shipment)?;
+
+    Ok(ShipmentOutput {
+        shipment_hash,
+        shipment,
+    })
+}
+
+/// Get a transaction's shipment and its tracking trail
+///
+/// Events are returned oldest first, so buyers and arbitrators can follow
+/// what happened to the goods.
+#[hdk_extern]
+pub fn get_shipment_history(transaction_hash: ActionHash) -> ExternResult<Option<ShipmentOutput>> {
+    let original_hash = records::get_original_action_hash(transaction_hash)?;
+
+    let shipment_hash = match get_shipment_hash(original_hash)? {
+        Some(hash) => hash,
+        None => return Ok(None),
+    };
+
+    match records::get_latest_record(shipment_hash.clone())? {
+        Some(record) => {
+            // Use shared utility for deserialization
+            let mut shipment: Shipment = error_handling::deserialize_entry(&record)?;
+            sort_tracking_events(&mut shipment.events);
+
+            Ok(Some(ShipmentOutput {
+                shipment_hash,
+                shipment,
+            }))
+        }
+        None => Ok(None),
+    }
+}
+
 /// Buyer confirms delivery
 ///
 /// State transition: Shipped → Delivered
+/// The buyer also signs the receipt terms, so the seller (or a sweep once
+/// the inspection window closes) can countersign to complete.
 #[hdk_extern]
 pub fn confirm_delivery(transaction_hash: ActionHash) -> ExternResult<TransactionOutput> {
-    update_transaction_status(
-        transaction_hash,
-        TransactionStatus::Delivered,
-        None,
-        vec![TransactionStatus::Shipped],
-    )
+    let original_hash = records::get_original_action_hash(transaction_hash.clone())?;
+
+    let output = update_transaction_status(transaction_hash, TransactionStatus::Delivered)?;
+
+    store_receipt_signature(original_hash, &output.transaction)?;
+
+    Ok(output)
 }
 
-/// Complete the transaction
+/// Sign the final terms of a delivered transaction
+///
+/// First step of completion: either party signs, and the other party
+/// countersigns with `countersign_receipt`.
+#[hdk_extern]
+pub fn sign_receipt(transaction_hash: ActionHash) -> ExternResult<ReceiptSignatureOutput> {
+    let original_hash = records::get_original_action_hash(transaction_hash.clone())?;
+
+    let (_, current) = get_latest_transaction(transaction_hash)?;
+    verify_transition(&current, &TransactionStatus::Completed)?;
+
+    let caller = agent_info()?.agent_initial_pubkey;
+    let signatures = get_receipt_signatures(original_hash.clone())?;
+    if signatures.iter().any(|(_, signature)| signature.signer == caller) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Receipt is already signed by this agent".into()
+        )));
+    }
+
+    store_receipt_signature(original_hash, &current)
+}
+
+/// Countersign the other party's receipt signature and complete the transaction
 ///
 /// State transition: Delivered → Completed
-/// This triggers MATL score updates for both buyer and seller
+/// Creates the `TransactionReceipt` carrying both signatures, releases
+/// the escrowed payment to the seller and updates both parties' MATL
+/// scores backed by the receipt.
 #[hdk_extern]
-pub fn complete_transaction(transaction_hash: ActionHash) -> ExternResult<TransactionOutput> {
+pub fn countersign_receipt(transaction_hash: ActionHash) -> ExternResult<TransactionOutput> {
+    let original_hash = records::get_original_action_hash(transaction_hash.clone())?;
+
     // Get current transaction
-    let current = get_transaction(transaction_hash.clone())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Transaction not found".into()
-        )))?;
+    let (_, current) = get_latest_transaction(transaction_hash.clone())?;
 
     // Verify state transition is valid
-    if current.transaction.status != TransactionStatus::Delivered {
-        return Err(wasm_error!(WasmErrorInner::Guest(format!(
-            "Cannot complete transaction from status {:?}",
-            current.transaction.status
-        ))));
+    verify_transition(&current, &TransactionStatus::Completed)?;
+
+    let caller = agent_info()?.agent_initial_pubkey;
+    let terms = ReceiptTerms::new(original_hash.clone(), &current);
+    let signatures = get_receipt_signatures(original_hash.clone())?;
+
+    let counterpart = counterpart_signature(&signatures, &terms, &caller).ok_or(wasm_error!(
+        WasmErrorInner::Guest("The other party has not signed the receipt yet".into())
+    ))?;
+
+    if !verify_signature(
+        counterpart.signer.clone(),
+        counterpart.signature.clone(),
+        terms.clone(),
+    )? {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "The other party's receipt signature does not verify".into()
+        )));
     }
 
-    // Update transaction status
-    let mut updated_transaction = current.transaction.clone();
-    updated_transaction.status = TransactionStatus::Completed;
-    updated_transaction.updated_at = time::now()?;
-    updated_transaction.epistemic.materiality = MaterialityLevel::M2Persistent;
+    let signature = sign(caller.clone(), terms.clone())?;
+    let (buyer_signature, seller_signature) = if caller == current.buyer {
+        (signature, counterpart.signature.clone())
+    } else {
+        (counterpart.signature.clone(), signature)
+    };
 
-    let new_action_hash = update_entry(transaction_hash, &updated_transaction)?;
+    let receipt = TransactionReceipt {
+        terms,
+        buyer_signature,
+        seller_signature,
+        countersigned_at: time::now()?,
+    };
 
-    // Call reputation zome to update MATL scores
-    // This is where the 45% Byzantine tolerance magic happens!
-    // Use shared utility for remote calls
-    remote_calls::call_zome_void(
-        "reputation",
-        "update_matl_score",
-        UpdateMatlInput {
-            agent: updated_transaction.seller.clone(),
-            successful: true,
-            transaction_value_cents: updated_transaction.total_price_cents,
+    let receipt_hash = create_entry(&EntryTypes::TransactionReceipt(receipt))?;
+
+    create_link(
+        original_hash.clone(),
+        receipt_hash.clone(),
+        LinkTypes::TransactionToReceipt,
+        (),
+    )?;
+
+    let output = update_transaction_with(
+        transaction_hash,
+        TransactionStatus::Completed,
+        |transaction| {
+            transaction.receipt_hash = Some(receipt_hash.clone());
+            transaction.epistemic.materiality = MaterialityLevel::M2Persistent;
         },
     )?;
+    let completed = &output.transaction;
+
+    // Pay the seller
+    release_remaining_escrow(original_hash, &output.transaction_hash, completed)?;
+
+    // Call reputation zome to update MATL scores
+    // This is where the 45% Byzantine tolerance magic happens!
+    // The buyer is scored too: confirming delivery and signing off is
+    // what got the transaction here.
+    for agent in [completed.seller.clone(), completed.buyer.clone()] {
+        // Use shared utility for remote calls
+        remote_calls::call_zome_void(
+            "reputation",
+            "update_matl_score",
+            UpdateMatlInput {
+                agent,
+                successful: true,
+                transaction_value_cents: completed.matl_value_cents(),
+                receipt_hash: receipt_hash.clone(),
+            },
+        )?;
+    }
 
     // Emit monitoring metric
     monitoring::emit_metric(
         monitoring::MetricType::TransactionCompleted,
-        updated_transaction.total_price_cents as f64,
-        Some(updated_transaction.buyer.clone()),
-        Some(format!("seller:{:?}", updated_transaction.seller)),
+        completed.matl_value_cents() as f64,
+        Some(completed.buyer.clone()),
+        Some(format!("seller:{:?}", completed.seller)),
     )?;
 
-    Ok(TransactionOutput {
-        transaction_hash: new_action_hash,
-        transaction: updated_transaction,
-    })
+    Ok(output)
+}
+
+/// Complete the transaction
+///
+/// State transition: Delivered → Completed
+/// Completion needs both parties' signatures, so this countersigns the
+/// other party's receipt signature (see `countersign_receipt`).
+#[hdk_extern]
+pub fn complete_transaction(transaction_hash: ActionHash) -> ExternResult<TransactionOutput> {
+    countersign_receipt(transaction_hash)
 }
 
 /// Dispute a transaction
 ///
-/// State transition: Any (except Completed/Cancelled) → Disputed
+/// State transition: Any (except Completed/Cancelled/Disputed) → Disputed
 #[hdk_extern]
 pub fn dispute_transaction(
     input: DisputeTransactionInput,
 ) -> ExternResult<TransactionOutput> {
-    // Get current transaction
-    let current = get_transaction(input.transaction_hash.clone())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Transaction not found".into()
-        )))?;
-
-    let agent_info = agent_info()?;
-    let caller = agent_info.agent_initial_pubkey;
-
-    // Verify caller is buyer or seller
-    if caller != current.transaction.buyer && caller != current.transaction.seller {
-        return Err(wasm_error!(WasmErrorInner::Guest(
-            "Only buyer or seller can dispute transaction".into()
-        )));
-    }
-
-    // Cannot dispute completed or cancelled transactions
-    if current.transaction.status == TransactionStatus::Completed
-        || current.transaction.status == TransactionStatus::Cancelled
-    {
-        return Err(wasm_error!(WasmErrorInner::Guest(format!(
-            "Cannot dispute transaction with status {:?}",
-            current.transaction.status
-        ))));
-    }
-
-    // Update transaction status
-    let mut updated_transaction = current.transaction.clone();
-    updated_transaction.status = TransactionStatus::Disputed;
-    updated_transaction.updated_at = time::now()?;
+    let caller = agent_info()?.agent_initial_pubkey;
 
-    let new_action_hash = update_entry(input.transaction_hash, &updated_transaction)?;
+    // Only buyer or seller, and only from an active state
+    let TransactionOutput {
+        transaction_hash: new_action_hash,
+        transaction: updated_transaction,
+    } = update_transaction_status(input.transaction_hash, TransactionStatus::Disputed)?;
 
     // Store dispute reason (linked to transaction)
     // This will be used by the arbitration zome
@@ -274,7 +526,7 @@ pub fn dispute_transaction(
     // Emit monitoring metric
     monitoring::emit_metric(
         monitoring::MetricType::TransactionDisputed,
-        updated_transaction.total_price_cents as f64,
+        updated_transaction.matl_value_cents() as f64,
         Some(caller),
         Some(format!("buyer:{:?},seller:{:?}", updated_transaction.buyer, updated_transaction.seller)),
     )?;
@@ -288,45 +540,286 @@ pub fn dispute_transaction(
 /// Cancel a transaction
 ///
 /// State transition: Pending/Confirmed → Cancelled
+/// The escrowed payment is refunded to the buyer and reserved stock released.
+/// The cancellation is reported to reputation as a failed transaction for
+/// the party at fault (the seller if they let confirmation lapse,
+/// otherwise whoever cancelled).
 #[hdk_extern]
 pub fn cancel_transaction(transaction_hash: ActionHash) -> ExternResult<TransactionOutput> {
-    // Get current transaction
-    let current = get_transaction(transaction_hash.clone())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Transaction not found".into()
-        )))?;
+    let original_hash = records::get_original_action_hash(transaction_hash.clone())?;
 
-    let agent_info = agent_info()?;
-    let caller = agent_info.agent_initial_pubkey;
+    let output = update_transaction_status(transaction_hash, TransactionStatus::Cancelled)?;
+
+    // Return reserved stock to the listing
+    // Use shared utility for remote calls
+    remote_calls::call_zome_void("listings", "release_reservation", original_hash.clone())?;
+
+    // Return the buyer's payment
+    let mut ledger = escrow::load_funded_ledger(original_hash.clone())?;
+    let held_cents = ledger.held_cents();
+    if held_cents > 0 {
+        escrow::record_movement(
+            &mut ledger,
+            original_hash,
+            EscrowKind::Refund,
+            output.transaction.buyer.clone(),
+            held_cents,
+            escrow::Authorization::Version(output.transaction_hash.clone()),
+        )?;
+    }
+
+    // Use shared utility for remote calls
+    remote_calls::call_zome_void("reputation", "record_cancellation", output.transaction_hash.clone())?;
+
+    Ok(output)
+}
+
+/// Buyer asks to return a delivered item
+///
+/// State transition: Delivered → ReturnRequested
+#[hdk_extern]
+pub fn request_return(input: RequestReturnInput) -> ExternResult<TransactionOutput> {
+    update_transaction_with(
+        input.transaction_hash,
+        TransactionStatus::ReturnRequested,
+        |transaction| {
+            transaction.return_request = Some(ReturnRequest {
+                reason: input.reason,
+                tracking_number: None,
+                refund_cents: None,
+            });
+        },
+    )
+}
+
+/// Seller accepts a return
+///
+/// State transition: ReturnRequested → ReturnApproved
+#[hdk_extern]
+pub fn approve_return(transaction_hash: ActionHash) -> ExternResult<TransactionOutput> {
+    update_transaction_status(transaction_hash, TransactionStatus::ReturnApproved)
+}
+
+/// Seller rejects a return
+///
+/// State transition: ReturnRequested → Disputed
+/// A rejected return goes to arbitration like any other dispute.
+#[hdk_extern]
+pub fn reject_return(input: DisputeTransactionInput) -> ExternResult<TransactionOutput> {
+    let (_, current) = get_latest_transaction(input.transaction_hash.clone())?;
+
+    if current.status != TransactionStatus::ReturnRequested {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only a requested return can be rejected".into()
+        )));
+    }
+
+    if current.seller != agent_info()?.agent_initial_pubkey {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the seller can reject a return".into()
+        )));
+    }
+
+    dispute_transaction(input)
+}
+
+/// Buyer ships the item back
+///
+/// State transition: ReturnApproved → ReturnShipped
+#[hdk_extern]
+pub fn mark_return_shipped(input: ReturnShippedInput) -> ExternResult<TransactionOutput> {
+    let tracking_number = input.tracking_number;
+
+    update_transaction_with(
+        input.transaction_hash,
+        TransactionStatus::ReturnShipped,
+        |transaction| {
+            if let Some(request) = transaction.return_request.as_mut() {
+                request.tracking_number = Some(tracking_number);
+            }
+        },
+    )
+}
+
+/// Seller refunds the buyer
+///
+/// State transition: ReturnRequested/ReturnShipped → Refunded
+/// From ReturnRequested this is a partial refund and the buyer keeps the
+/// item; from ReturnShipped the item is back and its stock is returned to
+/// the listing. The refund comes out of escrow and the rest is released to
+/// the seller.
+#[hdk_extern]
+pub fn refund_transaction(input: RefundTransactionInput) -> ExternResult<TransactionOutput> {
+    let original_hash = records::get_original_action_hash(input.transaction_hash.clone())?;
+    let (_, current) = get_latest_transaction(input.transaction_hash.clone())?;
+    let item_returned = current.status == TransactionStatus::ReturnShipped;
+
+    let output = update_transaction_with(
+        input.transaction_hash,
+        TransactionStatus::Refunded,
+        |transaction| {
+            if let Some(request) = transaction.return_request.as_mut() {
+                request.refund_cents = Some(input.refund_cents);
+            }
+        },
+    )?;
+
+    let mut ledger = escrow::load_funded_ledger(original_hash.clone())?;
+    let (refund_cents, release_cents) =
+        escrow::split_settlement(ledger.held_cents(), input.refund_cents);
+
+    if refund_cents > 0 {
+        escrow::record_movement(
+            &mut ledger,
+            original_hash.clone(),
+            EscrowKind::Refund,
+            output.transaction.buyer.clone(),
+            refund_cents,
+            escrow::Authorization::Version(output.transaction_hash.clone()),
+        )?;
+    }
+
+    if release_cents > 0 {
+        escrow::record_movement(
+            &mut ledger,
+            original_hash.clone(),
+            EscrowKind::Release,
+            output.transaction.seller.clone(),
+            release_cents,
+            escrow::Authorization::Version(output.transaction_hash.clone()),
+        )?;
+    }
+
+    if item_returned {
+        remote_calls::call_zome_void("listings", "release_reservation", original_hash)?;
+    }
+
+    Ok(output)
+}
+
+/// Get the escrow ledger of a transaction
+#[hdk_extern]
+pub fn get_escrow(transaction_hash: ActionHash) -> ExternResult<EscrowOutput> {
+    let original_hash = records::get_original_action_hash(transaction_hash)?;
+
+    let entries = escrow::get_escrow_entries(original_hash.clone())?;
+    let ledger = escrow::LocalLedger::from_entries(original_hash.clone(), &entries);
+
+    Ok(EscrowOutput {
+        transaction_hash: original_hash,
+        held_cents: ledger.held_cents(),
+        released_cents: ledger.released_cents(),
+        refunded_cents: ledger.refunded_cents(),
+        entries,
+    })
+}
+
+/// Settle the escrow of a disputed transaction
+///
+/// Uses the dispute's final `ArbitrationResult`: `compensation_cents`
+/// is refunded to the buyer and the rest of the escrow is released to
+/// the seller. Each party claims their own share, so every payout of a
+/// kind is recorded on one chain, where validation bounds it. A result
+/// is final once it can no longer be appealed (see the arbitration
+/// zome's `get_dispute_settlement`).
+#[hdk_extern]
+pub fn settle_disputed_escrow(dispute_hash: ActionHash) -> ExternResult<EscrowOutput> {
+    // Use shared utility for remote calls
+    let settlement: Option<DisputeSettlementInfo> =
+        remote_calls::call_zome("arbitration", "get_dispute_settlement", dispute_hash)?;
+    let settlement = settlement.ok_or(wasm_error!(WasmErrorInner::Guest(
+        "Dispute has no final arbitration result yet".into()
+    )))?;
+
+    let original_hash = records::get_original_action_hash(settlement.transaction_hash.clone())?;
+    let (_, transaction) = get_latest_transaction(original_hash.clone())?;
 
     // Verify caller is buyer or seller
-    if caller != current.transaction.buyer && caller != current.transaction.seller {
+    let caller = agent_info()?.agent_initial_pubkey;
+    if caller != transaction.buyer && caller != transaction.seller {
         return Err(wasm_error!(WasmErrorInner::Guest(
-            "Only buyer or seller can cancel transaction".into()
+            "Only buyer or seller can settle escrow".into()
         )));
     }
 
-    // Can only cancel from Pending or Confirmed states
-    match current.transaction.status {
-        TransactionStatus::Pending | TransactionStatus::Confirmed => {}
-        _ => {
-            return Err(wasm_error!(WasmErrorInner::Guest(format!(
-                "Cannot cancel transaction from status {:?}",
-                current.transaction.status
-            ))))
-        }
+    if transaction.status != TransactionStatus::Disputed {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Cannot settle escrow of transaction with status {:?}",
+            transaction.status
+        ))));
     }
 
-    // Update transaction status
-    let mut updated_transaction = current.transaction.clone();
-    updated_transaction.status = TransactionStatus::Cancelled;
-    updated_transaction.updated_at = time::now()?;
+    let mut ledger = escrow::load_funded_ledger(original_hash.clone())?;
+    let (refund_cents, release_cents) =
+        escrow::split_settlement(ledger.funded_cents(), settlement.compensation_cents);
 
-    let new_action_hash = update_entry(transaction_hash, &updated_transaction)?;
+    let (kind, share_cents, claimed_cents) = if caller == transaction.buyer {
+        (EscrowKind::Refund, refund_cents, ledger.refunded_cents())
+    } else {
+        (EscrowKind::Release, release_cents, ledger.released_cents())
+    };
 
-    Ok(TransactionOutput {
-        transaction_hash: new_action_hash,
-        transaction: updated_transaction,
+    if share_cents == 0 || claimed_cents > 0 {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Nothing left in escrow for the caller to claim".into()
+        )));
+    }
+
+    escrow::record_movement(
+        &mut ledger,
+        original_hash.clone(),
+        kind,
+        caller,
+        share_cents,
+        escrow::Authorization::Result(settlement.result_hash),
+    )?;
+
+    get_escrow(original_hash)
+}
+
+/// Export a statement of the caller's transactions for a period
+///
+/// Covers transactions created within `[period_start, period_end)`, as
+/// buyer or seller: line items with their escrow movements, totals by
+/// status and currency, and disputed transactions. Rendered as CSV or
+/// canonical JSON, with transaction `ActionHash` references for
+/// reconciliation.
+#[hdk_extern]
+pub fn export_statement(input: StatementInput) -> ExternResult<StatementOutput> {
+    if input.period_end <= input.period_start {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Statement period must end after it starts".into()
+        )));
+    }
+
+    let agent = agent_info()?.agent_initial_pubkey;
+
+    let mut lines = Vec::new();
+    for TransactionOutput {
+        transaction_hash,
+        transaction,
+    } in get_my_transactions(())?.transactions
+    {
+        // Skip the escrow lookups for transactions outside the period
+        if transaction.created_at < input.period_start || transaction.created_at >= input.period_end {
+            continue;
+        }
+
+        let entries = escrow::get_escrow_entries(transaction_hash.clone())?;
+        if let Some(line) = statement::statement_line(&agent, transaction_hash, &transaction, &entries) {
+            lines.push(line);
+        }
+    }
+
+    let statement = statement::build_statement(agent, input.period_start, input.period_end, lines);
+    let document = match input.format {
+        statement::StatementFormat::Csv => statement::render_csv(&statement),
+        statement::StatementFormat::Json => statement::render_json(&statement),
+    };
+
+    Ok(StatementOutput {
+        statement,
+        document,
     })
 }
 
@@ -351,40 +844,643 @@ pub fn get_listing_transactions(
     Ok(TransactionsResponse { transactions })
 }
 
-// ===== Helper Functions =====
+/// Act on the caller's transactions whose state deadline has passed
+///
+/// - Pending: the seller never confirmed, so the transaction is cancelled
+///   (refunding escrow and releasing reserved stock)
+/// - Delivered: the inspection window is over, so the transaction is
+///   completed (releasing escrow and updating MATL as usual) if the other
+///   party has signed the receipt; otherwise the caller signs and the
+///   transaction waits for the other party's countersignature
+/// - Shipped: delivery was never confirmed, so the caller is prompted to
+///   confirm delivery or open a dispute
+///
+/// A transaction the sweep cannot act on is reported in `failed` with its
+/// error, and the sweep moves on to the rest.
+#[hdk_extern]
+pub fn sweep_expired_transactions(_: ()) -> ExternResult<SweepOutput> {
+    let now = time::now()?;
+    let caller = agent_info()?.agent_initial_pubkey;
+    let mut output = SweepOutput::default();
 
-/// Update transaction status with validation
-fn update_transaction_status(
+    for TransactionOutput {
+        transaction_hash,
+        transaction,
+    } in get_my_transactions(())?.transactions
+    {
+        let swept = sweep_transaction(&transaction_hash, &transaction, now, &caller, &mut output);
+        if let Err(error) = swept {
+            output.failed.push(SweepFailure {
+                transaction_hash,
+                error: error.to_string(),
+            });
+        }
+    }
+
+    Ok(output)
+}
+
+/// Act on one transaction for `sweep_expired_transactions`
+fn sweep_transaction(
+    transaction_hash: &ActionHash,
+    transaction: &Transaction,
+    now: Timestamp,
+    caller: &AgentPubKey,
+    output: &mut SweepOutput,
+) -> ExternResult<()> {
+    match sweep_action(transaction, now) {
+        Some(SweepAction::Cancel) => {
+            cancel_transaction(transaction_hash.clone())?;
+            output.cancelled.push(transaction_hash.clone());
+        }
+        Some(SweepAction::Complete) => {
+            let original_hash = records::get_original_action_hash(transaction_hash.clone())?;
+            let terms = ReceiptTerms::new(original_hash.clone(), transaction);
+            let signatures = get_receipt_signatures(original_hash.clone())?;
+
+            if counterpart_signature(&signatures, &terms, caller).is_some() {
+                complete_transaction(transaction_hash.clone())?;
+                output.completed.push(transaction_hash.clone());
+            } else {
+                // Sign so the other party's sweep can complete
+                if !signatures.iter().any(|(_, signature)| &signature.signer == caller) {
+                    store_receipt_signature(original_hash, transaction)?;
+                }
+                output.awaiting_countersignature.push(transaction_hash.clone());
+            }
+        }
+        Some(SweepAction::PromptDispute) => {
+            emit_signal(TransactionSignal::DisputePrompt {
+                transaction_hash: transaction_hash.clone(),
+                deadline: transaction.deadline,
+            })?;
+            output.dispute_prompts.push(transaction_hash.clone());
+        }
+        None => {}
+    }
+
+    Ok(())
+}
+
+/// Publish exchange rates signed by the calling oracle
+///
+/// Only agents listed in `exchange_rate_oracles` in the DNA properties can
+/// publish; validation checks the signature against that list.
+#[hdk_extern]
+pub fn publish_exchange_rates(input: PublishExchangeRatesInput) -> ExternResult<ExchangeRatesOutput> {
+    let oracle = agent_info()?.agent_initial_pubkey;
+
+    if !transaction_properties()?.exchange_rate_oracles.contains(&oracle) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only configured oracles can publish exchange rates".into()
+        )));
+    }
+
+    let payload = ExchangeRatePayload {
+        base_currency: REFERENCE_CURRENCY.to_string(),
+        rates: input.rates,
+        source: input.source,
+        observed_at: time::now()?,
+    };
+
+    let signature = sign(oracle.clone(), payload.clone())?;
+
+    let snapshot = ExchangeRateSnapshot {
+        base_currency: payload.base_currency,
+        rates: payload.rates,
+        source: payload.source,
+        observed_at: payload.observed_at,
+        oracle,
+        signature,
+    };
+
+    if let Err(reason) = check_exchange_rates(&snapshot) {
+        return Err(wasm_error!(WasmErrorInner::Guest(reason)));
+    }
+
+    let snapshot_hash = create_entry(&EntryTypes::ExchangeRateSnapshot(snapshot.clone()))?;
+
+    create_link(
+        Path::from(EXCHANGE_RATES_PATH).path_entry_hash()?,
+        snapshot_hash.clone(),
+        LinkTypes::ExchangeRateSnapshots,
+        (),
+    )?;
+
+    Ok(ExchangeRatesOutput {
+        snapshot_hash,
+        snapshot,
+    })
+}
+
+/// Get the most recent exchange-rate snapshot
+#[hdk_extern]
+pub fn get_latest_exchange_rates(_: ()) -> ExternResult<Option<ExchangeRatesOutput>> {
+    Ok(latest_exchange_rates()?.map(|(snapshot_hash, snapshot)| ExchangeRatesOutput {
+        snapshot_hash,
+        snapshot,
+    }))
+}
+
+/// Add a listing to the caller's cart
+///
+/// Adding a listing that is already in the cart increases its quantity.
+#[hdk_extern]
+pub fn add_to_cart(item: CartItem) -> ExternResult<CartOutput> {
+    if item.quantity == 0 {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Quantity must be at least 1".into()
+        )));
+    }
+
+    let listing_hash = records::get_original_action_hash(item.listing_hash)?;
+
+    // Only listings that can be priced go in the cart
+    price_listing(listing_hash.clone(), item.variant_sku.clone(), item.quantity)?;
+
+    let mut items = get_my_cart()?.map(|(_, cart)| cart.items).unwrap_or_default();
+    add_cart_item(
+        &mut items,
+        CartItem {
+            listing_hash,
+            quantity: item.quantity,
+            variant_sku: item.variant_sku,
+        },
+    );
+
+    if items.len() > MAX_CART_ITEMS {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Cart cannot hold more than {} items",
+            MAX_CART_ITEMS
+        ))));
+    }
+
+    save_cart(items)
+}
+
+/// Remove a listing (or one variant of it) from the caller's cart
+#[hdk_extern]
+pub fn remove_from_cart(input: RemoveFromCartInput) -> ExternResult<CartOutput> {
+    let listing_hash = records::get_original_action_hash(input.listing_hash)?;
+
+    let mut items = get_my_cart()?.map(|(_, cart)| cart.items).unwrap_or_default();
+    if !remove_cart_item(&mut items, &listing_hash, input.variant_sku.as_deref()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Listing is not in the cart".into()
+        )));
+    }
+
+    save_cart(items)
+}
+
+/// Get the caller's cart
+#[hdk_extern]
+pub fn get_cart(_: ()) -> ExternResult<Option<CartOutput>> {
+    Ok(get_my_cart()?.map(|(cart_hash, cart)| CartOutput { cart_hash, cart }))
+}
+
+/// Check out the caller's cart
+///
+/// Every item is priced from its listing, the lines are recorded as one
+/// `Order`, and each seller's lines become one child transaction carrying
+/// the order hash and that seller's subtotal. The cart is emptied
+/// afterwards.
+#[hdk_extern]
+pub fn checkout_cart(_: ()) -> ExternResult<OrderOutput> {
+    let buyer = agent_info()?.agent_initial_pubkey;
+
+    let items = get_my_cart()?.map(|(_, cart)| cart.items).unwrap_or_default();
+    if items.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Cart is empty".into())));
+    }
+
+    let mut lines = Vec::new();
+    let mut order_currency: Option<String> = None;
+    for item in items {
+        let (line, currency) = price_listing(item.listing_hash, item.variant_sku, item.quantity)?;
+
+        // One order, one currency
+        match &order_currency {
+            Some(existing) if existing != &currency => {
+                return Err(wasm_error!(WasmErrorInner::Guest(
+                    "Cart mixes currencies; check out one currency at a time".into()
+                )));
+            }
+            Some(_) => {}
+            None => order_currency = Some(currency),
+        }
+
+        if line.seller == buyer {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Cannot buy your own listing".into()
+            )));
+        }
+
+        lines.push(line);
+    }
+
+    let total_price_cents = lines
+        .iter()
+        .try_fold(0u64, |total, line| total.checked_add(line.total_price_cents))
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Order total overflows".into()
+        )))?;
+
+    let currency = order_currency.unwrap_or_else(|| REFERENCE_CURRENCY.to_string());
+
+    let order = Order {
+        buyer: buyer.clone(),
+        lines,
+        total_price_cents,
+        currency: currency.clone(),
+        created_at: time::now()?,
+    };
+
+    let order_hash = create_entry(&EntryTypes::Order(order.clone()))?;
+
+    create_link(buyer, order_hash.clone(), LinkTypes::BuyerToOrders, ())?;
+
+    let mut transactions = Vec::new();
+    for lines in lines_by_seller(&order.lines) {
+        let output = open_transaction(lines, currency.clone(), Some(order_hash.clone()))?;
+
+        create_link(
+            order_hash.clone(),
+            output.transaction_hash.clone(),
+            LinkTypes::OrderToTransactions,
+            (),
+        )?;
+
+        transactions.push(output);
+    }
+
+    save_cart(Vec::new())?;
+
+    Ok(order_output(order_hash, order, transactions))
+}
+
+/// Get an order with its child transactions and aggregate status
+#[hdk_extern]
+pub fn get_order(order_hash: ActionHash) -> ExternResult<Option<OrderOutput>> {
+    let record = match get(order_hash.clone(), GetOptions::default())? {
+        Some(record) => record,
+        None => return Ok(None),
+    };
+
+    // Use shared utility for deserialization
+    let order: Order = error_handling::deserialize_entry(&record)?;
+
+    // Use shared utility for get_links
+    let links = link_queries::get_links_local(order_hash.clone(), LinkTypes::OrderToTransactions)?;
+
+    let mut transactions = Vec::new();
+    for link in links {
+        if let Some(action_hash) = link.target.into_action_hash() {
+            if let Some(output) = get_transaction(action_hash)? {
+                transactions.push(output);
+            }
+        }
+    }
+
+    Ok(Some(order_output(order_hash, order, transactions)))
+}
+
+/// Get the caller's orders
+#[hdk_extern]
+pub fn get_my_orders(_: ()) -> ExternResult<Vec<OrderOutput>> {
+    let agent = agent_info()?.agent_initial_pubkey;
+
+    // Use shared utility for get_links
+    let links = link_queries::get_links_local(agent, LinkTypes::BuyerToOrders)?;
+
+    let mut orders = Vec::new();
+    for link in links {
+        if let Some(action_hash) = link.target.into_action_hash() {
+            if let Some(output) = get_order(action_hash)? {
+                orders.push(output);
+            }
+        }
+    }
+
+    Ok(orders)
+}
+
+// ===== Helper Functions =====
+
+/// Price `quantity` units of a listing (or listing variant) from its
+/// current version
+///
+/// The seller, total price and currency are taken from the listing, never
+/// from the buyer.
+fn price_listing(
+    listing_hash: ActionHash,
+    variant_sku: Option<String>,
+    quantity: u32,
+) -> ExternResult<(OrderLine, String)> {
+    let listing_hash = records::get_original_action_hash(listing_hash)?;
+    let listing_record = records::get_latest_record(listing_hash.clone())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("Listing not found".into())
+    ))?;
+    let terms: ListingTerms = error_handling::deserialize_entry(&listing_record)?;
+
+    let (price_cents, _) = terms
+        .variant_terms(variant_sku.as_deref())
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?;
+
+    let total_price_cents = compute_total_price(price_cents, quantity).ok_or(
+        wasm_error!(WasmErrorInner::Guest("Total price overflows".into())),
+    )?;
+
+    if let Err(reason) = check_listing_terms(&terms, variant_sku.as_deref(), quantity, total_price_cents) {
+        return Err(wasm_error!(WasmErrorInner::Guest(reason)));
+    }
+
+    let line = OrderLine {
+        listing_hash,
+        listing_version_hash: listing_record.action_address().clone(),
+        seller: listing_record.action().author().clone(),
+        quantity,
+        total_price_cents,
+        variant_sku,
+    };
+
+    Ok((line, terms.currency))
+}
+
+/// Value an amount in the reference currency using the latest rates
+///
+/// Returns the snapshot used and the converted value, or (None, None) when
+/// the amount is already in the reference currency.
+fn value_in_reference_currency(
+    amount_minor: u64,
+    currency: &str,
+) -> ExternResult<(Option<ActionHash>, Option<u64>)> {
+    if currency == REFERENCE_CURRENCY {
+        return Ok((None, None));
+    }
+
+    let (snapshot_hash, snapshot) = latest_exchange_rates()?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("No exchange rates have been published".into())
+    ))?;
+
+    let max_age_micros =
+        transaction_properties()?.max_exchange_rate_age_hours as i64 * MICROS_PER_HOUR;
+    if time::now()?.as_micros() - snapshot.observed_at.as_micros() > max_age_micros {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Latest exchange rates are too old".into()
+        )));
+    }
+
+    let rate = snapshot.rate_for(currency).ok_or(wasm_error!(WasmErrorInner::Guest(
+        format!("No exchange rate for {}", currency)
+    )))?;
+
+    let value = money::Money::new(amount_minor, currency)
+        .convert(rate, REFERENCE_CURRENCY)
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Reference value overflows".into()
+        )))?;
+
+    Ok((Some(snapshot_hash), Some(value.amount_minor)))
+}
+
+/// The most recently observed exchange-rate snapshot
+fn latest_exchange_rates() -> ExternResult<Option<(ActionHash, ExchangeRateSnapshot)>> {
+    let anchor = Path::from(EXCHANGE_RATES_PATH).path_entry_hash()?;
+
+    // Use shared utility for get_links
+    let links = link_queries::get_links_local(anchor, LinkTypes::ExchangeRateSnapshots)?;
+
+    let mut latest: Option<(ActionHash, ExchangeRateSnapshot)> = None;
+    for link in links {
+        if let Some(action_hash) = link.target.into_action_hash() {
+            if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
+                // Use shared utility for deserialization
+                let snapshot: ExchangeRateSnapshot = error_handling::deserialize_entry(&record)?;
+
+                let newer = match &latest {
+                    Some((_, current)) => snapshot.observed_at > current.observed_at,
+                    None => true,
+                };
+                if newer {
+                    latest = Some((action_hash, snapshot));
+                }
+            }
+        }
+    }
+
+    Ok(latest)
+}
+
+/// Get the caller's cart (original action hash and latest version)
+fn get_my_cart() -> ExternResult<Option<(ActionHash, Cart)>> {
+    let agent = agent_info()?.agent_initial_pubkey;
+
+    // Use shared utility for get_links
+    let links = link_queries::get_links_local(agent, LinkTypes::BuyerToCart)?;
+
+    let cart_hash = match links.first().and_then(|link| link.target.clone().into_action_hash()) {
+        Some(hash) => hash,
+        None => return Ok(None),
+    };
+
+    match records::get_latest_record(cart_hash.clone())? {
+        Some(record) => {
+            // Use shared utility for deserialization
+            let cart: Cart = error_handling::deserialize_entry(&record)?;
+            Ok(Some((cart_hash, cart)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Write the caller's cart, creating it on first use
+///
+/// Updates are made against the latest version so validation can see
+/// the previous one.
+fn save_cart(items: Vec<CartItem>) -> ExternResult<CartOutput> {
+    let owner = agent_info()?.agent_initial_pubkey;
+
+    let cart = Cart {
+        owner: owner.clone(),
+        items,
+        updated_at: time::now()?,
+    };
+
+    let cart_hash = match get_my_cart()? {
+        Some((cart_hash, _)) => {
+            let latest = records::get_latest_record(cart_hash.clone())?.ok_or(wasm_error!(
+                WasmErrorInner::Guest("Cart not found".into())
+            ))?;
+            update_entry(latest.action_address().clone(), &cart)?;
+            cart_hash
+        }
+        None => {
+            let cart_hash = create_entry(&EntryTypes::Cart(cart.clone()))?;
+            create_link(owner, cart_hash.clone(), LinkTypes::BuyerToCart, ())?;
+            cart_hash
+        }
+    };
+
+    Ok(CartOutput { cart_hash, cart })
+}
+
+/// What a sweep does with a transaction at `now`, if anything
+pub fn sweep_action(transaction: &Transaction, now: Timestamp) -> Option<SweepAction> {
+    match transaction.deadline {
+        Some(deadline) if now > deadline => match transaction.status {
+            TransactionStatus::Pending => Some(SweepAction::Cancel),
+            TransactionStatus::Shipped => Some(SweepAction::PromptDispute),
+            TransactionStatus::Delivered => Some(SweepAction::Complete),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Add an item to a cart, merging it with an existing line for the same
+/// listing variant
+pub fn add_cart_item(items: &mut Vec<CartItem>, item: CartItem) {
+    match items.iter_mut().find(|existing| {
+        existing.listing_hash == item.listing_hash && existing.variant_sku == item.variant_sku
+    }) {
+        Some(existing) => existing.quantity = existing.quantity.saturating_add(item.quantity),
+        None => items.push(item),
+    }
+}
+
+/// Remove a listing variant from a cart, returns whether it was there
+pub fn remove_cart_item(
+    items: &mut Vec<CartItem>,
+    listing_hash: &ActionHash,
+    variant_sku: Option<&str>,
+) -> bool {
+    let before = items.len();
+    items.retain(|item| {
+        &item.listing_hash != listing_hash || item.variant_sku.as_deref() != variant_sku
+    });
+    items.len() != before
+}
+
+/// Aggregate the statuses of an order's child transactions
+///
+/// - Disputed if any child is disputed
+/// - Cancelled once every child is cancelled
+/// - Completed once every child is completed or cancelled
+/// - Pending while every child is pending
+/// - InProgress otherwise
+pub fn aggregate_order_status(statuses: &[TransactionStatus]) -> OrderStatus {
+    use TransactionStatus::*;
+
+    if statuses.iter().any(|status| status == &Disputed) {
+        OrderStatus::Disputed
+    } else if statuses.is_empty() || statuses.iter().all(|status| status == &Pending) {
+        OrderStatus::Pending
+    } else if statuses.iter().all(|status| status == &Cancelled) {
+        OrderStatus::Cancelled
+    } else if statuses
+        .iter()
+        .all(|status| status == &Completed || status == &Cancelled)
+    {
+        OrderStatus::Completed
+    } else {
+        OrderStatus::InProgress
+    }
+}
+
+/// An order's lines grouped by seller, in order of first appearance
+pub fn lines_by_seller(lines: &[OrderLine]) -> Vec<Vec<OrderLine>> {
+    let mut groups: Vec<Vec<OrderLine>> = Vec::new();
+
+    for line in lines {
+        match groups.iter_mut().find(|group| group[0].seller == line.seller) {
+            Some(group) => group.push(line.clone()),
+            None => groups.push(vec![line.clone()]),
+        }
+    }
+
+    groups
+}
+
+/// Per-seller subtotals of an order, in order of first appearance
+pub fn seller_subtotals(lines: &[OrderLine]) -> Vec<SellerSubtotal> {
+    let mut subtotals: Vec<SellerSubtotal> = Vec::new();
+
+    for line in lines {
+        match subtotals.iter_mut().find(|subtotal| subtotal.seller == line.seller) {
+            Some(subtotal) => {
+                subtotal.line_count += 1;
+                subtotal.subtotal_cents += line.total_price_cents;
+            }
+            None => subtotals.push(SellerSubtotal {
+                seller: line.seller.clone(),
+                line_count: 1,
+                subtotal_cents: line.total_price_cents,
+            }),
+        }
+    }
+
+    subtotals
+}
+
+fn order_output(
+    order_hash: ActionHash,
+    order: Order,
+    transactions: Vec<TransactionOutput>,
+) -> OrderOutput {
+    let statuses: Vec<TransactionStatus> = transactions
+        .iter()
+        .map(|output| output.transaction.status.clone())
+        .collect();
+
+    OrderOutput {
+        order_hash,
+        status: aggregate_order_status(&statuses),
+        sellers: seller_subtotals(&order.lines),
+        order,
+        transactions,
+    }
+}
+
+/// Update transaction status with validation
+///
+/// The state machine and caller roles are enforced by `check_transition`,
+/// the same check integrity validation runs on the update.
+fn update_transaction_status(
     transaction_hash: ActionHash,
     new_status: TransactionStatus,
-    tracking_info: Option<String>,
-    allowed_from_states: Vec<TransactionStatus>,
+) -> ExternResult<TransactionOutput> {
+    update_transaction_with(transaction_hash, new_status, |_| {})
+}
+
+/// Update transaction status, applying `apply` to the new version
+fn update_transaction_with(
+    transaction_hash: ActionHash,
+    new_status: TransactionStatus,
+    apply: impl FnOnce(&mut Transaction),
 ) -> ExternResult<TransactionOutput> {
     // Get current transaction
-    let current = get_transaction(transaction_hash.clone())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Transaction not found".into()
-        )))?;
+    let (latest_hash, current) = get_latest_transaction(transaction_hash)?;
 
     // Verify state transition is valid
-    if !allowed_from_states.contains(&current.transaction.status) {
-        return Err(wasm_error!(WasmErrorInner::Guest(format!(
-            "Invalid state transition from {:?} to {:?}",
-            current.transaction.status, new_status
-        ))));
-    }
+    verify_transition(&current, &new_status)?;
 
     // Create updated transaction
-    let mut updated_transaction = current.transaction.clone();
-    updated_transaction.status = new_status;
+    let mut updated_transaction = current.clone();
     updated_transaction.updated_at = time::now()?;
+    updated_transaction.deadline = state_deadline(
+        &new_status,
+        updated_transaction.updated_at,
+        &transaction_properties()?,
+    );
+    updated_transaction.status = new_status;
+    apply(&mut updated_transaction);
 
-    if let Some(info) = tracking_info {
-        updated_transaction.tracking_info = Some(info);
-    }
+    check_return_request(&current, &updated_transaction)
+        .map_err(|reason| wasm_error!(WasmErrorInner::Guest(reason)))?;
 
-    // Update entry
-    let new_action_hash = update_entry(transaction_hash, &updated_transaction)?;
+    // Update entry (against the previous version, so validation can see it)
+    let new_action_hash = update_entry(latest_hash, &updated_transaction)?;
 
     Ok(TransactionOutput {
         transaction_hash: new_action_hash,
@@ -392,14 +1488,156 @@ fn update_transaction_status(
     })
 }
 
+/// Release whatever is still held in escrow to the seller
+///
+/// `completed_hash` is the Completed version that authorizes the release.
+fn release_remaining_escrow(
+    transaction_hash: ActionHash,
+    completed_hash: &ActionHash,
+    transaction: &Transaction,
+) -> ExternResult<()> {
+    let mut ledger = escrow::load_funded_ledger(transaction_hash.clone())?;
+    let held_cents = ledger.held_cents();
+
+    if held_cents > 0 {
+        escrow::record_movement(
+            &mut ledger,
+            transaction_hash,
+            EscrowKind::Release,
+            transaction.seller.clone(),
+            held_cents,
+            escrow::Authorization::Version(completed_hash.clone()),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Get the shipment attached to a transaction (original action hash)
+fn get_shipment_hash(transaction_hash: ActionHash) -> ExternResult<Option<ActionHash>> {
+    // Use shared utility for get_links
+    let links = link_queries::get_links_local(transaction_hash, LinkTypes::TransactionToShipment)?;
+
+    Ok(links
+        .into_iter()
+        .next()
+        .and_then(|link| link.target.into_action_hash()))
+}
+
+/// Sign a transaction's receipt terms as the caller and store the signature
+fn store_receipt_signature(
+    transaction_hash: ActionHash,
+    transaction: &Transaction,
+) -> ExternResult<ReceiptSignatureOutput> {
+    let signer = agent_info()?.agent_initial_pubkey;
+    let terms = ReceiptTerms::new(transaction_hash.clone(), transaction);
+
+    let receipt_signature = ReceiptSignature {
+        signature: sign(signer.clone(), terms.clone())?,
+        terms,
+        signer,
+        signed_at: time::now()?,
+    };
+
+    let signature_hash = create_entry(&EntryTypes::ReceiptSignature(receipt_signature.clone()))?;
+
+    create_link(
+        transaction_hash,
+        signature_hash.clone(),
+        LinkTypes::TransactionToReceiptSignatures,
+        (),
+    )?;
+
+    Ok(ReceiptSignatureOutput {
+        signature_hash,
+        receipt_signature,
+    })
+}
+
+/// Get the receipt signatures stored for a transaction (original action hash)
+fn get_receipt_signatures(
+    transaction_hash: ActionHash,
+) -> ExternResult<Vec<(ActionHash, ReceiptSignature)>> {
+    // Use shared utility for get_links
+    let links = link_queries::get_links_local(
+        transaction_hash,
+        LinkTypes::TransactionToReceiptSignatures,
+    )?;
+
+    let mut signatures = Vec::new();
+    for link in links {
+        if let Some(action_hash) = link.target.into_action_hash() {
+            if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
+                // Use shared utility for deserialization
+                signatures.push((action_hash, error_handling::deserialize_entry(&record)?));
+            }
+        }
+    }
+
+    Ok(signatures)
+}
+
+/// The other party's signature over `terms`, if they have signed
+pub fn counterpart_signature<'a>(
+    signatures: &'a [(ActionHash, ReceiptSignature)],
+    terms: &ReceiptTerms,
+    caller: &AgentPubKey,
+) -> Option<&'a ReceiptSignature> {
+    signatures
+        .iter()
+        .map(|(_, signature)| signature)
+        .find(|signature| {
+            &signature.signer != caller
+                && (signature.signer == terms.buyer || signature.signer == terms.seller)
+                && &signature.terms == terms
+        })
+}
+
+/// Order tracking events by when they happened
+///
+/// The sort is stable, so events reported for the same moment keep the
+/// order the seller recorded them in.
+pub fn sort_tracking_events(events: &mut [TrackingEvent]) {
+    events.sort_by_key(|event| event.occurred_at);
+}
+
+/// Get the latest version of a transaction and its action hash
+fn get_latest_transaction(transaction_hash: ActionHash) -> ExternResult<(ActionHash, Transaction)> {
+    let record = records::get_latest_record(transaction_hash)?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Transaction not found".into()
+        )))?;
+
+    // Use shared utility for deserialization
+    let transaction: Transaction = error_handling::deserialize_entry(&record)?;
+
+    Ok((record.action_address().clone(), transaction))
+}
+
+/// Verify the caller may move a transaction to `new_status`
+fn verify_transition(transaction: &Transaction, new_status: &TransactionStatus) -> ExternResult<()> {
+    let caller = agent_info()?.agent_initial_pubkey;
+
+    check_transition(transaction, new_status, &caller)
+        .map_err(|reason| wasm_error!(WasmErrorInner::Guest(reason)))
+}
+
 // ===== Input/Output Types =====
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CreateTransactionInput {
-    pub seller: AgentPubKey,
     pub listing_hash: ActionHash,
     pub quantity: u32,
-    pub total_price_cents: u64,
+    /// Required for listings with variants
+    #[serde(default)]
+    pub variant_sku: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoveFromCartInput {
+    pub listing_hash: ActionHash,
+    #[serde(default)]
+    pub variant_sku: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -416,7 +1654,29 @@ pub struct TransactionsResponse {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MarkShippedInput {
     pub transaction_hash: ActionHash,
-    pub tracking_info: Option<String>,
+    pub carrier: String,
+    pub tracking_number: String,
+    pub shipping_method: ShippingMethod,
+    pub estimated_delivery_start: Option<Timestamp>,
+    pub estimated_delivery_end: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AddTrackingEventInput {
+    pub transaction_hash: ActionHash,
+    pub event: TrackingEvent,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ShipmentOutput {
+    pub shipment_hash: ActionHash,
+    pub shipment: Shipment,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReturnShippedInput {
+    pub transaction_hash: ActionHash,
+    pub tracking_number: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -425,11 +1685,156 @@ pub struct DisputeTransactionInput {
     pub reason: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RequestReturnInput {
+    pub transaction_hash: ActionHash,
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RefundTransactionInput {
+    pub transaction_hash: ActionHash,
+    pub refund_cents: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReserveStockInput {
+    pub listing_hash: ActionHash,
+    pub transaction_hash: ActionHash,
+    pub quantity: u32,
+    #[serde(default)]
+    pub variant_sku: Option<String>,
+}
+
+/// Mirror of the listings zome's `ReservationOutput` (the hash only)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReservedStock {
+    pub reservation_hash: ActionHash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EscrowOutput {
+    pub transaction_hash: ActionHash,
+    pub held_cents: u64,
+    pub released_cents: u64,
+    pub refunded_cents: u64,
+    pub entries: Vec<EscrowEntry>,
+}
+
+/// Final dispute outcome, as reported by the arbitration zome
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DisputeSettlementInfo {
+    pub transaction_hash: ActionHash,
+    pub result_hash: ActionHash,
+    pub compensation_cents: u64,
+}
+
+/// Timeout handling for a stalled transaction
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum SweepAction {
+    Cancel,
+    Complete,
+    PromptDispute,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SweepOutput {
+    pub cancelled: Vec<ActionHash>,
+    pub completed: Vec<ActionHash>,
+    pub awaiting_countersignature: Vec<ActionHash>,
+    pub dispute_prompts: Vec<ActionHash>,
+    /// Transactions the sweep could not act on
+    pub failed: Vec<SweepFailure>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SweepFailure {
+    pub transaction_hash: ActionHash,
+    pub error: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum TransactionSignal {
+    /// A shipment is overdue: confirm delivery or open a dispute
+    DisputePrompt {
+        transaction_hash: ActionHash,
+        deadline: Option<Timestamp>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StatementInput {
+    pub period_start: Timestamp,
+    pub period_end: Timestamp,
+    pub format: statement::StatementFormat,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StatementOutput {
+    pub statement: statement::Statement,
+    /// The statement rendered in the requested format
+    pub document: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReceiptSignatureOutput {
+    pub signature_hash: ActionHash,
+    pub receipt_signature: ReceiptSignature,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublishExchangeRatesInput {
+    pub rates: Vec<ExchangeRate>,
+    pub source: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExchangeRatesOutput {
+    pub snapshot_hash: ActionHash,
+    pub snapshot: ExchangeRateSnapshot,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CartOutput {
+    pub cart_hash: ActionHash,
+    pub cart: Cart,
+}
+
+/// Cart-level status, aggregated from the child transactions
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderStatus {
+    Pending,
+    InProgress,
+    Completed,
+    Cancelled,
+    Disputed,
+}
+
+/// One seller's share of an order
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SellerSubtotal {
+    pub seller: AgentPubKey,
+    pub line_count: u32,
+    pub subtotal_cents: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OrderOutput {
+    pub order_hash: ActionHash,
+    pub order: Order,
+    pub status: OrderStatus,
+    pub sellers: Vec<SellerSubtotal>,
+    pub transactions: Vec<TransactionOutput>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UpdateMatlInput {
     pub agent: AgentPubKey,
     pub successful: bool,
     pub transaction_value_cents: u64,
+    pub receipt_hash: ActionHash,
 }
 
 
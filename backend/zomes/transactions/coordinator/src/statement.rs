@@ -0,0 +1,539 @@
+//! Period statements for bookkeeping
+//!
+//! A statement covers the caller's transactions created within a period,
+//! as buyer or seller. Each line item carries the transaction's original
+//! `ActionHash` and the escrow movements recorded against it, so the
+//! statement can be reconciled against the DHT.
+//!
+//! Escrow movements are split three ways:
+//! - released: paid out to the seller
+//! - refunded: returned to the buyer by cancellation or return
+//! - compensation: returned to the buyer by an arbitration result
+//!
+//! Renderings:
+//! - CSV: one row per line item, amounts in minor units
+//! - JSON: canonical (sorted keys, no whitespace), hashes as strings
+
+use hdk::prelude::*;
+use serde_json::{json, Value};
+use transactions_integrity::*;
+
+/// Which side of a transaction the statement's agent was on
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementRole {
+    Buyer,
+    Seller,
+}
+
+impl StatementRole {
+    fn as_str(&self) -> &'static str {
+        match self {
+            StatementRole::Buyer => "buyer",
+            StatementRole::Seller => "seller",
+        }
+    }
+}
+
+/// Output format of a rendered statement
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementFormat {
+    Csv,
+    Json,
+}
+
+/// One transaction on a statement
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StatementLine {
+    /// Transaction (original action)
+    pub transaction_hash: ActionHash,
+    pub role: StatementRole,
+    pub counterparty: AgentPubKey,
+    pub listing_hash: ActionHash,
+    pub quantity: u32,
+    pub currency: String,
+    pub total_price_cents: u64,
+    pub reference_value_cents: Option<u64>,
+    pub status: TransactionStatus,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+    pub released_cents: u64,
+    pub refunded_cents: u64,
+    pub compensation_cents: u64,
+    pub order_hash: Option<ActionHash>,
+    pub receipt_hash: Option<ActionHash>,
+}
+
+/// Line count and value per status and currency
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StatusTotal {
+    pub status: TransactionStatus,
+    pub currency: String,
+    pub count: u32,
+    pub total_price_cents: u64,
+}
+
+/// Money moved per role and currency
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CurrencyTotal {
+    pub role: StatementRole,
+    pub currency: String,
+    pub released_cents: u64,
+    pub refunded_cents: u64,
+    pub compensation_cents: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Statement {
+    pub agent: AgentPubKey,
+    /// Inclusive
+    pub period_start: Timestamp,
+    /// Exclusive
+    pub period_end: Timestamp,
+    pub lines: Vec<StatementLine>,
+    pub totals_by_status: Vec<StatusTotal>,
+    pub totals_by_currency: Vec<CurrencyTotal>,
+    /// Transactions (original actions) that went to dispute
+    pub disputes: Vec<ActionHash>,
+}
+
+/// A statement line for `transaction`, from `agent`'s side
+///
+/// None if the agent is not a party.
+pub fn statement_line(
+    agent: &AgentPubKey,
+    transaction_hash: ActionHash,
+    transaction: &Transaction,
+    escrow_entries: &[EscrowEntry],
+) -> Option<StatementLine> {
+    let (role, counterparty) = if agent == &transaction.seller {
+        (StatementRole::Seller, transaction.buyer.clone())
+    } else if agent == &transaction.buyer {
+        (StatementRole::Buyer, transaction.seller.clone())
+    } else {
+        return None;
+    };
+
+    let mut line = StatementLine {
+        transaction_hash,
+        role,
+        counterparty,
+        listing_hash: transaction.listing_hash.clone(),
+        quantity: transaction.quantity,
+        currency: transaction.currency.clone(),
+        total_price_cents: transaction.total_price_cents,
+        reference_value_cents: transaction.reference_value_cents,
+        status: transaction.status.clone(),
+        created_at: transaction.created_at,
+        updated_at: transaction.updated_at,
+        released_cents: 0,
+        refunded_cents: 0,
+        compensation_cents: 0,
+        order_hash: transaction.order_hash.clone(),
+        receipt_hash: transaction.receipt_hash.clone(),
+    };
+
+    for entry in escrow_entries {
+        match (&entry.kind, &entry.arbitration_result_hash) {
+            (EscrowKind::Hold, _) => {}
+            (EscrowKind::Release, _) => line.released_cents += entry.amount_cents,
+            (EscrowKind::Refund, None) => line.refunded_cents += entry.amount_cents,
+            (EscrowKind::Refund, Some(_)) => line.compensation_cents += entry.amount_cents,
+        }
+    }
+
+    Some(line)
+}
+
+/// Build a statement from the lines created within the period
+///
+/// Lines are ordered by creation time, then transaction hash, so the
+/// same period always renders the same document.
+pub fn build_statement(
+    agent: AgentPubKey,
+    period_start: Timestamp,
+    period_end: Timestamp,
+    lines: Vec<StatementLine>,
+) -> Statement {
+    let mut lines: Vec<StatementLine> = lines
+        .into_iter()
+        .filter(|line| line.created_at >= period_start && line.created_at < period_end)
+        .collect();
+    lines.sort_by(|a, b| {
+        a.created_at
+            .cmp(&b.created_at)
+            .then_with(|| a.transaction_hash.get_raw_39().cmp(b.transaction_hash.get_raw_39()))
+    });
+
+    let mut totals_by_status: Vec<StatusTotal> = Vec::new();
+    let mut totals_by_currency: Vec<CurrencyTotal> = Vec::new();
+
+    for line in &lines {
+        match totals_by_status
+            .iter_mut()
+            .find(|total| total.status == line.status && total.currency == line.currency)
+        {
+            Some(total) => {
+                total.count += 1;
+                total.total_price_cents += line.total_price_cents;
+            }
+            None => totals_by_status.push(StatusTotal {
+                status: line.status.clone(),
+                currency: line.currency.clone(),
+                count: 1,
+                total_price_cents: line.total_price_cents,
+            }),
+        }
+
+        match totals_by_currency
+            .iter_mut()
+            .find(|total| total.role == line.role && total.currency == line.currency)
+        {
+            Some(total) => {
+                total.released_cents += line.released_cents;
+                total.refunded_cents += line.refunded_cents;
+                total.compensation_cents += line.compensation_cents;
+            }
+            None => totals_by_currency.push(CurrencyTotal {
+                role: line.role.clone(),
+                currency: line.currency.clone(),
+                released_cents: line.released_cents,
+                refunded_cents: line.refunded_cents,
+                compensation_cents: line.compensation_cents,
+            }),
+        }
+    }
+
+    // Settled disputes keep the Disputed status, and compensation only
+    // comes out of a dispute
+    let disputes = lines
+        .iter()
+        .filter(|line| line.status == TransactionStatus::Disputed || line.compensation_cents > 0)
+        .map(|line| line.transaction_hash.clone())
+        .collect();
+
+    Statement {
+        agent,
+        period_start,
+        period_end,
+        lines,
+        totals_by_status,
+        totals_by_currency,
+        disputes,
+    }
+}
+
+/// CSV columns, in order
+pub const CSV_HEADER: &str = "transaction_hash,role,counterparty,listing_hash,quantity,currency,\
+total_price_cents,reference_value_cents,status,created_at_micros,updated_at_micros,\
+released_cents,refunded_cents,compensation_cents,order_hash,receipt_hash";
+
+/// Render a statement as CSV, one row per line item
+pub fn render_csv(statement: &Statement) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+
+    for line in &statement.lines {
+        let fields = [
+            line.transaction_hash.to_string(),
+            line.role.as_str().to_string(),
+            line.counterparty.to_string(),
+            line.listing_hash.to_string(),
+            line.quantity.to_string(),
+            line.currency.clone(),
+            line.total_price_cents.to_string(),
+            optional(&line.reference_value_cents),
+            status_name(&line.status),
+            line.created_at.as_micros().to_string(),
+            line.updated_at.as_micros().to_string(),
+            line.released_cents.to_string(),
+            line.refunded_cents.to_string(),
+            line.compensation_cents.to_string(),
+            optional(&line.order_hash),
+            optional(&line.receipt_hash),
+        ];
+
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Render a statement as canonical JSON
+///
+/// Object keys are sorted and there is no insignificant whitespace, so
+/// equal statements render byte-for-byte equal.
+pub fn render_json(statement: &Statement) -> String {
+    let lines: Vec<Value> = statement
+        .lines
+        .iter()
+        .map(|line| {
+            json!({
+                "transaction_hash": line.transaction_hash.to_string(),
+                "role": line.role.as_str(),
+                "counterparty": line.counterparty.to_string(),
+                "listing_hash": line.listing_hash.to_string(),
+                "quantity": line.quantity,
+                "currency": line.currency,
+                "total_price_cents": line.total_price_cents,
+                "reference_value_cents": line.reference_value_cents,
+                "status": status_name(&line.status),
+                "created_at_micros": line.created_at.as_micros(),
+                "updated_at_micros": line.updated_at.as_micros(),
+                "released_cents": line.released_cents,
+                "refunded_cents": line.refunded_cents,
+                "compensation_cents": line.compensation_cents,
+                "order_hash": line.order_hash.as_ref().map(|hash| hash.to_string()),
+                "receipt_hash": line.receipt_hash.as_ref().map(|hash| hash.to_string()),
+            })
+        })
+        .collect();
+
+    let totals_by_status: Vec<Value> = statement
+        .totals_by_status
+        .iter()
+        .map(|total| {
+            json!({
+                "status": status_name(&total.status),
+                "currency": total.currency,
+                "count": total.count,
+                "total_price_cents": total.total_price_cents,
+            })
+        })
+        .collect();
+
+    let totals_by_currency: Vec<Value> = statement
+        .totals_by_currency
+        .iter()
+        .map(|total| {
+            json!({
+                "role": total.role.as_str(),
+                "currency": total.currency,
+                "released_cents": total.released_cents,
+                "refunded_cents": total.refunded_cents,
+                "compensation_cents": total.compensation_cents,
+            })
+        })
+        .collect();
+
+    let disputes: Vec<String> = statement.disputes.iter().map(|hash| hash.to_string()).collect();
+
+    // serde_json's default map is ordered by key
+    json!({
+        "agent": statement.agent.to_string(),
+        "period_start_micros": statement.period_start.as_micros(),
+        "period_end_micros": statement.period_end.as_micros(),
+        "lines": lines,
+        "totals_by_status": totals_by_status,
+        "totals_by_currency": totals_by_currency,
+        "disputes": disputes,
+    })
+    .to_string()
+}
+
+/// Status as stored on the DHT (lowercase)
+fn status_name(status: &TransactionStatus) -> String {
+    format!("{:?}", status).to_lowercase()
+}
+
+fn optional<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(|value| value.to_string()).unwrap_or_default()
+}
+
+/// Quote a CSV field if it contains a separator, quote or newline
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buyer() -> AgentPubKey {
+        AgentPubKey::from_raw_36(vec![1u8; 36])
+    }
+
+    fn seller() -> AgentPubKey {
+        AgentPubKey::from_raw_36(vec![2u8; 36])
+    }
+
+    fn transaction(status: TransactionStatus, created_at: i64) -> Transaction {
+        Transaction {
+            buyer: buyer(),
+            seller: seller(),
+            listing_hash: ActionHash::from_raw_36(vec![3u8; 36]),
+            listing_version_hash: ActionHash::from_raw_36(vec![3u8; 36]),
+            quantity: 1,
+            total_price_cents: 1000,
+            currency: "USD".to_string(),
+            exchange_rate_hash: None,
+            reference_value_cents: None,
+            status,
+            created_at: Timestamp::from_micros(created_at),
+            updated_at: Timestamp::from_micros(created_at),
+            shipment_hash: None,
+            epistemic: EpistemicClassification {
+                empirical: EmpiricalLevel::E1Testimonial,
+                normative: NormativeLevel::N1Communal,
+                materiality: MaterialityLevel::M1Temporal,
+            },
+            order_hash: None,
+            deadline: None,
+            return_request: None,
+            receipt_hash: None,
+            variant_sku: None,
+            lines: vec![],
+        }
+    }
+
+    fn escrow(kind: EscrowKind, amount_cents: u64, arbitrated: bool) -> EscrowEntry {
+        EscrowEntry {
+            transaction_hash: ActionHash::from_raw_36(vec![4u8; 36]),
+            kind,
+            amount_cents,
+            party: buyer(),
+            settlement_reference: "local:test".to_string(),
+            arbitration_result_hash: arbitrated.then(|| ActionHash::from_raw_36(vec![5u8; 36])),
+            transaction_version_hash: None,
+            recorded_at: Timestamp::from_micros(1000000),
+        }
+    }
+
+    fn line(hash: u8, status: TransactionStatus, created_at: i64, entries: &[EscrowEntry]) -> StatementLine {
+        statement_line(
+            &seller(),
+            ActionHash::from_raw_36(vec![hash; 36]),
+            &transaction(status, created_at),
+            entries,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_line_splits_escrow_movements() {
+        let entries = [
+            escrow(EscrowKind::Hold, 1000, false),
+            escrow(EscrowKind::Refund, 300, true),
+            escrow(EscrowKind::Release, 700, true),
+        ];
+
+        let line = line(10, TransactionStatus::Disputed, 1000, &entries);
+
+        assert_eq!(line.role, StatementRole::Seller);
+        assert_eq!(line.counterparty, buyer());
+        assert_eq!((line.released_cents, line.refunded_cents, line.compensation_cents), (700, 0, 300));
+    }
+
+    #[test]
+    fn test_line_only_for_parties() {
+        let outsider = AgentPubKey::from_raw_36(vec![9u8; 36]);
+        let hash = ActionHash::from_raw_36(vec![10u8; 36]);
+        let transaction = transaction(TransactionStatus::Pending, 1000);
+
+        assert!(statement_line(&outsider, hash.clone(), &transaction, &[]).is_none());
+        assert_eq!(
+            statement_line(&buyer(), hash, &transaction, &[]).unwrap().role,
+            StatementRole::Buyer
+        );
+    }
+
+    #[test]
+    fn test_statement_filters_period_and_totals() {
+        let lines = vec![
+            line(10, TransactionStatus::Completed, 2000, &[escrow(EscrowKind::Release, 1000, false)]),
+            line(11, TransactionStatus::Completed, 1000, &[escrow(EscrowKind::Release, 1000, false)]),
+            line(12, TransactionStatus::Cancelled, 1500, &[escrow(EscrowKind::Refund, 1000, false)]),
+            line(13, TransactionStatus::Disputed, 1200, &[escrow(EscrowKind::Refund, 400, true)]),
+            line(14, TransactionStatus::Completed, 5000, &[]), // After the period
+        ];
+
+        let statement = build_statement(
+            seller(),
+            Timestamp::from_micros(1000),
+            Timestamp::from_micros(5000),
+            lines,
+        );
+
+        // Oldest first, period end is exclusive
+        let created: Vec<i64> = statement.lines.iter().map(|line| line.created_at.as_micros()).collect();
+        assert_eq!(created, vec![1000, 1200, 1500, 2000]);
+
+        let completed = statement
+            .totals_by_status
+            .iter()
+            .find(|total| total.status == TransactionStatus::Completed)
+            .unwrap();
+        assert_eq!((completed.count, completed.total_price_cents), (2, 2000));
+
+        assert_eq!(
+            statement.totals_by_currency,
+            vec![CurrencyTotal {
+                role: StatementRole::Seller,
+                currency: "USD".to_string(),
+                released_cents: 2000,
+                refunded_cents: 1000,
+                compensation_cents: 400,
+            }]
+        );
+
+        assert_eq!(statement.disputes, vec![ActionHash::from_raw_36(vec![13u8; 36])]);
+    }
+
+    #[test]
+    fn test_csv_has_a_row_per_line() {
+        let statement = build_statement(
+            seller(),
+            Timestamp::from_micros(0),
+            Timestamp::from_micros(10_000),
+            vec![line(10, TransactionStatus::Completed, 1000, &[])],
+        );
+
+        let csv = render_csv(&statement);
+        let rows: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], CSV_HEADER);
+        assert_eq!(rows[1].split(',').count(), CSV_HEADER.split(',').count());
+        assert!(rows[1].starts_with(&ActionHash::from_raw_36(vec![10u8; 36]).to_string()));
+        assert!(rows[1].contains(",completed,"));
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("USD"), "USD");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_json_is_canonical() {
+        let lines = vec![
+            line(10, TransactionStatus::Completed, 1000, &[]),
+            line(11, TransactionStatus::Cancelled, 1000, &[]),
+        ];
+        let mut reversed = lines.clone();
+        reversed.reverse();
+
+        let period = (Timestamp::from_micros(0), Timestamp::from_micros(10_000));
+        let json = render_json(&build_statement(seller(), period.0, period.1, lines));
+        let again = render_json(&build_statement(seller(), period.0, period.1, reversed));
+
+        // Same statement, same bytes, whatever order the lines were found in
+        assert_eq!(json, again);
+        assert!(json.starts_with("{\"agent\":"));
+        assert!(!json.contains(' '));
+
+        let parsed: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            parsed["lines"][0]["transaction_hash"],
+            ActionHash::from_raw_36(vec![10u8; 36]).to_string()
+        );
+    }
+}
@@ -1,7 +1,8 @@
+use super::*;
+
 #[cfg(test)]
-mod tests {
+mod transactions_tests {
     use super::*;
-    use transactions_integrity::*;
 
     // Helper functions for tests
     fn mock_transaction() -> Transaction {
@@ -9,17 +10,38 @@ mod tests {
             buyer: AgentPubKey::from_raw_36(vec![1u8; 36]),
             seller: AgentPubKey::from_raw_36(vec![2u8; 36]),
             listing_hash: ActionHash::from_raw_36(vec![3u8; 36]),
+            listing_version_hash: ActionHash::from_raw_36(vec![3u8; 36]),
             quantity: 1,
             total_price_cents: 1999,
+            currency: "USD".to_string(),
+            exchange_rate_hash: None,
+            reference_value_cents: None,
             status: TransactionStatus::Pending,
             created_at: Timestamp::from_micros(1000000),
             updated_at: Timestamp::from_micros(1000000),
-            tracking_info: None,
+            shipment_hash: None,
             epistemic: EpistemicClassification {
                 empirical: EmpiricalLevel::E1Testimonial,
                 normative: NormativeLevel::N1Communal,
                 materiality: MaterialityLevel::M1Temporal,
             },
+            order_hash: None,
+            deadline: None,
+            return_request: None,
+            receipt_hash: None,
+            variant_sku: None,
+            lines: vec![],
+        }
+    }
+
+    fn mock_line(seller: u8, total_price_cents: u64) -> OrderLine {
+        OrderLine {
+            listing_hash: ActionHash::from_raw_36(vec![seller + 10; 36]),
+            listing_version_hash: ActionHash::from_raw_36(vec![seller + 10; 36]),
+            seller: AgentPubKey::from_raw_36(vec![seller; 36]),
+            quantity: 1,
+            total_price_cents,
+            variant_sku: None,
         }
     }
 
@@ -28,7 +50,7 @@ mod tests {
     #[test]
     fn test_transaction_lifecycle_states() {
         // Verify all states exist
-        let states = vec![
+        let states = [
             TransactionStatus::Pending,
             TransactionStatus::Confirmed,
             TransactionStatus::Shipped,
@@ -133,6 +155,172 @@ mod tests {
         assert_eq!(transaction.status, TransactionStatus::Pending);
     }
 
+    #[test]
+    fn test_check_transition_follows_lifecycle() {
+        let mut transaction = mock_transaction();
+        let buyer = transaction.buyer.clone();
+        let seller = transaction.seller.clone();
+
+        let steps = [
+            (TransactionStatus::Confirmed, &seller),
+            (TransactionStatus::Shipped, &seller),
+            (TransactionStatus::Delivered, &buyer),
+            (TransactionStatus::Completed, &buyer),
+        ];
+
+        for (status, author) in steps {
+            assert!(check_transition(&transaction, &status, author).is_ok());
+            transaction.status = status;
+        }
+    }
+
+    #[test]
+    fn test_check_transition_rejects_skipped_states() {
+        let transaction = mock_transaction();
+
+        // Pending cannot jump to Shipped or Completed
+        assert!(check_transition(&transaction, &TransactionStatus::Shipped, &transaction.seller).is_err());
+        assert!(check_transition(&transaction, &TransactionStatus::Completed, &transaction.buyer).is_err());
+
+        // Terminal states cannot move
+        let mut completed = mock_transaction();
+        completed.status = TransactionStatus::Completed;
+        assert!(check_transition(&completed, &TransactionStatus::Disputed, &completed.buyer).is_err());
+        assert!(check_transition(&completed, &TransactionStatus::Cancelled, &completed.seller).is_err());
+    }
+
+    #[test]
+    fn test_check_transition_enforces_roles() {
+        let mut transaction = mock_transaction();
+        let outsider = AgentPubKey::from_raw_36(vec![9u8; 36]);
+
+        // Only the seller confirms
+        assert!(check_transition(&transaction, &TransactionStatus::Confirmed, &transaction.buyer).is_err());
+        assert!(check_transition(&transaction, &TransactionStatus::Cancelled, &outsider).is_err());
+
+        // Only the buyer confirms delivery
+        transaction.status = TransactionStatus::Shipped;
+        assert!(check_transition(&transaction, &TransactionStatus::Delivered, &transaction.seller).is_err());
+        assert!(check_transition(&transaction, &TransactionStatus::Delivered, &transaction.buyer).is_ok());
+
+        // Either party can dispute
+        assert!(check_transition(&transaction, &TransactionStatus::Disputed, &transaction.seller).is_ok());
+        assert!(check_transition(&transaction, &TransactionStatus::Disputed, &outsider).is_err());
+    }
+
+    #[test]
+    fn test_check_transition_return_path() {
+        let mut transaction = mock_transaction();
+        transaction.status = TransactionStatus::Delivered;
+        let buyer = transaction.buyer.clone();
+        let seller = transaction.seller.clone();
+
+        // Seller cannot open a return
+        assert!(check_transition(&transaction, &TransactionStatus::ReturnRequested, &seller).is_err());
+
+        let steps = [
+            (TransactionStatus::ReturnRequested, &buyer),
+            (TransactionStatus::ReturnApproved, &seller),
+            (TransactionStatus::ReturnShipped, &buyer),
+            (TransactionStatus::Refunded, &seller),
+        ];
+
+        for (status, author) in steps {
+            assert!(check_transition(&transaction, &status, author).is_ok());
+            transaction.status = status;
+        }
+
+        // Refunded is terminal
+        assert!(check_transition(&transaction, &TransactionStatus::Disputed, &buyer).is_err());
+    }
+
+    #[test]
+    fn test_rejected_return_can_be_disputed() {
+        let mut transaction = mock_transaction();
+        transaction.status = TransactionStatus::ReturnRequested;
+
+        assert!(check_transition(&transaction, &TransactionStatus::Disputed, &transaction.seller).is_ok());
+        // Partial refund without a return
+        assert!(check_transition(&transaction, &TransactionStatus::Refunded, &transaction.seller).is_ok());
+        assert!(check_transition(&transaction, &TransactionStatus::Refunded, &transaction.buyer).is_err());
+    }
+
+    #[test]
+    fn test_check_return_request() {
+        let mut delivered = mock_transaction();
+        delivered.status = TransactionStatus::Delivered;
+
+        let mut requested = delivered.clone();
+        requested.status = TransactionStatus::ReturnRequested;
+        requested.return_request = Some(ReturnRequest {
+            reason: "Arrived damaged".to_string(),
+            tracking_number: None,
+            refund_cents: None,
+        });
+        assert!(check_return_request(&delivered, &requested).is_ok());
+
+        // A return needs a reason
+        let mut no_reason = requested.clone();
+        no_reason.return_request.as_mut().unwrap().reason = " ".to_string();
+        assert!(check_return_request(&delivered, &no_reason).is_err());
+
+        // Shipping back needs a tracking number
+        let mut shipped = requested.clone();
+        shipped.status = TransactionStatus::ReturnShipped;
+        assert!(check_return_request(&requested, &shipped).is_err());
+        shipped.return_request.as_mut().unwrap().tracking_number = Some("1Z999".to_string());
+        assert!(check_return_request(&requested, &shipped).is_ok());
+
+        // Refund is capped at the total price
+        let mut refunded = shipped.clone();
+        refunded.status = TransactionStatus::Refunded;
+        refunded.return_request.as_mut().unwrap().refund_cents = Some(5000);
+        assert!(check_return_request(&shipped, &refunded).is_err());
+        refunded.return_request.as_mut().unwrap().refund_cents = Some(1000);
+        assert!(check_return_request(&shipped, &refunded).is_ok());
+    }
+
+    #[test]
+    fn test_escrow_payout_limits() {
+        let mut transaction = mock_transaction();
+
+        // Active transactions authorize no payout
+        assert_eq!(version_payout_limits(&transaction), None);
+
+        // Completed: release only
+        transaction.status = TransactionStatus::Completed;
+        let (refund, release) = version_payout_limits(&transaction).unwrap();
+        assert!(check_payout(&EscrowKind::Refund, 1, refund).is_err());
+        assert!(check_payout(&EscrowKind::Release, 1999, release.clone()).is_ok());
+        assert!(check_payout(&EscrowKind::Release, 2000, release).is_err());
+
+        // Cancelled: refund only
+        transaction.status = TransactionStatus::Cancelled;
+        let (refund, release) = version_payout_limits(&transaction).unwrap();
+        assert!(check_payout(&EscrowKind::Refund, 1999, refund).is_ok());
+        assert!(check_payout(&EscrowKind::Release, 1, release).is_err());
+
+        // Refunded: the agreed refund, the rest to the seller
+        transaction.status = TransactionStatus::Refunded;
+        transaction.return_request = Some(ReturnRequest {
+            reason: "Arrived damaged".to_string(),
+            tracking_number: None,
+            refund_cents: Some(500),
+        });
+        let (refund, release) = version_payout_limits(&transaction).unwrap();
+        assert!(check_payout(&EscrowKind::Refund, 501, refund.clone()).is_err());
+        assert!(check_payout(&EscrowKind::Refund, 500, refund).is_ok());
+        assert!(check_payout(&EscrowKind::Release, 1499, release.clone()).is_ok());
+        assert!(check_payout(&EscrowKind::Release, 1500, release).is_err());
+
+        // Arbitration: exactly the compensation, at most the rest
+        let (refund, release) = settlement_payout_limits(750, 1999);
+        assert!(check_payout(&EscrowKind::Refund, 700, refund.clone()).is_err());
+        assert!(check_payout(&EscrowKind::Refund, 750, refund).is_ok());
+        assert!(check_payout(&EscrowKind::Release, 1249, release.clone()).is_ok());
+        assert!(check_payout(&EscrowKind::Release, 1999, release).is_err());
+    }
+
     // ===== Validation Tests =====
 
     #[test]
@@ -179,19 +367,75 @@ mod tests {
         assert_eq!(transaction.epistemic.normative, NormativeLevel::N1Communal);
     }
 
-    // ===== Tracking Info Tests =====
+    // ===== Shipment Tests =====
+
+    fn mock_shipment() -> Shipment {
+        Shipment {
+            transaction_hash: ActionHash::from_raw_36(vec![1u8; 36]),
+            carrier: "USPS".to_string(),
+            tracking_number: "1234567890".to_string(),
+            shipping_method: ShippingMethod::Standard,
+            estimated_delivery_start: Some(Timestamp::from_micros(2000000)),
+            estimated_delivery_end: Some(Timestamp::from_micros(3000000)),
+            events: Vec::new(),
+            created_at: Timestamp::from_micros(1000000),
+            updated_at: Timestamp::from_micros(1000000),
+        }
+    }
+
+    fn mock_event(kind: TrackingEventKind, occurred_at: i64) -> TrackingEvent {
+        TrackingEvent {
+            kind,
+            description: "Carrier scan".to_string(),
+            location: None,
+            occurred_at: Timestamp::from_micros(occurred_at),
+        }
+    }
 
     #[test]
-    fn test_tracking_info_optional() {
+    fn test_shipment_optional_until_shipped() {
         let mut transaction = mock_transaction();
 
-        // Initially no tracking info
-        assert!(transaction.tracking_info.is_none());
+        // Initially no shipment
+        assert!(transaction.shipment_hash.is_none());
+
+        // Attached when shipped
+        transaction.shipment_hash = Some(ActionHash::from_raw_36(vec![7u8; 36]));
+        assert!(transaction.shipment_hash.is_some());
+    }
 
-        // Can add tracking when shipped
-        transaction.tracking_info = Some("USPS-1234567890".to_string());
-        assert!(transaction.tracking_info.is_some());
-        assert_eq!(transaction.tracking_info.unwrap(), "USPS-1234567890");
+    #[test]
+    fn test_tracking_events_are_append_only() {
+        let mut previous = mock_shipment();
+        previous.events.push(mock_event(TrackingEventKind::LabelCreated, 1000));
+
+        let mut appended = previous.clone();
+        appended.events.push(mock_event(TrackingEventKind::InTransit, 2000));
+        assert!(check_shipment_update(&previous, &appended).is_ok());
+
+        // Rewriting history is rejected
+        let mut rewritten = appended.clone();
+        rewritten.events[0].description = "Never happened".to_string();
+        assert!(check_shipment_update(&previous, &rewritten).is_err());
+
+        // So is switching tracking numbers
+        let mut relabelled = appended.clone();
+        relabelled.tracking_number = "0000".to_string();
+        assert!(check_shipment_update(&previous, &relabelled).is_err());
+    }
+
+    #[test]
+    fn test_tracking_events_sorted_oldest_first() {
+        let mut events = vec![
+            mock_event(TrackingEventKind::Delivered, 3000),
+            mock_event(TrackingEventKind::LabelCreated, 1000),
+            mock_event(TrackingEventKind::InTransit, 2000),
+        ];
+
+        sort_tracking_events(&mut events);
+
+        assert_eq!(events[0].kind, TrackingEventKind::LabelCreated);
+        assert_eq!(events[2].kind, TrackingEventKind::Delivered);
     }
 
     // ===== Timestamp Tests =====
@@ -229,15 +473,16 @@ mod tests {
     fn test_completion_triggers_matl_update() {
         let transaction = mock_transaction();
 
-        // When transaction is completed, MATL should be updated
-        // This would call: update_matl_score(seller, successful: true, value)
+        // When transaction is completed, MATL should be updated for both sides
+        // This would call: update_matl_score(seller and buyer, successful: true, value, receipt)
 
         // Verify transaction is in completed state
         let completed_status = TransactionStatus::Completed;
         assert_eq!(completed_status, TransactionStatus::Completed);
 
-        // Verify we have seller info for MATL update
+        // Verify we have seller and buyer info for MATL update
         assert_ne!(transaction.seller, AgentPubKey::from_raw_36(vec![0u8; 36]));
+        assert_ne!(transaction.buyer, AgentPubKey::from_raw_36(vec![0u8; 36]));
 
         // Verify we have transaction value for MATL weighting
         assert!(transaction.total_price_cents > 0);
@@ -254,29 +499,136 @@ mod tests {
         assert_ne!(transaction.status, TransactionStatus::Completed);
     }
 
+    fn mock_receipt_signature(signer: AgentPubKey, terms: ReceiptTerms) -> (ActionHash, ReceiptSignature) {
+        (
+            ActionHash::from_raw_36(vec![9u8; 36]),
+            ReceiptSignature {
+                terms,
+                signer,
+                signature: Signature([0u8; 64]),
+                signed_at: Timestamp::from_micros(1000000),
+            },
+        )
+    }
+
+    #[test]
+    fn test_receipt_terms_use_reference_value() {
+        let mut transaction = mock_transaction();
+        transaction.currency = "EUR".to_string();
+        transaction.reference_value_cents = Some(2158);
+
+        let transaction_hash = ActionHash::from_raw_36(vec![4u8; 36]);
+        let terms = ReceiptTerms::new(transaction_hash.clone(), &transaction);
+
+        assert_eq!(terms.transaction_hash, transaction_hash);
+        assert_eq!(terms.total_price_cents, 1999);
+        assert_eq!(terms.matl_value_cents, 2158);
+
+        // Status changes do not change the terms being signed
+        transaction.status = TransactionStatus::Delivered;
+        assert_eq!(ReceiptTerms::new(transaction_hash, &transaction), terms);
+    }
+
+    #[test]
+    fn test_countersign_needs_other_party_signature() {
+        let transaction = mock_transaction();
+        let terms = ReceiptTerms::new(ActionHash::from_raw_36(vec![4u8; 36]), &transaction);
+
+        let buyer_signed = vec![mock_receipt_signature(transaction.buyer.clone(), terms.clone())];
+
+        // The seller can countersign the buyer's signature, the buyer cannot
+        assert!(counterpart_signature(&buyer_signed, &terms, &transaction.seller).is_some());
+        assert!(counterpart_signature(&buyer_signed, &terms, &transaction.buyer).is_none());
+        assert!(counterpart_signature(&[], &terms, &transaction.seller).is_none());
+    }
+
+    #[test]
+    fn test_countersign_ignores_stale_or_outside_signatures() {
+        let transaction = mock_transaction();
+        let terms = ReceiptTerms::new(ActionHash::from_raw_36(vec![4u8; 36]), &transaction);
+
+        let mut other_terms = terms.clone();
+        other_terms.total_price_cents = 1;
+        let outsider = AgentPubKey::from_raw_36(vec![7u8; 36]);
+
+        let signatures = vec![
+            mock_receipt_signature(transaction.buyer.clone(), other_terms),
+            mock_receipt_signature(outsider, terms.clone()),
+        ];
+
+        assert!(counterpart_signature(&signatures, &terms, &transaction.seller).is_none());
+    }
+
     // ===== Create Transaction Input Tests =====
 
     #[test]
     fn test_create_transaction_input() {
+        // Seller and total price come from the listing, not the buyer
         let input = CreateTransactionInput {
-            seller: AgentPubKey::from_raw_36(vec![2u8; 36]),
             listing_hash: ActionHash::from_raw_36(vec![3u8; 36]),
+            variant_sku: None,
             quantity: 2,
-            total_price_cents: 3998, // 2 * 1999
         };
 
         assert_eq!(input.quantity, 2);
-        assert_eq!(input.total_price_cents, 3998);
     }
 
     #[test]
     fn test_price_quantity_relationship() {
         // Total price should match unit_price * quantity
-        let unit_price = 1999;
-        let quantity = 3;
-        let total_price = unit_price * quantity;
+        assert_eq!(compute_total_price(1999, 3), Some(5997));
+        assert_eq!(compute_total_price(u64::MAX, 2), None);
+    }
+
+    #[test]
+    fn test_listing_terms_checked() {
+        let terms = ListingTerms {
+            price_cents: 1999,
+            currency: "USD".to_string(),
+            quantity_available: 5,
+            variants: vec![],
+            status: "active".to_string(),
+        };
 
-        assert_eq!(total_price, 5997);
+        assert!(check_listing_terms(&terms, None, 2, 3998).is_ok());
+        assert!(check_listing_terms(&terms, None, 2, 1).is_err()); // Buyer-chosen price
+        assert!(check_listing_terms(&terms, None, 6, 11994).is_err()); // More than stocked
+        assert!(check_listing_terms(&terms, Some("TS-S"), 1, 1999).is_err()); // No variants
+
+        let sold = ListingTerms {
+            status: "sold".to_string(),
+            ..terms
+        };
+        assert!(check_listing_terms(&sold, None, 1, 1999).is_err());
+    }
+
+    #[test]
+    fn test_variant_terms_checked() {
+        let terms = ListingTerms {
+            price_cents: 2500,
+            currency: "USD".to_string(),
+            quantity_available: 5,
+            variants: vec![
+                VariantTerms {
+                    sku: "TS-S".to_string(),
+                    price_cents: None,
+                    quantity_available: 2,
+                },
+                VariantTerms {
+                    sku: "TS-M".to_string(),
+                    price_cents: Some(2800),
+                    quantity_available: 3,
+                },
+            ],
+            status: "active".to_string(),
+        };
+
+        assert!(check_listing_terms(&terms, Some("TS-S"), 2, 5000).is_ok());
+        assert!(check_listing_terms(&terms, Some("TS-M"), 3, 8400).is_ok()); // Price override
+        assert!(check_listing_terms(&terms, Some("TS-M"), 1, 2500).is_err()); // Listing price
+        assert!(check_listing_terms(&terms, Some("TS-S"), 3, 7500).is_err()); // Variant stock
+        assert!(check_listing_terms(&terms, Some("TS-XL"), 1, 2500).is_err());
+        assert!(check_listing_terms(&terms, None, 1, 2500).is_err()); // Must pick a variant
     }
 
     // ===== Mark Shipped Input Tests =====
@@ -285,22 +637,30 @@ mod tests {
     fn test_mark_shipped_input() {
         let input = MarkShippedInput {
             transaction_hash: ActionHash::from_raw_36(vec![1u8; 36]),
-            tracking_info: Some("FedEx-9876543210".to_string()),
+            carrier: "FedEx".to_string(),
+            tracking_number: "9876543210".to_string(),
+            shipping_method: ShippingMethod::Express,
+            estimated_delivery_start: None,
+            estimated_delivery_end: None,
         };
 
-        assert!(input.tracking_info.is_some());
-        assert!(input.tracking_info.unwrap().starts_with("FedEx"));
+        assert_eq!(input.carrier, "FedEx");
+        assert_eq!(input.shipping_method, ShippingMethod::Express);
     }
 
     #[test]
-    fn test_shipping_without_tracking() {
+    fn test_shipping_without_estimate() {
         let input = MarkShippedInput {
             transaction_hash: ActionHash::from_raw_36(vec![1u8; 36]),
-            tracking_info: None,
+            carrier: "Local courier".to_string(),
+            tracking_number: "LC-1".to_string(),
+            shipping_method: ShippingMethod::LocalPickup,
+            estimated_delivery_start: None,
+            estimated_delivery_end: None,
         };
 
-        // Tracking info is optional
-        assert!(input.tracking_info.is_none());
+        // Delivery estimate is optional
+        assert!(input.estimated_delivery_end.is_none());
     }
 
     // ===== Dispute Input Tests =====
@@ -351,11 +711,11 @@ mod tests {
         transaction.status = TransactionStatus::Confirmed;
         assert_eq!(transaction.status, TransactionStatus::Confirmed);
 
-        // Step 3: Seller ships with tracking
+        // Step 3: Seller ships with a shipment
         transaction.status = TransactionStatus::Shipped;
-        transaction.tracking_info = Some("UPS-1122334455".to_string());
+        transaction.shipment_hash = Some(ActionHash::from_raw_36(vec![7u8; 36]));
         assert_eq!(transaction.status, TransactionStatus::Shipped);
-        assert!(transaction.tracking_info.is_some());
+        assert!(transaction.shipment_hash.is_some());
 
         // Step 4: Buyer confirms delivery
         transaction.status = TransactionStatus::Delivered;
@@ -417,4 +777,221 @@ mod tests {
 
         assert_eq!(transaction.total_price_cents, 100_000_000);
     }
+
+    // ===== Cart & Order Tests =====
+
+    #[test]
+    fn test_add_cart_item_merges_same_listing() {
+        let listing = ActionHash::from_raw_36(vec![3u8; 36]);
+        let mut items = Vec::new();
+
+        let item = |listing_hash: &ActionHash, quantity, variant_sku: Option<&str>| CartItem {
+            listing_hash: listing_hash.clone(),
+            quantity,
+            variant_sku: variant_sku.map(String::from),
+        };
+
+        add_cart_item(&mut items, item(&listing, 1, None));
+        add_cart_item(&mut items, item(&listing, 2, None));
+        add_cart_item(&mut items, item(&ActionHash::from_raw_36(vec![4u8; 36]), 1, None));
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].quantity, 3);
+
+        assert!(remove_cart_item(&mut items, &listing, None));
+        assert!(!remove_cart_item(&mut items, &listing, None));
+        assert_eq!(items.len(), 1);
+    }
+
+    #[test]
+    fn test_cart_keeps_variants_of_a_listing_apart() {
+        let listing = ActionHash::from_raw_36(vec![3u8; 36]);
+        let mut items = Vec::new();
+
+        for (sku, quantity) in [("TS-S", 1), ("TS-M", 1), ("TS-S", 2)] {
+            add_cart_item(
+                &mut items,
+                CartItem {
+                    listing_hash: listing.clone(),
+                    quantity,
+                    variant_sku: Some(sku.to_string()),
+                },
+            );
+        }
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].quantity, 3);
+
+        assert!(remove_cart_item(&mut items, &listing, Some("TS-M")));
+        assert!(!remove_cart_item(&mut items, &listing, None));
+        assert_eq!(items.len(), 1);
+    }
+
+    #[test]
+    fn test_order_status_aggregation() {
+        use TransactionStatus::*;
+
+        assert_eq!(aggregate_order_status(&[Pending, Pending]), OrderStatus::Pending);
+        assert_eq!(aggregate_order_status(&[Confirmed, Pending]), OrderStatus::InProgress);
+        assert_eq!(aggregate_order_status(&[Completed, Shipped]), OrderStatus::InProgress);
+        assert_eq!(aggregate_order_status(&[Completed, Cancelled]), OrderStatus::Completed);
+        assert_eq!(aggregate_order_status(&[Cancelled, Cancelled]), OrderStatus::Cancelled);
+        assert_eq!(aggregate_order_status(&[Completed, Disputed]), OrderStatus::Disputed);
+    }
+
+    #[test]
+    fn test_seller_subtotals() {
+        let lines = vec![mock_line(2, 1000), mock_line(5, 250), mock_line(2, 499)];
+        let subtotals = seller_subtotals(&lines);
+
+        assert_eq!(subtotals.len(), 2);
+        assert_eq!(subtotals[0].seller, AgentPubKey::from_raw_36(vec![2u8; 36]));
+        assert_eq!(subtotals[0].line_count, 2);
+        assert_eq!(subtotals[0].subtotal_cents, 1499);
+        assert_eq!(subtotals[1].subtotal_cents, 250);
+    }
+
+    #[test]
+    fn test_lines_grouped_into_one_transaction_per_seller() {
+        let lines = vec![mock_line(2, 1000), mock_line(5, 250), mock_line(2, 499)];
+        let groups = lines_by_seller(&lines);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0], vec![lines[0].clone(), lines[2].clone()]);
+        assert_eq!(groups[1], vec![lines[1].clone()]);
+    }
+
+    #[test]
+    fn test_transaction_lines_match_the_transaction() {
+        let mut first = mock_line(2, 1000);
+        first.listing_hash = ActionHash::from_raw_36(vec![3u8; 36]);
+        first.listing_version_hash = ActionHash::from_raw_36(vec![3u8; 36]);
+        let second = mock_line(2, 499);
+
+        let mut transaction = mock_transaction();
+        assert_eq!(check_transaction_lines(&transaction), Ok(()));
+        assert_eq!(transaction.purchase_lines().len(), 1);
+
+        transaction.order_hash = Some(ActionHash::from_raw_36(vec![9u8; 36]));
+        transaction.total_price_cents = 1499;
+        transaction.lines = vec![first.clone(), second.clone()];
+        assert_eq!(check_transaction_lines(&transaction), Ok(()));
+        assert_eq!(transaction.purchase_lines(), vec![first.clone(), second.clone()]);
+
+        // Totals are shared across the lines
+        transaction.total_price_cents = 1000;
+        assert!(check_transaction_lines(&transaction).is_err());
+        transaction.total_price_cents = 1499;
+
+        // The first line is the transaction's listing
+        transaction.lines = vec![second.clone(), first.clone()];
+        assert!(check_transaction_lines(&transaction).is_err());
+
+        // One seller per transaction
+        transaction.lines = vec![first.clone(), mock_line(5, 499)];
+        assert!(check_transaction_lines(&transaction).is_err());
+
+        // Lines only come from a checkout
+        transaction.lines = vec![first, second];
+        transaction.order_hash = None;
+        assert!(check_transaction_lines(&transaction).is_err());
+    }
+
+    // ===== Timeout Tests =====
+
+    #[test]
+    fn test_state_deadlines() {
+        let properties = TransactionProperties::default();
+        let from = Timestamp::from_micros(0);
+        let hour: i64 = 60 * 60 * 1_000_000;
+
+        assert_eq!(
+            state_deadline(&TransactionStatus::Pending, from, &properties),
+            Some(Timestamp::from_micros(72 * hour))
+        );
+        assert_eq!(
+            state_deadline(&TransactionStatus::Delivered, from, &properties),
+            Some(Timestamp::from_micros(7 * 24 * hour))
+        );
+        assert_eq!(state_deadline(&TransactionStatus::Confirmed, from, &properties), None);
+        assert_eq!(state_deadline(&TransactionStatus::Completed, from, &properties), None);
+    }
+
+    #[test]
+    fn test_sweep_actions() {
+        let mut transaction = mock_transaction();
+        let before = Timestamp::from_micros(1_000);
+        let after = Timestamp::from_micros(3_000);
+        transaction.deadline = Some(Timestamp::from_micros(2_000));
+
+        assert_eq!(sweep_action(&transaction, before), None);
+        assert_eq!(sweep_action(&transaction, after), Some(SweepAction::Cancel));
+
+        transaction.status = TransactionStatus::Shipped;
+        assert_eq!(sweep_action(&transaction, after), Some(SweepAction::PromptDispute));
+
+        transaction.status = TransactionStatus::Delivered;
+        assert_eq!(sweep_action(&transaction, after), Some(SweepAction::Complete));
+
+        // No deadline, nothing to do
+        transaction.deadline = None;
+        assert_eq!(sweep_action(&transaction, after), None);
+    }
+
+    // ===== Currency Tests =====
+
+    fn mock_snapshot(rates: Vec<ExchangeRate>) -> ExchangeRateSnapshot {
+        ExchangeRateSnapshot {
+            base_currency: REFERENCE_CURRENCY.to_string(),
+            rates,
+            source: "ECB reference rates".to_string(),
+            observed_at: Timestamp::from_micros(1000000),
+            oracle: AgentPubKey::from_raw_36(vec![8u8; 36]),
+            signature: Signature([0u8; 64]),
+        }
+    }
+
+    #[test]
+    fn test_matl_value_uses_reference_currency() {
+        let mut transaction = mock_transaction();
+        assert_eq!(transaction.matl_value_cents(), 1999);
+
+        transaction.currency = "EUR".to_string();
+        transaction.reference_value_cents = Some(2158);
+        assert_eq!(transaction.matl_value_cents(), 2158);
+    }
+
+    #[test]
+    fn test_reference_value_matches_common_money() {
+        let rate = 1_080_000_000; // 1 EUR = 1.08 USD
+
+        for amount in [1, 1999, 123_456_789] {
+            let converted = mycelix_common::money::Money::new(amount, "EUR")
+                .convert(rate, REFERENCE_CURRENCY)
+                .map(|money| money.amount_minor);
+
+            // Coordinator and validation must agree to the cent
+            assert_eq!(converted, reference_value_minor(amount, rate));
+        }
+    }
+
+    #[test]
+    fn test_check_exchange_rates() {
+        let eur = ExchangeRate { currency: "EUR".to_string(), rate: 1_080_000_000 };
+        let myc = ExchangeRate { currency: "MYC".to_string(), rate: 25_000_000 };
+
+        let snapshot = mock_snapshot(vec![eur.clone(), myc]);
+        assert!(check_exchange_rates(&snapshot).is_ok());
+        assert_eq!(snapshot.rate_for("EUR"), Some(1_080_000_000));
+        assert_eq!(snapshot.rate_for("GBP"), None);
+
+        // Duplicates, zero rates and quoting the base are rejected
+        assert!(check_exchange_rates(&mock_snapshot(vec![eur.clone(), eur.clone()])).is_err());
+        assert!(check_exchange_rates(&mock_snapshot(vec![ExchangeRate { rate: 0, ..eur }])).is_err());
+        assert!(check_exchange_rates(&mock_snapshot(vec![ExchangeRate {
+            currency: "USD".to_string(),
+            rate: RATE_SCALE,
+        }]))
+        .is_err());
+    }
 }
@@ -13,12 +13,28 @@ pub struct Transaction {
     /// Listing being purchased
     pub listing_hash: ActionHash,
 
+    /// Listing version the price was taken from
+    pub listing_version_hash: ActionHash,
+
     /// Quantity purchased
     pub quantity: u32,
 
-    /// Total price in cents (quantity * unit_price)
+    /// Total price in minor units of `currency` (quantity * unit_price)
     pub total_price_cents: u64,
 
+    /// Currency of the listing (ISO-4217 or registered custom code)
+    #[serde(default = "default_currency")]
+    pub currency: String,
+
+    /// Exchange-rate snapshot used to value a non-reference-currency purchase
+    #[serde(default)]
+    pub exchange_rate_hash: Option<ActionHash>,
+
+    /// Total in the reference currency (USD cents), for MATL value weighting
+    /// (None when already priced in the reference currency)
+    #[serde(default)]
+    pub reference_value_cents: Option<u64>,
+
     /// Current status
     pub status: TransactionStatus,
 
@@ -28,12 +44,370 @@ pub struct Transaction {
     /// Last update timestamp
     pub updated_at: Timestamp,
 
-    /// Delivery tracking (optional)
-    pub tracking_info: Option<String>,
+    /// Shipment carrying the order (set when shipped)
+    #[serde(default)]
+    pub shipment_hash: Option<ActionHash>,
 
     /// Epistemic classification
     /// Transactions are N1 (communal) agreements between buyer-seller
     pub epistemic: EpistemicClassification,
+
+    /// Order this transaction was checked out in (None for single purchases)
+    #[serde(default)]
+    pub order_hash: Option<ActionHash>,
+
+    /// When the current state times out (Pending, Shipped and Delivered only)
+    #[serde(default)]
+    pub deadline: Option<Timestamp>,
+
+    /// Return requested after delivery, if any
+    #[serde(default)]
+    pub return_request: Option<ReturnRequest>,
+
+    /// Countersigned receipt the transaction was completed with
+    #[serde(default)]
+    pub receipt_hash: Option<ActionHash>,
+
+    /// Variant purchased, for listings with variants
+    #[serde(default)]
+    pub variant_sku: Option<String>,
+
+    /// Every line bought, for an order transaction covering several of the
+    /// seller's listings (empty for one line). The listing fields above
+    /// are the first line and `total_price_cents` the sum of all lines.
+    #[serde(default)]
+    pub lines: Vec<OrderLine>,
+}
+
+/// The final terms of a transaction, as signed by both parties
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReceiptTerms {
+    /// Transaction being completed (original action)
+    pub transaction_hash: ActionHash,
+    pub buyer: AgentPubKey,
+    pub seller: AgentPubKey,
+    pub listing_hash: ActionHash,
+    pub listing_version_hash: ActionHash,
+    pub quantity: u32,
+    pub total_price_cents: u64,
+    pub currency: String,
+
+    /// Value the MATL update is weighted by (reference-currency cents)
+    pub matl_value_cents: u64,
+}
+
+impl ReceiptTerms {
+    /// Terms of `transaction`, created at `transaction_hash`
+    pub fn new(transaction_hash: ActionHash, transaction: &Transaction) -> Self {
+        ReceiptTerms {
+            transaction_hash,
+            buyer: transaction.buyer.clone(),
+            seller: transaction.seller.clone(),
+            listing_hash: transaction.listing_hash.clone(),
+            listing_version_hash: transaction.listing_version_hash.clone(),
+            quantity: transaction.quantity,
+            total_price_cents: transaction.total_price_cents,
+            currency: transaction.currency.clone(),
+            matl_value_cents: transaction.matl_value_cents(),
+        }
+    }
+}
+
+/// One party's signature over a transaction's receipt terms
+///
+/// The first step of completing a transaction: the first party to sign
+/// stores its signature here, and the other party countersigns it into
+/// a `TransactionReceipt`.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ReceiptSignature {
+    /// Terms being signed
+    pub terms: ReceiptTerms,
+
+    /// Buyer or seller who signed
+    pub signer: AgentPubKey,
+
+    /// Signer's signature over `terms`
+    pub signature: Signature,
+
+    /// Timestamp
+    pub signed_at: Timestamp,
+}
+
+/// Receipt of a completed transaction, signed by buyer and seller
+///
+/// A transaction only moves to Completed with a receipt, and the
+/// reputation zome only counts a completion backed by one.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct TransactionReceipt {
+    /// Final terms, as signed by both parties
+    pub terms: ReceiptTerms,
+
+    /// Buyer's signature over `terms`
+    pub buyer_signature: Signature,
+
+    /// Seller's signature over `terms`
+    pub seller_signature: Signature,
+
+    /// When the second party countersigned
+    pub countersigned_at: Timestamp,
+}
+
+/// Maximum number of tracking events on a shipment
+pub const MAX_TRACKING_EVENTS: usize = 100;
+
+/// Shipment entry - how a transaction's goods were sent
+///
+/// Created by the seller when marking the transaction shipped. The seller
+/// appends tracking events by updating the shipment (each update against
+/// the previous version); earlier events never change.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Shipment {
+    /// Transaction being shipped (original action)
+    pub transaction_hash: ActionHash,
+
+    /// Carrier name (e.g. "UPS")
+    pub carrier: String,
+
+    /// Carrier tracking number
+    pub tracking_number: String,
+
+    /// Shipping service level
+    pub shipping_method: ShippingMethod,
+
+    /// Earliest estimated delivery
+    pub estimated_delivery_start: Option<Timestamp>,
+
+    /// Latest estimated delivery
+    pub estimated_delivery_end: Option<Timestamp>,
+
+    /// Tracking events, in the order the seller recorded them
+    pub events: Vec<TrackingEvent>,
+
+    /// Creation timestamp
+    pub created_at: Timestamp,
+
+    /// Last update timestamp
+    pub updated_at: Timestamp,
+}
+
+/// Shipping service levels
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShippingMethod {
+    Standard,
+    Express,
+    Overnight,
+    Freight,
+    LocalPickup,
+}
+
+/// One step of a shipment's journey
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TrackingEvent {
+    /// What happened
+    pub kind: TrackingEventKind,
+
+    /// Carrier's description of the event
+    pub description: String,
+
+    /// Where it happened, if known
+    pub location: Option<String>,
+
+    /// When it happened (as reported by the carrier)
+    pub occurred_at: Timestamp,
+}
+
+/// Kinds of tracking events
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackingEventKind {
+    LabelCreated,
+    InTransit,
+    OutForDelivery,
+    Delivered,
+    Exception,
+}
+
+/// A buyer's return request (RMA)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReturnRequest {
+    /// Why the buyer wants to return the item
+    pub reason: String,
+
+    /// Tracking number of the return shipment (set when shipped back)
+    pub tracking_number: Option<String>,
+
+    /// Amount refunded to the buyer (set when refunded)
+    pub refund_cents: Option<u64>,
+}
+
+/// Maximum length of a return reason
+pub const MAX_RETURN_REASON_LENGTH: usize = 1000;
+
+impl Transaction {
+    /// Value used to weight MATL updates, in the reference currency
+    pub fn matl_value_cents(&self) -> u64 {
+        self.reference_value_cents.unwrap_or(self.total_price_cents)
+    }
+
+    /// Every line the transaction buys
+    pub fn purchase_lines(&self) -> Vec<OrderLine> {
+        if !self.lines.is_empty() {
+            return self.lines.clone();
+        }
+
+        vec![OrderLine {
+            listing_hash: self.listing_hash.clone(),
+            listing_version_hash: self.listing_version_hash.clone(),
+            seller: self.seller.clone(),
+            quantity: self.quantity,
+            total_price_cents: self.total_price_cents,
+            variant_sku: self.variant_sku.clone(),
+        }]
+    }
+}
+
+/// Check the lines of a multi-line transaction against its own fields
+///
+/// Only checkout transactions have lines. The first line is the listing
+/// the transaction names, every line is from the transaction's seller and
+/// the lines add up to its total. Single-line transactions carry no lines.
+pub fn check_transaction_lines(transaction: &Transaction) -> Result<(), String> {
+    let lines = &transaction.lines;
+    if lines.is_empty() {
+        return Ok(());
+    }
+
+    if transaction.order_hash.is_none() {
+        return Err("Only order transactions have several lines".into());
+    }
+
+    if lines.len() < 2 || lines.len() > MAX_CART_ITEMS {
+        return Err(format!(
+            "A multi-line transaction must have between 2 and {} lines",
+            MAX_CART_ITEMS
+        ));
+    }
+
+    let first = &lines[0];
+    if first.listing_hash != transaction.listing_hash
+        || first.listing_version_hash != transaction.listing_version_hash
+        || first.quantity != transaction.quantity
+        || first.variant_sku != transaction.variant_sku
+    {
+        return Err("The first line must be the transaction's listing".into());
+    }
+
+    if lines.iter().any(|line| line.seller != transaction.seller) {
+        return Err("Every line must be from the transaction's seller".into());
+    }
+
+    if lines.iter().any(|line| line.quantity == 0) {
+        return Err("Line quantity must be at least 1".into());
+    }
+
+    let total = lines
+        .iter()
+        .try_fold(0u64, |total, line| total.checked_add(line.total_price_cents));
+    if total != Some(transaction.total_price_cents) {
+        return Err("Transaction total must be the sum of its lines".into());
+    }
+
+    Ok(())
+}
+
+/// Currency that MATL value weighting is expressed in
+pub const REFERENCE_CURRENCY: &str = "USD";
+
+/// Fixed-point scale of exchange rates (1.0 == RATE_SCALE)
+pub const RATE_SCALE: u64 = 1_000_000_000;
+
+fn default_currency() -> String {
+    REFERENCE_CURRENCY.to_string()
+}
+
+/// Exchange-rate snapshot - rates signed by a configured rate oracle
+///
+/// Anyone may store a snapshot, but only one carrying a valid signature
+/// from an oracle listed in the DNA properties is accepted. Transactions
+/// priced in other currencies reference the snapshot they were valued with.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ExchangeRateSnapshot {
+    /// Currency the rates convert into (always the reference currency)
+    pub base_currency: String,
+
+    /// Rates for each quoted currency
+    pub rates: Vec<ExchangeRate>,
+
+    /// Where the oracle got the rates
+    pub source: String,
+
+    /// When the rates were observed
+    pub observed_at: Timestamp,
+
+    /// Oracle that signed the rates
+    pub oracle: AgentPubKey,
+
+    /// Oracle's signature over `ExchangeRatePayload`
+    pub signature: Signature,
+}
+
+/// The signed part of an exchange-rate snapshot
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExchangeRatePayload {
+    pub base_currency: String,
+    pub rates: Vec<ExchangeRate>,
+    pub source: String,
+    pub observed_at: Timestamp,
+}
+
+/// One quoted currency
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExchangeRate {
+    /// Quoted currency code
+    pub currency: String,
+
+    /// Base-currency minor units per quoted minor unit, scaled by `RATE_SCALE`
+    pub rate: u64,
+}
+
+impl ExchangeRateSnapshot {
+    /// The data the oracle signs
+    pub fn payload(&self) -> ExchangeRatePayload {
+        ExchangeRatePayload {
+            base_currency: self.base_currency.clone(),
+            rates: self.rates.clone(),
+            source: self.source.clone(),
+            observed_at: self.observed_at,
+        }
+    }
+
+    /// Rate for a quoted currency
+    pub fn rate_for(&self, currency: &str) -> Option<u64> {
+        self.rates
+            .iter()
+            .find(|rate| rate.currency == currency)
+            .map(|rate| rate.rate)
+    }
+}
+
+/// Value an amount in the reference currency, rounding down
+///
+/// Same fixed-point conversion as `mycelix_common::money::Money::convert`.
+pub fn reference_value_minor(amount_minor: u64, rate: u64) -> Option<u64> {
+    u64::try_from(amount_minor as u128 * rate as u128 / RATE_SCALE as u128).ok()
+}
+
+/// Whether a code looks like an ISO-4217 or custom currency code
+pub fn is_valid_currency_code(code: &str) -> bool {
+    (3..=8).contains(&code.len())
+        && code
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
 }
 
 /// Transaction lifecycle states
@@ -43,7 +417,7 @@ pub enum TransactionStatus {
     /// Buyer created, awaiting seller confirmation
     Pending,
 
-    /// Seller confirmed, payment/arrangement made
+    /// Seller confirmed, buyer's payment held in escrow
     Confirmed,
 
     /// Seller marked as shipped
@@ -60,6 +434,291 @@ pub enum TransactionStatus {
 
     /// Cancelled before completion
     Cancelled,
+
+    /// Buyer asked to return a delivered item
+    ReturnRequested,
+
+    /// Seller accepted the return
+    ReturnApproved,
+
+    /// Buyer shipped the item back
+    ReturnShipped,
+
+    /// Seller refunded the buyer, in full or in part
+    Refunded,
+}
+
+/// Transaction timeout settings, read from the DNA properties
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, SerializedBytes)]
+pub struct TransactionProperties {
+    /// Hours the seller has to confirm before a Pending transaction is cancelled
+    #[serde(default = "default_confirmation_timeout_hours")]
+    pub confirmation_timeout_hours: u32,
+
+    /// Days after shipping before the buyer is prompted to dispute
+    #[serde(default = "default_delivery_timeout_days")]
+    pub delivery_timeout_days: u32,
+
+    /// Days the buyer has to inspect a delivery before it auto-completes
+    #[serde(default = "default_inspection_window_days")]
+    pub inspection_window_days: u32,
+
+    /// Agents whose signed exchange rates are trusted
+    #[serde(default)]
+    pub exchange_rate_oracles: Vec<AgentPubKey>,
+
+    /// Hours an exchange-rate snapshot stays usable for pricing
+    #[serde(default = "default_max_exchange_rate_age_hours")]
+    pub max_exchange_rate_age_hours: u32,
+}
+
+fn default_confirmation_timeout_hours() -> u32 {
+    72
+}
+
+fn default_delivery_timeout_days() -> u32 {
+    30
+}
+
+fn default_inspection_window_days() -> u32 {
+    7
+}
+
+fn default_max_exchange_rate_age_hours() -> u32 {
+    24
+}
+
+impl Default for TransactionProperties {
+    fn default() -> Self {
+        Self {
+            confirmation_timeout_hours: default_confirmation_timeout_hours(),
+            delivery_timeout_days: default_delivery_timeout_days(),
+            inspection_window_days: default_inspection_window_days(),
+            exchange_rate_oracles: Vec::new(),
+            max_exchange_rate_age_hours: default_max_exchange_rate_age_hours(),
+        }
+    }
+}
+
+/// Read transaction settings from the DNA properties
+///
+/// Falls back to the defaults when the properties are missing or malformed.
+pub fn transaction_properties() -> ExternResult<TransactionProperties> {
+    let properties = dna_info()?.modifiers.properties;
+    Ok(TransactionProperties::try_from(properties).unwrap_or_default())
+}
+
+pub const MICROS_PER_HOUR: i64 = 60 * 60 * 1_000_000;
+
+/// Deadline for a transaction entering `status` at `from`
+///
+/// Only states that can stall have one: Pending (seller confirmation),
+/// Shipped (delivery) and Delivered (buyer inspection).
+pub fn state_deadline(
+    status: &TransactionStatus,
+    from: Timestamp,
+    properties: &TransactionProperties,
+) -> Option<Timestamp> {
+    let hours = match status {
+        TransactionStatus::Pending => properties.confirmation_timeout_hours as i64,
+        TransactionStatus::Shipped => properties.delivery_timeout_days as i64 * 24,
+        TransactionStatus::Delivered => properties.inspection_window_days as i64 * 24,
+        _ => return None,
+    };
+
+    Some(Timestamp::from_micros(from.as_micros() + hours * MICROS_PER_HOUR))
+}
+
+/// Maximum number of distinct listings in a cart or order
+pub const MAX_CART_ITEMS: usize = 50;
+
+/// Cart entry - a buyer's persistent list of items to check out
+///
+/// Each buyer has one cart, updated in place (each update against the
+/// previous version) as items are added and removed.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Cart {
+    /// Buyer who owns the cart
+    pub owner: AgentPubKey,
+
+    /// Items in the cart, at most one per listing variant
+    pub items: Vec<CartItem>,
+
+    /// Last update timestamp
+    pub updated_at: Timestamp,
+}
+
+/// One listing (or listing variant) in a cart
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CartItem {
+    /// Listing (original action)
+    pub listing_hash: ActionHash,
+
+    /// Quantity wanted
+    pub quantity: u32,
+
+    /// Variant wanted, for listings with variants
+    #[serde(default)]
+    pub variant_sku: Option<String>,
+}
+
+/// Order entry - one checkout of a cart
+///
+/// The order is split into child transactions, one per seller, which
+/// carry the order hash and that seller's lines; the order total is the
+/// sum of every line.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Order {
+    /// Buyer checking out
+    pub buyer: AgentPubKey,
+
+    /// Priced line items
+    pub lines: Vec<OrderLine>,
+
+    /// Sum of all line totals (in minor units of `currency`)
+    pub total_price_cents: u64,
+
+    /// Currency shared by every line
+    #[serde(default = "default_currency")]
+    pub currency: String,
+
+    /// Creation timestamp
+    pub created_at: Timestamp,
+}
+
+/// A priced line of an order
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OrderLine {
+    /// Listing (original action)
+    pub listing_hash: ActionHash,
+
+    /// Listing version the price was taken from
+    pub listing_version_hash: ActionHash,
+
+    /// Listing author
+    pub seller: AgentPubKey,
+
+    /// Quantity purchased
+    pub quantity: u32,
+
+    /// Line total in minor units of the order currency (quantity * unit_price)
+    pub total_price_cents: u64,
+
+    /// Variant purchased, for listings with variants
+    #[serde(default)]
+    pub variant_sku: Option<String>,
+}
+
+/// Escrow entry - one movement of a transaction's escrowed funds
+///
+/// The entries linked from a transaction form its escrow ledger:
+/// the buyer's hold, then releases to the seller and refunds to the buyer.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct EscrowEntry {
+    /// Transaction the funds belong to (original action)
+    pub transaction_hash: ActionHash,
+
+    /// Kind of movement
+    pub kind: EscrowKind,
+
+    /// Amount moved (in cents)
+    pub amount_cents: u64,
+
+    /// Payer for holds, payee for releases and refunds
+    pub party: AgentPubKey,
+
+    /// Reference returned by the settlement adapter
+    pub settlement_reference: String,
+
+    /// Arbitration result that authorized a dispute settlement
+    pub arbitration_result_hash: Option<ActionHash>,
+
+    /// Transaction version that authorized a release or refund
+    /// (Completed, Cancelled or Refunded)
+    #[serde(default)]
+    pub transaction_version_hash: Option<ActionHash>,
+
+    /// Timestamp
+    pub recorded_at: Timestamp,
+}
+
+/// Escrow movements
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EscrowKind {
+    /// Buyer funds locked when the transaction is created
+    Hold,
+
+    /// Held funds paid out to the seller
+    Release,
+
+    /// Held funds returned to the buyer
+    Refund,
+}
+
+/// The listing terms a transaction is priced against
+///
+/// Mirrors the fields of `listings_integrity::Listing` that validation
+/// needs, so this zome does not depend on the listings integrity zome.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, SerializedBytes)]
+pub struct ListingTerms {
+    pub price_cents: u64,
+    #[serde(default = "default_currency")]
+    pub currency: String,
+    pub quantity_available: u32,
+    #[serde(default)]
+    pub variants: Vec<VariantTerms>,
+    pub status: String,
+}
+
+/// The fields of `listings_integrity::ListingVariant` a purchase is priced against
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VariantTerms {
+    pub sku: String,
+    pub price_cents: Option<u64>,
+    pub quantity_available: u32,
+}
+
+impl ListingTerms {
+    /// Unit price and stock of the variant a purchase targets
+    ///
+    /// Listings with variants must be bought by SKU; listings without
+    /// variants must not be.
+    pub fn variant_terms(&self, variant_sku: Option<&str>) -> Result<(u64, u32), String> {
+        match variant_sku {
+            Some(sku) => self
+                .variants
+                .iter()
+                .find(|variant| variant.sku == sku)
+                .map(|variant| {
+                    (
+                        variant.price_cents.unwrap_or(self.price_cents),
+                        variant.quantity_available,
+                    )
+                })
+                .ok_or(format!("Listing has no variant {}", sku)),
+            None if self.variants.is_empty() => Ok((self.price_cents, self.quantity_available)),
+            None => Err("Choose a variant of this listing".into()),
+        }
+    }
+}
+
+/// The fields of `arbitration_integrity::ArbitrationResult` a dispute
+/// settlement is checked against
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, SerializedBytes)]
+pub struct SettlementTerms {
+    pub dispute_hash: ActionHash,
+    pub compensation_cents: Option<u64>,
+}
+
+/// The fields of `arbitration_integrity::Dispute` a dispute settlement
+/// is checked against
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, SerializedBytes)]
+pub struct DisputeTerms {
+    pub transaction_hash: ActionHash,
 }
 
 /// Epistemic classification
@@ -105,28 +764,97 @@ pub enum LinkTypes {
 
     /// Listing -> Transactions
     ListingToTransactions,
+
+    /// Transaction -> Escrow entries
+    TransactionToEscrow,
+
+    /// Buyer -> Cart
+    BuyerToCart,
+
+    /// Buyer -> Orders
+    BuyerToOrders,
+
+    /// Order -> child Transactions
+    OrderToTransactions,
+
+    /// Transaction -> Shipment
+    TransactionToShipment,
+
+    /// Exchange-rate anchor -> Snapshots
+    ExchangeRateSnapshots,
+
+    /// Transaction -> Receipt signatures
+    TransactionToReceiptSignatures,
+
+    /// Transaction -> Countersigned receipt
+    TransactionToReceipt,
 }
 
 #[hdk_entry_types]
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
     Transaction(Transaction),
+    EscrowEntry(EscrowEntry),
+    Cart(Cart),
+    Order(Order),
+    Shipment(Shipment),
+    ExchangeRateSnapshot(ExchangeRateSnapshot),
+    ReceiptSignature(ReceiptSignature),
+    TransactionReceipt(TransactionReceipt),
 }
 
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
     match op.flattened::<EntryTypes, LinkTypes>()? {
-        FlatOp::StoreEntry(store_entry) => match store_entry {
-            OpEntry::CreateEntry { app_entry, .. } => match app_entry {
-                EntryTypes::Transaction(transaction) => validate_transaction(&transaction),
-            },
-            _ => Ok(ValidateCallbackResult::Valid),
+        FlatOp::StoreEntry(OpEntry::CreateEntry { app_entry, action }) => match app_entry {
+            EntryTypes::Transaction(transaction) => {
+                validate_create_transaction(&transaction, &action)
+            }
+            EntryTypes::EscrowEntry(escrow) => validate_escrow_entry(&escrow, &action),
+            EntryTypes::Cart(cart) => validate_cart(&cart, &action.author),
+            EntryTypes::Order(order) => validate_order(&order, &action),
+            EntryTypes::Shipment(shipment) => validate_create_shipment(&shipment, &action),
+            EntryTypes::ExchangeRateSnapshot(snapshot) => {
+                validate_exchange_rate_snapshot(&snapshot)
+            }
+            EntryTypes::ReceiptSignature(signature) => {
+                validate_receipt_signature(&signature, &action)
+            }
+            EntryTypes::TransactionReceipt(receipt) => {
+                validate_transaction_receipt(&receipt, &action)
+            }
+        },
+        FlatOp::RegisterUpdate(OpUpdate::Entry { app_entry, action }) => match app_entry {
+            EntryTypes::Transaction(transaction) => {
+                validate_update_transaction(&transaction, &action)
+            }
+            EntryTypes::EscrowEntry(_) => Ok(ValidateCallbackResult::Invalid(
+                "Escrow entries cannot be updated".into(),
+            )),
+            EntryTypes::Cart(cart) => validate_update_cart(&cart, &action),
+            EntryTypes::Order(_) => Ok(ValidateCallbackResult::Invalid(
+                "Orders cannot be updated".into(),
+            )),
+            EntryTypes::Shipment(shipment) => validate_update_shipment(&shipment, &action),
+            EntryTypes::ExchangeRateSnapshot(_) => Ok(ValidateCallbackResult::Invalid(
+                "Exchange-rate snapshots cannot be updated".into(),
+            )),
+            EntryTypes::ReceiptSignature(_) => Ok(ValidateCallbackResult::Invalid(
+                "Receipt signatures cannot be updated".into(),
+            )),
+            EntryTypes::TransactionReceipt(_) => Ok(ValidateCallbackResult::Invalid(
+                "Transaction receipts cannot be updated".into(),
+            )),
         },
         _ => Ok(ValidateCallbackResult::Valid),
     }
 }
 
-fn validate_transaction(transaction: &Transaction) -> ExternResult<ValidateCallbackResult> {
+/// Check a transaction version written at `written_at` (its action's timestamp)
+fn validate_transaction(
+    transaction: &Transaction,
+    written_at: Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
     // Quantity validation
     if transaction.quantity == 0 {
         return Ok(ValidateCallbackResult::Invalid(
@@ -148,6 +876,33 @@ fn validate_transaction(transaction: &Transaction) -> ExternResult<ValidateCallb
         ));
     }
 
+    if let Err(reason) = check_transaction_lines(transaction) {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    // Timed states carry a deadline no later than the configured window,
+    // counted from when the version was written rather than the
+    // `updated_at` its author chose
+    let latest_deadline = state_deadline(
+        &transaction.status,
+        written_at,
+        &transaction_properties()?,
+    );
+    match (&transaction.deadline, &latest_deadline) {
+        (Some(deadline), Some(latest)) if deadline > latest => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Transaction deadline exceeds the configured timeout".into(),
+            ))
+        }
+        (Some(_), None) => {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "{:?} transactions have no deadline",
+                transaction.status
+            )))
+        }
+        _ => {}
+    }
+
     // Transactions should be N1 (communal - buyer-seller agreement)
     if transaction.epistemic.normative != NormativeLevel::N1Communal {
         return Ok(ValidateCallbackResult::Invalid(
@@ -157,3 +912,1174 @@ fn validate_transaction(transaction: &Transaction) -> ExternResult<ValidateCallb
 
     Ok(ValidateCallbackResult::Valid)
 }
+
+/// Validate a new transaction against the listing it buys from
+///
+/// The seller must be the listing's author and the total must be the
+/// quantity times the listed price, so neither can be chosen by the buyer.
+/// Transactions from a checkout must buy exactly their seller's lines of
+/// the order, each checked against its listing the same way.
+fn validate_create_transaction(
+    transaction: &Transaction,
+    action: &Create,
+) -> ExternResult<ValidateCallbackResult> {
+    if let ValidateCallbackResult::Invalid(reason) = validate_transaction(transaction, action.timestamp)? {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    if action.author != transaction.buyer {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the buyer can create a transaction".into(),
+        ));
+    }
+
+    if transaction.status != TransactionStatus::Pending {
+        return Ok(ValidateCallbackResult::Invalid(
+            "New transactions must be pending".into(),
+        ));
+    }
+
+    if let Some(order_hash) = &transaction.order_hash {
+        let order_record = must_get_valid_record(order_hash.clone())?;
+        let order: Order = match order_record.entry().to_app_option() {
+            Ok(Some(order)) => order,
+            _ => {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Transaction must reference an order".into(),
+                ))
+            }
+        };
+
+        // The transaction buys exactly the order's lines from its seller
+        let seller_lines: Vec<OrderLine> = order
+            .lines
+            .iter()
+            .filter(|line| line.seller == transaction.seller)
+            .cloned()
+            .collect();
+        let in_order = order.buyer == transaction.buyer
+            && order.currency == transaction.currency
+            && seller_lines == transaction.purchase_lines();
+
+        if !in_order {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Transaction does not match its seller's lines of the order".into(),
+            ));
+        }
+    }
+
+    for line in transaction.purchase_lines() {
+        if let ValidateCallbackResult::Invalid(reason) = validate_listing_purchase(
+            &line.listing_hash,
+            &line.listing_version_hash,
+            &line.seller,
+            line.variant_sku.as_deref(),
+            line.quantity,
+            line.total_price_cents,
+            &transaction.currency,
+        )? {
+            return Ok(ValidateCallbackResult::Invalid(reason));
+        }
+    }
+
+    validate_reference_value(transaction, action)
+}
+
+/// Check the reference-currency value a transaction records for MATL
+///
+/// Purchases in the reference currency need no conversion. Others must
+/// cite a recent oracle snapshot and record the total converted at its rate.
+/// The snapshot's age is measured at the action that writes the
+/// transaction, not at the `created_at` the author chose.
+fn validate_reference_value(
+    transaction: &Transaction,
+    action: &Create,
+) -> ExternResult<ValidateCallbackResult> {
+    if transaction.currency == REFERENCE_CURRENCY {
+        if transaction.exchange_rate_hash.is_some() || transaction.reference_value_cents.is_some() {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Reference-currency transactions need no exchange rate".into(),
+            ));
+        }
+        return Ok(ValidateCallbackResult::Valid);
+    }
+
+    let snapshot_hash = match &transaction.exchange_rate_hash {
+        Some(hash) => hash.clone(),
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "{} transactions must record the exchange rate used",
+                transaction.currency
+            )))
+        }
+    };
+
+    let snapshot_record = must_get_valid_record(snapshot_hash)?;
+    let snapshot: ExchangeRateSnapshot = match snapshot_record.entry().to_app_option() {
+        Ok(Some(snapshot)) => snapshot,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Exchange rate must reference a rate snapshot".into(),
+            ))
+        }
+    };
+
+    let properties = transaction_properties()?;
+    let max_age_micros = properties.max_exchange_rate_age_hours as i64 * MICROS_PER_HOUR;
+    let age_micros = action.timestamp.as_micros() - snapshot.observed_at.as_micros();
+    if !(0..=max_age_micros).contains(&age_micros) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Exchange-rate snapshot is too old for this transaction".into(),
+        ));
+    }
+
+    let expected = snapshot
+        .rate_for(&transaction.currency)
+        .and_then(|rate| reference_value_minor(transaction.total_price_cents, rate));
+
+    match expected {
+        Some(value) if transaction.reference_value_cents == Some(value) => {
+            Ok(ValidateCallbackResult::Valid)
+        }
+        Some(_) => Ok(ValidateCallbackResult::Invalid(
+            "Reference value does not match the snapshot rate".into(),
+        )),
+        None => Ok(ValidateCallbackResult::Invalid(format!(
+            "Snapshot has no usable rate for {}",
+            transaction.currency
+        ))),
+    }
+}
+
+/// Validate an exchange-rate snapshot and its oracle signature
+fn validate_exchange_rate_snapshot(
+    snapshot: &ExchangeRateSnapshot,
+) -> ExternResult<ValidateCallbackResult> {
+    if let Err(reason) = check_exchange_rates(snapshot) {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    let properties = transaction_properties()?;
+    if !properties.exchange_rate_oracles.contains(&snapshot.oracle) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Exchange rates must come from a configured oracle".into(),
+        ));
+    }
+
+    if !verify_signature(
+        snapshot.oracle.clone(),
+        snapshot.signature.clone(),
+        snapshot.payload(),
+    )? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Exchange-rate signature does not match the oracle".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Check the contents of an exchange-rate snapshot
+pub fn check_exchange_rates(snapshot: &ExchangeRateSnapshot) -> Result<(), String> {
+    if snapshot.base_currency != REFERENCE_CURRENCY {
+        return Err(format!("Exchange rates must be quoted in {}", REFERENCE_CURRENCY));
+    }
+
+    if snapshot.source.trim().is_empty() || snapshot.source.len() > 200 {
+        return Err("Rate source must be 1-200 characters".into());
+    }
+
+    if snapshot.rates.is_empty() || snapshot.rates.len() > 200 {
+        return Err("Snapshot must quote between 1 and 200 currencies".into());
+    }
+
+    for (i, rate) in snapshot.rates.iter().enumerate() {
+        if !is_valid_currency_code(&rate.currency) || rate.currency == snapshot.base_currency {
+            return Err(format!("Invalid quoted currency: {}", rate.currency));
+        }
+
+        if rate.rate == 0 {
+            return Err(format!("Rate for {} must be greater than zero", rate.currency));
+        }
+
+        if snapshot.rates[..i].iter().any(|other| other.currency == rate.currency) {
+            return Err(format!("{} is quoted twice", rate.currency));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check a purchase against the listing version it was priced from
+fn validate_listing_purchase(
+    listing_hash: &ActionHash,
+    listing_version_hash: &ActionHash,
+    seller: &AgentPubKey,
+    variant_sku: Option<&str>,
+    quantity: u32,
+    total_price_cents: u64,
+    currency: &str,
+) -> ExternResult<ValidateCallbackResult> {
+    let listing_record = must_get_valid_record(listing_version_hash.clone())?;
+    let terms: ListingTerms = match listing_record.entry().to_app_option() {
+        Ok(Some(terms)) => terms,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Transaction must reference a listing".into(),
+            ))
+        }
+    };
+
+    // The priced version is the listing itself or one of its updates
+    let is_listing_version = match listing_record.action() {
+        Action::Create(_) => listing_record.action_address() == listing_hash,
+        Action::Update(update) => {
+            &original_action_hash(update.original_action_address.clone())? == listing_hash
+        }
+        _ => false,
+    };
+
+    if !is_listing_version {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Listing version does not belong to the purchased listing".into(),
+        ));
+    }
+
+    if listing_record.action().author() != seller {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Seller must be the listing's author".into(),
+        ));
+    }
+
+    if terms.currency != currency {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Listing is priced in {}, not {}",
+            terms.currency, currency
+        )));
+    }
+
+    match check_listing_terms(&terms, variant_sku, quantity, total_price_cents) {
+        Ok(()) => Ok(ValidateCallbackResult::Valid),
+        Err(reason) => Ok(ValidateCallbackResult::Invalid(reason)),
+    }
+}
+
+/// Total price for `quantity` units at the listed price
+pub fn compute_total_price(price_cents: u64, quantity: u32) -> Option<u64> {
+    price_cents.checked_mul(quantity as u64)
+}
+
+/// Check that a purchase matches the listing's (or variant's) terms
+pub fn check_listing_terms(
+    terms: &ListingTerms,
+    variant_sku: Option<&str>,
+    quantity: u32,
+    total_price_cents: u64,
+) -> Result<(), String> {
+    if terms.status != "active" {
+        return Err("Listing is not available for purchase".into());
+    }
+
+    let (price_cents, quantity_available) = terms.variant_terms(variant_sku)?;
+
+    if quantity > quantity_available {
+        return Err(format!(
+            "Only {} items are available",
+            quantity_available
+        ));
+    }
+
+    match compute_total_price(price_cents, quantity) {
+        Some(expected) if expected == total_price_cents => Ok(()),
+        Some(expected) => Err(format!(
+            "Total price must be {} cents ({} x {})",
+            expected, quantity, price_cents
+        )),
+        None => Err("Total price overflows".into()),
+    }
+}
+
+fn validate_escrow_entry(
+    escrow: &EscrowEntry,
+    action: &Create,
+) -> ExternResult<ValidateCallbackResult> {
+    if escrow.amount_cents == 0 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Escrow amount must be greater than zero".into(),
+        ));
+    }
+
+    if escrow.settlement_reference.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Escrow entry needs a settlement reference".into(),
+        ));
+    }
+
+    let transaction_record = must_get_valid_record(escrow.transaction_hash.clone())?;
+    let transaction: Transaction = match transaction_record.entry().to_app_option() {
+        Ok(Some(transaction)) => transaction,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Escrow entry must reference a transaction".into(),
+            ))
+        }
+    };
+
+    if escrow.amount_cents > transaction.total_price_cents {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Escrow amount cannot exceed the transaction total".into(),
+        ));
+    }
+
+    // Only the parties move escrowed funds
+    if action.author != transaction.buyer && action.author != transaction.seller {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only buyer or seller can record escrow entries".into(),
+        ));
+    }
+
+    let (expected_party, party_name) = match escrow.kind {
+        EscrowKind::Hold => {
+            // The buyer funds the full price up front
+            if action.author != transaction.buyer
+                || escrow.amount_cents != transaction.total_price_cents
+            {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Escrow hold must be the buyer's full payment".into(),
+                ));
+            }
+            (&transaction.buyer, "buyer")
+        }
+        EscrowKind::Release => (&transaction.seller, "seller"),
+        EscrowKind::Refund => (&transaction.buyer, "buyer"),
+    };
+
+    if &escrow.party != expected_party {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Escrow {:?} party must be the {}",
+            escrow.kind, party_name
+        )));
+    }
+
+    if escrow.kind == EscrowKind::Hold {
+        if escrow.arbitration_result_hash.is_some() || escrow.transaction_version_hash.is_some() {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Escrow holds are not authorized by a result or transaction version".into(),
+            ));
+        }
+
+        if has_earlier_hold(escrow, action)? {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Transaction escrow is already funded".into(),
+            ));
+        }
+
+        return Ok(ValidateCallbackResult::Valid);
+    }
+
+    let limit = match (&escrow.arbitration_result_hash, &escrow.transaction_version_hash) {
+        (Some(result_hash), None) => settlement_limit(escrow, result_hash, &transaction, &action.author)?,
+        (None, Some(version_hash)) => version_limit(escrow, version_hash, &action.author)?,
+        _ => Err(
+            "Escrow payouts need either an arbitration result or a transaction version".into(),
+        ),
+    };
+    let limit = match limit {
+        Ok(limit) => limit,
+        Err(reason) => return Ok(ValidateCallbackResult::Invalid(reason)),
+    };
+
+    // Earlier payouts of this kind count against the same limit
+    let created_at = transaction_record.action().timestamp();
+    let paid_cents = earlier_payout_cents(escrow, action, created_at)?;
+
+    match check_payout(&escrow.kind, paid_cents.saturating_add(escrow.amount_cents), limit) {
+        Ok(()) => Ok(ValidateCallbackResult::Valid),
+        Err(reason) => Ok(ValidateCallbackResult::Invalid(reason)),
+    }
+}
+
+/// Most a payout may move, or exactly how much it must move
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayoutLimit {
+    /// Up to this amount
+    AtMost(u64),
+    /// Exactly this amount
+    Exactly(u64),
+}
+
+/// Payout limits (refund, release) a transaction version authorizes
+///
+/// - Completed: the full total to the seller, no refund
+/// - Cancelled: the full total back to the buyer, no release
+/// - Refunded: the agreed refund to the buyer, the rest to the seller
+///
+/// Any other status authorizes nothing.
+pub fn version_payout_limits(transaction: &Transaction) -> Option<(PayoutLimit, PayoutLimit)> {
+    let total = transaction.total_price_cents;
+
+    match transaction.status {
+        TransactionStatus::Completed => Some((PayoutLimit::AtMost(0), PayoutLimit::AtMost(total))),
+        TransactionStatus::Cancelled => Some((PayoutLimit::AtMost(total), PayoutLimit::AtMost(0))),
+        TransactionStatus::Refunded => {
+            let refund = transaction
+                .return_request
+                .as_ref()
+                .and_then(|request| request.refund_cents)
+                .unwrap_or(0)
+                .min(total);
+            Some((PayoutLimit::AtMost(refund), PayoutLimit::AtMost(total - refund)))
+        }
+        _ => None,
+    }
+}
+
+/// Payout limits (refund, release) an arbitration result authorizes
+///
+/// The buyer is refunded exactly the compensation, the seller gets at
+/// most the rest.
+pub fn settlement_payout_limits(
+    compensation_cents: u64,
+    total_price_cents: u64,
+) -> (PayoutLimit, PayoutLimit) {
+    (
+        PayoutLimit::Exactly(compensation_cents),
+        PayoutLimit::AtMost(total_price_cents.saturating_sub(compensation_cents)),
+    )
+}
+
+/// Check a payout amount against its limit
+pub fn check_payout(kind: &EscrowKind, amount_cents: u64, limit: PayoutLimit) -> Result<(), String> {
+    match limit {
+        PayoutLimit::AtMost(max) if amount_cents > max => Err(format!(
+            "Escrow {:?} of {} cents exceeds the {} cents authorized",
+            kind, amount_cents, max
+        )),
+        PayoutLimit::Exactly(expected) if amount_cents != expected => Err(format!(
+            "Escrow {:?} must be exactly {} cents",
+            kind, expected
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Pick the refund or release limit for a payout
+fn payout_limit(kind: &EscrowKind, limits: (PayoutLimit, PayoutLimit)) -> PayoutLimit {
+    match kind {
+        EscrowKind::Refund => limits.0,
+        _ => limits.1,
+    }
+}
+
+/// Limit on a payout authorized by a version of its transaction
+///
+/// The version's author records its payouts, so they all sit on one chain.
+fn version_limit(
+    escrow: &EscrowEntry,
+    version_hash: &ActionHash,
+    author: &AgentPubKey,
+) -> ExternResult<Result<PayoutLimit, String>> {
+    let version_record = must_get_valid_record(version_hash.clone())?;
+    let version: Transaction = match version_record.entry().to_app_option() {
+        Ok(Some(version)) => version,
+        _ => return Ok(Err("Escrow payout must reference a transaction version".into())),
+    };
+
+    if version_record.action().author() != author {
+        return Ok(Err("Payouts for a transaction version are recorded by its author".into()));
+    }
+
+    if original_action_hash(version_hash.clone())? != escrow.transaction_hash {
+        return Ok(Err("Transaction version belongs to a different transaction".into()));
+    }
+
+    Ok(match version_payout_limits(&version) {
+        Some(limits) => Ok(payout_limit(&escrow.kind, limits)),
+        None => Err(format!(
+            "A {:?} transaction does not authorize escrow payouts",
+            version.status
+        )),
+    })
+}
+
+/// Limit on a payout authorized by a dispute's arbitration result
+///
+/// Each party claims their own share, so each kind of payout sits on
+/// the payee's chain.
+fn settlement_limit(
+    escrow: &EscrowEntry,
+    result_hash: &ActionHash,
+    transaction: &Transaction,
+    author: &AgentPubKey,
+) -> ExternResult<Result<PayoutLimit, String>> {
+    if author != &escrow.party {
+        return Ok(Err("Settlement payouts are claimed by the party paid".into()));
+    }
+
+    let result_record = must_get_valid_record(result_hash.clone())?;
+    let result: SettlementTerms = match result_record.entry().to_app_option() {
+        Ok(Some(result)) => result,
+        _ => return Ok(Err("Escrow settlement must reference an arbitration result".into())),
+    };
+
+    let dispute_record = must_get_valid_record(result.dispute_hash.clone())?;
+    let dispute: DisputeTerms = match dispute_record.entry().to_app_option() {
+        Ok(Some(dispute)) => dispute,
+        _ => return Ok(Err("Arbitration result must reference a dispute".into())),
+    };
+
+    if original_action_hash(dispute.transaction_hash)? != escrow.transaction_hash {
+        return Ok(Err("Arbitration result is for a different transaction".into()));
+    }
+
+    let limits = settlement_payout_limits(
+        result.compensation_cents.unwrap_or(0),
+        transaction.total_price_cents,
+    );
+    Ok(Ok(payout_limit(&escrow.kind, limits)))
+}
+
+/// Whether the buyer already funded the transaction's escrow
+///
+/// Holds are written by the buyer after creating the transaction, so any
+/// earlier hold sits on the buyer's chain between the transaction and
+/// this action.
+fn has_earlier_hold(escrow: &EscrowEntry, action: &Create) -> ExternResult<bool> {
+    let filter = ChainFilter::new(action.prev_action.clone())
+        .until_hash(escrow.transaction_hash.clone());
+
+    for activity in must_get_agent_activity(action.author.clone(), filter)? {
+        let entry_hash = match activity.action.action() {
+            Action::Create(create) if matches!(create.entry_type, EntryType::App(_)) => {
+                create.entry_hash.clone()
+            }
+            _ => continue,
+        };
+
+        if let Ok(earlier) = EscrowEntry::try_from(must_get_entry(entry_hash)?) {
+            if earlier.kind == EscrowKind::Hold && earlier.transaction_hash == escrow.transaction_hash {
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/// Cents the author already paid out of this kind from the transaction's escrow
+///
+/// Every payout of a kind is recorded by one agent (see `version_limit`
+/// and `settlement_limit`), so the earlier ones sit on this author's
+/// chain after the transaction was created.
+fn earlier_payout_cents(
+    escrow: &EscrowEntry,
+    action: &Create,
+    transaction_created_at: Timestamp,
+) -> ExternResult<u64> {
+    let filter = ChainFilter::new(action.prev_action.clone()).until_timestamp(transaction_created_at);
+
+    let mut paid_cents: u64 = 0;
+    for activity in must_get_agent_activity(action.author.clone(), filter)? {
+        let entry_hash = match activity.action.action() {
+            Action::Create(create) if matches!(create.entry_type, EntryType::App(_)) => {
+                create.entry_hash.clone()
+            }
+            _ => continue,
+        };
+
+        if let Ok(earlier) = EscrowEntry::try_from(must_get_entry(entry_hash)?) {
+            if earlier.kind == escrow.kind && earlier.transaction_hash == escrow.transaction_hash {
+                paid_cents = paid_cents.saturating_add(earlier.amount_cents);
+            }
+        }
+    }
+
+    Ok(paid_cents)
+}
+
+fn validate_cart(cart: &Cart, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if author != &cart.owner {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the owner can write their cart".into(),
+        ));
+    }
+
+    if cart.items.len() > MAX_CART_ITEMS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Cart cannot hold more than {} items",
+            MAX_CART_ITEMS
+        )));
+    }
+
+    for (i, item) in cart.items.iter().enumerate() {
+        if item.quantity == 0 {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Cart item quantity must be at least 1".into(),
+            ));
+        }
+
+        if cart.items[..i].iter().any(|other| {
+            other.listing_hash == item.listing_hash && other.variant_sku == item.variant_sku
+        }) {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Cart lists the same listing variant twice".into(),
+            ));
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_update_cart(cart: &Cart, action: &Update) -> ExternResult<ValidateCallbackResult> {
+    if let ValidateCallbackResult::Invalid(reason) = validate_cart(cart, &action.author)? {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    let previous_record = must_get_valid_record(action.original_action_address.clone())?;
+    let previous: Cart = match previous_record.entry().to_app_option() {
+        Ok(Some(previous)) => previous,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Updated entry must be a cart".into(),
+            ))
+        }
+    };
+
+    if previous.owner != cart.owner {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Cart owner cannot change".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate an order: every line is priced from its listing and the
+/// order total is the sum of the lines
+fn validate_order(order: &Order, action: &Create) -> ExternResult<ValidateCallbackResult> {
+    if action.author != order.buyer {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the buyer can place an order".into(),
+        ));
+    }
+
+    if order.lines.is_empty() || order.lines.len() > MAX_CART_ITEMS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Order must have between 1 and {} lines",
+            MAX_CART_ITEMS
+        )));
+    }
+
+    let mut total: u64 = 0;
+    for line in &order.lines {
+        if line.seller == order.buyer {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Buyer cannot order their own listing".into(),
+            ));
+        }
+
+        if let ValidateCallbackResult::Invalid(reason) = validate_listing_purchase(
+            &line.listing_hash,
+            &line.listing_version_hash,
+            &line.seller,
+            line.variant_sku.as_deref(),
+            line.quantity,
+            line.total_price_cents,
+            &order.currency,
+        )? {
+            return Ok(ValidateCallbackResult::Invalid(reason));
+        }
+
+        total = match total.checked_add(line.total_price_cents) {
+            Some(total) => total,
+            None => {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Order total overflows".into(),
+                ))
+            }
+        };
+    }
+
+    if total != order.total_price_cents {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Order total must be the sum of its lines".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_shipment(shipment: &Shipment) -> ExternResult<ValidateCallbackResult> {
+    if shipment.carrier.trim().is_empty() || shipment.carrier.len() > 100 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Carrier must be 1-100 characters".into(),
+        ));
+    }
+
+    if shipment.tracking_number.trim().is_empty() || shipment.tracking_number.len() > 100 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Tracking number must be 1-100 characters".into(),
+        ));
+    }
+
+    if let (Some(start), Some(end)) = (
+        &shipment.estimated_delivery_start,
+        &shipment.estimated_delivery_end,
+    ) {
+        if end < start {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Estimated delivery window ends before it starts".into(),
+            ));
+        }
+    }
+
+    if shipment.events.len() > MAX_TRACKING_EVENTS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Shipment cannot have more than {} tracking events",
+            MAX_TRACKING_EVENTS
+        )));
+    }
+
+    if shipment
+        .events
+        .iter()
+        .any(|event| event.description.trim().is_empty() || event.description.len() > 500)
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Tracking event descriptions must be 1-500 characters".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Shipments are recorded by the transaction's seller
+fn validate_create_shipment(
+    shipment: &Shipment,
+    action: &Create,
+) -> ExternResult<ValidateCallbackResult> {
+    if let ValidateCallbackResult::Invalid(reason) = validate_shipment(shipment)? {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    let transaction_record = must_get_valid_record(shipment.transaction_hash.clone())?;
+    let transaction: Transaction = match transaction_record.entry().to_app_option() {
+        Ok(Some(transaction)) => transaction,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Shipment must reference a transaction".into(),
+            ))
+        }
+    };
+
+    if action.author != transaction.seller {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the seller can record a shipment".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Shipment updates only append tracking events or revise the estimate
+fn validate_update_shipment(
+    shipment: &Shipment,
+    action: &Update,
+) -> ExternResult<ValidateCallbackResult> {
+    if let ValidateCallbackResult::Invalid(reason) = validate_shipment(shipment)? {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    let previous_record = must_get_valid_record(action.original_action_address.clone())?;
+    let previous: Shipment = match previous_record.entry().to_app_option() {
+        Ok(Some(previous)) => previous,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Updated entry must be a shipment".into(),
+            ))
+        }
+    };
+
+    if previous_record.action().author() != &action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the seller can update a shipment".into(),
+        ));
+    }
+
+    match check_shipment_update(&previous, shipment) {
+        Ok(()) => Ok(ValidateCallbackResult::Valid),
+        Err(reason) => Ok(ValidateCallbackResult::Invalid(reason)),
+    }
+}
+
+/// Check that a shipment update keeps its identity and only appends events
+pub fn check_shipment_update(previous: &Shipment, shipment: &Shipment) -> Result<(), String> {
+    if shipment.transaction_hash != previous.transaction_hash
+        || shipment.carrier != previous.carrier
+        || shipment.tracking_number != previous.tracking_number
+        || shipment.shipping_method != previous.shipping_method
+    {
+        return Err("Shipment transaction, carrier, tracking number and method cannot change".into());
+    }
+
+    if !shipment.events.starts_with(&previous.events) {
+        return Err("Tracking events can only be appended".into());
+    }
+
+    Ok(())
+}
+
+/// Validate transaction updates
+///
+/// Each update is made against the previous version, so the previous
+/// status is known and only the coordinator's state machine is allowed:
+/// Pending → Confirmed → Shipped → Delivered → Completed, plus Disputed
+/// from any active state, Cancelled from Pending/Confirmed and the return
+/// path Delivered → ReturnRequested → ReturnApproved → ReturnShipped → Refunded.
+fn validate_update_transaction(
+    transaction: &Transaction,
+    action: &Update,
+) -> ExternResult<ValidateCallbackResult> {
+    if let ValidateCallbackResult::Invalid(reason) = validate_transaction(transaction, action.timestamp)? {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    let previous_record = must_get_valid_record(action.original_action_address.clone())?;
+    let previous: Transaction = match previous_record.entry().to_app_option() {
+        Ok(Some(previous)) => previous,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Updated entry must be a transaction".into(),
+            ))
+        }
+    };
+
+    // Parties and terms are fixed at creation
+    if transaction.buyer != previous.buyer
+        || transaction.seller != previous.seller
+        || transaction.listing_hash != previous.listing_hash
+        || transaction.listing_version_hash != previous.listing_version_hash
+        || transaction.quantity != previous.quantity
+        || transaction.variant_sku != previous.variant_sku
+        || transaction.lines != previous.lines
+        || transaction.currency != previous.currency
+        || transaction.exchange_rate_hash != previous.exchange_rate_hash
+        || transaction.reference_value_cents != previous.reference_value_cents
+        || transaction.order_hash != previous.order_hash
+        || transaction.total_price_cents != previous.total_price_cents
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Transaction parties, listing, quantity, price and valuation cannot change".into(),
+        ));
+    }
+
+    if let Err(reason) = check_transition(&previous, &transaction.status, &action.author) {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    // A shipment is attached when shipping and never replaced
+    if previous.shipment_hash.is_some() && transaction.shipment_hash != previous.shipment_hash {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Transaction shipment cannot change".into(),
+        ));
+    }
+
+    if previous.shipment_hash.is_none() {
+        match (&transaction.status, &transaction.shipment_hash) {
+            (TransactionStatus::Shipped, Some(shipment_hash)) => {
+                let shipment_record = must_get_valid_record(shipment_hash.clone())?;
+                if shipment_record.action().author() != &transaction.seller {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "Shipment must be recorded by the seller".into(),
+                    ));
+                }
+            }
+            (TransactionStatus::Shipped, None) => {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Shipped transactions need a shipment".into(),
+                ))
+            }
+            (_, Some(_)) => {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "A shipment is attached when the transaction ships".into(),
+                ))
+            }
+            (_, None) => {}
+        }
+    }
+
+    // Completion needs a receipt countersigned by both parties
+    match (&transaction.status, &transaction.receipt_hash) {
+        (TransactionStatus::Completed, Some(receipt_hash)) => {
+            let receipt_record = must_get_valid_record(receipt_hash.clone())?;
+            let receipt: TransactionReceipt = match receipt_record.entry().to_app_option() {
+                Ok(Some(receipt)) => receipt,
+                _ => {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "Receipt must be a transaction receipt".into(),
+                    ))
+                }
+            };
+
+            let original_hash = original_action_hash(action.original_action_address.clone())?;
+            let expected = ReceiptTerms::new(original_hash, transaction);
+            if receipt.terms != expected {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Receipt terms do not match the transaction".into(),
+                ));
+            }
+        }
+        (TransactionStatus::Completed, None) => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Completed transactions need a countersigned receipt".into(),
+            ))
+        }
+        (_, Some(_)) => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "A receipt is attached when the transaction completes".into(),
+            ))
+        }
+        (_, None) => {}
+    }
+
+    match check_return_request(&previous, transaction) {
+        Ok(()) => Ok(ValidateCallbackResult::Valid),
+        Err(reason) => Ok(ValidateCallbackResult::Invalid(reason)),
+    }
+}
+
+/// Follow an update chain back to the action that created the entry
+fn original_action_hash(action_hash: ActionHash) -> ExternResult<ActionHash> {
+    let mut current = action_hash;
+    loop {
+        match must_get_action(current.clone())?.action() {
+            Action::Update(update) => current = update.original_action_address.clone(),
+            _ => return Ok(current),
+        }
+    }
+}
+
+/// Receipt signatures come from a party, over the transaction's terms
+fn validate_receipt_signature(
+    receipt_signature: &ReceiptSignature,
+    action: &Create,
+) -> ExternResult<ValidateCallbackResult> {
+    if receipt_signature.signer != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Receipt signatures are stored by their signer".into(),
+        ));
+    }
+
+    if let ValidateCallbackResult::Invalid(reason) = validate_receipt_terms(&receipt_signature.terms)? {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    if receipt_signature.signer != receipt_signature.terms.buyer
+        && receipt_signature.signer != receipt_signature.terms.seller
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only buyer or seller can sign a receipt".into(),
+        ));
+    }
+
+    if !verify_signature(
+        receipt_signature.signer.clone(),
+        receipt_signature.signature.clone(),
+        receipt_signature.terms.clone(),
+    )? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Receipt signature does not verify".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Receipts carry valid signatures from both parties over the transaction's terms
+fn validate_transaction_receipt(
+    receipt: &TransactionReceipt,
+    action: &Create,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != receipt.terms.buyer && action.author != receipt.terms.seller {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only buyer or seller can countersign a receipt".into(),
+        ));
+    }
+
+    if let ValidateCallbackResult::Invalid(reason) = validate_receipt_terms(&receipt.terms)? {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    let signatures = [
+        (&receipt.terms.buyer, &receipt.buyer_signature, "buyer"),
+        (&receipt.terms.seller, &receipt.seller_signature, "seller"),
+    ];
+    for (party, signature, party_name) in signatures {
+        if !verify_signature(party.clone(), signature.clone(), receipt.terms.clone())? {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Receipt {} signature does not verify",
+                party_name
+            )));
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Receipt terms must be the terms the transaction was created with
+fn validate_receipt_terms(terms: &ReceiptTerms) -> ExternResult<ValidateCallbackResult> {
+    let transaction_record = must_get_valid_record(terms.transaction_hash.clone())?;
+
+    if !matches!(transaction_record.action(), Action::Create(_)) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Receipt must reference the transaction's original action".into(),
+        ));
+    }
+
+    let transaction: Transaction = match transaction_record.entry().to_app_option() {
+        Ok(Some(transaction)) => transaction,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Receipt must reference a transaction".into(),
+            ))
+        }
+    };
+
+    // Terms are fixed at creation, so the original version is enough
+    if terms != &ReceiptTerms::new(terms.transaction_hash.clone(), &transaction) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Receipt terms do not match the transaction".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Check the return request carried by an update
+///
+/// The request is opened with a reason when the return is requested,
+/// gains a tracking number when the item is shipped back and a refund
+/// amount when refunded. Once set, none of these change.
+pub fn check_return_request(previous: &Transaction, transaction: &Transaction) -> Result<(), String> {
+    use TransactionStatus::*;
+
+    let (old, request) = match (&previous.return_request, &transaction.return_request) {
+        (None, None) => return Ok(()),
+        (Some(_), None) => return Err("Return request cannot be removed".into()),
+        (None, Some(request)) => {
+            if transaction.status != ReturnRequested {
+                return Err("A return can only be opened by requesting it".into());
+            }
+            if request.reason.trim().is_empty() || request.reason.len() > MAX_RETURN_REASON_LENGTH {
+                return Err(format!(
+                    "Return reason must be 1-{} characters",
+                    MAX_RETURN_REASON_LENGTH
+                ));
+            }
+            if request.tracking_number.is_some() || request.refund_cents.is_some() {
+                return Err("New return requests have no tracking or refund yet".into());
+            }
+            return Ok(());
+        }
+        (Some(old), Some(request)) => {
+            if request.reason != old.reason {
+                return Err("Return reason cannot change".into());
+            }
+            (old, request)
+        }
+    };
+
+    if old.tracking_number.is_some() && request.tracking_number != old.tracking_number {
+        return Err("Return tracking number cannot change".into());
+    }
+
+    if old.refund_cents.is_some() && request.refund_cents != old.refund_cents {
+        return Err("Refund amount cannot change".into());
+    }
+
+    if transaction.status == ReturnShipped {
+        match &request.tracking_number {
+            Some(tracking) if !tracking.trim().is_empty() => {}
+            _ => return Err("Return shipment needs a tracking number".into()),
+        }
+    } else if old.tracking_number.is_none() && request.tracking_number.is_some() {
+        return Err("Tracking number is set when the return is shipped".into());
+    }
+
+    if transaction.status == Refunded {
+        match request.refund_cents {
+            Some(refund) if refund > 0 && refund <= transaction.total_price_cents => {}
+            _ => {
+                return Err(
+                    "Refund must be greater than zero and at most the total price".into(),
+                )
+            }
+        }
+    } else if old.refund_cents.is_none() && request.refund_cents.is_some() {
+        return Err("Refund amount is set when the transaction is refunded".into());
+    }
+
+    Ok(())
+}
+
+/// Check that `author` may move a transaction to `new_status`
+///
+/// - Seller: confirm (Pending) and ship (Confirmed)
+/// - Buyer: confirm delivery (Shipped), request a return (Delivered),
+///   ship the return back (ReturnApproved)
+/// - Seller: approve a return (ReturnRequested) and refund, either
+///   partially without a return (ReturnRequested) or once the item is back
+///   (ReturnShipped)
+/// - Either party: complete (Delivered), dispute (any active state,
+///   including a rejected return), cancel (Pending/Confirmed)
+pub fn check_transition(
+    transaction: &Transaction,
+    new_status: &TransactionStatus,
+    author: &AgentPubKey,
+) -> Result<(), String> {
+    use TransactionStatus::*;
+
+    let is_buyer = author == &transaction.buyer;
+    let is_seller = author == &transaction.seller;
+
+    let (allowed_from, allowed_author): (&[TransactionStatus], bool) = match new_status {
+        Confirmed => (&[Pending], is_seller),
+        Shipped => (&[Confirmed], is_seller),
+        Delivered => (&[Shipped], is_buyer),
+        Completed => (&[Delivered], is_buyer || is_seller),
+        Disputed => (
+            &[
+                Pending,
+                Confirmed,
+                Shipped,
+                Delivered,
+                ReturnRequested,
+                ReturnApproved,
+                ReturnShipped,
+            ],
+            is_buyer || is_seller,
+        ),
+        Cancelled => (&[Pending, Confirmed], is_buyer || is_seller),
+        ReturnRequested => (&[Delivered], is_buyer),
+        ReturnApproved => (&[ReturnRequested], is_seller),
+        ReturnShipped => (&[ReturnApproved], is_buyer),
+        Refunded => (&[ReturnRequested, ReturnShipped], is_seller),
+        Pending => (&[], false),
+    };
+
+    if !allowed_from.contains(&transaction.status) {
+        return Err(format!(
+            "Invalid state transition from {:?} to {:?}",
+            transaction.status, new_status
+        ));
+    }
+
+    if !allowed_author {
+        return Err(format!(
+            "Agent is not allowed to move a transaction to {:?}",
+            new_status
+        ));
+    }
+
+    Ok(())
+}